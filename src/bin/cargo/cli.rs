@@ -1,7 +1,16 @@
-use std::path::PathBuf;
+use std::{env, ffi::OsString, path::PathBuf};
 
 use anyhow::Result;
-use clap::{ArgAction, Parser, ValueEnum};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use cargo_tree_tui::core::{
+    dependency::find_manifest, export::GraphFormat, profile::profile_args, remote::RemoteCrate,
+};
+use cargo_tree_tui::ops::tree::tui::{
+    options::{ExpandPolicy, ScrollbarSide},
+    widget::{BreadcrumbElision, IndentWidth, Theme},
+};
 
 use crate::commands;
 
@@ -10,18 +19,102 @@ use crate::commands;
 #[command(bin_name = "cargo")]
 #[command(styles = clap_cargo::style::CLAP_STYLING)]
 pub enum Command {
-    #[command(about, author, version)]
+    #[command(about, author, version, args_override_self = true)]
     TreeTui(TreeArgs),
 }
 
 impl Command {
+    /// Parses the command line, then parses it again with the flags of
+    /// `--profile` inserted in front, so that explicit flags win.
+    pub fn parse_with_profile() -> Result<Self> {
+        let command = Self::parse();
+        let Command::TreeTui(args) = &command;
+        let Some(name) = &args.profile else {
+            return Ok(command);
+        };
+        let manifest = find_manifest(args.manifest_path.clone())?;
+        let mut argv: Vec<OsString> = env::args_os().collect();
+        // Right after `cargo-tree-tui tree-tui`.
+        let at = argv.len().min(2);
+        argv.splice(
+            at..at,
+            profile_args(&manifest, name)?
+                .into_iter()
+                .map(OsString::from),
+        );
+        Ok(Self::parse_from(argv))
+    }
+
     pub fn exec(self) -> Result<()> {
         match self {
-            Command::TreeTui(args) => commands::tree_tui::run(args),
+            Command::TreeTui(mut args) => match args.command.take() {
+                Some(TreeCommand::Query(query)) => commands::query::run(args, query),
+                Some(TreeCommand::Diet) => commands::diet::run(args),
+                Some(TreeCommand::Locate { spec }) => commands::locate::run(args, &spec),
+                Some(TreeCommand::Keys { format }) => commands::keys::run(args, format),
+                Some(TreeCommand::Completions { shell }) => commands::generate::completions(shell),
+                Some(TreeCommand::Manpage) => commands::generate::manpage(),
+                Some(TreeCommand::Load { resolve }) => {
+                    commands::load::run(args, resolve.as_deref())
+                }
+                None => commands::tree_tui::run(args),
+            },
         }
     }
 }
 
+#[derive(Debug, Subcommand)]
+pub enum TreeCommand {
+    /// Run a graph query and print the result as JSON, without the TUI
+    Query(QueryArgs),
+    /// Print dependency-reduction opportunities, most crates saved first
+    Diet,
+    /// Print where a crate's manifest is and the lines declaring it, as
+    /// `path:line: description` for editors
+    Locate {
+        /// Crate to locate, as `name` or `name@version`
+        #[arg(value_name = "SPEC")]
+        spec: String,
+    },
+    /// Print the key bindings, including the commands configured for the
+    /// workspace, as a cheat sheet
+    Keys {
+        #[arg(long, value_enum, default_value = "md")]
+        format: KeysFormat,
+    },
+    /// Print a shell completion script for `cargo-tree-tui`
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
+    /// Print the man page in roff format
+    Manpage,
+    /// Resolve the workspace and print the tree as JSON, for the TUI to
+    /// load in a child process it can kill
+    #[command(hide = true)]
+    Load {
+        /// Versions to resolve to, as serialized by the TUI; the locked
+        /// ones by default
+        #[arg(long, value_name = "JSON")]
+        resolve: Option<String>,
+    },
+}
+
+#[derive(Debug, Parser)]
+pub struct QueryArgs {
+    /// `paths-to <crate>`, `duplicates`, `reverse-deps <crate>` or
+    /// `closure <crate>`; crates may be given as `name@version`
+    #[arg(value_name = "EXPR", required = true, num_args = 1..)]
+    pub expr: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum KeysFormat {
+    /// Markdown table
+    Md,
+    Json,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum Prefix {
     Depth,
@@ -37,6 +130,13 @@ pub enum Charset {
 
 #[derive(Debug, Parser)]
 pub struct TreeArgs {
+    #[command(subcommand)]
+    pub command: Option<TreeCommand>,
+
+    /// Apply the flags of a profile from `[workspace.metadata.tree-tui.profile]`
+    #[arg(long = "profile", value_name = "NAME")]
+    pub profile: Option<String>,
+
     /// Deprecated, use --no-dedupe instead
     #[arg(short = 'a', long = "all", hide = true)]
     pub all: bool,
@@ -130,9 +230,269 @@ pub struct TreeArgs {
     #[arg(long = "manifest-path", value_name = "PATH")]
     pub manifest_path: Option<PathBuf>,
 
+    /// Explore a registry crate outside of any workspace, e.g. `tokio@1.38`
+    #[arg(
+        long = "crate",
+        value_name = "NAME[@VERSION]",
+        conflicts_with_all = ["manifest_path", "package", "workspace"]
+    )]
+    pub remote_crate: Option<RemoteCrate>,
+
     /// Path to Cargo.lock
     #[arg(long = "lockfile-path", value_name = "PATH")]
     pub lockfile_path: Option<PathBuf>,
+
+    /// Initial expansion: `all`, `none`, `members`, `fit` (to the terminal height) or a depth
+    #[arg(long = "expand", value_name = "POLICY", default_value = "all")]
+    pub expand: ExpandPolicy,
+
+    /// Start with only the crates whose name contains PATTERN shown, along with their ancestors
+    #[arg(long = "filter", value_name = "PATTERN")]
+    pub filter: Option<String>,
+
+    /// Start on the first occurrence of this crate (`name` or `name@version`), expanded to and centered
+    #[arg(long = "select", value_name = "SPEC")]
+    pub select: Option<String>,
+
+    /// Stop expanding crates this many levels below the members, even in cyclic graphs
+    #[arg(long = "max-depth-guard", value_name = "DEPTH")]
+    pub max_depth_guard: Option<usize>,
+
+    /// Color theme: `default`, `high-contrast` or `colorblind`
+    #[arg(
+        long = "theme",
+        value_name = "THEME",
+        env = "CARGO_TREE_TUI_THEME",
+        default_value = "default"
+    )]
+    pub theme: Theme,
+
+    /// Draw the TUI in ROWS lines below the prompt instead of taking over the screen
+    #[arg(
+        long = "inline",
+        value_name = "ROWS",
+        value_parser = clap::value_parser!(u16).range(3..)
+    )]
+    pub inline: Option<u16>,
+
+    /// Cells per indentation level of the tree guides: 2, 3 or 4
+    #[arg(long = "indent", value_name = "WIDTH", default_value = "3")]
+    pub indent: IndentWidth,
+
+    /// Show Nerd Font icons next to each dependency
+    #[arg(long = "icons")]
+    pub icons: bool,
+
+    /// Wrap long lines instead of truncating them
+    #[arg(long = "wrap")]
+    pub wrap: bool,
+
+    /// Scrollbar placement: `left`, `right` or `none`
+    #[arg(
+        long = "scrollbar",
+        value_name = "SIDE",
+        env = "CARGO_TREE_TUI_SCROLLBAR",
+        default_value = "right"
+    )]
+    pub scrollbar: ScrollbarSide,
+
+    /// Ancestors a long breadcrumb keeps: `head` (below the root), `tail` (above the selection) or `ends` (just the parent)
+    #[arg(
+        long = "breadcrumb-elision",
+        value_name = "POLICY",
+        default_value = "head"
+    )]
+    pub breadcrumb_elision: BreadcrumbElision,
+
+    /// Keep the scrollbar visible even when the whole tree fits
+    #[arg(long = "scrollbar-always")]
+    pub scrollbar_always: bool,
+
+    /// Screen reader friendly output: plain-text rows, selection on top
+    #[arg(long = "accessible")]
+    pub accessible: bool,
+
+    /// Wrap around when moving past the first or last sibling
+    #[arg(long = "wrap-siblings")]
+    pub wrap_siblings: bool,
+
+    /// Start zoomed out, showing crate names only
+    #[arg(long = "compact")]
+    pub compact: bool,
+
+    /// Hide versions except for crates present in several versions
+    #[arg(long = "clean-versions")]
+    pub clean_versions: bool,
+
+    /// Show a minimap of the whole tree along the right edge
+    #[arg(long = "minimap")]
+    pub minimap: bool,
+
+    /// Animate scrolling over a few frames on long jumps, e.g. to a search hit
+    #[arg(long = "smooth-scroll")]
+    pub smooth_scroll: bool,
+
+    /// Show how many crates depend on each dependency
+    #[arg(long = "used-by")]
+    pub used_by: bool,
+
+    /// List the lib, bin, example, test and bench targets of workspace members
+    #[arg(long = "targets")]
+    pub targets: bool,
+
+    /// Drop the `[dev-dependencies]` and `[build-dependencies]` headers, badging their crates instead
+    #[arg(long = "hide-groups")]
+    pub hide_groups: bool,
+
+    /// Only show workspace members and the dependencies between them
+    #[arg(long = "members-only")]
+    pub members_only: bool,
+
+    /// List each crate once with the crates depending on it below
+    #[arg(long = "by-crate")]
+    pub by_crate: bool,
+
+    /// Nest workspace members under folders mirroring their directories
+    #[arg(long = "group-by-dir")]
+    pub group_by_dir: bool,
+
+    /// Show each subtree's estimated share of the build
+    #[arg(long = "build-cost")]
+    pub build_cost: bool,
+
+    /// Color versions by release age (green = recent, red = years old)
+    #[arg(long = "age-heatmap")]
+    pub age_heatmap: bool,
+
+    /// Flag crates that pull `std` into `#![no_std]` workspace members
+    #[arg(long = "no-std-check")]
+    pub no_std_check: bool,
+
+    /// Attribute binary sizes from saved `cargo bloat --message-format json` output
+    #[arg(long = "bloat", value_name = "FILE")]
+    pub bloat: Option<PathBuf>,
+
+    /// Sort dependencies by how many crates depend on them
+    #[arg(long = "sort-by-used")]
+    pub sort_by_used: bool,
+
+    /// Remember committed searches across sessions of this workspace
+    #[arg(long = "save-search-history")]
+    pub save_search_history: bool,
+
+    /// Resolve the workspace even if a cached tree is up to date
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// Give up on resolving the workspace, or on a cargo command run from the TUI, after this many seconds
+    #[arg(long = "load-timeout", value_name = "SECS")]
+    pub load_timeout: Option<u64>,
+
+    /// Write the tree as a standalone, collapsible HTML page and exit
+    #[arg(long = "export-html", value_name = "PATH")]
+    pub export_html: Option<PathBuf>,
+
+    /// Write the deduplicated graph with typed edges for graph tools and exit
+    #[arg(long = "export-graph", value_name = "PATH")]
+    pub export_graph: Option<PathBuf>,
+
+    /// Write the tree as text colored with ANSI escape codes, in the --theme colors, and exit
+    #[arg(long = "export-ansi", value_name = "PATH")]
+    pub export_ansi: Option<PathBuf>,
+
+    /// Format of --export-graph: `graphml` or `json` [default: from the extension]
+    #[arg(
+        long = "graph-format",
+        value_name = "FORMAT",
+        requires = "export_graph"
+    )]
+    pub graph_format: Option<GraphFormat>,
+
+    /// Also write the debug log (F12 in the TUI) to this file
+    #[arg(long = "log-file", value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Print how long each startup phase took after exiting
+    #[arg(long = "timings-self")]
+    pub timings_self: bool,
+
+    /// Only show dependencies used by at least N crates
+    #[arg(long = "min-used-by", value_name = "N")]
+    pub min_used_by: Option<usize>,
+
+    /// Query crates.io for owners, publish dates and downloads
+    #[arg(long = "online")]
+    pub online: bool,
+
+    /// Show supply-chain trust signals (implies --online)
+    #[arg(long = "trust")]
+    pub trust: bool,
+
+    /// Run this command with each crate's name and version to annotate it
+    #[arg(long = "annotate-command", value_name = "COMMAND")]
+    pub annotate_command: Option<String>,
+
+    /// Accept commands like `select serde` on a Unix socket at this path
+    #[cfg(unix)]
+    #[arg(long = "control-socket", value_name = "PATH")]
+    pub control_socket: Option<PathBuf>,
+}
+
+impl TreeArgs {
+    /// Returns the flags selecting and loading the graph, to be repeated
+    /// when reproducing the current view.
+    pub fn launch_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut value = |flag: &str, value: String| {
+            args.push(flag.to_string());
+            args.push(value);
+        };
+        let path = |path: &PathBuf| path.display().to_string();
+        // `--crate` stands for the scratch project made for it.
+        if let Some(remote) = &self.remote_crate {
+            let spec = match &remote.version {
+                Some(version) => format!("{}@{version}", remote.name),
+                None => remote.name.clone(),
+            };
+            value("--crate", spec);
+        } else if let Some(manifest) = &self.manifest_path {
+            value("--manifest-path", path(manifest));
+        }
+        if let Some(lockfile) = &self.lockfile_path {
+            value("--lockfile-path", path(lockfile));
+        }
+        for package in &self.package {
+            value("--package", package.clone());
+        }
+        for exclude in &self.exclude {
+            value("--exclude", exclude.clone());
+        }
+        if !self.features.is_empty() {
+            value("--features", self.features.join(","));
+        }
+        for target in &self.target {
+            value("--target", target.clone());
+        }
+        if let Some(bloat) = &self.bloat {
+            value("--bloat", path(bloat));
+        }
+        if let Some(command) = &self.annotate_command {
+            value("--annotate-command", command.clone());
+        }
+        let switches = [
+            (self.workspace, "--workspace"),
+            (self.all_features, "--all-features"),
+            (self.no_default_features, "--no-default-features"),
+            (self.sort_by_used, "--sort-by-used"),
+        ];
+        args.extend(
+            switches
+                .into_iter()
+                .filter(|&(on, _)| on)
+                .map(|(_, flag)| flag.to_string()),
+        );
+        args
+    }
 }
 
 #[test]
@@ -140,3 +500,31 @@ fn verify_app() {
     use clap::CommandFactory;
     Command::command().debug_assert();
 }
+
+#[test]
+fn launch_args_round_trip() {
+    let argv = [
+        "cargo",
+        "tree-tui",
+        "--crate",
+        "tokio@1.38",
+        "-F",
+        "rt,macros",
+        "--no-default-features",
+        "--target",
+        "wasm32-unknown-unknown",
+        "--annotate-command",
+        "audit {name}",
+    ];
+    let Command::TreeTui(args) = Command::parse_from(argv);
+    let launch_args = args.launch_args();
+    let Command::TreeTui(reparsed) = Command::parse_from(
+        ["cargo", "tree-tui"]
+            .into_iter()
+            .map(String::from)
+            .chain(launch_args.clone()),
+    );
+    assert_eq!(reparsed.launch_args(), launch_args);
+    assert_eq!(reparsed.features, ["rt", "macros"]);
+    assert_eq!(reparsed.annotate_command.as_deref(), Some("audit {name}"));
+}
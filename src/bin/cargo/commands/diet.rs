@@ -0,0 +1,14 @@
+use anyhow::Result;
+
+use cargo_tree_tui::core::diet::{diet_report, report_lines};
+
+use crate::cli::TreeArgs;
+
+/// Entry point for the `cargo tree-tui diet` command.
+pub fn run(args: TreeArgs) -> Result<()> {
+    let tree = super::load_tree(args.manifest_path, args.no_cache)?;
+    for line in report_lines(&tree, &diet_report(&tree)) {
+        println!("{line}");
+    }
+    Ok(())
+}
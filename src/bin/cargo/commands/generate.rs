@@ -0,0 +1,45 @@
+use std::io;
+
+use anyhow::{Context, Result};
+use clap::{Command, CommandFactory};
+use clap_complete::Shell;
+use clap_mangen::Man;
+
+use crate::cli;
+
+/// Name the artifacts are generated for, as installed by `cargo install`.
+const BIN_NAME: &str = "cargo-tree-tui";
+
+/// Entry point for the `cargo tree-tui completions` command.
+pub fn completions(shell: Shell) -> Result<()> {
+    clap_complete::generate(shell, &mut command(), BIN_NAME, &mut io::stdout());
+    Ok(())
+}
+
+/// Entry point for the `cargo tree-tui manpage` command.
+///
+/// Documents the `tree-tui` subcommand, which holds every flag, under the
+/// binary's name.
+pub fn manpage() -> Result<()> {
+    let mut command = command();
+    // Fills in the `cargo-tree-tui tree-tui` synopsis of the subcommand.
+    command.build();
+    let tree_tui = command
+        .find_subcommand("tree-tui")
+        .context("missing the tree-tui subcommand")?
+        .clone()
+        .name(BIN_NAME)
+        .display_name(BIN_NAME);
+    Man::new(tree_tui).render(&mut io::stdout())?;
+    Ok(())
+}
+
+/// Returns the command line as the binary parses it: `cargo tree-tui` runs
+/// it as `cargo-tree-tui tree-tui`.
+fn command() -> Command {
+    cli::Command::command()
+        .name(BIN_NAME)
+        .bin_name(BIN_NAME)
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .version(env!("CARGO_PKG_VERSION"))
+}
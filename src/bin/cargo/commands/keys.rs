@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use cargo_tree_tui::core::{commands::NodeCommands, dependency::find_manifest};
+use cargo_tree_tui::ops::tree::tui::help::keymap;
+
+use crate::cli::{KeysFormat, TreeArgs};
+
+/// Entry point for the `cargo tree-tui keys` command.
+///
+/// Outside a Cargo project, only the built-in bindings are listed.
+pub fn run(args: TreeArgs, format: KeysFormat) -> Result<()> {
+    let commands = match find_manifest(args.manifest_path) {
+        Ok(manifest) => NodeCommands::load(&manifest)?,
+        Err(_) => NodeCommands::default(),
+    };
+    let bindings = keymap(&commands);
+    match format {
+        KeysFormat::Md => {
+            println!("| Key | Action |");
+            println!("| --- | --- |");
+            for binding in bindings {
+                println!(
+                    "| `{}` | {} |",
+                    binding.key.replace('|', "\\|"),
+                    binding.action.replace('|', "\\|")
+                );
+            }
+        }
+        KeysFormat::Json => println!("{}", serde_json::to_string_pretty(&bindings)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use std::io::{self, Write};
+
+use anyhow::{Context, Result};
+
+use cargo_tree_tui::ops::tree::tui::command::Resolve;
+
+use crate::cli::TreeArgs;
+
+/// Entry point for the hidden `cargo tree-tui load` command, the
+/// [`LoadCommand`](cargo_tree_tui::ops::tree::tui::command::LoadCommand) of
+/// this binary.
+///
+/// Prints the tree and its load timings as JSON on stdout.
+pub fn run(args: TreeArgs, resolve: Option<&str>) -> Result<()> {
+    let resolve: Resolve = match resolve {
+        Some(json) => serde_json::from_str(json).context("invalid --resolve")?,
+        None => Resolve::Locked,
+    };
+    let loaded = resolve.load(args.manifest_path, args.no_cache)?;
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &loaded)?;
+    stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anyhow::{Result, bail};
+
+use cargo_tree_tui::core::manifest::locate;
+
+use crate::cli::TreeArgs;
+
+/// Entry point for the `cargo tree-tui locate` command.
+///
+/// Prints one `path:line: description` line per spot, the format Vim's
+/// quickfix list and most editors' error parsers read.
+pub fn run(args: TreeArgs, spec: &str) -> Result<()> {
+    let tree = super::load_tree(args.manifest_path, args.no_cache)?;
+    let spots = locate(&tree, spec);
+    if spots.is_empty() {
+        bail!("no crate matches `{spec}`");
+    }
+    for spot in spots {
+        println!(
+            "{}:{}: {}",
+            spot.path.display(),
+            spot.line,
+            spot.description
+        );
+    }
+    Ok(())
+}
@@ -1 +1,26 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use cargo_tree_tui::core::{DependencyTree, cache};
+
+pub mod diet;
+pub mod generate;
+pub mod keys;
+pub mod load;
+pub mod locate;
+pub mod query;
 pub mod tree_tui;
+
+/// Loads the tree for a headless command, passing Cargo's warnings on to
+/// stderr.
+fn load_tree(manifest_path: Option<PathBuf>, no_cache: bool) -> Result<DependencyTree> {
+    let (tree, timings) = if no_cache {
+        DependencyTree::load_timed(manifest_path)?
+    } else {
+        cache::load(manifest_path)?
+    };
+    for warning in &timings.warnings {
+        eprintln!("warning: {warning}");
+    }
+    Ok(tree)
+}
@@ -0,0 +1,14 @@
+use anyhow::Result;
+
+use cargo_tree_tui::core::query::Query;
+
+use crate::cli::{QueryArgs, TreeArgs};
+
+/// Entry point for the `cargo tree-tui query` command.
+pub fn run(args: TreeArgs, query_args: QueryArgs) -> Result<()> {
+    let query = Query::parse(&query_args.expr)?;
+    let tree = super::load_tree(args.manifest_path, args.no_cache)?;
+    let result = query.run(&tree)?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
@@ -1,33 +1,232 @@
-use std::{sync::mpsc, thread, time::Duration};
+use std::{
+    env,
+    ffi::OsString,
+    fs::{self, File},
+    path::Path,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow, bail};
 use crossterm::event::{self, Event as CrosstermEvent};
+use ratatui::{DefaultTerminal, TerminalOptions, Viewport};
+use tracing::{Level, debug, info};
+use tracing_subscriber::{
+    filter::Targets,
+    fmt::{
+        self,
+        writer::{BoxMakeWriter, MakeWriterExt},
+    },
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+};
 
+#[cfg(unix)]
+use cargo_tree_tui::ops::tree::tui::control::spawn_control_socket;
 use cargo_tree_tui::{
-    core::DependencyTree,
+    core::{
+        DependencyTree,
+        annotate::{Annotators, CommandAnnotator},
+        bloat::BloatReport,
+        commands::NodeCommands,
+        dependency::{default_members, find_manifest, workspace_root},
+        export::{GraphFormat, graph_export, tree_html},
+        layers::LayerRules,
+        workspace_state::WorkspaceState,
+    },
     ops::tree::tui::{
+        command::{CancelToken, LoadCommand, Resolve, load_in_child, run_suspended},
+        crash::{WorkspaceSummary, install_panic_hook},
+        debug_log::LogBuffer,
         draw_tui,
-        state::{Event, SearchRequest, SearchResult, TuiState},
-        widget::TreeWidgetState,
+        editor::open_in_editor,
+        export::tree_ansi,
+        options::{MemberSelection, TuiOptions},
+        state::{Event, SearchMessage, SearchResult, TuiState},
+        widget::{NodeIcons, SearchIndex, TreeWidgetState, TreeWidgetStyle},
     },
 };
 
 use crate::cli::TreeArgs;
 
+/// Frames taking longer than this are logged.
+const SLOW_FRAME: Duration = Duration::from_millis(50);
+
 /// Entry point for the `cargo tree-tui` command.
-pub fn run(args: TreeArgs) -> Result<()> {
-    let dependency_tree = DependencyTree::load(args.manifest_path)?;
+pub fn run(mut args: TreeArgs) -> Result<()> {
+    if let Some(remote) = &args.remote_crate {
+        args.manifest_path = Some(remote.create_project()?);
+    }
+    let manifest = find_manifest(args.manifest_path.clone())?;
+    let layer_rules = LayerRules::load(&manifest)?;
+    let node_commands = NodeCommands::load(&manifest)?;
+    let group_by_dir = if args.group_by_dir {
+        Some(workspace_root(&manifest)?)
+    } else {
+        None
+    };
+    let bloat = args.bloat.as_deref().map(BloatReport::load).transpose()?;
+    let mut annotators = Annotators::default();
+    if let Some(command) = &args.annotate_command {
+        annotators.register(CommandAnnotator::new(command));
+    }
+    let load_timeout = args.load_timeout.map(Duration::from_secs);
+    let options = TuiOptions {
+        manifest_path: args.manifest_path.clone(),
+        load_timeout,
+        load_command: Some(load_command(args.manifest_path.as_deref(), true)?),
+        launch_args: args.launch_args(),
+        members: member_selection(&args, &manifest)?,
+        remote_crate: args.remote_crate.clone(),
+        expand: args.expand,
+        max_depth_guard: args.max_depth_guard,
+        theme: args.theme,
+        icons: args.icons,
+        wrap: args.wrap,
+        accessible: args.accessible,
+        wrap_siblings: args.wrap_siblings,
+        scrollbar: args.scrollbar,
+        breadcrumb_elision: args.breadcrumb_elision,
+        indent_width: args.indent,
+        inline: args.inline,
+        scrollbar_always: args.scrollbar_always,
+        compact: args.compact,
+        ambiguous_versions_only: args.clean_versions,
+        minimap: args.minimap,
+        smooth_scroll: args.smooth_scroll,
+        show_dependents: args.used_by,
+        min_dependents: args.min_used_by,
+        filter: args.filter.clone(),
+        show_targets: args.targets,
+        sort_by_dependents: args.sort_by_used,
+        hide_groups: args.hide_groups,
+        members_only: args.members_only,
+        group_by_crate: args.by_crate,
+        group_by_dir: group_by_dir.clone(),
+        show_cost: args.build_cost,
+        show_age: args.age_heatmap,
+        check_no_std: args.no_std_check,
+        bloat,
+        layer_rules,
+        annotators,
+        node_commands,
+        save_search_history: args.save_search_history,
+        online: args.online,
+        trust: args.trust,
+    };
+    let log_buffer = init_logging(args.log_file.as_deref())?;
+    let startup = Instant::now();
+    let manifest_path = args.manifest_path.clone();
+    // With a timeout, the workspace is resolved in a child process that is
+    // killed once it runs over. Ctrl-C ends the child along with this
+    // process, as both are in the terminal's foreground process group, and
+    // the terminal is not set up yet, so there is nothing to restore.
+    let (dependency_tree, load_timings) = match load_timeout {
+        Some(timeout) => load_in_child(
+            &load_command(manifest_path.as_deref(), args.no_cache)?,
+            &Resolve::Locked,
+            CancelToken::default(),
+            Some(timeout),
+        )
+        .map_err(|stopped| {
+            anyhow!(
+                "resolving the workspace {stopped}; cargo may be waiting on the network, \
+                 try again with CARGO_NET_OFFLINE=true"
+            )
+        })??,
+        None => Resolve::Locked.load(manifest_path, args.no_cache)?,
+    };
+    let mut dependency_tree = options.members.apply(dependency_tree);
+    if dependency_tree.roots.is_empty()
+        && let MemberSelection::Packages(specs) = &options.members
+    {
+        bail!("no workspace member matches `{}`", specs.join("`, `"));
+    }
+    if let Some(remote) = &args.remote_crate {
+        dependency_tree = remote.root_of(&dependency_tree)?;
+    }
+    let start = Instant::now();
+    if args.targets {
+        dependency_tree.add_target_nodes();
+    }
+    if args.sort_by_used {
+        dependency_tree.sort_by_dependents();
+    }
+    let sort = start.elapsed();
+    if args.export_html.is_some() || args.export_graph.is_some() || args.export_ansi.is_some() {
+        for warning in &load_timings.warnings {
+            eprintln!("warning: {warning}");
+        }
+        if args.members_only {
+            dependency_tree = dependency_tree.workspace_only();
+        }
+        if let Some(path) = &args.export_html {
+            let notes = WorkspaceState::load(&manifest).notes;
+            write_export(path, tree_html(&dependency_tree, &notes))?;
+        }
+        if let Some(path) = &args.export_graph {
+            let format = args
+                .graph_format
+                .unwrap_or_else(|| GraphFormat::from_path(path));
+            write_export(path, graph_export(&dependency_tree, format))?;
+        }
+        if let Some(path) = &args.export_ansi {
+            let style = TreeWidgetStyle {
+                icons: args.icons.then(NodeIcons::nerd_font),
+                show_dependents: args.used_by,
+                compact: args.compact,
+                ambiguous_versions_only: args.clean_versions,
+                ..TreeWidgetStyle::themed(args.theme).indented(args.indent)
+            };
+            write_export(path, tree_ansi(&dependency_tree, &style))?;
+        }
+        return Ok(());
+    }
+    if let Some(root) = &group_by_dir {
+        dependency_tree.group_members_by_dir(root);
+    }
+    let (node_count, root_count) = (dependency_tree.nodes.len(), dependency_tree.members().len());
 
-    let (search_tx, search_rx) = mpsc::channel::<SearchRequest>();
+    let (search_tx, search_rx) = mpsc::channel::<SearchMessage>();
     let (event_tx, event_rx) = mpsc::channel::<Event>();
     let worker_tree = dependency_tree.clone();
-    let worker_handle = thread::spawn(move || search_worker(worker_tree, search_rx, event_tx));
+    let worker_event_tx = event_tx.clone();
+    let worker_handle =
+        thread::spawn(move || search_worker(worker_tree, search_rx, worker_event_tx));
 
-    let mut state = TuiState::new(dependency_tree, search_tx);
-    let mut terminal = ratatui::init();
+    install_panic_hook(WorkspaceSummary::new(&dependency_tree), log_buffer.clone());
+    #[cfg(unix)]
+    if let Some(path) = &args.control_socket {
+        spawn_control_socket(path, event_tx.clone())?;
+    }
+    let mut state = TuiState::new(dependency_tree, options, search_tx, event_tx);
+    state.log_buffer = log_buffer;
+    state.set_workspace_state(WorkspaceState::load(&manifest));
+    state.show_cargo_warnings(&load_timings.warnings);
+    if let Some(spec) = &args.select {
+        state.select_spec(spec).map_err(|err| anyhow!(err))?;
+    }
+    let mut terminal = init_terminal(args.inline)?;
+    let mut first_frame = None;
 
     while state.running {
+        let start = Instant::now();
         terminal.draw(|frame| draw_tui(frame, &mut state))?;
+        let frame_time = start.elapsed();
+        let is_first = first_frame.is_none();
+        if is_first {
+            first_frame = Some((frame_time, startup.elapsed()));
+        }
+        if frame_time > SLOW_FRAME {
+            debug!(?frame_time, "slow frame");
+            // Animation frames would only make a slow terminal lag further.
+            // The first frame is slow anyway, building the caches.
+            if state.options.smooth_scroll && !is_first {
+                info!("smooth scrolling turned off on a slow terminal");
+                state.options.smooth_scroll = false;
+            }
+        }
 
         while let Ok(event) = event_rx.try_recv() {
             state.handle_event(event);
@@ -38,25 +237,174 @@ pub fn run(args: TreeArgs) -> Result<()> {
         {
             state.handle_event(Event::Key(key_event));
         }
+
+        if let Some(request) = state.shell_request.take() {
+            ratatui::restore();
+            eprintln!("$ {}", request.args.join(" "));
+            let log = run_suspended(&request);
+            terminal = init_terminal(args.inline)?;
+            state.command_log = Some(log);
+        }
+
+        if let Some(request) = state.editor_request.take() {
+            ratatui::restore();
+            let result = open_in_editor(&request.path, request.line);
+            terminal = init_terminal(args.inline)?;
+            if let Err(err) = result {
+                state.report_error("editor", &err);
+            }
+        }
     }
 
     drop(state);
+    if args.inline.is_some() {
+        // Leave the last frame in the scrollback, with the prompt below it.
+        let area = terminal.get_frame().area();
+        terminal.set_cursor_position((0, area.bottom().saturating_sub(1)))?;
+    }
     ratatui::restore();
+    if args.inline.is_some() {
+        println!();
+    }
     let _ = worker_handle.join();
+    #[cfg(unix)]
+    if let Some(path) = &args.control_socket {
+        let _ = fs::remove_file(path);
+    }
+
+    if args.timings_self {
+        let (first_frame, total) = first_frame.unwrap_or_default();
+        let phases = [
+            (
+                if load_timings.cached {
+                    "read cache"
+                } else {
+                    "resolve workspace"
+                },
+                load_timings.resolve,
+            ),
+            ("collect packages", load_timings.collect),
+            ("wire edges", load_timings.wire),
+            ("sort", sort),
+            ("first frame", first_frame),
+            ("total to first frame", total),
+        ];
+        eprintln!("startup timings ({node_count} nodes, {root_count} members):");
+        for (phase, duration) in phases {
+            eprintln!("  {phase:<22}{duration:>10.1?}");
+        }
+    }
+    Ok(())
+}
+
+/// Sets the terminal up for the TUI: the alternate screen, or `inline`
+/// rows below the prompt.
+fn init_terminal(inline: Option<u16>) -> Result<DefaultTerminal> {
+    let terminal = match inline {
+        Some(rows) => ratatui::try_init_with_options(TerminalOptions {
+            viewport: Viewport::Inline(rows),
+        }),
+        None => ratatui::try_init(),
+    };
+    terminal.context("failed to initialize the terminal")
+}
+
+/// Returns the hidden `load` subcommand of this binary, which resolves the
+/// workspace in a child process.
+fn load_command(manifest_path: Option<&Path>, no_cache: bool) -> Result<LoadCommand> {
+    let program = env::current_exe().context("failed to locate the cargo-tree-tui binary")?;
+    let mut args = vec![OsString::from("tree-tui")];
+    if let Some(path) = manifest_path {
+        args.extend([OsString::from("--manifest-path"), path.into()]);
+    }
+    if no_cache {
+        args.push("--no-cache".into());
+    }
+    args.push("load".into());
+    Ok(LoadCommand { program, args })
+}
+
+/// Picks the members to root the tree on from `--package`, or `--workspace`
+/// minus `--exclude`, defaulting to the workspace's `default-members` like
+/// Cargo does.
+fn member_selection(args: &TreeArgs, manifest: &Path) -> Result<MemberSelection> {
+    if !args.package.is_empty() {
+        return Ok(MemberSelection::Packages(args.package.clone()));
+    }
+    if args.workspace {
+        return Ok(MemberSelection::Excluding(args.exclude.clone()));
+    }
+    Ok(default_members(manifest)?.map_or(MemberSelection::All, MemberSelection::Dirs))
+}
+
+/// Writes an export given on the command line and reports where it went.
+fn write_export(path: &Path, contents: String) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+    eprintln!("wrote {}", path.display());
     Ok(())
 }
 
+/// Routes `tracing` output to the in-TUI log pane, and to `log_file` when
+/// given.
+fn init_logging(log_file: Option<&Path>) -> Result<LogBuffer> {
+    let buffer = LogBuffer::default();
+    let writer = match log_file {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            BoxMakeWriter::new(buffer.clone().and(file))
+        }
+        None => BoxMakeWriter::new(buffer.clone()),
+    };
+    // The `cargo` library traces heavily while resolving; keep only its warnings.
+    let targets = Targets::new()
+        .with_target("cargo_tree_tui", Level::DEBUG)
+        .with_default(Level::WARN);
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(writer).with_ansi(false))
+        .with(targets)
+        .try_init()
+        .map_err(|err| anyhow!(err))?;
+    Ok(buffer)
+}
+
 fn search_worker(
-    dependency_tree: DependencyTree,
-    search_rx: mpsc::Receiver<SearchRequest>,
+    mut dependency_tree: DependencyTree,
+    search_rx: mpsc::Receiver<SearchMessage>,
     event_tx: mpsc::Sender<Event>,
 ) {
-    while let Ok(mut request) = search_rx.recv() {
-        while let Ok(next_request) = search_rx.try_recv() {
-            request = next_request;
+    // The index is built off this thread so the first searches are not
+    // blocked on it; they scan linearly until it arrives.
+    let (index_tx, index_rx) = mpsc::channel();
+    spawn_index(&dependency_tree, 0, &index_tx);
+    let mut tree_generation = 0;
+    let mut index = None;
+
+    while let Ok(message) = search_rx.recv() {
+        // Coalesce queued messages: apply every tree swap, keep only the latest query.
+        let mut request = None;
+        for message in std::iter::once(message).chain(search_rx.try_iter()) {
+            match message {
+                SearchMessage::Search(next_request) => request = Some(next_request),
+                SearchMessage::SetTree(tree) => {
+                    dependency_tree = tree;
+                    tree_generation += 1;
+                    index = None;
+                    spawn_index(&dependency_tree, tree_generation, &index_tx);
+                }
+            }
         }
+        let Some(request) = request else {
+            continue;
+        };
 
-        let search_state = TreeWidgetState::search(&dependency_tree, &request.query);
+        for (generation, built) in index_rx.try_iter() {
+            if generation == tree_generation {
+                index = Some(built);
+            }
+        }
+        let search_state =
+            TreeWidgetState::search_indexed(&dependency_tree, index.as_ref(), &request.query);
         let event = Event::SearchResult(SearchResult {
             generation: request.generation,
             query: request.query,
@@ -68,3 +416,17 @@ fn search_worker(
         }
     }
 }
+
+/// Builds the search index of `tree` on its own thread, tagged with the
+/// generation of the tree it belongs to.
+fn spawn_index(
+    tree: &DependencyTree,
+    generation: u64,
+    index_tx: &mpsc::Sender<(u64, SearchIndex)>,
+) {
+    let tree = tree.clone();
+    let index_tx = index_tx.clone();
+    thread::spawn(move || {
+        let _ = index_tx.send((generation, SearchIndex::build(&tree)));
+    });
+}
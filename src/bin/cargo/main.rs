@@ -1,9 +1,7 @@
-use clap::Parser as _;
-
 mod cli;
 mod commands;
 
 fn main() -> anyhow::Result<()> {
-    let command = cli::Command::parse();
+    let command = cli::Command::parse_with_profile()?;
     command.exec()
 }
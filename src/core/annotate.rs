@@ -0,0 +1,151 @@
+use std::{fmt, process::Command, sync::Arc};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use tracing::warn;
+
+use super::{Dependency, DependencyTree, dependency::CrateKey};
+
+/// Custom metadata attached to a crate, e.g. an internal approval status.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotation {
+    /// Short badge rendered after the crate's name.
+    pub badge: Option<String>,
+    /// `(label, value)` pairs listed in the details panel.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.badge.is_none() && self.fields.is_empty()
+    }
+
+    /// Appends `other`, joining the badges with a space.
+    fn merge(&mut self, other: Annotation) {
+        self.badge = match (self.badge.take(), other.badge) {
+            (Some(badge), Some(other)) => Some(format!("{badge} {other}")),
+            (badge, other) => badge.or(other),
+        };
+        self.fields.extend(other.fields);
+    }
+}
+
+/// Extension point for surfacing org-specific metadata in the tree.
+///
+/// Annotators run on a background thread once per distinct crate and
+/// version, so they may block on I/O.
+pub trait NodeAnnotator: Send + Sync {
+    /// Returns the annotation of `dependency`, `None` to leave it alone.
+    fn annotate(&self, dependency: &Dependency) -> Option<Annotation>;
+}
+
+impl<F> NodeAnnotator for F
+where
+    F: Fn(&Dependency) -> Option<Annotation> + Send + Sync,
+{
+    fn annotate(&self, dependency: &Dependency) -> Option<Annotation> {
+        self(dependency)
+    }
+}
+
+/// Registry of [`NodeAnnotator`]s, run in registration order.
+#[derive(Clone, Default)]
+pub struct Annotators(Vec<Arc<dyn NodeAnnotator>>);
+
+impl fmt::Debug for Annotators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Annotators").field(&self.0.len()).finish()
+    }
+}
+
+impl Annotators {
+    pub fn register(&mut self, annotator: impl NodeAnnotator + 'static) {
+        self.0.push(Arc::new(annotator));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Merges what every annotator has to say about `dependency`.
+    pub fn annotate(&self, dependency: &Dependency) -> Option<Annotation> {
+        let mut annotation = Annotation::default();
+        for annotator in &self.0 {
+            if let Some(other) = annotator.annotate(dependency) {
+                annotation.merge(other);
+            }
+        }
+        (!annotation.is_empty()).then_some(annotation)
+    }
+
+    /// Annotates every distinct crate of `tree`.
+    pub fn annotate_tree(&self, tree: &DependencyTree) -> FxHashMap<CrateKey, Annotation> {
+        let mut annotations = FxHashMap::default();
+        let mut seen = FxHashSet::default();
+        for id in tree.crate_nodes() {
+            let Some(dependency) = tree.node(id).and_then(|node| node.as_dependency()) else {
+                continue;
+            };
+            let key = dependency.key();
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            if let Some(annotation) = self.annotate(dependency) {
+                annotations.insert(key, annotation);
+            }
+        }
+        annotations
+    }
+}
+
+/// Annotator running an external command once per crate.
+///
+/// The command is split on whitespace and given the crate's name and
+/// version as its last two arguments. The first line it prints becomes the
+/// badge (left out when blank) and later `label: value` lines become detail
+/// fields. Crates for which the command fails are not annotated.
+#[derive(Debug, Clone)]
+pub struct CommandAnnotator {
+    command: String,
+}
+
+impl CommandAnnotator {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+impl NodeAnnotator for CommandAnnotator {
+    fn annotate(&self, dependency: &Dependency) -> Option<Annotation> {
+        let mut parts = self.command.split_whitespace();
+        let output = Command::new(parts.next()?)
+            .args(parts)
+            .arg(&dependency.name)
+            .arg(&dependency.version)
+            .output()
+            .inspect_err(|err| warn!(command = self.command, %err, "annotator failed to run"))
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let annotation = parse_annotation(&String::from_utf8_lossy(&output.stdout));
+        (!annotation.is_empty()).then_some(annotation)
+    }
+}
+
+/// Parses the output of a [`CommandAnnotator`].
+pub fn parse_annotation(output: &str) -> Annotation {
+    let mut lines = output.lines();
+    let badge = lines
+        .next()
+        .map(str::trim)
+        .filter(|badge| !badge.is_empty())
+        .map(str::to_string);
+    let fields = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(label, value)| (label.trim().to_string(), value.trim().to_string()))
+        .filter(|(label, _)| !label.is_empty())
+        .collect();
+    Annotation { badge, fields }
+}
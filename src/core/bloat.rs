@@ -0,0 +1,162 @@
+use std::{fs, path::Path};
+
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+use super::{DependencyNode, DependencyTree, NodeId};
+
+/// Size of a built binary broken down by crate, as reported by
+/// `cargo bloat --message-format json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BloatReport {
+    /// Size of the whole binary in bytes.
+    pub file_size: u64,
+    /// Size of the `.text` section in bytes, which the crate sizes add up to.
+    pub text_size: u64,
+    /// Bytes of code per crate, keyed by the crate name used in symbols
+    /// (with underscores).
+    pub crates: FxHashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawReport {
+    file_size: u64,
+    text_section_size: u64,
+    /// Present with `--crates`.
+    #[serde(default)]
+    crates: Vec<RawCrate>,
+    /// Present without `--crates`.
+    #[serde(default)]
+    functions: Vec<RawFunction>,
+}
+
+#[derive(Deserialize)]
+struct RawCrate {
+    name: String,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct RawFunction {
+    #[serde(rename = "crate")]
+    krate: Option<String>,
+    size: u64,
+}
+
+impl BloatReport {
+    /// Parses the output of `cargo bloat --message-format json`, either per
+    /// crate (`--crates`) or per function, summing functions by crate.
+    pub fn parse(json: &str) -> Result<Self> {
+        let raw: RawReport =
+            serde_json::from_str(json).map_err(|source| Error::InvalidResponse {
+                what: "cargo bloat report",
+                source,
+            })?;
+        let mut crates: FxHashMap<String, u64> = FxHashMap::default();
+        for krate in raw.crates {
+            *crates.entry(krate.name).or_default() += krate.size;
+        }
+        for function in raw.functions {
+            if let Some(krate) = function.krate {
+                *crates.entry(krate).or_default() += function.size;
+            }
+        }
+        Ok(Self {
+            file_size: raw.file_size,
+            text_size: raw.text_section_size,
+            crates,
+        })
+    }
+
+    /// Reads a report saved from `cargo bloat --message-format json`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&json)
+    }
+}
+
+/// Binary size attributed to every crate and to the subtree below it.
+#[derive(Debug, Clone, Default)]
+pub struct BinarySize {
+    /// Bytes of each crate on its own, indexed by node id.
+    pub own: Vec<u64>,
+    /// Bytes of each crate plus everything it transitively depends on,
+    /// indexed by node id.
+    pub subtree: Vec<u64>,
+    /// Size of the `.text` section the shares are relative to.
+    pub total: u64,
+}
+
+impl BinarySize {
+    /// Attributes the sizes in `report` to the crates of `tree`.
+    ///
+    /// Symbols do not carry crate versions, so a crate present in several
+    /// versions has its size split evenly between them.
+    pub fn attribute(tree: &DependencyTree, report: &BloatReport) -> Self {
+        let symbol_name = |idx: usize| match &tree.nodes[idx] {
+            DependencyNode::Crate(dependency) => Some(dependency.name.replace('-', "_")),
+            _ => None,
+        };
+        let mut versions: FxHashMap<String, u64> = FxHashMap::default();
+        for idx in 0..tree.nodes.len() {
+            if let Some(name) = symbol_name(idx) {
+                *versions.entry(name).or_default() += 1;
+            }
+        }
+        let own: Vec<u64> = (0..tree.nodes.len())
+            .map(|idx| {
+                symbol_name(idx)
+                    .and_then(|name| Some(report.crates.get(&name)? / versions[&name]))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let subtree = (0..tree.nodes.len())
+            .map(|idx| {
+                if !tree.nodes[idx].is_crate() {
+                    return 0;
+                }
+                let deps = tree.transitive_dependencies(NodeId(idx));
+                own[idx] + deps.iter().map(|id| own[id.0]).sum::<u64>()
+            })
+            .collect();
+        Self {
+            own,
+            subtree,
+            total: report.text_size,
+        }
+    }
+
+    /// Returns the bytes of code `id` and its dependencies add to the binary.
+    pub fn subtree_size(&self, id: NodeId) -> Option<u64> {
+        self.subtree.get(id.0).copied().filter(|&size| size > 0)
+    }
+
+    /// Returns the share of the `.text` section taken by `id` and its
+    /// dependencies, in percent.
+    pub fn subtree_share(&self, id: NodeId) -> Option<f64> {
+        let size = self.subtree_size(id)?;
+        (self.total > 0).then(|| size as f64 * 100.0 / self.total as f64)
+    }
+}
+
+/// Formats `bytes` with a binary unit, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    env, fs,
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::Result;
+
+use super::dependency::{DependencyTree, LoadTimings, find_manifest};
+
+/// Tree cached for one manifest, valid while the workspace key matches.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    key: u64,
+    tree: T,
+    /// Cargo's warnings from the resolve, shown again on every hit.
+    warnings: Vec<String>,
+}
+
+/// Loads the tree from the on-disk cache when the workspace is unchanged,
+/// resolving it and refreshing the cache otherwise.
+///
+/// Cache failures are never fatal: an unreadable entry is treated as a miss
+/// and a failed write is ignored.
+pub fn load(manifest_path: Option<PathBuf>) -> Result<(DependencyTree, LoadTimings)> {
+    let start = Instant::now();
+    let manifest = find_manifest(manifest_path)?;
+    let Some(file) = cache_file(&manifest) else {
+        return DependencyTree::load_timed(Some(manifest));
+    };
+
+    if let Some((tree, warnings)) = read(&file, &manifest) {
+        debug!(file = %file.display(), "tree cache hit");
+        let timings = LoadTimings {
+            resolve: start.elapsed(),
+            cached: true,
+            warnings,
+            ..LoadTimings::default()
+        };
+        return Ok((tree, timings));
+    }
+
+    debug!(file = %file.display(), "tree cache miss");
+    let (tree, timings) = DependencyTree::load_timed(Some(manifest.clone()))?;
+    if let Some(key) = workspace_key(&manifest, &tree)
+        && let Err(err) = write(&file, key, &tree, &timings.warnings)
+    {
+        warn!(file = %file.display(), %err, "failed to write tree cache");
+    }
+    Ok((tree, timings))
+}
+
+/// Hashes `Cargo.lock`, the root manifest, the manifest of every path crate
+/// in `tree` and the Cargo config files above the workspace, or returns
+/// `None` when there is no lockfile to go by.
+///
+/// Changes outside these files (e.g. `$CARGO_HOME/config.toml` when it is
+/// not above the workspace) are not noticed.
+pub fn workspace_key(manifest: &Path, tree: &DependencyTree) -> Option<u64> {
+    let lockfile = manifest
+        .parent()?
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())?;
+
+    let mut files: Vec<PathBuf> = tree
+        .crate_nodes()
+        .filter_map(|id| tree.node(id)?.as_dependency()?.manifest_dir.as_deref())
+        .map(|dir| Path::new(dir).join("Cargo.toml"))
+        .collect();
+    files.sort();
+    files.dedup();
+    let configs = manifest
+        .parent()?
+        .ancestors()
+        .flat_map(|dir| ["config.toml", "config"].map(|file| dir.join(".cargo").join(file)));
+    files.extend(configs.filter(|path| path.is_file()));
+
+    let mut hasher = FxHasher::default();
+    hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+    for path in [lockfile.as_path(), manifest]
+        .into_iter()
+        .chain(files.iter().map(PathBuf::as_path))
+    {
+        let contents = fs::read(path).unwrap_or_default();
+        hasher.write(path.as_os_str().as_encoded_bytes());
+        hasher.write_usize(contents.len());
+        hasher.write(&contents);
+    }
+    Some(hasher.finish())
+}
+
+/// Returns the cache file of `manifest` under `$XDG_CACHE_HOME`, falling
+/// back to `~/.cache`.
+/// Returns the per-user cache directory of cargo-tree-tui, under
+/// `$XDG_CACHE_HOME` or `~/.cache`.
+pub fn cache_root() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".cache")))?;
+    Some(dir.join("cargo-tree-tui"))
+}
+
+fn cache_file(manifest: &Path) -> Option<PathBuf> {
+    let mut hasher = FxHasher::default();
+    hasher.write(manifest.as_os_str().as_encoded_bytes());
+    Some(cache_root()?.join(format!("{:016x}.json", hasher.finish())))
+}
+
+fn read(file: &Path, manifest: &Path) -> Option<(DependencyTree, Vec<String>)> {
+    let entry: CacheEntry<DependencyTree> = serde_json::from_slice(&fs::read(file).ok()?).ok()?;
+    let valid = entry.tree.validate().is_ok();
+    (valid && workspace_key(manifest, &entry.tree) == Some(entry.key))
+        .then_some((entry.tree, entry.warnings))
+}
+
+fn write(file: &Path, key: u64, tree: &DependencyTree, warnings: &[String]) -> io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write then rename so a concurrent reader never sees a partial entry.
+    let tmp = file.with_extension("json.tmp");
+    fs::write(
+        &tmp,
+        serde_json::to_vec(&CacheEntry {
+            key,
+            tree,
+            warnings: warnings.to_vec(),
+        })?,
+    )?;
+    fs::rename(&tmp, file)?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::{fs, path::PathBuf};
+
+use crate::{Error, Result};
+
+use super::{Dependency, http};
+
+/// File names probed for release notes, in order of preference.
+pub const CHANGELOG_FILES: &[&str] = &[
+    "CHANGELOG.md",
+    "CHANGES.md",
+    "RELEASES.md",
+    "HISTORY.md",
+    "NEWS.md",
+    "CHANGELOG",
+];
+
+/// Where a [`Changelog`] was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangelogSource {
+    /// A file in the crate sources on disk.
+    Local(PathBuf),
+    /// A file fetched from the crate repository.
+    Remote(String),
+}
+
+impl ChangelogSource {
+    /// Short description of the source for display.
+    pub fn describe(&self) -> String {
+        match self {
+            ChangelogSource::Local(path) => path.display().to_string(),
+            ChangelogSource::Remote(url) => url.clone(),
+        }
+    }
+}
+
+/// Release notes of a crate.
+#[derive(Debug, Clone)]
+pub struct Changelog {
+    pub source: ChangelogSource,
+    pub text: String,
+}
+
+impl Changelog {
+    /// Loads the release notes of `dependency`.
+    ///
+    /// The crate sources on disk are tried first so this works offline; the
+    /// repository is only fetched when none of [`CHANGELOG_FILES`] is there.
+    pub fn load(dependency: &Dependency) -> Result<Self> {
+        if let Some(changelog) = Self::load_local(dependency) {
+            return Ok(changelog);
+        }
+
+        let Some(repository) = &dependency.repository else {
+            return Err(Error::NotFound(format!(
+                "no changelog in the sources of {} and no repository declared",
+                dependency.name
+            )));
+        };
+        let urls = raw_urls(repository);
+        if urls.is_empty() {
+            return Err(Error::NotFound(format!(
+                "no changelog in the sources and unsupported repository {repository}"
+            )));
+        }
+        for url in urls {
+            if let Some(text) = http::get(&url)? {
+                return Ok(Self {
+                    source: ChangelogSource::Remote(url),
+                    text,
+                });
+            }
+        }
+        Err(Error::NotFound(format!(
+            "no changelog found in the sources or at {repository}"
+        )))
+    }
+
+    fn load_local(dependency: &Dependency) -> Option<Self> {
+        dependency.source_dirs().into_iter().find_map(|dir| {
+            CHANGELOG_FILES.iter().find_map(|file| {
+                let path = dir.join(file);
+                let text = fs::read_to_string(&path).ok()?;
+                Some(Self {
+                    source: ChangelogSource::Local(path),
+                    text,
+                })
+            })
+        })
+    }
+
+    /// Returns the 0-based line of the first heading mentioning `version`.
+    pub fn version_line(&self, version: &str) -> Option<usize> {
+        self.text.lines().position(|line| {
+            let line = line.trim_start();
+            line.starts_with('#')
+                && line
+                    .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+                    .any(|word| word.trim_start_matches('v') == version)
+        })
+    }
+}
+
+/// Splits a repository URL into its host, owner and repository name.
+fn split_repository(repository: &str) -> Option<(&str, &str, &str)> {
+    let repository = repository.trim().trim_end_matches('/');
+    let rest = repository
+        .strip_prefix("https://")
+        .or_else(|| repository.strip_prefix("http://"))?;
+    let mut parts = rest.split('/');
+    let (host, owner, repo) = (parts.next()?, parts.next()?, parts.next()?);
+    Some((host, owner, repo.trim_end_matches(".git")))
+}
+
+/// Returns the owner and name of a GitHub repository URL.
+pub fn github_repo(repository: &str) -> Option<(&str, &str)> {
+    match split_repository(repository)? {
+        ("github.com" | "www.github.com", owner, repo) => Some((owner, repo)),
+        _ => None,
+    }
+}
+
+/// Returns the raw file URLs to try for a repository URL.
+///
+/// Only GitHub and GitLab are supported; the default branch is used since
+/// tags are not named consistently across crates.
+pub fn raw_urls(repository: &str) -> Vec<String> {
+    let base = match split_repository(repository) {
+        Some(("github.com" | "www.github.com", owner, repo)) => {
+            format!("https://raw.githubusercontent.com/{owner}/{repo}/HEAD")
+        }
+        Some(("gitlab.com", owner, repo)) => {
+            format!("https://gitlab.com/{owner}/{repo}/-/raw/HEAD")
+        }
+        _ => return Vec::new(),
+    };
+    CHANGELOG_FILES
+        .iter()
+        .map(|file| format!("{base}/{file}"))
+        .collect()
+}
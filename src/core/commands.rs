@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use crate::{Error, Result};
+
+use super::{Dependency, manifest::tree_tui_metadata};
+
+/// Shell command bound to a key, run for the selected crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeCommand {
+    pub key: char,
+    /// Command line with `{name}`, `{version}` and `{manifest_dir}`
+    /// placeholders.
+    pub template: String,
+}
+
+impl NodeCommand {
+    /// Returns the program and arguments to run for `dependency`.
+    ///
+    /// The template is split into words like a POSIX shell would, honoring
+    /// single and double quotes and backslash escapes, before the
+    /// placeholders are filled in, so values containing spaces stay a single
+    /// argument. `{manifest_dir}` is the crate's source directory, empty
+    /// when it is not on disk.
+    pub fn expand(&self, dependency: &Dependency) -> Vec<String> {
+        let manifest_dir = dependency
+            .source_dirs()
+            .into_iter()
+            .find(|dir| dir.is_dir())
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        split_words(&self.template)
+            .0
+            .into_iter()
+            .map(|word| {
+                word.replace("{name}", &dependency.name)
+                    .replace("{version}", &dependency.version)
+                    .replace("{manifest_dir}", &manifest_dir)
+            })
+            .collect()
+    }
+}
+
+/// Splits `line` into words like a POSIX shell, without expanding anything,
+/// and returns whether every quote was closed.
+///
+/// An unclosed quote runs to the end of the line.
+fn split_words(line: &str) -> (Vec<String>, bool) {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut closed = true;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => {
+                            closed = false;
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => word.extend(['\\', c]),
+                            None => word.push('\\'),
+                        },
+                        Some(c) => word.push(c),
+                        None => {
+                            closed = false;
+                            break;
+                        }
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_default().extend(chars.next()),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    (words, closed)
+}
+
+/// Commands bound to keys, run with `!<key>` in the tree.
+///
+/// Read from the `commands` table of `[workspace.metadata.tree-tui]` (or
+/// `[package.metadata.tree-tui]` in a single-crate project):
+///
+/// ```toml
+/// [workspace.metadata.tree-tui.commands]
+/// a = "cargo audit -p {name}"
+/// o = "xdg-open {manifest_dir}"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeCommands(Vec<NodeCommand>);
+
+impl NodeCommands {
+    /// Loads the commands of the workspace `manifest` belongs to.
+    pub fn load(manifest: &Path) -> Result<Self> {
+        let Some(commands) = tree_tui_metadata(manifest, "commands")? else {
+            return Ok(Self::default());
+        };
+        let invalid = |message: String| Error::InvalidConfig(format!("commands: {message}"));
+        let commands = commands
+            .as_table()
+            .ok_or_else(|| invalid("expected a table of `key = \"command\"`".to_string()))?;
+        commands
+            .iter()
+            .map(|(key, template)| {
+                let mut chars = key.chars();
+                let (Some(key), None) = (chars.next(), chars.next()) else {
+                    return Err(invalid(format!("`{key}` is not a single key")));
+                };
+                let template = template
+                    .as_str()
+                    .filter(|template| !template.trim().is_empty())
+                    .ok_or_else(|| invalid(format!("`{key}` expects a command line")))?;
+                if !split_words(template).1 {
+                    return Err(invalid(format!("`{key}` has an unclosed quote")));
+                }
+                Ok(NodeCommand {
+                    key,
+                    template: template.to_string(),
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the configured commands.
+    pub fn iter(&self) -> impl Iterator<Item = &NodeCommand> {
+        self.0.iter()
+    }
+
+    /// Returns the command bound to `key`.
+    pub fn get(&self, key: char) -> Option<&NodeCommand> {
+        self.0.iter().find(|command| command.key == key)
+    }
+}
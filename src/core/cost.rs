@@ -0,0 +1,133 @@
+use std::{fs, path::Path};
+
+use super::{Dependency, DependencyNode, DependencyTree, NodeId, dependency::TargetKind};
+
+/// Points per compile unit (library, build script, binary).
+pub const UNIT_COST: u64 = 20;
+/// Source bytes worth one point.
+pub const BYTES_PER_POINT: u64 = 1024;
+
+/// Heuristic build cost of every crate and of the subtree below it.
+///
+/// Without timings data this only ranks crates: the score counts compile
+/// units and source size, doubled for proc-macros since they are built for
+/// the host and hold up every crate using them.
+#[derive(Debug, Clone, Default)]
+pub struct BuildCost {
+    /// Score of each crate on its own, indexed by node id.
+    pub own: Vec<u64>,
+    /// Score of each crate plus everything it transitively depends on,
+    /// indexed by node id.
+    pub subtree: Vec<u64>,
+    /// Score of the whole graph.
+    pub total: u64,
+}
+
+impl BuildCost {
+    /// Estimates the cost of every crate in `tree`, reading the size of its
+    /// sources from disk where they are available.
+    pub fn estimate(tree: &DependencyTree) -> Self {
+        let sizes = tree
+            .nodes
+            .iter()
+            .map(|node| {
+                node.as_dependency()
+                    .and_then(|dependency| dependency.source_dirs().into_iter().next())
+                    .map_or(0, |dir| source_bytes(&dir))
+            })
+            .collect::<Vec<_>>();
+        Self::from_sizes(tree, &sizes)
+    }
+
+    /// Computes the scores from the source size of every node in bytes.
+    pub fn from_sizes(tree: &DependencyTree, sizes: &[u64]) -> Self {
+        let own: Vec<u64> = tree
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| match node {
+                DependencyNode::Crate(dependency) => crate_cost(
+                    dependency,
+                    sizes.get(idx).copied().unwrap_or_default(),
+                    tree.is_workspace_member(NodeId(idx)),
+                ),
+                _ => 0,
+            })
+            .collect();
+        let subtree = (0..tree.nodes.len())
+            .map(|idx| {
+                if own[idx] == 0 {
+                    return 0;
+                }
+                let deps = tree.transitive_dependencies(NodeId(idx));
+                own[idx] + deps.iter().map(|id| own[id.0]).sum::<u64>()
+            })
+            .collect();
+        Self {
+            total: own.iter().sum(),
+            own,
+            subtree,
+        }
+    }
+
+    /// Returns the share of the whole graph's score spent building `id` and
+    /// its dependencies, in percent.
+    pub fn subtree_share(&self, id: NodeId) -> Option<f64> {
+        let cost = *self.subtree.get(id.0)?;
+        (cost > 0 && self.total > 0).then(|| cost as f64 * 100.0 / self.total as f64)
+    }
+}
+
+/// Scores one crate from its targets and source size.
+///
+/// Binaries, tests and examples only count for workspace members, since
+/// Cargo never builds them for dependencies.
+pub fn crate_cost(dependency: &Dependency, source_bytes: u64, is_member: bool) -> u64 {
+    let units = dependency
+        .targets
+        .iter()
+        .map(|target| match target.kind {
+            TargetKind::Lib => 1,
+            // Compiled, then run before the library is built.
+            TargetKind::BuildScript => 2,
+            _ if is_member => 1,
+            _ => 0,
+        })
+        .sum::<u64>()
+        .max(1);
+    let cost = units * UNIT_COST + source_bytes / BYTES_PER_POINT;
+    if dependency.is_proc_macro {
+        cost * 2
+    } else {
+        cost
+    }
+}
+
+/// Sums the size of the `.rs` files below `dir`, skipping `target` and
+/// hidden directories as well as nested packages.
+fn source_bytes(dir: &Path) -> u64 {
+    let mut total = 0;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if file_type.is_dir() {
+                if name != "target" && !name.starts_with('.') && !path.join("Cargo.toml").is_file()
+                {
+                    stack.push(path);
+                }
+            } else if name.ends_with(".rs") {
+                total += entry.metadata().map_or(0, |metadata| metadata.len());
+            }
+        }
+    }
+    total
+}
@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+use super::http;
+
+const API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// Registry metadata of a crate from the crates.io API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateInfo {
+    /// Logins of the users and teams owning the crate.
+    pub owners: Vec<String>,
+    /// Publish date (`YYYY-MM-DD`) of the resolved version.
+    pub published: Option<String>,
+    /// Publish date (`YYYY-MM-DD`) of the newest release.
+    pub last_release: Option<String>,
+    /// Repository URL registered on crates.io.
+    pub repository: Option<String>,
+    /// All-time downloads of the crate.
+    pub downloads: u64,
+    /// Downloads of the crate in the last 90 days.
+    pub recent_downloads: Option<u64>,
+    /// Downloads of the resolved version.
+    pub version_downloads: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: CrateData,
+    #[serde(default)]
+    versions: Vec<VersionData>,
+}
+
+#[derive(Deserialize)]
+struct CrateData {
+    downloads: u64,
+    recent_downloads: Option<u64>,
+    repository: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VersionData {
+    num: String,
+    created_at: String,
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+struct OwnersResponse {
+    users: Vec<OwnerData>,
+}
+
+#[derive(Deserialize)]
+struct OwnerData {
+    login: String,
+}
+
+impl CrateInfo {
+    /// Queries crates.io for `name`, picking out the details of `version`.
+    pub fn fetch(name: &str, version: &str) -> Result<Self> {
+        let crate_json = http::get(&format!("{API_URL}/{name}"))?
+            .ok_or_else(|| Error::NotFound(format!("{name} is not published on crates.io")))?;
+        let owners_json = http::get(&format!("{API_URL}/{name}/owners"))?.unwrap_or_default();
+        Self::from_responses(&crate_json, &owners_json, version)
+    }
+
+    /// Builds the info from the bodies of the crate and owners endpoints.
+    ///
+    /// An empty owners body is treated as a crate without owners.
+    pub fn from_responses(crate_json: &str, owners_json: &str, version: &str) -> Result<Self> {
+        let response: CrateResponse =
+            serde_json::from_str(crate_json).map_err(|source| Error::InvalidResponse {
+                what: "crates.io response",
+                source,
+            })?;
+        let owners = if owners_json.is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str::<OwnersResponse>(owners_json)
+                .map_err(|source| Error::InvalidResponse {
+                    what: "crates.io owners response",
+                    source,
+                })?
+                .users
+                .into_iter()
+                .map(|owner| owner.login)
+                .collect()
+        };
+
+        let resolved = response.versions.iter().find(|v| v.num == version);
+        let last_release = response.versions.iter().map(|v| &v.created_at).max();
+        Ok(Self {
+            owners,
+            published: resolved.map(|v| date(&v.created_at).to_string()),
+            last_release: last_release.map(|created_at| date(created_at).to_string()),
+            repository: response.krate.repository,
+            downloads: response.krate.downloads,
+            recent_downloads: response.krate.recent_downloads,
+            version_downloads: resolved.map(|v| v.downloads),
+        })
+    }
+}
+
+/// Returns the date part of an RFC 3339 timestamp.
+fn date(timestamp: &str) -> &str {
+    timestamp.split('T').next().unwrap_or(timestamp)
+}
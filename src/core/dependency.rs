@@ -1,30 +1,55 @@
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    env, fs,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    iter,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use anyhow::{Context, Result};
 use cargo::{
     GlobalContext,
     core::{
-        Package, PackageId, Workspace,
+        FeatureMap, FeatureValue, Package, PackageId, Shell, SourceId, Target, Workspace,
         compiler::{CompileKind, CompileKindFallback, RustcTargetData},
         dependency::DepKind,
         resolver::features::{CliFeatures, ForceAllTargets, HasDevUnits},
     },
     ops,
-    util::important_paths::find_root_manifest_for_wd,
+    util::{
+        context::homedir, important_paths::find_root_manifest_for_wd, interning::InternedString,
+    },
 };
 use cargo_util::paths::normalize_path;
-use clap_cargo::style::{DEP_BUILD, DEP_DEV, DEP_NORMAL};
-use ratatui::style::Style;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use semver::{Op, VersionReq};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::{Error, Result};
+
+use super::{
+    registry::{Registry, RegistryNames, is_outdated, newest_matching, published_in_index},
+    trust::days_since_epoch,
+};
 
 /// Identifier for a node within the dependency tree arena.
 ///
 /// The `usize` represents the index into the arena vector.
 /// This is used for efficient storage and traversal of the tree structure.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NodeId(pub usize);
 
+/// Identity of a node that survives rebuilding the tree, unlike [`NodeId`],
+/// which shifts whenever crates are added or removed.
+///
+/// See [`DependencyTree::stable_keys`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StableKey(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DependencyType {
     Normal,
     Dev,
@@ -40,11 +65,98 @@ impl DependencyType {
         }
     }
 
-    pub fn style(&self) -> Style {
+    /// Returns the `cargo add`/`cargo remove` flag selecting this section.
+    pub fn cargo_flag(&self) -> Option<&'static str> {
+        match self {
+            Self::Normal => None,
+            Self::Dev => Some("--dev"),
+            Self::Build => Some("--build"),
+        }
+    }
+}
+
+/// Where a crate's source code comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourceKind {
+    /// Local path (workspace members and `path = "..."` dependencies).
+    Path,
+    /// Git repository.
+    Git,
+    /// Package registry (crates.io or an alternate registry).
+    Registry,
+}
+
+impl SourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Git => "git",
+            Self::Registry => "registry",
+        }
+    }
+}
+
+/// Kind of a Cargo build target, in the order targets are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+    BuildScript,
+}
+
+impl TargetKind {
+    pub fn label(&self) -> &'static str {
         match self {
-            Self::Normal => DEP_NORMAL.into(),
-            Self::Dev => DEP_DEV.into(),
-            Self::Build => DEP_BUILD.into(),
+            Self::Lib => "lib",
+            Self::Bin => "bin",
+            Self::Example => "example",
+            Self::Test => "test",
+            Self::Bench => "bench",
+            Self::BuildScript => "build-script",
+        }
+    }
+}
+
+/// Build target of a crate, e.g. its library or one of its binaries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateTarget {
+    pub kind: TargetKind,
+    pub name: String,
+}
+
+impl From<&Target> for CrateTarget {
+    fn from(target: &Target) -> Self {
+        let kind = if target.is_lib() {
+            TargetKind::Lib
+        } else if target.is_bin() {
+            TargetKind::Bin
+        } else if target.is_example() {
+            TargetKind::Example
+        } else if target.is_bench() {
+            TargetKind::Bench
+        } else if target.is_custom_build() {
+            TargetKind::BuildScript
+        } else {
+            TargetKind::Test
+        };
+        Self {
+            kind,
+            name: target.name().to_owned(),
+        }
+    }
+}
+
+impl From<SourceId> for SourceKind {
+    fn from(value: SourceId) -> Self {
+        if value.is_path() {
+            Self::Path
+        } else if value.is_git() {
+            Self::Git
+        } else {
+            Self::Registry
         }
     }
 }
@@ -59,10 +171,13 @@ impl From<DepKind> for DependencyType {
     }
 }
 
+/// Crate name and version, identifying a crate across tree loads.
+pub type CrateKey = (String, String);
+
 /// Flat representation of a dependency node in the deduplicated tree.
 ///
 /// See [`DependencyTree`] for the full tree structure.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dependency {
     /// Crate name.
     pub name: String,
@@ -72,10 +187,176 @@ pub struct Dependency {
     pub manifest_dir: Option<String>,
     /// Whether this crate exposes a proc-macro target.
     pub is_proc_macro: bool,
+    /// Kind of source the crate was resolved from.
+    pub source: SourceKind,
+    /// Registry the crate was published to, `None` for path and git crates.
+    ///
+    /// Boxed to keep [`DependencyNode`] variants close in size.
+    pub registry: Option<Box<Registry>>,
+    /// SPDX license expression from the manifest, if declared.
+    pub license: Option<String>,
+    /// Repository URL from the manifest, if declared.
+    pub repository: Option<String>,
+    /// Build targets of the package, ordered by kind then name.
+    pub targets: Vec<CrateTarget>,
+    /// Features enabled on the crate across the whole graph, sorted by name.
+    /// Leaves out `default` and features already enabled by another one.
+    pub features: Vec<String>,
+    /// Whether the `default` feature is enabled, or the crate declares none.
+    pub default_features: bool,
+    /// Whether the crate declares a non-empty `default` feature.
+    pub has_default_features: bool,
+    /// Features the crate asks for on dependencies it does not use with
+    /// plain default features.
+    ///
+    /// A boxed slice to keep [`DependencyNode`] variants close in size.
+    pub feature_requests: Box<[FeatureRequest]>,
+    /// Version requirements declared on registry dependencies (only for
+    /// workspace members).
+    pub declared: Vec<DeclaredDependency>,
     /// Children represented as node indices for downward traversal.
     pub children: Vec<NodeId>,
 }
 
+/// Features a crate asks for on one of its dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureRequest {
+    /// Package name, even when the dependency is renamed.
+    pub name: String,
+    pub default_features: bool,
+    /// Features enabled in the dependency declaration or through the
+    /// crate's own enabled features (`dep/feature`), sorted by name.
+    pub features: Vec<String>,
+}
+
+/// Dependents of a crate pulling its features in opposite directions, e.g.
+/// one opting out of default features for `no_std` while another enables
+/// `std`.
+///
+/// Cargo unifies features across the graph, so the crate gets the union
+/// and the opt-out has no effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureConflict {
+    /// Dependents opting out of default features.
+    pub opted_out: Vec<NodeId>,
+    /// Dependents enabling default features or `std`.
+    pub enabling: Vec<NodeId>,
+}
+
+/// Version requirement a manifest declares on a dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    /// Package name, even when the dependency is renamed.
+    pub name: String,
+    pub kind: DependencyType,
+    /// Requirement as Cargo normalizes it, e.g. `^1.0`.
+    pub requirement: String,
+    /// Whether the manifest inherits it from `[workspace.dependencies]`
+    /// with `workspace = true`.
+    #[serde(default)]
+    pub inherited: bool,
+    /// Key the manifest declares it under when renamed, e.g. `alias` in
+    /// `alias = { package = "name" }`.
+    #[serde(default)]
+    pub rename: Option<String>,
+}
+
+/// Requirement that is risky to publish with, flagged by the lint layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequirementLint {
+    /// `*`, accepting any version.
+    Wildcard,
+    /// Only lower bounds, e.g. `>=1.0`, accepting future breaking releases.
+    Unbounded,
+    /// Pins a pre-release, e.g. `^1.0.0-alpha.1`.
+    PreRelease,
+}
+
+impl RequirementLint {
+    /// Returns the lint a requirement triggers, if any.
+    pub fn of(requirement: &str) -> Option<Self> {
+        let requirement = VersionReq::parse(requirement).ok()?;
+        let comparators = &requirement.comparators;
+        if comparators.is_empty() {
+            Some(Self::Wildcard)
+        } else if comparators
+            .iter()
+            .any(|comparator| !comparator.pre.is_empty())
+        {
+            Some(Self::PreRelease)
+        } else if comparators
+            .iter()
+            .all(|comparator| matches!(comparator.op, Op::Greater | Op::GreaterEq))
+        {
+            Some(Self::Unbounded)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Wildcard => "wildcard requirement",
+            Self::Unbounded => "no upper bound",
+            Self::PreRelease => "pre-release requirement",
+        }
+    }
+}
+
+impl Dependency {
+    /// Returns the name and version identifying this crate.
+    pub fn key(&self) -> CrateKey {
+        (self.name.clone(), self.version.clone())
+    }
+
+    /// Returns the requirement this crate declares on its `kind` dependency
+    /// `name`.
+    pub fn requirement_on(&self, name: &str, kind: DependencyType) -> Option<&str> {
+        self.declaration_on(name, kind)
+            .map(|declared| declared.requirement.as_str())
+    }
+
+    /// Returns how this crate declares its `kind` dependency `name`.
+    pub fn declaration_on(&self, name: &str, kind: DependencyType) -> Option<&DeclaredDependency> {
+        self.declared
+            .iter()
+            .find(|declared| declared.name == name && declared.kind == kind)
+    }
+
+    /// Returns whether the crate matches a package spec, `name` or
+    /// `name@version`.
+    pub fn matches_spec(&self, spec: &str) -> bool {
+        match spec.split_once('@') {
+            Some((name, version)) => self.name == name && self.version == version,
+            None => self.name == spec,
+        }
+    }
+
+    /// Returns the directories that may hold the sources of this crate.
+    ///
+    /// Workspace members live in their manifest directory; registry crates
+    /// are looked up in the extracted sources under `$CARGO_HOME`.
+    pub fn source_dirs(&self) -> Vec<PathBuf> {
+        if let Some(dir) = &self.manifest_dir {
+            return vec![PathBuf::from(dir)];
+        }
+        if self.source != SourceKind::Registry {
+            return Vec::new();
+        }
+
+        let Some(cargo_home) = cargo_home() else {
+            return Vec::new();
+        };
+        let package = format!("{}-{}", self.name, self.version);
+        fs::read_dir(cargo_home.join("registry").join("src"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path().join(&package)))
+            .filter(|dir| dir.is_dir())
+            .collect()
+    }
+}
+
 impl From<&PackageSnapshot> for Dependency {
     fn from(snapshot: &PackageSnapshot) -> Self {
         Dependency {
@@ -83,13 +364,23 @@ impl From<&PackageSnapshot> for Dependency {
             version: snapshot.version.clone(),
             manifest_dir: snapshot.manifest_dir.clone(),
             is_proc_macro: snapshot.is_proc_macro,
+            source: snapshot.source,
+            registry: snapshot.registry.clone().map(Box::new),
+            license: snapshot.license.clone(),
+            repository: snapshot.repository.clone(),
+            targets: snapshot.targets.clone(),
+            features: snapshot.features.clone(),
+            default_features: snapshot.default_features,
+            has_default_features: snapshot.has_default_features,
+            feature_requests: snapshot.feature_requests.clone().into_boxed_slice(),
+            declared: snapshot.declared.clone(),
             children: Vec::new(), // filled in by wire_edges
         }
     }
 }
 
 /// Dependency group node (e.g. `[dev-dependencies]`) within the deduplicated tree.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyGroup {
     /// Group kind in Cargo metadata.
     pub kind: DependencyType,
@@ -103,11 +394,25 @@ impl DependencyGroup {
     }
 }
 
+/// Directory holding workspace members, see
+/// [`DependencyTree::group_members_by_dir`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyFolder {
+    /// Path relative to the parent folder or the workspace root, with a
+    /// trailing slash, e.g. `crates/`.
+    pub path: String,
+    /// Member crates and subfolders, subfolders first.
+    pub children: Vec<NodeId>,
+}
+
 /// Unified dependency node type for the deduplicated tree arena.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DependencyNode {
     Crate(Dependency),
     Group(DependencyGroup),
+    /// Build target of the parent crate, see [`DependencyTree::add_target_nodes`].
+    Target(CrateTarget),
+    Folder(DependencyFolder),
 }
 
 impl DependencyNode {
@@ -115,6 +420,8 @@ impl DependencyNode {
         match self {
             Self::Crate(node) => &node.children,
             Self::Group(node) => &node.children,
+            Self::Target(_) => &[],
+            Self::Folder(folder) => &folder.children,
         }
     }
 
@@ -122,10 +429,16 @@ impl DependencyNode {
         matches!(self, Self::Group(_))
     }
 
+    pub fn is_crate(&self) -> bool {
+        matches!(self, Self::Crate(_))
+    }
+
     pub fn display_name(&self) -> &str {
         match self {
             Self::Crate(node) => node.name.as_str(),
             Self::Group(node) => node.label(),
+            Self::Target(target) => target.name.as_str(),
+            Self::Folder(folder) => folder.path.as_str(),
         }
     }
 
@@ -142,6 +455,20 @@ impl DependencyNode {
             _ => None,
         }
     }
+
+    pub fn as_target(&self) -> Option<&CrateTarget> {
+        match self {
+            Self::Target(target) => Some(target),
+            _ => None,
+        }
+    }
+
+    pub fn as_folder(&self) -> Option<&DependencyFolder> {
+        match self {
+            Self::Folder(folder) => Some(folder),
+            _ => None,
+        }
+    }
 }
 
 /// Deduplicated dependency tree: one arena node per unique package.
@@ -168,7 +495,7 @@ impl DependencyNode {
 ///   1 -> [0]
 ///   2 -> [0]
 ///   3 -> [1, 2]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyTree {
     /// Name of the root package (or workspace placeholder when missing).
     pub workspace_name: String,
@@ -180,21 +507,104 @@ pub struct DependencyTree {
     pub roots: Vec<NodeId>,
 }
 
+/// Time spent in each phase of [`DependencyTree::load_timed`], along with
+/// what Cargo warned about.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadTimings {
+    /// Loading the workspace and running Cargo's resolver.
+    pub resolve: Duration,
+    /// Snapshotting packages into arena nodes.
+    pub collect: Duration,
+    /// Deduplicating, classifying and wiring dependency edges.
+    pub wire: Duration,
+    /// Whether the tree was read from the on-disk cache, in which case
+    /// `resolve` is the time spent reading it.
+    pub cached: bool,
+    /// Warnings Cargo printed while resolving, e.g. about an unused patch
+    /// or a yanked version, without the `warning: ` prefix.
+    pub warnings: Vec<String>,
+}
+
 impl DependencyTree {
     /// Resolves the Cargo workspace via the `cargo` library and converts the
     /// resolved graph into a [`DependencyTree`].
     pub fn load(manifest_path: Option<PathBuf>) -> Result<Self> {
-        let resolved = ResolvedWorkspace::load(manifest_path)?;
+        Self::load_timed(manifest_path).map(|(tree, _)| tree)
+    }
+
+    /// Resolves the workspace picking the lowest version every requirement
+    /// allows, like `cargo update -Z minimal-versions`, to check that the
+    /// declared lower bounds hold up.
+    ///
+    /// `Cargo.lock` is neither read nor written.
+    pub fn load_minimal_versions(manifest_path: Option<PathBuf>) -> Result<Self> {
+        Self::load_with(manifest_path, ResolveMode::MinimalVersions).map(|(tree, _)| tree)
+    }
+
+    /// Resolves the workspace as if `cargo update -p <spec> --precise
+    /// <version>` had been run, to preview what upgrading one crate drags
+    /// along.
+    ///
+    /// The update is applied to a copy of `Cargo.lock` in a temporary
+    /// directory; the workspace's own lockfile is left untouched.
+    pub fn load_upgraded(
+        manifest_path: Option<PathBuf>,
+        spec: &str,
+        version: &str,
+    ) -> Result<Self> {
+        Self::load_with(manifest_path, ResolveMode::Upgraded { spec, version })
+            .map(|(tree, _)| tree)
+    }
+
+    /// Same as [`DependencyTree::load`], also reporting how long each phase took.
+    pub fn load_timed(manifest_path: Option<PathBuf>) -> Result<(Self, LoadTimings)> {
+        Self::load_with(manifest_path, ResolveMode::Locked)
+    }
+
+    #[instrument(skip_all)]
+    fn load_with(
+        manifest_path: Option<PathBuf>,
+        mode: ResolveMode<'_>,
+    ) -> Result<(Self, LoadTimings)> {
+        let start = Instant::now();
+        let resolved = ResolvedWorkspace::load(manifest_path, mode)?;
+        let resolve = start.elapsed();
+
+        let start = Instant::now();
         let workspace_name = resolved.workspace_name.clone();
+        let warnings = resolved.warnings.clone();
         let mut collected = collect_packages(&resolved);
+        let collect = start.elapsed();
+
+        let start = Instant::now();
         let parents = wire_edges(&resolved, &collected.pkg_index, &mut collected.nodes);
+        let wire = start.elapsed();
 
-        Ok(DependencyTree {
+        let tree = DependencyTree {
             workspace_name,
             parents,
             nodes: collected.nodes,
             roots: collected.roots,
-        })
+        };
+        tree.validate()?;
+        info!(
+            nodes = tree.nodes.len(),
+            roots = tree.roots.len(),
+            ?resolve,
+            ?collect,
+            ?wire,
+            "loaded dependency tree"
+        );
+        Ok((
+            tree,
+            LoadTimings {
+                resolve,
+                collect,
+                wire,
+                cached: false,
+                warnings,
+            },
+        ))
     }
 
     /// Returns immutable access to a node identified by `id`.
@@ -207,12 +617,748 @@ impl DependencyTree {
         &self.roots
     }
 
+    /// Returns whether the node is a workspace member: a crate at the top
+    /// level or in a member folder.
+    pub fn is_workspace_member(&self, id: NodeId) -> bool {
+        if !self.node(id).is_some_and(DependencyNode::is_crate) {
+            return false;
+        }
+        self.roots.contains(&id)
+            || self.parents.get(id.0).is_some_and(|parents| {
+                parents.iter().any(|&parent| {
+                    self.node(parent)
+                        .and_then(DependencyNode::as_folder)
+                        .is_some()
+                })
+            })
+    }
+
+    /// Returns the workspace members in display order, looking through
+    /// member folders.
+    pub fn members(&self) -> Vec<NodeId> {
+        let mut members = Vec::new();
+        let mut stack: Vec<NodeId> = self.roots.iter().rev().copied().collect();
+        while let Some(id) = stack.pop() {
+            match self.node(id) {
+                Some(DependencyNode::Folder(folder)) => {
+                    stack.extend(folder.children.iter().rev().copied());
+                }
+                Some(DependencyNode::Crate(_)) => members.push(id),
+                _ => {}
+            }
+        }
+        members
+    }
+
+    /// Returns the distinct crates that directly depend on `id`.
+    ///
+    /// Group nodes are resolved to the crate that owns them, so a crate used
+    /// as both a normal and a dev dependency of the same parent counts once.
+    pub fn dependents(&self, id: NodeId) -> Vec<NodeId> {
+        let mut dependents: Vec<NodeId> = Vec::new();
+        for &parent in self
+            .parents
+            .get(id.0)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+        {
+            let owners = match self.node(parent) {
+                Some(DependencyNode::Group(_)) => self.parents[parent.0].as_slice(),
+                Some(DependencyNode::Folder(_)) => &[],
+                _ => std::slice::from_ref(&parent),
+            };
+            for &owner in owners {
+                if !dependents.contains(&owner) {
+                    dependents.push(owner);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Reorders every crate's children so the most depended-upon crates come
+    /// first. Target nodes stay before and group nodes after the normal
+    /// dependencies.
+    pub fn sort_by_dependents(&mut self) {
+        let counts: Vec<usize> = (0..self.nodes.len())
+            .map(|idx| self.dependents(NodeId(idx)).len())
+            .collect();
+        let rank: Vec<u8> = self
+            .nodes
+            .iter()
+            .map(|node| match node {
+                DependencyNode::Target(_) | DependencyNode::Folder(_) => 0,
+                DependencyNode::Crate(_) => 1,
+                DependencyNode::Group(_) => 2,
+            })
+            .collect();
+
+        for node in &mut self.nodes {
+            let children = match node {
+                DependencyNode::Crate(dependency) => &mut dependency.children,
+                DependencyNode::Group(group) => &mut group.children,
+                DependencyNode::Target(_) | DependencyNode::Folder(_) => continue,
+            };
+            children.sort_by_key(|id| (rank[id.0], std::cmp::Reverse(counts[id.0])));
+        }
+    }
+
+    /// Adds the build targets of every workspace member as leaf nodes in
+    /// front of its dependencies.
+    ///
+    /// Target nodes are not crates: they are skipped by [`Self::crate_nodes`]
+    /// and never counted as dependencies.
+    pub fn add_target_nodes(&mut self) {
+        for root in self.roots.clone() {
+            let Some(DependencyNode::Crate(dependency)) = self.nodes.get(root.0) else {
+                continue;
+            };
+            let targets = dependency.targets.clone();
+            let first = self.nodes.len();
+            let ids: Vec<NodeId> = (first..first + targets.len()).map(NodeId).collect();
+            self.nodes
+                .extend(targets.into_iter().map(DependencyNode::Target));
+            self.parents.resize(self.nodes.len(), vec![root]);
+            if let Some(DependencyNode::Crate(dependency)) = self.nodes.get_mut(root.0) {
+                dependency.children.splice(0..0, ids);
+            }
+        }
+    }
+
+    /// Returns the `(member, dependency, version)` of direct registry
+    /// dependencies whose declared requirement allows a newer version than
+    /// the resolved one.
+    ///
+    /// Versions are looked up in the index copies Cargo cached under
+    /// `cargo_home`, so nothing is fetched.
+    pub fn updates_in_range(&self, cargo_home: &Path) -> Vec<(NodeId, NodeId, String)> {
+        let mut indexes: FxHashMap<&str, Option<String>> = FxHashMap::default();
+        let mut updates = Vec::new();
+        for member in self.members() {
+            let Some(DependencyNode::Crate(owner)) = self.node(member) else {
+                continue;
+            };
+            for (id, kind) in self.direct_dependencies(member) {
+                let Some(DependencyNode::Crate(dependency)) = self.node(id) else {
+                    continue;
+                };
+                let (Some(registry), Some(requirement)) = (
+                    &dependency.registry,
+                    owner.requirement_on(&dependency.name, kind),
+                ) else {
+                    continue;
+                };
+                let index = indexes
+                    .entry(&dependency.name)
+                    .or_insert_with(|| registry.cached_index(cargo_home, &dependency.name));
+                if let Some(newest) = index
+                    .as_deref()
+                    .and_then(|index| newest_matching(index, requirement))
+                    .filter(|newest| is_outdated(&dependency.version, newest))
+                {
+                    updates.push((member, id, newest));
+                }
+            }
+        }
+        updates
+    }
+
+    /// Returns how many days before `today` (in days since the Unix epoch)
+    /// each registry crate's version was published, read from the index
+    /// Cargo cached under `cargo_home`.
+    ///
+    /// Crates whose index entry has no publish time are left out.
+    pub fn release_ages(&self, cargo_home: &Path, today: i64) -> FxHashMap<NodeId, i64> {
+        let mut indexes: FxHashMap<&str, Option<String>> = FxHashMap::default();
+        let mut ages = FxHashMap::default();
+        for id in self.crate_nodes() {
+            let Some(DependencyNode::Crate(dependency)) = self.node(id) else {
+                continue;
+            };
+            let Some(registry) = &dependency.registry else {
+                continue;
+            };
+            let index = indexes
+                .entry(&dependency.name)
+                .or_insert_with(|| registry.cached_index(cargo_home, &dependency.name));
+            if let Some(published) = index
+                .as_deref()
+                .and_then(|index| published_in_index(index, &dependency.version))
+                .and_then(|published| days_since_epoch(&published))
+            {
+                ages.insert(id, (today - published).max(0));
+            }
+        }
+        ages
+    }
+
+    /// Returns the `(member, dependency, lint)` of direct dependencies whose
+    /// declared requirement is `*`, lacks an upper bound or pins a
+    /// pre-release.
+    pub fn requirement_lints(&self) -> Vec<(NodeId, NodeId, RequirementLint)> {
+        let mut lints = Vec::new();
+        for member in self.members() {
+            let Some(DependencyNode::Crate(owner)) = self.node(member) else {
+                continue;
+            };
+            for (id, kind) in self.direct_dependencies(member) {
+                let Some(DependencyNode::Crate(dependency)) = self.node(id) else {
+                    continue;
+                };
+                if let Some(lint) = owner
+                    .requirement_on(&dependency.name, kind)
+                    .and_then(RequirementLint::of)
+                {
+                    lints.push((member, id, lint));
+                }
+            }
+        }
+        lints
+    }
+
+    /// Returns the dependents of `id` whose feature wishes Cargo had to
+    /// unify against each other, if any.
+    ///
+    /// Enabling default features only counts when the crate declares some.
+    pub fn feature_conflict(&self, id: NodeId) -> Option<FeatureConflict> {
+        let DependencyNode::Crate(dependency) = self.node(id)? else {
+            return None;
+        };
+        let mut conflict = FeatureConflict {
+            opted_out: Vec::new(),
+            enabling: Vec::new(),
+        };
+        for parent in self.dependents(id) {
+            let Some(DependencyNode::Crate(owner)) = self.node(parent) else {
+                continue;
+            };
+            let requests: Vec<&FeatureRequest> = owner
+                .feature_requests
+                .iter()
+                .filter(|request| request.name == dependency.name)
+                .collect();
+            let defaults =
+                requests.is_empty() || requests.iter().any(|request| request.default_features);
+            let std = requests
+                .iter()
+                .any(|request| request.features.iter().any(|feature| feature == "std"));
+            if defaults && dependency.has_default_features || std {
+                conflict.enabling.push(parent);
+            } else {
+                conflict.opted_out.push(parent);
+            }
+        }
+        (!conflict.opted_out.is_empty() && !conflict.enabling.is_empty()).then_some(conflict)
+    }
+
+    /// Returns every crate with a [`FeatureConflict`].
+    pub fn feature_conflicts(&self) -> Vec<NodeId> {
+        self.crate_nodes()
+            .filter(|&id| self.feature_conflict(id).is_some())
+            .collect()
+    }
+
+    /// Returns every crate whose name appears in the graph in more than one
+    /// version.
+    pub fn duplicated_crates(&self) -> Vec<NodeId> {
+        let mut versions: FxHashMap<&str, usize> = FxHashMap::default();
+        for id in self.crate_nodes() {
+            if let Some(dependency) = self.node(id).and_then(DependencyNode::as_dependency) {
+                *versions.entry(dependency.name.as_str()).or_default() += 1;
+            }
+        }
+        self.crate_nodes()
+            .filter(|&id| {
+                self.node(id)
+                    .and_then(DependencyNode::as_dependency)
+                    .is_some_and(|dependency| versions[dependency.name.as_str()] > 1)
+            })
+            .collect()
+    }
+
+    /// Returns the direct dependencies of `id` with their kind, looking
+    /// through group nodes.
+    pub(crate) fn direct_dependencies(&self, id: NodeId) -> Vec<(NodeId, DependencyType)> {
+        let Some(node) = self.node(id) else {
+            return Vec::new();
+        };
+        node.children()
+            .iter()
+            .flat_map(|&child| match self.node(child) {
+                Some(DependencyNode::Group(group)) => group
+                    .children
+                    .iter()
+                    .map(|&id| (id, group.kind))
+                    .collect::<Vec<_>>(),
+                _ => vec![(child, DependencyType::Normal)],
+            })
+            .collect()
+    }
+
+    /// Returns the tree with its dependency groups dissolved, so dev and build
+    /// dependencies are listed right below their owner after its normal
+    /// ones, along with the kind of every edge that went through a group.
+    ///
+    /// A crate depended upon both directly and through a group keeps one
+    /// edge, of the kind it is listed with first. Node ids are unchanged: the
+    /// dissolved groups stay in the arena, emptied and unreachable.
+    pub fn without_groups(&self) -> (Self, FxHashMap<(NodeId, NodeId), DependencyType>) {
+        let mut kinds = FxHashMap::default();
+        let mut nodes = self.nodes.clone();
+        for (idx, node) in nodes.iter_mut().enumerate() {
+            let children = match node {
+                DependencyNode::Crate(dependency) => &mut dependency.children,
+                DependencyNode::Folder(folder) => &mut folder.children,
+                DependencyNode::Group(group) => {
+                    group.children.clear();
+                    continue;
+                }
+                DependencyNode::Target(_) => continue,
+            };
+            let mut flat = Vec::with_capacity(children.len());
+            for &child in children.iter() {
+                let Some(DependencyNode::Group(group)) = self.node(child) else {
+                    if !flat.contains(&child) {
+                        flat.push(child);
+                    }
+                    continue;
+                };
+                for &dependency in &group.children {
+                    if !flat.contains(&dependency) {
+                        flat.push(dependency);
+                        kinds.insert((NodeId(idx), dependency), group.kind);
+                    }
+                }
+            }
+            *children = flat;
+        }
+
+        let mut parents = vec![Vec::new(); nodes.len()];
+        for (idx, node) in nodes.iter().enumerate() {
+            for &child in node.children() {
+                parents[child.0].push(NodeId(idx));
+            }
+        }
+        let tree = Self {
+            workspace_name: self.workspace_name.clone(),
+            nodes,
+            parents,
+            roots: self.roots.clone(),
+        };
+        (tree, kinds)
+    }
+
+    /// Returns the subgraph of workspace members and the edges between them,
+    /// dropping every third-party crate.
+    ///
+    /// Groups and target nodes of members are kept; a group left without
+    /// members is dropped. Node ids are renumbered.
+    pub fn workspace_only(&self) -> Self {
+        self.subgraph(&self.roots, |id| match self.node(id) {
+            Some(DependencyNode::Crate(_)) => self.is_workspace_member(id),
+            Some(DependencyNode::Group(group)) => group
+                .children
+                .iter()
+                .any(|&child| self.is_workspace_member(child)),
+            Some(DependencyNode::Target(_) | DependencyNode::Folder(_)) => true,
+            None => false,
+        })
+    }
+
+    /// Returns the part of the graph reachable from `id`, with `id` as the
+    /// only root. Node ids are renumbered.
+    pub fn rooted_at(&self, id: NodeId) -> Self {
+        self.subgraph(&[id], |_| true)
+    }
+
+    /// Returns the part of the graph reachable from the workspace members
+    /// accepted by `keep`, which become the only roots. Node ids are
+    /// renumbered.
+    pub fn members_where(&self, keep: impl Fn(&Dependency) -> bool) -> Self {
+        let roots: Vec<NodeId> = self
+            .members()
+            .into_iter()
+            .filter(|&id| {
+                self.node(id)
+                    .and_then(DependencyNode::as_dependency)
+                    .is_some_and(&keep)
+            })
+            .collect();
+        self.subgraph(&roots, |_| true)
+    }
+
+    /// Nests the workspace members under folder nodes mirroring their
+    /// directories below `root`, e.g. `crates/` and `tools/`.
+    ///
+    /// Members directly in `root` or outside of it stay at the top level,
+    /// ahead of the folders. A folder holding nothing but one subfolder is
+    /// merged into it, giving paths like `tools/xtask/`.
+    pub fn group_members_by_dir(&mut self, root: &Path) {
+        #[derive(Default)]
+        struct Dir {
+            members: Vec<NodeId>,
+            dirs: BTreeMap<String, Dir>,
+        }
+
+        let mut top = Dir::default();
+        for id in self.members() {
+            let relative = self
+                .node(id)
+                .and_then(DependencyNode::as_dependency)
+                .and_then(|dependency| dependency.manifest_dir.as_deref())
+                .and_then(|dir| Path::new(dir).parent()?.strip_prefix(root).ok());
+            let mut dir = &mut top;
+            for component in relative.into_iter().flat_map(Path::iter) {
+                let name = component.to_string_lossy().into_owned();
+                dir = dir.dirs.entry(name).or_default();
+            }
+            dir.members.push(id);
+        }
+        if top.dirs.is_empty() {
+            return;
+        }
+
+        fn emit(tree: &mut DependencyTree, dir: Dir) -> Vec<NodeId> {
+            let mut children = dir.members;
+            for (mut path, mut sub) in dir.dirs {
+                while sub.members.is_empty() && sub.dirs.len() == 1 {
+                    let (name, only) = sub.dirs.pop_first().expect("one subfolder");
+                    path = format!("{path}/{name}");
+                    sub = only;
+                }
+                let folder = DependencyFolder {
+                    path: format!("{path}/"),
+                    children: emit(tree, sub),
+                };
+                let id = NodeId(tree.nodes.len());
+                tree.nodes.push(DependencyNode::Folder(folder));
+                tree.parents.push(Vec::new());
+                children.push(id);
+            }
+            children
+        }
+
+        self.roots = emit(self, top);
+        for parents in &mut self.parents {
+            parents.retain(|&parent| self.nodes[parent.0].as_folder().is_none());
+        }
+        for idx in 0..self.nodes.len() {
+            if let DependencyNode::Folder(folder) = &self.nodes[idx] {
+                for &child in &folder.children {
+                    self.parents[child.0].push(NodeId(idx));
+                }
+            }
+        }
+    }
+
+    /// Copies the nodes reachable from `roots` through children accepted by
+    /// `keep` into a new arena, in depth-first order.
+    fn subgraph(&self, roots: &[NodeId], keep: impl Fn(NodeId) -> bool) -> Self {
+        let mut new_ids: Vec<Option<NodeId>> = vec![None; self.nodes.len()];
+        let mut stack: Vec<NodeId> = roots.iter().rev().copied().collect();
+        let mut order = Vec::new();
+        while let Some(id) = stack.pop() {
+            if new_ids[id.0].is_some() {
+                continue;
+            }
+            new_ids[id.0] = Some(NodeId(order.len()));
+            order.push(id);
+            if let Some(node) = self.node(id) {
+                stack.extend(
+                    node.children()
+                        .iter()
+                        .rev()
+                        .copied()
+                        .filter(|&child| keep(child)),
+                );
+            }
+        }
+
+        let remap = |children: &[NodeId]| -> Vec<NodeId> {
+            children
+                .iter()
+                .filter(|&&child| keep(child))
+                .filter_map(|child| new_ids[child.0])
+                .collect()
+        };
+        let nodes: Vec<DependencyNode> = order
+            .iter()
+            .map(|id| match &self.nodes[id.0] {
+                DependencyNode::Crate(dependency) => DependencyNode::Crate(Dependency {
+                    children: remap(&dependency.children),
+                    ..dependency.clone()
+                }),
+                DependencyNode::Group(group) => DependencyNode::Group(DependencyGroup {
+                    kind: group.kind,
+                    children: remap(&group.children),
+                }),
+                DependencyNode::Target(target) => DependencyNode::Target(target.clone()),
+                DependencyNode::Folder(folder) => DependencyNode::Folder(DependencyFolder {
+                    path: folder.path.clone(),
+                    children: remap(&folder.children),
+                }),
+            })
+            .collect();
+
+        let mut parents = vec![Vec::new(); nodes.len()];
+        for (idx, node) in nodes.iter().enumerate() {
+            for &child in node.children() {
+                parents[child.0].push(NodeId(idx));
+            }
+        }
+        Self {
+            workspace_name: self.workspace_name.clone(),
+            roots: roots.iter().filter_map(|root| new_ids[root.0]).collect(),
+            nodes,
+            parents,
+        }
+    }
+
+    /// Returns the length of the shortest path from a root to each node,
+    /// indexed by node id, `usize::MAX` for nodes no root reaches.
+    pub fn depths(&self) -> Vec<usize> {
+        let mut depths = vec![usize::MAX; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        for &root in &self.roots {
+            depths[root.0] = 0;
+            queue.push_back(root);
+        }
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = self.node(id) else {
+                continue;
+            };
+            for &child in node.children() {
+                if depths[child.0] == usize::MAX {
+                    depths[child.0] = depths[id.0] + 1;
+                    queue.push_back(child);
+                }
+            }
+        }
+        depths
+    }
+
+    /// Returns a node-indexed mask of every node lying on a path from `from` to `to`.
+    ///
+    /// A node is on a path when it is reachable from `from` and `to` is
+    /// reachable from it. Both endpoints are included; the mask is all `false`
+    /// when `to` is not reachable from `from`.
+    pub fn nodes_on_paths(&self, from: NodeId, to: NodeId) -> Vec<bool> {
+        let mut forward = vec![false; self.nodes.len()];
+        let mut stack = vec![from];
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut forward[id.0], true) {
+                continue;
+            }
+            if let Some(node) = self.node(id) {
+                stack.extend_from_slice(node.children());
+            }
+        }
+
+        let mut on_path = vec![false; self.nodes.len()];
+        if !forward[to.0] {
+            return on_path;
+        }
+        let mut stack = vec![to];
+        while let Some(id) = stack.pop() {
+            if !forward[id.0] || std::mem::replace(&mut on_path[id.0], true) {
+                continue;
+            }
+            if id != from {
+                stack.extend_from_slice(&self.parents[id.0]);
+            }
+        }
+        on_path
+    }
+
+    /// Returns the crates transitively reachable from `id`, excluding `id` itself.
+    ///
+    /// Group nodes are walked through but never included in the result.
+    pub fn transitive_dependencies(&self, id: NodeId) -> FxHashSet<NodeId> {
+        let mut reachable = FxHashSet::default();
+        let mut visited = FxHashSet::default();
+        let mut stack = self
+            .node(id)
+            .map(|n| n.children().to_vec())
+            .unwrap_or_default();
+
+        while let Some(node_id) = stack.pop() {
+            if !visited.insert(node_id) {
+                continue;
+            }
+            let Some(node) = self.node(node_id) else {
+                continue;
+            };
+            if node.is_crate() && node_id != id {
+                reachable.insert(node_id);
+            }
+            stack.extend_from_slice(node.children());
+        }
+
+        reachable
+    }
+
+    /// Returns the crates that both `a` and `b` transitively depend on.
+    pub fn common_dependencies(&self, a: NodeId, b: NodeId) -> Vec<NodeId> {
+        let a_deps = self.transitive_dependencies(a);
+        let b_deps = self.transitive_dependencies(b);
+        self.sorted_by_name(a_deps.intersection(&b_deps).copied())
+    }
+
+    /// Returns the crates transitively reachable from `a` but not from `b`.
+    pub fn exclusive_dependencies(&self, a: NodeId, b: NodeId) -> Vec<NodeId> {
+        let a_deps = self.transitive_dependencies(a);
+        let b_deps = self.transitive_dependencies(b);
+        self.sorted_by_name(a_deps.difference(&b_deps).copied())
+    }
+
+    /// Collects node ids ordered by display name, then by arena index.
+    pub(crate) fn sorted_by_name(&self, ids: impl Iterator<Item = NodeId>) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = ids.collect();
+        ids.sort_by(|a, b| {
+            let name = |id: &NodeId| self.node(*id).map(DependencyNode::display_name);
+            name(a).cmp(&name(b)).then(a.0.cmp(&b.0))
+        });
+        ids
+    }
+
+    /// Checks that every edge points into the arena and that `parents`
+    /// mirrors the children lists.
+    pub fn validate(&self) -> Result<()> {
+        let len = self.nodes.len();
+        if self.parents.len() != len {
+            return Err(Error::ResolveMissing(format!(
+                "parent index covers {} of {len} nodes",
+                self.parents.len()
+            )));
+        }
+        if let Some(root) = self.roots.iter().find(|root| root.0 >= len) {
+            return Err(Error::ResolveMissing(format!(
+                "root {} is outside the arena of {len} nodes",
+                root.0
+            )));
+        }
+        for (idx, node) in self.nodes.iter().enumerate() {
+            for &child in node.children() {
+                if child.0 >= len {
+                    return Err(Error::ResolveMissing(format!(
+                        "node {idx} has child {} outside the arena",
+                        child.0
+                    )));
+                }
+                if !self.parents[child.0].contains(&NodeId(idx)) {
+                    return Err(Error::ResolveMissing(format!(
+                        "node {} is missing parent {idx}",
+                        child.0
+                    )));
+                }
+            }
+        }
+        for (idx, parents) in self.parents.iter().enumerate() {
+            for &parent in parents {
+                let has_child = self
+                    .node(parent)
+                    .is_some_and(|node| node.children().contains(&NodeId(idx)));
+                if !has_child {
+                    return Err(Error::ResolveMissing(format!(
+                        "node {idx} lists {} as parent without an edge",
+                        parent.0
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the `(from, to)` edges closing a cycle, i.e. pointing at a node
+    /// still on the depth-first path from a workspace member.
+    ///
+    /// Cargo allows such cycles through dev-dependencies. The walk is
+    /// iterative, so arbitrarily deep graphs cannot overflow the stack.
+    pub fn back_edges(&self) -> Vec<(NodeId, NodeId)> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            OnPath,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.nodes.len()];
+        let mut back_edges = Vec::new();
+        for &root in &self.roots {
+            if marks[root.0] != Mark::New {
+                continue;
+            }
+            marks[root.0] = Mark::OnPath;
+            // Each frame is a node and the index of its next child to visit.
+            let mut stack = vec![(root, 0)];
+            while let Some((id, next)) = stack.last_mut() {
+                let id = *id;
+                let Some(&child) = self.node(id).and_then(|node| node.children().get(*next)) else {
+                    marks[id.0] = Mark::Done;
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                match marks[child.0] {
+                    Mark::New => {
+                        marks[child.0] = Mark::OnPath;
+                        stack.push((child, 0));
+                    }
+                    Mark::OnPath => back_edges.push((id, child)),
+                    Mark::Done => {}
+                }
+            }
+        }
+        back_edges
+    }
+
     /// Returns the crate node ids that can be matched by search.
     pub fn crate_nodes(&self) -> impl Iterator<Item = NodeId> {
         self.nodes
             .iter()
             .enumerate()
-            .filter_map(|(idx, node)| (!node.is_group()).then_some(NodeId(idx)))
+            .filter_map(|(idx, node)| node.is_crate().then_some(NodeId(idx)))
+    }
+
+    /// Returns the [`StableKey`] of every node, indexed by node id.
+    ///
+    /// Crates are keyed by package id, which is unique in the deduplicated
+    /// tree. Groups, targets and folders have no identity of their own and
+    /// hash their label into the key of their first parent, following the
+    /// parent chain up to the nearest crate or top-level folder.
+    pub fn stable_keys(&self) -> Vec<StableKey> {
+        let mut keys = vec![None; self.nodes.len()];
+        (0..self.nodes.len())
+            .map(|idx| self.stable_key(NodeId(idx), &mut keys))
+            .collect()
+    }
+
+    fn stable_key(&self, id: NodeId, keys: &mut [Option<StableKey>]) -> StableKey {
+        if let Some(key) = keys[id.0] {
+            return key;
+        }
+        let node = &self.nodes[id.0];
+        // Crates never chain, so this recurses at most a few levels.
+        let parent = match node {
+            DependencyNode::Crate(_) => None,
+            _ => self.parents[id.0]
+                .first()
+                .map(|&parent| self.stable_key(parent, keys)),
+        };
+        let mut hasher = FxHasher::default();
+        parent.hash(&mut hasher);
+        match node {
+            DependencyNode::Crate(dependency) => {
+                (0u8, &dependency.name, &dependency.version).hash(&mut hasher);
+                (dependency.source, &dependency.registry).hash(&mut hasher);
+            }
+            DependencyNode::Group(group) => (1u8, group.kind).hash(&mut hasher),
+            DependencyNode::Target(target) => (2u8, target.kind, &target.name).hash(&mut hasher),
+            DependencyNode::Folder(folder) => (3u8, &folder.path).hash(&mut hasher),
+        }
+        let key = StableKey(hasher.finish());
+        keys[id.0] = Some(key);
+        key
     }
 }
 
@@ -222,23 +1368,199 @@ pub struct PackageSnapshot {
     version: String,
     manifest_dir: Option<String>,
     is_proc_macro: bool,
+    source: SourceKind,
+    registry: Option<Registry>,
+    license: Option<String>,
+    repository: Option<String>,
+    targets: Vec<CrateTarget>,
+    features: Vec<String>,
+    default_features: bool,
+    has_default_features: bool,
+    feature_requests: Vec<FeatureRequest>,
+    declared: Vec<DeclaredDependency>,
 }
 
 impl PackageSnapshot {
-    fn from_package(package: &Package) -> Self {
+    fn from_package(
+        package: &Package,
+        registries: &RegistryNames,
+        features: &[InternedString],
+    ) -> Self {
+        let source_id = package.package_id().source_id();
+        let mut targets: Vec<CrateTarget> =
+            package.targets().iter().map(CrateTarget::from).collect();
+        targets.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
         let manifest_dir = package
             .package_id()
             .source_id()
             .is_path()
             .then(|| package.root().display().to_string());
+        let feature_map = package.summary().features();
+        let default_features = features.iter().any(|feature| *feature == "default")
+            || !feature_map.contains_key("default");
+        let mut declared: Vec<DeclaredDependency> = if manifest_dir.is_some() {
+            package
+                .dependencies()
+                .iter()
+                .filter(|dep| dep.source_id().is_registry())
+                .map(|dep| DeclaredDependency {
+                    name: dep.package_name().to_string(),
+                    kind: dep.kind().into(),
+                    requirement: dep.version_req().to_string(),
+                    inherited: inherits(package, dep),
+                    rename: dep.explicit_name_in_toml().map(|name| name.to_string()),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        declared.dedup();
 
         Self {
+            feature_requests: feature_requests(package, features),
+            has_default_features: feature_map
+                .get("default")
+                .is_some_and(|default| !default.is_empty()),
             name: package.name().as_str().to_owned(),
             version: package.version().to_string(),
             manifest_dir,
             is_proc_macro: package.proc_macro(),
+            source: SourceKind::from(source_id),
+            registry: Registry::from_source(&source_id.as_url().to_string(), registries),
+            license: package.manifest().metadata().license.clone(),
+            repository: package.manifest().metadata().repository.clone(),
+            targets,
+            features: explicit_features(feature_map, features),
+            default_features,
+            declared,
+        }
+    }
+}
+
+/// Returns whether `package` declares `dep` with `workspace = true`.
+///
+/// Any target table of the dependency's kind counts, since the normalized
+/// dependency no longer tells which one it came from.
+fn inherits(package: &Package, dep: &cargo::core::Dependency) -> bool {
+    let Some(toml) = package.manifest().original_toml() else {
+        return false;
+    };
+    let platforms = toml.target.iter().flatten().map(|(_, platform)| platform);
+    let tables: Vec<_> = match dep.kind() {
+        DepKind::Normal => iter::once(toml.dependencies.as_ref())
+            .chain(platforms.map(|platform| platform.dependencies.as_ref()))
+            .collect(),
+        DepKind::Development => iter::once(toml.dev_dependencies())
+            .chain(platforms.map(|platform| platform.dev_dependencies()))
+            .collect(),
+        DepKind::Build => iter::once(toml.build_dependencies())
+            .chain(platforms.map(|platform| platform.build_dependencies()))
+            .collect(),
+    };
+    tables.into_iter().flatten().any(|table| {
+        table
+            .get(dep.name_in_toml().as_str())
+            .is_some_and(|declaration| declaration.is_inherited())
+    })
+}
+
+/// Returns what `package` asks for on each dependency it does not use with
+/// plain default features, counting `dep/feature` values of its `enabled`
+/// features.
+fn feature_requests(package: &Package, enabled: &[InternedString]) -> Vec<FeatureRequest> {
+    let feature_map = package.summary().features();
+    let mut via_features: FxHashMap<InternedString, Vec<String>> = FxHashMap::default();
+    for value in enabled
+        .iter()
+        .filter_map(|feature| feature_map.get(feature))
+        .flatten()
+    {
+        if let FeatureValue::DepFeature {
+            dep_name,
+            dep_feature,
+            ..
+        } = value
+        {
+            via_features
+                .entry(*dep_name)
+                .or_default()
+                .push(dep_feature.to_string());
         }
     }
+    let mut requests: Vec<FeatureRequest> = package
+        .dependencies()
+        .iter()
+        .filter_map(|dep| {
+            let mut features: Vec<String> = dep
+                .features()
+                .iter()
+                .map(|feature| feature.to_string())
+                .chain(
+                    via_features
+                        .get(&dep.name_in_toml())
+                        .into_iter()
+                        .flatten()
+                        .cloned(),
+                )
+                .collect();
+            if dep.uses_default_features() && features.is_empty() {
+                return None;
+            }
+            features.sort_unstable();
+            features.dedup();
+            Some(FeatureRequest {
+                name: dep.package_name().to_string(),
+                default_features: dep.uses_default_features(),
+                features,
+            })
+        })
+        .collect();
+    requests.dedup();
+    requests
+}
+
+/// Returns the enabled features that are not already enabled by another
+/// one, without `default`, sorted by name.
+fn explicit_features(feature_map: &FeatureMap, enabled: &[InternedString]) -> Vec<String> {
+    let mut implied = FxHashSet::default();
+    let mut stack: Vec<InternedString> = enabled.to_vec();
+    while let Some(feature) = stack.pop() {
+        for value in feature_map.get(&feature).into_iter().flatten() {
+            let name = match value {
+                FeatureValue::Feature(name) => *name,
+                // `dep/feature` also enables the implicit feature of an
+                // optional dependency.
+                FeatureValue::DepFeature {
+                    dep_name,
+                    weak: false,
+                    ..
+                } => *dep_name,
+                _ => continue,
+            };
+            if feature_map.contains_key(&name) && implied.insert(name) {
+                stack.push(name);
+            }
+        }
+    }
+    let mut features: Vec<String> = enabled
+        .iter()
+        .filter(|feature| **feature != "default" && !implied.contains(*feature))
+        .map(|feature| feature.to_string())
+        .collect();
+    features.sort_unstable();
+    features
+}
+
+/// Which versions [`ResolvedWorkspace::load`] resolves to.
+#[derive(Debug, Clone, Copy)]
+enum ResolveMode<'a> {
+    /// The versions recorded in `Cargo.lock`.
+    Locked,
+    /// The lowest versions every requirement allows.
+    MinimalVersions,
+    /// The lockfile's versions with the package matching `spec` moved to
+    /// `version`.
+    Upgraded { spec: &'a str, version: &'a str },
 }
 
 /// Resolved Cargo workspace with the data required to build the dependency tree.
@@ -248,6 +1570,61 @@ struct ResolvedWorkspace {
     /// Deduplicated, classified outgoing edges keyed by source package.
     edges: FxHashMap<PackageId, Vec<(PackageId, DependencyType)>>,
     workspace_ids: Vec<PackageId>,
+    /// Warnings Cargo printed while loading and resolving.
+    warnings: Vec<String>,
+}
+
+/// Output of Cargo's shell, kept instead of printed so it neither scrolls
+/// away before the TUI starts nor draws over it.
+#[derive(Debug, Clone, Default)]
+struct ShellCapture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for ShellCapture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl ShellCapture {
+    /// Returns the warnings written so far, each with the lines following
+    /// it up to the next message.
+    fn warnings(&self) -> Vec<String> {
+        let output = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        let mut warnings: Vec<String> = Vec::new();
+        let mut in_warning = false;
+        for line in String::from_utf8_lossy(&output).lines() {
+            if let Some(warning) = line.strip_prefix("warning: ") {
+                warnings.push(warning.to_string());
+                in_warning = true;
+            } else if line.starts_with("error: ") || is_status_line(line) {
+                in_warning = false;
+            } else if in_warning && let Some(warning) = warnings.last_mut() {
+                warning.push('\n');
+                warning.push_str(line);
+            }
+        }
+        warnings
+    }
+}
+
+/// Returns whether `line` is a Cargo status message like
+/// `    Updating crates.io index`, whose verb is right-aligned in 12 columns.
+fn is_status_line(line: &str) -> bool {
+    line.get(..13).is_some_and(|head| {
+        let verb = head.trim();
+        head.starts_with(' ')
+            && head.ends_with(' ')
+            && !verb.contains(' ')
+            && verb.starts_with(|c: char| c.is_ascii_uppercase())
+    })
 }
 
 impl ResolvedWorkspace {
@@ -258,23 +1635,75 @@ impl ResolvedWorkspace {
     /// reachable package into a compact [`PackageSnapshot`], classifies outgoing
     /// edges by dependency kind, and records the workspace member ids that act
     /// as graph roots.
-    fn load(manifest_path: Option<PathBuf>) -> Result<Self> {
-        let gctx = GlobalContext::default().context("failed to initialize Cargo context")?;
+    ///
+    /// `mode` picks between the lockfile's versions, the lowest matching
+    /// ones, and the lockfile's with one package upgraded.
+    fn load(manifest_path: Option<PathBuf>, mode: ResolveMode<'_>) -> Result<Self> {
+        let mut gctx =
+            GlobalContext::default().map_err(cargo_failed("failed to initialize Cargo context"))?;
+        let capture = ShellCapture::default();
+        *gctx.shell() = Shell::from_write(Box::new(capture.clone()));
+        let minimal_versions = matches!(mode, ResolveMode::MinimalVersions);
+        if !matches!(mode, ResolveMode::Locked) {
+            // The minimal resolver mode is unstable; allow it on any
+            // toolchain.
+            gctx.nightly_features_allowed = minimal_versions;
+            let unstable_flags = if minimal_versions {
+                vec!["minimal-versions".to_string()]
+            } else {
+                Vec::new()
+            };
+            gctx.configure(
+                0,
+                false,
+                None,
+                false,
+                false,
+                false,
+                &None,
+                &unstable_flags,
+                &[],
+            )
+            .map_err(cargo_failed("failed to configure Cargo"))?;
+        }
         let manifest_path = resolve_manifest_path(&gctx, manifest_path)?;
-        let ws = Workspace::new(&manifest_path, &gctx).context("failed to load Cargo workspace")?;
+        // Read `.cargo/config.toml` from the workspace rather than from the
+        // current directory, like `cargo build` run in the workspace would.
+        if let Some(dir) = manifest_path.parent() {
+            gctx.reload_rooted_at(dir)
+                .map_err(cargo_failed("failed to read Cargo config"))?;
+        }
+        let mut ws = Workspace::new(&manifest_path, &gctx)
+            .map_err(cargo_failed("failed to load Cargo workspace"))?;
+        // Manifest warnings are only written when asked for. Lints denied in
+        // the manifest fail a build, but need not stop showing the tree.
+        let _ = ws.emit_warnings();
+        ws.set_ignore_lock(minimal_versions);
+        let scratch = match mode {
+            ResolveMode::Upgraded { spec, version } => {
+                Some(upgrade_lockfile(&mut ws, spec, version)?)
+            }
+            _ => None,
+        };
 
+        // Honor `build.target`; without it every platform's dependencies
+        // are shown.
         let requested_kinds = CompileKind::from_requested_targets_with_fallback(
             ws.gctx(),
             &[],
-            CompileKindFallback::JustHost,
+            CompileKindFallback::BuildConfig,
         )
-        .context("failed to determine Cargo target kinds")?;
+        .map_err(cargo_failed("failed to determine Cargo target kinds"))?;
+        let all_targets = requested_kinds.iter().all(CompileKind::is_host);
+        if !all_targets {
+            info!(targets = ?requested_kinds, "filtering by configured build target");
+        }
 
-        let mut target_data =
-            RustcTargetData::new(&ws, &requested_kinds).context("failed to load target data")?;
+        let mut target_data = RustcTargetData::new(&ws, &requested_kinds)
+            .map_err(cargo_failed("failed to load target data"))?;
         let specs = ops::Packages::All(Vec::new())
             .to_package_id_specs(&ws)
-            .context("failed to resolve workspace package specs")?;
+            .map_err(cargo_failed("failed to resolve workspace package specs"))?;
         let ws_resolve = ops::resolve_ws_with_opts(
             &ws,
             &mut target_data,
@@ -284,8 +1713,12 @@ impl ResolvedWorkspace {
             HasDevUnits::Yes,
             ForceAllTargets::Yes,
             false,
-        )
-        .context("failed to resolve Cargo dependencies")?;
+        );
+        if let Some(dir) = scratch {
+            let _ = fs::remove_dir_all(dir);
+        }
+        let ws_resolve =
+            ws_resolve.map_err(cargo_failed("failed to resolve Cargo dependencies"))?;
 
         let pkg_set = ws_resolve.pkg_set;
         let resolve = ws_resolve.targeted_resolve;
@@ -298,14 +1731,18 @@ impl ResolvedWorkspace {
         // Snapshot every reachable package: workspace members first (so a
         // member that also appears in pkg_set keeps its workspace identity),
         // then everything else from the resolved package set.
+        let registries = RegistryNames::from_config(&gctx);
         let mut packages: FxHashMap<PackageId, PackageSnapshot> = FxHashMap::default();
         for pkg in ws.members() {
-            packages.insert(pkg.package_id(), PackageSnapshot::from_package(pkg));
+            packages.insert(
+                pkg.package_id(),
+                PackageSnapshot::from_package(pkg, &registries, resolve.features(pkg.package_id())),
+            );
         }
         for pkg in pkg_set.packages() {
-            packages
-                .entry(pkg.package_id())
-                .or_insert_with(|| PackageSnapshot::from_package(pkg));
+            packages.entry(pkg.package_id()).or_insert_with(|| {
+                PackageSnapshot::from_package(pkg, &registries, resolve.features(pkg.package_id()))
+            });
         }
 
         // Build classified, kind-deduplicated edges keyed by source package.
@@ -318,6 +1755,16 @@ impl ResolvedWorkspace {
                 let mut seen_dev = false;
                 let mut seen_build = false;
                 for dep in deps.iter() {
+                    // Build dependencies run on the host.
+                    let activated = all_targets
+                        || requested_kinds
+                            .iter()
+                            .any(|&kind| target_data.dep_platform_activated(dep, kind))
+                        || dep.kind() == DepKind::Build
+                            && target_data.dep_platform_activated(dep, CompileKind::Host);
+                    if !activated {
+                        continue;
+                    }
                     let kind = DependencyType::from(dep.kind());
                     let already = match kind {
                         DependencyType::Normal => std::mem::replace(&mut seen_normal, true),
@@ -339,18 +1786,109 @@ impl ResolvedWorkspace {
             packages,
             edges,
             workspace_ids,
+            warnings: capture.warnings(),
         })
     }
 }
 
+/// Points `ws` at a scratch copy of its lockfile and updates the package
+/// matching `spec` to `version` there, returning the scratch directory.
+fn upgrade_lockfile(ws: &mut Workspace<'_>, spec: &str, version: &str) -> Result<PathBuf> {
+    let lockfile = ws.lock_root().as_path_unlocked().join("Cargo.lock");
+    let dir = env::temp_dir().join(format!("cargo-tree-tui-upgrade-{}", std::process::id()));
+    let scratch = dir.join("Cargo.lock");
+    let io_error = |path: PathBuf| move |source| Error::Io { path, source };
+    fs::create_dir_all(&dir).map_err(io_error(dir.clone()))?;
+    if lockfile.is_file() {
+        fs::copy(&lockfile, &scratch).map_err(io_error(lockfile))?;
+    }
+    ws.set_requested_lockfile_path(Some(scratch));
+    let options = ops::UpdateOptions {
+        gctx: ws.gctx(),
+        to_update: vec![spec.to_string()],
+        precise: Some(version),
+        recursive: false,
+        dry_run: false,
+        workspace: false,
+    };
+    if let Err(err) = ops::update_lockfile(ws, &options) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(cargo_failed("failed to upgrade dependency")(err));
+    }
+    Ok(dir)
+}
+
+/// Returns the manifest [`DependencyTree::load`] would read for `manifest_path`.
+pub fn find_manifest(manifest_path: Option<PathBuf>) -> Result<PathBuf> {
+    let gctx =
+        GlobalContext::default().map_err(cargo_failed("failed to initialize Cargo context"))?;
+    resolve_manifest_path(&gctx, manifest_path)
+}
+
+/// Returns the directories of the `default-members` declared by the
+/// workspace of `manifest`, or `None` when it declares none.
+pub fn default_members(manifest: &Path) -> Result<Option<Vec<PathBuf>>> {
+    let mut gctx =
+        GlobalContext::default().map_err(cargo_failed("failed to initialize Cargo context"))?;
+    if let Some(dir) = manifest.parent() {
+        gctx.reload_rooted_at(dir)
+            .map_err(cargo_failed("failed to read Cargo config"))?;
+    }
+    let ws =
+        Workspace::new(manifest, &gctx).map_err(cargo_failed("failed to load Cargo workspace"))?;
+
+    // Cargo falls back to the root package or all members on its own;
+    // only an explicit list counts here.
+    let root_manifest = ws.root_manifest();
+    let contents = fs::read_to_string(root_manifest).map_err(|source| Error::Io {
+        path: root_manifest.to_path_buf(),
+        source,
+    })?;
+    let declared = toml::from_str::<toml::Table>(&contents)
+        .ok()
+        .and_then(|manifest| manifest.get("workspace")?.get("default-members").cloned())
+        .is_some();
+    Ok(declared.then(|| {
+        ws.default_members()
+            .map(|package| package.root().to_path_buf())
+            .collect()
+    }))
+}
+
+/// Returns Cargo's home directory, `$CARGO_HOME` or `~/.cargo`.
+pub fn cargo_home() -> Option<PathBuf> {
+    env::current_dir().ok().and_then(|cwd| homedir(&cwd))
+}
+
+/// Returns the root directory of the workspace `manifest` belongs to.
+pub fn workspace_root(manifest: &Path) -> Result<PathBuf> {
+    let gctx =
+        GlobalContext::default().map_err(cargo_failed("failed to initialize Cargo context"))?;
+    let ws =
+        Workspace::new(manifest, &gctx).map_err(cargo_failed("failed to load Cargo workspace"))?;
+    Ok(ws.root().to_path_buf())
+}
+
+/// Wraps an error from the `cargo` library as [`Error::MetadataFailed`].
+fn cargo_failed(context: &'static str) -> impl FnOnce(anyhow::Error) -> Error {
+    move |err| Error::MetadataFailed {
+        context,
+        stderr: format!("{err:#}"),
+    }
+}
+
 /// Helper function to resolve the manifest path, handling absolute vs relative paths and
 /// defaulting to finding the workspace root when no path is provided.
 fn resolve_manifest_path(gctx: &GlobalContext, manifest_path: Option<PathBuf>) -> Result<PathBuf> {
     let raw = match manifest_path {
         Some(path) if path.is_absolute() => path,
         Some(path) => gctx.cwd().join(path),
-        None => find_root_manifest_for_wd(gctx.cwd()).context("failed to find Cargo.toml")?,
+        None => find_root_manifest_for_wd(gctx.cwd())
+            .map_err(|_| Error::ManifestNotFound(gctx.cwd().to_path_buf()))?,
     };
+    if !raw.is_file() {
+        return Err(Error::ManifestNotFound(raw));
+    }
     // Cargo's `Workspace::new` compares manifest paths against the normalized
     // paths it discovers via filesystem walks. Without lexical normalization,
     // an input like `../zed/Cargo.toml` produces `.../cwd/../zed/Cargo.toml`
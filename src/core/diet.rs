@@ -0,0 +1,286 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{DependencyNode, DependencyTree, NodeId, dependency::DependencyType};
+
+/// Transitive dependencies a crate used by a single dependent needs to be
+/// reported as heavy.
+pub const SINGLE_USE_MIN: usize = 10;
+
+/// Dependency-reduction opportunity found by [`diet_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// Direct dependency of a member whose removal would drop `unique`
+    /// crates no other path reaches.
+    HeavySubtree {
+        member: NodeId,
+        dependency: NodeId,
+        unique: usize,
+    },
+    /// Crate resolved in several versions that could be unified.
+    Duplicate { name: String, versions: Vec<String> },
+    /// Dev-dependency of `member` that also ships in its normal build
+    /// through the direct dependency `via`.
+    DevLeak {
+        member: NodeId,
+        dependency: NodeId,
+        via: NodeId,
+    },
+    /// Crate with a single dependent and at least [`SINGLE_USE_MIN`]
+    /// dependencies of its own.
+    SingleUse {
+        dependent: NodeId,
+        dependency: NodeId,
+        subtree: usize,
+    },
+}
+
+impl Finding {
+    /// Returns roughly how many crates acting on the finding could save,
+    /// used to rank findings.
+    pub fn weight(&self) -> usize {
+        match self {
+            Self::HeavySubtree { unique, .. } => *unique,
+            Self::Duplicate { versions, .. } => versions.len() - 1,
+            Self::DevLeak { .. } => 1,
+            Self::SingleUse { subtree, .. } => *subtree,
+        }
+    }
+
+    /// Describes the finding in one line.
+    pub fn describe(&self, tree: &DependencyTree) -> String {
+        let name = |id: NodeId| {
+            tree.node(id)
+                .and_then(DependencyNode::as_dependency)
+                .map_or_else(String::new, |dependency| {
+                    format!("{} v{}", dependency.name, dependency.version)
+                })
+        };
+        match self {
+            Self::HeavySubtree {
+                member,
+                dependency,
+                unique,
+            } => format!(
+                "{} pulls in {unique} crates only it uses (direct dependency of {})",
+                name(*dependency),
+                name(*member)
+            ),
+            Self::Duplicate { name, versions } => format!(
+                "{name} is built in {} versions ({}), unify them",
+                versions.len(),
+                versions.join(", ")
+            ),
+            Self::DevLeak {
+                member,
+                dependency,
+                via,
+            } => format!(
+                "{} is a dev-dependency of {} but ships through {}",
+                name(*dependency),
+                name(*member),
+                name(*via)
+            ),
+            Self::SingleUse {
+                dependent,
+                dependency,
+                subtree,
+            } => format!(
+                "{} brings {subtree} crates for its only user {}",
+                name(*dependency),
+                name(*dependent)
+            ),
+        }
+    }
+}
+
+/// Lists dependency-reduction opportunities, most crates saved first.
+pub fn diet_report(tree: &DependencyTree) -> Vec<Finding> {
+    let mut findings = heavy_subtrees(tree);
+    findings.extend(duplicates(tree));
+    findings.extend(dev_leaks(tree));
+    findings.extend(single_use(tree));
+    // Stable, so findings of equal weight keep the order above.
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.weight()));
+    findings
+}
+
+/// Renders the findings of [`diet_report`] as numbered lines.
+pub fn report_lines(tree: &DependencyTree, findings: &[Finding]) -> Vec<String> {
+    if findings.is_empty() {
+        return vec!["no dependency-reduction opportunities found".to_string()];
+    }
+    findings
+        .iter()
+        .enumerate()
+        .map(|(idx, finding)| {
+            format!(
+                "{:>3}. [-{}] {}",
+                idx + 1,
+                finding.weight(),
+                finding.describe(tree)
+            )
+        })
+        .collect()
+}
+
+/// Returns the normal and build dependencies of `id`, skipping dev ones,
+/// which never reach the shipped build.
+fn shipped_dependencies(tree: &DependencyTree, id: NodeId) -> Vec<NodeId> {
+    let Some(node) = tree.node(id) else {
+        return Vec::new();
+    };
+    node.children()
+        .iter()
+        .flat_map(|&child| match tree.node(child) {
+            Some(DependencyNode::Group(group)) if group.kind == DependencyType::Dev => Vec::new(),
+            Some(DependencyNode::Group(group)) => group.children.clone(),
+            Some(DependencyNode::Crate(_)) => vec![child],
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Returns the crates reachable from `starts` through shipped edges,
+/// without walking through `skip`.
+fn reachable(tree: &DependencyTree, starts: &[NodeId], skip: Option<NodeId>) -> FxHashSet<NodeId> {
+    let mut seen = FxHashSet::default();
+    let mut stack: Vec<NodeId> = starts
+        .iter()
+        .copied()
+        .filter(|&id| Some(id) != skip)
+        .collect();
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        stack.extend(
+            shipped_dependencies(tree, id)
+                .into_iter()
+                .filter(|&child| Some(child) != skip),
+        );
+    }
+    seen
+}
+
+fn heavy_subtrees(tree: &DependencyTree) -> Vec<Finding> {
+    let members = tree.members();
+    let all = reachable(tree, &members, None);
+    let mut findings = Vec::new();
+    let mut seen = FxHashSet::default();
+    for &member in &members {
+        for dependency in shipped_dependencies(tree, member) {
+            // Crates used beyond the workspace stay when a member drops them.
+            let used_elsewhere = tree
+                .dependents(dependency)
+                .iter()
+                .any(|&dependent| !tree.is_workspace_member(dependent));
+            if used_elsewhere || tree.is_workspace_member(dependency) || !seen.insert(dependency) {
+                continue;
+            }
+            let without = reachable(tree, &members, Some(dependency));
+            // The dependency itself is not counted.
+            let unique = all.len() - without.len() - 1;
+            if unique > 0 {
+                findings.push(Finding::HeavySubtree {
+                    member,
+                    dependency,
+                    unique,
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn duplicates(tree: &DependencyTree) -> Vec<Finding> {
+    let mut versions: FxHashMap<&str, Vec<String>> = FxHashMap::default();
+    for id in tree.crate_nodes() {
+        if let Some(DependencyNode::Crate(dependency)) = tree.node(id) {
+            versions
+                .entry(dependency.name.as_str())
+                .or_default()
+                .push(dependency.version.clone());
+        }
+    }
+    let mut duplicates: Vec<(&str, Vec<String>)> = versions
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .collect();
+    duplicates.sort_unstable_by_key(|(name, _)| *name);
+    duplicates
+        .into_iter()
+        .map(|(name, mut versions)| {
+            versions.sort();
+            Finding::Duplicate {
+                name: name.to_owned(),
+                versions,
+            }
+        })
+        .collect()
+}
+
+fn dev_leaks(tree: &DependencyTree) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for member in tree.members() {
+        let Some(node) = tree.node(member) else {
+            continue;
+        };
+        let dev: Vec<NodeId> = node
+            .children()
+            .iter()
+            .filter_map(|&child| match tree.node(child) {
+                Some(DependencyNode::Group(group)) if group.kind == DependencyType::Dev => {
+                    Some(group.children.clone())
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        if dev.is_empty() {
+            continue;
+        }
+        let direct = shipped_dependencies(tree, member);
+        for &via in &direct {
+            let shipped = reachable(tree, &[via], None);
+            for &dependency in &dev {
+                // Also declaring a dev-dependency as a normal one is fine.
+                let leaked = !direct.contains(&dependency) && shipped.contains(&dependency);
+                let reported = findings.iter().any(|finding| {
+                    matches!(finding, Finding::DevLeak { member: m, dependency: d, .. }
+                        if *m == member && *d == dependency)
+                });
+                if leaked && !reported {
+                    findings.push(Finding::DevLeak {
+                        member,
+                        dependency,
+                        via,
+                    });
+                }
+            }
+        }
+    }
+    findings
+}
+
+fn single_use(tree: &DependencyTree) -> Vec<Finding> {
+    let direct: FxHashSet<NodeId> = tree
+        .members()
+        .into_iter()
+        .flat_map(|member| shipped_dependencies(tree, member))
+        .collect();
+    tree.crate_nodes()
+        .filter(|id| !direct.contains(id) && !tree.is_workspace_member(*id))
+        .filter_map(|dependency| {
+            let dependents = tree.dependents(dependency);
+            let [dependent] = dependents[..] else {
+                return None;
+            };
+            let subtree = reachable(tree, &[dependency], None).len() - 1;
+            (subtree >= SINGLE_USE_MIN).then_some(Finding::SingleUse {
+                dependent,
+                dependency,
+                subtree,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,273 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{
+    Dependency, DependencyGroup, DependencyNode, DependencyTree, NodeId, dependency::DependencyType,
+};
+
+/// How a crate changed in a [`TreeDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrateChange {
+    Added,
+    /// A tombstone standing in for a crate that is gone.
+    Removed,
+    VersionChanged,
+}
+
+impl CrateChange {
+    /// Returns the badge marking the change in the tree.
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Added => "+",
+            Self::Removed => "-",
+            Self::VersionChanged => "~",
+        }
+    }
+}
+
+/// Differences in resolved crates between two [`DependencyTree`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeDiff {
+    /// Crates only present in the new tree, as `(name, version)`.
+    pub added: Vec<(String, String)>,
+    /// Crates only present in the old tree, as `(name, version)`.
+    pub removed: Vec<(String, String)>,
+    /// Crates resolved in exactly one version in both trees whose version
+    /// changed, as `(name, old version, new version)`.
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl TreeDiff {
+    /// Compares the crates resolved in `old` and `new`.
+    pub fn between(old: &DependencyTree, new: &DependencyTree) -> Self {
+        Self::since(old, &Self::default(), new)
+    }
+
+    /// Compares `old` and `new` like [`between`](Self::between), leaving out
+    /// the tombstones `earlier` added to `old`.
+    pub fn since(old: &DependencyTree, earlier: &TreeDiff, new: &DependencyTree) -> Self {
+        let mut old_versions = versions_by_name(old);
+        for (name, version) in &earlier.removed {
+            if let Some(versions) = old_versions.get_mut(name.as_str()) {
+                versions.remove(version);
+                if versions.is_empty() {
+                    old_versions.remove(name.as_str());
+                }
+            }
+        }
+        let new_versions = versions_by_name(new);
+        let mut diff = Self::default();
+
+        for (name, old_set) in &old_versions {
+            let new_set = new_versions.get(name);
+            if let Some(new_set) = new_set
+                && old_set.len() == 1
+                && new_set.len() == 1
+                && old_set != new_set
+            {
+                let old_version = old_set.iter().next().cloned().unwrap_or_default();
+                let new_version = new_set.iter().next().cloned().unwrap_or_default();
+                diff.changed
+                    .push((name.to_string(), old_version, new_version));
+                continue;
+            }
+            for version in old_set {
+                if new_set.is_none_or(|set| !set.contains(version)) {
+                    diff.removed.push((name.to_string(), version.clone()));
+                }
+            }
+        }
+
+        for (name, new_set) in &new_versions {
+            let old_set = old_versions.get(name);
+            if old_set.is_some_and(|old_set| old_set.len() == 1 && new_set.len() == 1) {
+                continue;
+            }
+            for version in new_set {
+                if old_set.is_none_or(|set| !set.contains(version)) {
+                    diff.added.push((name.to_string(), version.clone()));
+                }
+            }
+        }
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Returns whether both trees resolve the same crates.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Returns how `dependency` changed, if it did. Removed crates are only
+    /// found as tombstones, see [`add_tombstones`](Self::add_tombstones).
+    pub fn change_of(&self, dependency: &Dependency) -> Option<CrateChange> {
+        let key = (dependency.name.clone(), dependency.version.clone());
+        if self.removed.contains(&key) {
+            Some(CrateChange::Removed)
+        } else if self.added.contains(&key) {
+            Some(CrateChange::Added)
+        } else if self
+            .changed
+            .iter()
+            .any(|(name, _, version)| *name == dependency.name && *version == dependency.version)
+        {
+            Some(CrateChange::VersionChanged)
+        } else {
+            None
+        }
+    }
+
+    /// Adds a leaf for every removed crate of `old` to `new`, under the
+    /// crates that depended on it when they are still there.
+    ///
+    /// Dependents are matched by name and version, or by name alone when
+    /// their version changed. Removed crates left without a dependent are
+    /// not shown.
+    pub fn add_tombstones(&self, old: &DependencyTree, new: &mut DependencyTree) {
+        let mut new_ids: FxHashMap<(&str, &str), NodeId> = FxHashMap::default();
+        for id in new.crate_nodes() {
+            if let Some(dependency) = new.node(id).and_then(DependencyNode::as_dependency) {
+                new_ids.insert((&dependency.name, &dependency.version), id);
+            }
+        }
+        let find_new = |dependency: &Dependency| {
+            new_ids
+                .get(&(dependency.name.as_str(), dependency.version.as_str()))
+                .or_else(|| {
+                    self.changed
+                        .iter()
+                        .find(|(name, old, _)| {
+                            *name == dependency.name && *old == dependency.version
+                        })
+                        .and_then(|(name, _, version)| {
+                            new_ids.get(&(name.as_str(), version.as_str()))
+                        })
+                })
+                .copied()
+        };
+
+        let mut edges = Vec::new();
+        for id in old.crate_nodes() {
+            let Some(removed) = old.node(id).and_then(DependencyNode::as_dependency) else {
+                continue;
+            };
+            if !self.removed.contains(&removed.key()) {
+                continue;
+            }
+            let mut owners = Vec::new();
+            for &parent in &old.parents[id.0] {
+                match old.node(parent) {
+                    Some(DependencyNode::Group(group)) => owners.extend(
+                        old.parents[parent.0]
+                            .iter()
+                            .map(|&owner| (owner, group.kind)),
+                    ),
+                    Some(DependencyNode::Crate(_)) => {
+                        owners.push((parent, DependencyType::Normal));
+                    }
+                    _ => {}
+                }
+            }
+            let owners: Vec<(NodeId, DependencyType)> = owners
+                .into_iter()
+                .filter_map(|(owner, kind)| {
+                    let owner = old.node(owner)?.as_dependency()?;
+                    Some((find_new(owner)?, kind))
+                })
+                .collect();
+            if !owners.is_empty() {
+                edges.push((removed.clone(), owners));
+            }
+        }
+
+        for (removed, owners) in edges {
+            let tombstone = NodeId(new.nodes.len());
+            new.nodes.push(DependencyNode::Crate(Dependency {
+                children: Vec::new(),
+                ..removed
+            }));
+            new.parents.push(Vec::new());
+            for (owner, kind) in owners {
+                let parent = match kind {
+                    DependencyType::Normal => owner,
+                    kind => group_of(new, owner, kind),
+                };
+                attach(new, parent, tombstone);
+            }
+        }
+    }
+
+    /// Renders the diff as human-readable lines, one change per line.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (name, old, new) in &self.changed {
+            lines.push(format!("~ {name} v{old} -> v{new}"));
+        }
+        for (name, version) in &self.added {
+            lines.push(format!("+ {name} v{version}"));
+        }
+        for (name, version) in &self.removed {
+            lines.push(format!("- {name} v{version}"));
+        }
+        lines
+    }
+}
+
+/// Returns the `kind` group of `owner` in `tree`, creating it if needed.
+fn group_of(tree: &mut DependencyTree, owner: NodeId, kind: DependencyType) -> NodeId {
+    let existing = tree.node(owner).and_then(|node| {
+        node.children().iter().copied().find(|&child| {
+            tree.node(child)
+                .and_then(DependencyNode::as_group)
+                .is_some_and(|group| group.kind == kind)
+        })
+    });
+    if let Some(group) = existing {
+        return group;
+    }
+    let group = NodeId(tree.nodes.len());
+    tree.nodes.push(DependencyNode::Group(DependencyGroup {
+        kind,
+        children: Vec::new(),
+    }));
+    tree.parents.push(Vec::new());
+    attach(tree, owner, group);
+    group
+}
+
+/// Adds `child` to the children of `parent`, keeping normal dependencies
+/// ahead of the dependency groups.
+fn attach(tree: &mut DependencyTree, parent: NodeId, child: NodeId) {
+    let is_group = tree.node(child).is_some_and(DependencyNode::is_group);
+    let first_group = tree.node(parent).and_then(|node| {
+        node.children()
+            .iter()
+            .position(|&sibling| tree.node(sibling).is_some_and(DependencyNode::is_group))
+    });
+    let children = match &mut tree.nodes[parent.0] {
+        DependencyNode::Crate(dependency) => &mut dependency.children,
+        DependencyNode::Group(group) => &mut group.children,
+        _ => return,
+    };
+    match first_group {
+        Some(idx) if !is_group => children.insert(idx, child),
+        _ => children.push(child),
+    }
+    tree.parents[child.0].push(parent);
+}
+
+/// Groups the resolved versions of every crate by name.
+fn versions_by_name(tree: &DependencyTree) -> FxHashMap<&str, FxHashSet<String>> {
+    let mut versions: FxHashMap<&str, FxHashSet<String>> = FxHashMap::default();
+    for id in tree.crate_nodes() {
+        if let Some(DependencyNode::Crate(dependency)) = tree.node(id) {
+            versions
+                .entry(dependency.name.as_str())
+                .or_default()
+                .insert(dependency.version.clone());
+        }
+    }
+    versions
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use rustc_hash::FxHashSet;
+
+use super::{DependencyNode, DependencyTree, NodeId, dependency::DependencyType};
+
+/// Registry dependency that workspace members declare differently, a
+/// candidate for unifying in `[workspace.dependencies]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub name: String,
+    /// Declarations of the dependency, sorted by member name.
+    pub uses: Vec<MemberUse>,
+}
+
+impl Drift {
+    /// Returns how many distinct requirements the members declare.
+    pub fn requirements(&self) -> usize {
+        let mut requirements: Vec<&str> = self
+            .uses
+            .iter()
+            .map(|member_use| member_use.requirement.as_str())
+            .collect();
+        requirements.sort_unstable();
+        requirements.dedup();
+        requirements.len()
+    }
+}
+
+/// How one workspace member declares a [`Drift`] dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberUse {
+    pub member: NodeId,
+    /// Crate the declaration resolves to.
+    pub dependency: NodeId,
+    pub kind: DependencyType,
+    pub requirement: String,
+    pub inherited: bool,
+    pub default_features: bool,
+    /// Features the member asks for, sorted by name.
+    pub features: Vec<String>,
+}
+
+impl MemberUse {
+    fn declaration(&self) -> (&str, bool, &[String]) {
+        (&self.requirement, self.default_features, &self.features)
+    }
+}
+
+/// Lists the registry dependencies of several workspace members that they
+/// require at different versions or with different feature sets, sorted by
+/// name.
+pub fn version_drift(tree: &DependencyTree) -> Vec<Drift> {
+    let mut by_name: BTreeMap<&str, Vec<MemberUse>> = BTreeMap::new();
+    for member in tree.members() {
+        let Some(DependencyNode::Crate(owner)) = tree.node(member) else {
+            continue;
+        };
+        for (id, kind) in tree.direct_dependencies(member) {
+            let Some(DependencyNode::Crate(dependency)) = tree.node(id) else {
+                continue;
+            };
+            let Some(declared) = owner.declaration_on(&dependency.name, kind) else {
+                continue;
+            };
+            let request = owner
+                .feature_requests
+                .iter()
+                .find(|request| request.name == dependency.name);
+            by_name
+                .entry(dependency.name.as_str())
+                .or_default()
+                .push(MemberUse {
+                    member,
+                    dependency: id,
+                    kind,
+                    requirement: declared.requirement.clone(),
+                    inherited: declared.inherited,
+                    default_features: request.is_none_or(|request| request.default_features),
+                    features: request
+                        .map(|request| request.features.clone())
+                        .unwrap_or_default(),
+                });
+        }
+    }
+
+    by_name
+        .into_iter()
+        .filter_map(|(name, mut uses)| {
+            let first = uses.first()?.declaration();
+            let drifts = uses
+                .iter()
+                .any(|member_use| member_use.declaration() != first);
+            let members: FxHashSet<NodeId> =
+                uses.iter().map(|member_use| member_use.member).collect();
+            if !drifts || members.len() < 2 {
+                return None;
+            }
+            let member_name = |member_use: &MemberUse| {
+                tree.node(member_use.member)
+                    .map(DependencyNode::display_name)
+            };
+            uses.sort_by(|a, b| member_name(a).cmp(&member_name(b)));
+            Some(Drift {
+                name: name.to_string(),
+                uses,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,402 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path, str::FromStr};
+
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+
+use super::{
+    Dependency, DependencyNode, DependencyTree, NodeId,
+    dependency::{DependencyType, SourceKind},
+};
+
+/// Page the tree is embedded in by [`tree_html`].
+const HTML_TEMPLATE: &str = include_str!("export.html");
+
+/// Renders the direct dependencies of crate `id` as Cargo manifest tables,
+/// one table per dependency kind, e.g. to paste into a new crate split off
+/// from it.
+///
+/// Versions are the resolved ones, written as caret requirements, and the
+/// features are those enabled across the whole graph, so the output is a
+/// starting point rather than an exact copy of the original manifest. Git
+/// sources are not recorded in the tree and come out as registry versions,
+/// marked with a comment.
+pub fn dependencies_toml(tree: &DependencyTree, id: NodeId) -> Option<String> {
+    let dependency = tree.node(id)?.as_dependency()?;
+    let mut tables: Vec<(DependencyType, Vec<&Dependency>)> = Vec::new();
+    let mut push = |kind, child: NodeId| {
+        let Some(child) = tree.node(child).and_then(DependencyNode::as_dependency) else {
+            return;
+        };
+        match tables
+            .iter_mut()
+            .find(|(table_kind, _)| *table_kind == kind)
+        {
+            Some((_, deps)) => deps.push(child),
+            None => tables.push((kind, vec![child])),
+        }
+    };
+    for &child in &dependency.children {
+        match tree.node(child) {
+            Some(DependencyNode::Crate(_)) => push(DependencyType::Normal, child),
+            Some(DependencyNode::Group(group)) => {
+                for &grandchild in &group.children {
+                    push(group.kind, grandchild);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = String::new();
+    for (kind, mut deps) in tables {
+        deps.sort_by(|a, b| a.name.cmp(&b.name));
+        deps.dedup_by(|a, b| a.name == b.name);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{}", kind.label());
+        for dep in deps {
+            let _ = writeln!(out, "{}", dependency_line(dep));
+        }
+    }
+    Some(out)
+}
+
+/// Renders one `name = ...` entry of a dependency table.
+fn dependency_line(dependency: &Dependency) -> String {
+    let mut fields = Vec::new();
+    match (&dependency.manifest_dir, &dependency.registry) {
+        (Some(dir), _) => fields.push(format!("path = {}", quote(dir))),
+        (None, Some(registry)) if !registry.is_crates_io() => {
+            fields.push(format!(
+                "version = {}",
+                quote(requirement(&dependency.version))
+            ));
+            fields.push(format!("registry = {}", quote(&registry.name)));
+        }
+        _ => fields.push(format!(
+            "version = {}",
+            quote(requirement(&dependency.version))
+        )),
+    }
+    if !dependency.default_features {
+        fields.push("default-features = false".to_string());
+    }
+    if !dependency.features.is_empty() {
+        let features: Vec<String> = dependency.features.iter().map(|f| quote(f)).collect();
+        fields.push(format!("features = [{}]", features.join(", ")));
+    }
+
+    let value = match fields.as_slice() {
+        [only] if only.starts_with("version = ") => quote(requirement(&dependency.version)),
+        _ => format!("{{ {} }}", fields.join(", ")),
+    };
+    let comment = if dependency.source == SourceKind::Git {
+        " # resolved from git"
+    } else {
+        ""
+    };
+    format!("{} = {value}{comment}", dependency.name)
+}
+
+/// Renders `tree` as a standalone HTML page with a collapsible tree, a
+/// filter box and expand/collapse buttons, needing nothing but a browser.
+///
+/// Like `cargo tree`, a crate's dependencies are only listed under its first
+/// occurrence; later ones link back to it, marked `(*)`. `notes` are shown next to the
+/// crates they are attached to.
+pub fn tree_html(tree: &DependencyTree, notes: &BTreeMap<String, String>) -> String {
+    let mut html = HtmlTree {
+        tree,
+        notes,
+        seen: FxHashSet::default(),
+        out: String::new(),
+    };
+    html.out.push_str("<ul>\n");
+    for &root in tree.roots() {
+        html.node(root, true);
+    }
+    html.out.push_str("</ul>");
+    HTML_TEMPLATE
+        .replace("{{title}}", &escape(&tree.workspace_name))
+        .replace("{{tree}}", &html.out)
+}
+
+/// Walk state of [`tree_html`].
+struct HtmlTree<'a> {
+    tree: &'a DependencyTree,
+    notes: &'a BTreeMap<String, String>,
+    /// Crates whose dependencies were already listed.
+    seen: FxHashSet<NodeId>,
+    out: String,
+}
+
+impl HtmlTree<'_> {
+    fn node(&mut self, id: NodeId, open: bool) {
+        let Some(node) = self.tree.node(id) else {
+            return;
+        };
+        let label = self.label(id, node);
+        if node.children().is_empty() {
+            let _ = writeln!(self.out, "<li class=\"leaf\">{label}</li>");
+            return;
+        }
+        if node.is_crate() && !self.seen.insert(id) {
+            let _ = writeln!(
+                self.out,
+                "<li class=\"leaf\"><a href=\"#n{}\">{label}</a> <span class=\"repeat\">(*)</span></li>",
+                id.0
+            );
+            return;
+        }
+
+        let open = if open { " open" } else { "" };
+        let _ = writeln!(
+            self.out,
+            "<li id=\"n{}\"><details{open}><summary>{label}</summary><ul>",
+            id.0
+        );
+        for &child in node.children() {
+            self.node(child, false);
+        }
+        self.out.push_str("</ul></details></li>\n");
+    }
+
+    /// Returns the escaped contents of a node's row.
+    fn label(&self, id: NodeId, node: &DependencyNode) -> String {
+        match node {
+            DependencyNode::Crate(dependency) => {
+                let class = if self.tree.is_workspace_member(id) {
+                    "name member"
+                } else {
+                    "name"
+                };
+                let mut label = format!(
+                    "<span class=\"{class}\">{}</span> <span class=\"version\">v{}</span>",
+                    escape(&dependency.name),
+                    escape(&dependency.version)
+                );
+                if dependency.is_proc_macro {
+                    label.push_str(" <span class=\"tag\">(proc-macro)</span>");
+                }
+                if dependency.source != SourceKind::Registry {
+                    let _ = write!(
+                        label,
+                        " <span class=\"tag\">({})</span>",
+                        dependency.source.label()
+                    );
+                }
+                if let Some(note) = self.notes.get(&dependency.name) {
+                    let _ = write!(label, " <span class=\"note\">✎ {}</span>", escape(note));
+                }
+                label
+            }
+            DependencyNode::Group(group) => {
+                format!("<span class=\"group\">{}</span>", escape(group.label()))
+            }
+            DependencyNode::Target(target) => format!(
+                "<span class=\"group\">{}: {}</span>",
+                target.kind.label(),
+                escape(&target.name)
+            ),
+            DependencyNode::Folder(folder) => {
+                format!("<span class=\"group\">{}</span>", escape(&folder.path))
+            }
+        }
+    }
+}
+
+/// Escapes text for use in HTML or XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// File format of [`graph_export`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// GraphML, read by Gephi, yEd and NetworkX.
+    #[default]
+    GraphMl,
+    /// A `{"nodes": [...], "edges": [...]}` document.
+    Json,
+}
+
+impl GraphFormat {
+    /// Guesses the format from the extension of `path`, defaulting to
+    /// GraphML.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            _ => Self::GraphMl,
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "graphml" => Ok(Self::GraphMl),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected `graphml` or `json`, got `{s}`")),
+        }
+    }
+}
+
+/// A crate in [`graph_export`] output, identified by its node id.
+#[derive(Serialize)]
+struct GraphNode<'a> {
+    id: usize,
+    name: &'a str,
+    version: &'a str,
+    source: &'static str,
+    workspace_member: bool,
+    proc_macro: bool,
+    /// Crates depending on this one, each counted once whatever the kinds
+    /// of its edges.
+    dependents: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<&'a str>,
+}
+
+/// A dependency between two [`GraphNode`]s.
+#[derive(Serialize)]
+struct GraphEdge {
+    source: usize,
+    target: usize,
+    kind: &'static str,
+}
+
+/// Exports the crates of `tree` and the typed dependency edges between them,
+/// for analysis in graph tools.
+///
+/// Unlike the tree views, every crate appears once, and group and target
+/// nodes are left out: an edge's `kind` (`normal`, `dev` or `build`) tells
+/// which group it came from. Each crate carries its number of dependents,
+/// so the in-degree need not be recomputed from edges of several kinds.
+pub fn graph_export(tree: &DependencyTree, format: GraphFormat) -> String {
+    let nodes: Vec<GraphNode> = tree
+        .crate_nodes()
+        .filter_map(|id| {
+            let dependency = tree.node(id)?.as_dependency()?;
+            Some(GraphNode {
+                id: id.0,
+                name: &dependency.name,
+                version: &dependency.version,
+                source: dependency.source.label(),
+                workspace_member: tree.is_workspace_member(id),
+                proc_macro: dependency.is_proc_macro,
+                dependents: tree.dependents(id).len(),
+                license: dependency.license.as_deref(),
+            })
+        })
+        .collect();
+    let mut edges = Vec::new();
+    for node in &nodes {
+        let Some(DependencyNode::Crate(dependency)) = tree.node(NodeId(node.id)) else {
+            continue;
+        };
+        for &child in &dependency.children {
+            let (kind, targets) = match tree.node(child) {
+                Some(DependencyNode::Crate(_)) => {
+                    (DependencyType::Normal, std::slice::from_ref(&child))
+                }
+                Some(DependencyNode::Group(group)) => (group.kind, group.children.as_slice()),
+                _ => continue,
+            };
+            edges.extend(targets.iter().map(|target| GraphEdge {
+                source: node.id,
+                target: target.0,
+                kind: edge_kind(kind),
+            }));
+        }
+    }
+
+    match format {
+        GraphFormat::GraphMl => graphml(&nodes, &edges),
+        GraphFormat::Json => {
+            let graph = serde_json::json!({ "nodes": nodes, "edges": edges });
+            serde_json::to_string_pretty(&graph).unwrap_or_default()
+        }
+    }
+}
+
+fn edge_kind(kind: DependencyType) -> &'static str {
+    match kind {
+        DependencyType::Normal => "normal",
+        DependencyType::Dev => "dev",
+        DependencyType::Build => "build",
+    }
+}
+
+/// Renders the nodes and edges of [`graph_export`] as a directed GraphML
+/// graph.
+fn graphml(nodes: &[GraphNode], edges: &[GraphEdge]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n\
+         <key id=\"version\" for=\"node\" attr.name=\"version\" attr.type=\"string\"/>\n\
+         <key id=\"source\" for=\"node\" attr.name=\"source\" attr.type=\"string\"/>\n\
+         <key id=\"workspace_member\" for=\"node\" attr.name=\"workspace_member\" attr.type=\"boolean\"/>\n\
+         <key id=\"proc_macro\" for=\"node\" attr.name=\"proc_macro\" attr.type=\"boolean\"/>\n\
+         <key id=\"dependents\" for=\"node\" attr.name=\"dependents\" attr.type=\"int\"/>\n\
+         <key id=\"license\" for=\"node\" attr.name=\"license\" attr.type=\"string\"/>\n\
+         <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n\
+         <graph id=\"dependencies\" edgedefault=\"directed\">\n",
+    );
+    for node in nodes {
+        let _ = writeln!(out, "<node id=\"n{}\">", node.id);
+        let _ = writeln!(out, "  <data key=\"name\">{}</data>", escape(node.name));
+        let _ = writeln!(
+            out,
+            "  <data key=\"version\">{}</data>",
+            escape(node.version)
+        );
+        let _ = writeln!(out, "  <data key=\"source\">{}</data>", node.source);
+        let _ = writeln!(
+            out,
+            "  <data key=\"workspace_member\">{}</data>",
+            node.workspace_member
+        );
+        let _ = writeln!(out, "  <data key=\"proc_macro\">{}</data>", node.proc_macro);
+        let _ = writeln!(out, "  <data key=\"dependents\">{}</data>", node.dependents);
+        if let Some(license) = node.license {
+            let _ = writeln!(out, "  <data key=\"license\">{}</data>", escape(license));
+        }
+        out.push_str("</node>\n");
+    }
+    for edge in edges {
+        let _ = writeln!(
+            out,
+            "<edge source=\"n{}\" target=\"n{}\"><data key=\"kind\">{}</data></edge>",
+            edge.source, edge.target, edge.kind
+        );
+    }
+    out.push_str("</graph>\n</graphml>\n");
+    out
+}
+
+/// Returns `version` without build metadata, which requirements ignore.
+fn requirement(version: &str) -> &str {
+    version
+        .split_once('+')
+        .map_or(version, |(version, _)| version)
+}
+
+/// Quotes `value` as a TOML basic string.
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
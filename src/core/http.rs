@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use curl::easy::Easy;
+use tracing::{debug, instrument};
+
+use crate::{Error, Result};
+
+/// Downloads `url`, returning `None` when it does not exist.
+#[instrument]
+pub fn get(url: &str) -> Result<Option<String>> {
+    let failed = |err: curl::Error| Error::Http {
+        url: url.to_string(),
+        message: err.to_string(),
+    };
+    let mut body = Vec::new();
+    let mut easy = Easy::new();
+    easy.url(url).map_err(failed)?;
+    easy.follow_location(true).map_err(failed)?;
+    easy.useragent(concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION")
+    ))
+    .map_err(failed)?;
+    easy.connect_timeout(Duration::from_secs(5))
+        .map_err(failed)?;
+    easy.timeout(Duration::from_secs(15)).map_err(failed)?;
+    {
+        let mut transfer = easy.transfer();
+        transfer
+            .write_function(|data| {
+                body.extend_from_slice(data);
+                Ok(data.len())
+            })
+            .map_err(failed)?;
+        transfer.perform().map_err(failed)?;
+    }
+
+    let code = easy.response_code().map_err(failed)?;
+    debug!(code, bytes = body.len(), "response");
+    match code {
+        200 => Ok(Some(String::from_utf8_lossy(&body).into_owned())),
+        404 => Ok(None),
+        code => Err(Error::Http {
+            url: url.to_string(),
+            message: format!("HTTP {code}"),
+        }),
+    }
+}
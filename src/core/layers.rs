@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use crate::{Error, Result};
+
+use super::{DependencyNode, DependencyTree, NodeId, manifest::tree_tui_metadata};
+
+/// Layering rule between two workspace members, e.g. `core -> app forbidden`.
+///
+/// Either side may be `*` to match any member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerRule {
+    pub from: String,
+    pub to: String,
+    pub allowed: bool,
+}
+
+impl LayerRule {
+    /// Parses `<from> -> <to> allowed|forbidden`.
+    pub fn parse(rule: &str) -> Result<Self> {
+        let invalid = || {
+            Error::InvalidConfig(format!(
+                "layer rule `{rule}`, expected `<from> -> <to> allowed|forbidden`"
+            ))
+        };
+        let (from, rest) = rule.split_once("->").ok_or_else(invalid)?;
+        let mut words = rest.split_whitespace();
+        let (Some(to), Some(verdict), None) = (words.next(), words.next(), words.next()) else {
+            return Err(invalid());
+        };
+        let allowed = match verdict {
+            "allowed" => true,
+            "forbidden" => false,
+            _ => return Err(invalid()),
+        };
+        let from = from.trim();
+        if from.is_empty() || from.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            allowed,
+        })
+    }
+
+    /// Returns how specifically the rule matches `from -> to`, `None` if it
+    /// does not match at all.
+    fn specificity(&self, from: &str, to: &str) -> Option<u8> {
+        let side = |pattern: &str, name: &str| match pattern {
+            "*" => Some(0),
+            _ if pattern == name => Some(1),
+            _ => None,
+        };
+        Some(side(&self.from, from)? + side(&self.to, to)?)
+    }
+}
+
+/// Layering rules for the dependencies between workspace members.
+///
+/// Read from the `layers` array of `[workspace.metadata.tree-tui]` (or
+/// `[package.metadata.tree-tui]` in a single-crate project):
+///
+/// ```toml
+/// [workspace.metadata.tree-tui]
+/// layers = ["* -> core allowed", "core -> * forbidden"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerRules(Vec<LayerRule>);
+
+impl LayerRules {
+    pub fn parse<S: AsRef<str>>(rules: &[S]) -> Result<Self> {
+        rules
+            .iter()
+            .map(|rule| LayerRule::parse(rule.as_ref()))
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// Loads the rules of the workspace `manifest` belongs to, looking in the
+    /// manifest itself and then up to the workspace root.
+    pub fn load(manifest: &Path) -> Result<Self> {
+        let Some(layers) = tree_tui_metadata(manifest, "layers")? else {
+            return Ok(Self::default());
+        };
+        let layers = layers.as_array().map(Vec::as_slice).unwrap_or_default();
+        let rules: Vec<&str> = layers.iter().filter_map(|rule| rule.as_str()).collect();
+        Self::parse(&rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether `from` may depend on `to`.
+    ///
+    /// The most specific matching rule decides, the last one on a tie, and
+    /// edges no rule matches are allowed.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, rule)| Some(((rule.specificity(from, to)?, idx), rule.allowed)))
+            .max_by_key(|&(key, _)| key)
+            .is_none_or(|(_, allowed)| allowed)
+    }
+
+    /// Returns the `(member, dependency)` edges between workspace members
+    /// that the rules forbid, looking through group nodes.
+    pub fn violations(&self, tree: &DependencyTree) -> Vec<(NodeId, NodeId)> {
+        let mut violations = Vec::new();
+        if self.is_empty() {
+            return violations;
+        }
+        for member in tree.members() {
+            let Some(DependencyNode::Crate(from)) = tree.node(member) else {
+                continue;
+            };
+            let dependencies = from
+                .children
+                .iter()
+                .flat_map(|&child| match tree.node(child) {
+                    Some(DependencyNode::Group(group)) => group.children.clone(),
+                    _ => vec![child],
+                });
+            for dependency in dependencies {
+                let Some(DependencyNode::Crate(to)) = tree.node(dependency) else {
+                    continue;
+                };
+                if tree.is_workspace_member(dependency)
+                    && !self.allows(&from.name, &to.name)
+                    && !violations.contains(&(member, dependency))
+                {
+                    violations.push((member, dependency));
+                }
+            }
+        }
+        violations
+    }
+}
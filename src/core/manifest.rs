@@ -0,0 +1,261 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    slice,
+};
+
+use crate::{Error, Result};
+
+use super::{Dependency, DependencyNode, DependencyTree, NodeId, dependency::DependencyType};
+
+/// Returns `key` of the `[workspace.metadata.tree-tui]` table (or
+/// `[package.metadata.tree-tui]` in a single-crate project) that `manifest`
+/// belongs to, looking in the manifest itself and then up to the workspace
+/// root.
+pub fn tree_tui_metadata(manifest: &Path, key: &str) -> Result<Option<toml::Value>> {
+    for dir in manifest.parent().into_iter().flat_map(Path::ancestors) {
+        let path = dir.join("Cargo.toml");
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut toml: toml::Table = toml::from_str(&contents).map_err(|err| {
+            Error::InvalidConfig(format!("{}: {}", path.display(), err.message()))
+        })?;
+        let is_workspace = toml.contains_key("workspace");
+        let mut metadata = |table: &str| {
+            toml.get_mut(table)?
+                .get_mut("metadata")?
+                .get_mut("tree-tui")?
+                .as_table_mut()?
+                .remove(key)
+        };
+        if let Some(value) = metadata("workspace").or_else(|| metadata("package")) {
+            return Ok(Some(value));
+        }
+        if is_workspace {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+/// Line of a `Cargo.toml` reported by [`locate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSpot {
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+    /// What is found there, e.g. `serde 1.0.200 in [dependencies] of app`.
+    pub description: String,
+}
+
+/// Lists where the crates matching `spec` (`name` or `name@version`) are
+/// found on disk: the manifest of each one whose sources are there, then
+/// the line declaring it in the manifest of each crate depending on it
+/// directly.
+///
+/// Declarations the line scanner misses point at the first line of the
+/// manifest, as in the open-in-editor action.
+pub fn locate(tree: &DependencyTree, spec: &str) -> Vec<ManifestSpot> {
+    let mut spots = Vec::new();
+    for id in tree.crate_nodes() {
+        let Some(DependencyNode::Crate(dependency)) = tree.node(id) else {
+            continue;
+        };
+        if !dependency.matches_spec(spec) {
+            continue;
+        }
+        let label = format!("{} {}", dependency.name, dependency.version);
+        if let Some(dir) = dependency
+            .source_dirs()
+            .into_iter()
+            .find(|dir| dir.join("Cargo.toml").is_file())
+        {
+            spots.push(ManifestSpot {
+                path: dir.join("Cargo.toml"),
+                line: 1,
+                description: format!("{label} manifest"),
+            });
+        }
+        for (owner, kind) in declaring_owners(tree, id) {
+            let Some(dir) = &owner.manifest_dir else {
+                continue;
+            };
+            let path = Path::new(dir).join("Cargo.toml");
+            let line = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| find_dependency_line(&contents, &dependency.name, kind))
+                .unwrap_or(1);
+            spots.push(ManifestSpot {
+                path,
+                line,
+                description: format!("{label} in {} of {}", kind.label(), owner.name),
+            });
+        }
+    }
+    spots
+}
+
+/// Finds where the workspace pins the crate `id` when a member inherits it
+/// with `workspace = true`: the line declaring it under
+/// `[workspace.dependencies]` in the workspace root manifest.
+pub fn workspace_declaration(tree: &DependencyTree, id: NodeId) -> Option<ManifestSpot> {
+    let dependency = tree.node(id)?.as_dependency()?;
+    let (owner, _) = declaring_owners(tree, id)
+        .into_iter()
+        .find(|(owner, kind)| {
+            owner
+                .declaration_on(&dependency.name, *kind)
+                .is_some_and(|declared| declared.inherited)
+        })?;
+    let member_dir = Path::new(owner.manifest_dir.as_ref()?);
+    member_dir.ancestors().find_map(|dir| {
+        let path = dir.join("Cargo.toml");
+        let contents = fs::read_to_string(&path).ok()?;
+        let toml: toml::Table = toml::from_str(&contents).ok()?;
+        if !toml.contains_key("workspace") {
+            return None;
+        }
+        Some(ManifestSpot {
+            line: find_line(&contents, &dependency.name, "workspace.dependencies").unwrap_or(1),
+            path,
+            description: format!("{} in [workspace.dependencies]", dependency.name),
+        })
+    })
+}
+
+/// Lists the crates depending directly on `id`, along with the kind of
+/// table declaring it in their manifest.
+fn declaring_owners(tree: &DependencyTree, id: NodeId) -> Vec<(&Dependency, DependencyType)> {
+    let mut owners = Vec::new();
+    for &parent in &tree.parents[id.0] {
+        let (ids, kind) = match tree.node(parent) {
+            Some(DependencyNode::Crate(_)) => (slice::from_ref(&parent), DependencyType::Normal),
+            Some(DependencyNode::Group(group)) => (tree.parents[parent.0].as_slice(), group.kind),
+            _ => continue,
+        };
+        owners.extend(
+            ids.iter()
+                .filter_map(|&owner| tree.node(owner)?.as_dependency())
+                .map(|owner| (owner, kind)),
+        );
+    }
+    owners
+}
+
+/// Finds the 1-based line declaring dependency `name` in a `Cargo.toml`.
+///
+/// This is a lightweight line scanner rather than a full TOML parser. It
+/// understands the layouts cargo and `cargo add` produce:
+///
+/// - `name = ...` / `"name" = ...` keys in a `[dependencies]`-like table,
+///   including `[target.'cfg(..)'.dependencies]`,
+/// - renamed dependencies (`alias = { package = "name", ... }`),
+/// - dotted tables such as `[dependencies.name]`.
+///
+/// Only the table matching `kind` is searched.
+pub fn find_dependency_line(contents: &str, name: &str, kind: DependencyType) -> Option<usize> {
+    find_line(contents, name, section_name(kind))
+}
+
+/// Finds the 1-based line declaring dependency `name` in the `section`
+/// table of a `Cargo.toml`, as described in [`find_dependency_line`].
+fn find_line(contents: &str, name: &str, section: &str) -> Option<usize> {
+    let mut in_section = false;
+
+    for (idx, raw_line) in contents.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let header = header.trim_matches(|c| c == '[' || c == ']').trim();
+            let (table, key) = split_header(header, section);
+            in_section = table;
+            if key.is_some_and(|key| unquote(key) == name) {
+                return Some(idx + 1);
+            }
+            continue;
+        }
+
+        if !in_section {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if unquote(key.trim()) == name || declares_package(value, name) {
+            return Some(idx + 1);
+        }
+    }
+
+    None
+}
+
+/// Returns the manifest table name for a dependency kind.
+fn section_name(kind: DependencyType) -> &'static str {
+    match kind {
+        DependencyType::Normal => "dependencies",
+        DependencyType::Dev => "dev-dependencies",
+        DependencyType::Build => "build-dependencies",
+    }
+}
+
+/// Splits a table header into whether it is the wanted dependency table and,
+/// for dotted headers like `dependencies.serde`, the dependency key.
+fn split_header<'a>(header: &'a str, section: &str) -> (bool, Option<&'a str>) {
+    let header = match header.strip_prefix("target.") {
+        // `target.'cfg(unix)'.dependencies` → `dependencies`
+        Some(_) => match header.find(&format!(".{section}")) {
+            Some(pos) => &header[pos + 1..],
+            None => return (false, None),
+        },
+        None => header,
+    };
+
+    match header.strip_prefix(section) {
+        Some("") => (true, None),
+        Some(rest) => match rest.strip_prefix('.') {
+            Some(key) => (false, Some(key.trim())),
+            None => (false, None),
+        },
+        None => (false, None),
+    }
+}
+
+/// Returns whether an inline-table value renames `name` via `package = "name"`.
+fn declares_package(value: &str, name: &str) -> bool {
+    value.split([',', '{', '}']).any(|part| {
+        part.split_once('=')
+            .is_some_and(|(key, val)| key.trim() == "package" && unquote(val.trim()) == name)
+    })
+}
+
+/// Removes a trailing `#` comment that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = None;
+    for (idx, ch) in line.char_indices() {
+        match (ch, in_string) {
+            ('"' | '\'', None) => in_string = Some(ch),
+            (c, Some(quote)) if c == quote => in_string = None,
+            ('#', None) => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Strips matching surrounding quotes from a TOML key or string.
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
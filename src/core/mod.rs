@@ -1,3 +1,26 @@
+pub mod annotate;
+pub mod bloat;
+pub mod cache;
+pub mod changelog;
+pub mod commands;
+pub mod cost;
+pub mod crates_io;
 pub mod dependency;
+pub mod diet;
+pub mod diff;
+pub mod drift;
+pub mod export;
+pub mod http;
+pub mod layers;
+pub mod manifest;
+pub mod no_std;
+pub mod occurrences;
+pub mod profile;
+pub mod query;
+pub mod registry;
+pub mod remote;
+pub mod stats;
+pub mod trust;
+pub mod workspace_state;
 
 pub use dependency::{Dependency, DependencyGroup, DependencyNode, DependencyTree, NodeId};
@@ -0,0 +1,125 @@
+use std::{fs, path::Path};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{Dependency, DependencyNode, DependencyTree, NodeId};
+
+/// How a crate pulls in `std`, as guessed from its sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StdUse {
+    /// The library never declares `#![no_std]`.
+    Always,
+    /// The library supports `no_std`, but its `std` feature is enabled.
+    Feature,
+}
+
+impl StdUse {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Always => "needs std",
+            Self::Feature => "std feature",
+        }
+    }
+}
+
+/// Returns whether `source` declares `#![no_std]`, unconditionally or
+/// through `cfg_attr`.
+pub fn declares_no_std(source: &str) -> bool {
+    source.lines().map(str::trim_start).any(|line| {
+        line.starts_with("#![")
+            && line
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|word| word == "no_std")
+    })
+}
+
+/// Guesses how `dependency` pulls in `std` from its library root and
+/// manifest, `None` when it does not or its sources are not on disk.
+///
+/// Proc-macros only run on the host and are skipped.
+pub fn std_use(dependency: &Dependency) -> Option<StdUse> {
+    if dependency.is_proc_macro {
+        return None;
+    }
+    let dir = dependency
+        .source_dirs()
+        .into_iter()
+        .find(|dir| dir.is_dir())?;
+    let source = fs::read_to_string(dir.join("src/lib.rs")).ok()?;
+    if !declares_no_std(&source) {
+        return Some(StdUse::Always);
+    }
+    let std_enabled = dependency.features.iter().any(|feature| feature == "std")
+        || dependency.default_features && default_enables_std(&dir);
+    std_enabled.then_some(StdUse::Feature)
+}
+
+/// Returns whether the `default` feature in the manifest below `dir` lists
+/// `std`.
+fn default_enables_std(dir: &Path) -> bool {
+    let Ok(manifest) = fs::read_to_string(dir.join("Cargo.toml")) else {
+        return false;
+    };
+    let Ok(manifest) = manifest.parse::<toml::Table>() else {
+        return false;
+    };
+    manifest
+        .get("features")
+        .and_then(|features| features.get("default"))
+        .and_then(toml::Value::as_array)
+        .is_some_and(|default| default.iter().any(|value| value.as_str() == Some("std")))
+}
+
+/// Returns whether a workspace member declares `#![no_std]` in its library
+/// or binary root.
+fn member_is_no_std(member: &Dependency) -> bool {
+    let Some(dir) = &member.manifest_dir else {
+        return false;
+    };
+    ["src/lib.rs", "src/main.rs"].iter().any(|root| {
+        fs::read_to_string(Path::new(dir).join(root)).is_ok_and(|source| declares_no_std(&source))
+    })
+}
+
+/// Finds crates pulling `std` into workspace members declaring `#![no_std]`.
+///
+/// Walks the normal dependencies of those members only, since build and
+/// dev dependencies run on the host. Empty when no member is `no_std`.
+pub fn std_creep(tree: &DependencyTree) -> FxHashMap<NodeId, StdUse> {
+    let mut stack: Vec<NodeId> = tree
+        .members()
+        .into_iter()
+        .filter(|&id| {
+            tree.node(id)
+                .and_then(DependencyNode::as_dependency)
+                .is_some_and(member_is_no_std)
+        })
+        .collect();
+    let mut visited: FxHashSet<NodeId> = stack.iter().copied().collect();
+    let mut creep = FxHashMap::default();
+    while let Some(id) = stack.pop() {
+        let Some(node) = tree.node(id) else {
+            continue;
+        };
+        for &child in node.children() {
+            let Some(DependencyNode::Crate(dependency)) = tree.node(child) else {
+                continue;
+            };
+            if !visited.insert(child) {
+                continue;
+            }
+            if tree.is_workspace_member(child) {
+                // Members speak for themselves.
+                if !member_is_no_std(dependency) {
+                    creep.insert(child, StdUse::Always);
+                }
+            } else if let Some(std_use) = std_use(dependency) {
+                creep.insert(child, std_use);
+            }
+            if !dependency.is_proc_macro {
+                stack.push(child);
+            }
+        }
+    }
+    creep
+}
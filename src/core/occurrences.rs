@@ -0,0 +1,84 @@
+use rustc_hash::FxHashMap;
+
+use super::{
+    Dependency, DependencyGroup, DependencyNode, DependencyTree, NodeId, dependency::DependencyType,
+};
+
+/// Returns an inverted index over `tree`: every crate listed once at the top
+/// level, with the crates depending on it as children.
+///
+/// Those children list their own dependents in turn, so expanding a crate
+/// walks every path from it back to the workspace members. Dev and build
+/// dependents are nested under a group node of their kind, as in the
+/// regular tree. Crates are sorted by name and version; target and folder
+/// nodes are left out.
+pub fn occurrences(tree: &DependencyTree) -> DependencyTree {
+    let mut crates: Vec<(NodeId, &Dependency)> = tree
+        .crate_nodes()
+        .filter_map(|id| Some((id, tree.node(id)?.as_dependency()?)))
+        .collect();
+    crates.sort_by(|(_, a), (_, b)| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    let new_ids: FxHashMap<NodeId, NodeId> = crates
+        .iter()
+        .enumerate()
+        .map(|(idx, &(id, _))| (id, NodeId(idx)))
+        .collect();
+
+    let mut nodes: Vec<DependencyNode> = Vec::with_capacity(crates.len());
+    let mut groups: Vec<DependencyNode> = Vec::new();
+    for &(id, dependency) in &crates {
+        let mut children = Vec::new();
+        let mut dev = Vec::new();
+        let mut build = Vec::new();
+        for &parent in &tree.parents[id.0] {
+            let (list, owners) = match tree.node(parent) {
+                Some(DependencyNode::Crate(_)) => (&mut children, std::slice::from_ref(&parent)),
+                Some(DependencyNode::Group(group)) => {
+                    let list = match group.kind {
+                        DependencyType::Normal => &mut children,
+                        DependencyType::Dev => &mut dev,
+                        DependencyType::Build => &mut build,
+                    };
+                    (list, tree.parents[parent.0].as_slice())
+                }
+                _ => continue,
+            };
+            for owner in owners
+                .iter()
+                .filter_map(|owner| new_ids.get(owner).copied())
+            {
+                if !list.contains(&owner) {
+                    list.push(owner);
+                }
+            }
+        }
+        for (kind, dependents) in [(DependencyType::Dev, dev), (DependencyType::Build, build)] {
+            if dependents.is_empty() {
+                continue;
+            }
+            children.push(NodeId(crates.len() + groups.len()));
+            groups.push(DependencyNode::Group(DependencyGroup {
+                kind,
+                children: dependents,
+            }));
+        }
+        nodes.push(DependencyNode::Crate(Dependency {
+            children,
+            ..dependency.clone()
+        }));
+    }
+    nodes.extend(groups);
+
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        for &child in node.children() {
+            parents[child.0].push(NodeId(idx));
+        }
+    }
+    DependencyTree {
+        workspace_name: tree.workspace_name.clone(),
+        roots: (0..crates.len()).map(NodeId).collect(),
+        nodes,
+        parents,
+    }
+}
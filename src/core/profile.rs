@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use crate::{Error, Result};
+
+use super::manifest::tree_tui_metadata;
+
+/// Returns the command-line flags bundled by the startup profile `name`.
+///
+/// Profiles live in the `profile` table of `[workspace.metadata.tree-tui]`
+/// (or `[package.metadata.tree-tui]` in a single-crate project), keyed by
+/// long flag name:
+///
+/// ```toml
+/// [workspace.metadata.tree-tui.profile.security]
+/// trust = true
+/// edges = ["no-dev"]
+/// expand = "members"
+/// ```
+pub fn profile_args(manifest: &Path, name: &str) -> Result<Vec<String>> {
+    let missing = || Error::InvalidConfig(format!("no profile `{name}` in `tree-tui.profile`"));
+    let profiles = tree_tui_metadata(manifest, "profile")?.ok_or_else(missing)?;
+    let profile = profiles.get(name).ok_or_else(missing)?;
+    let Some(flags) = profile.as_table() else {
+        return Err(Error::InvalidConfig(format!(
+            "profile `{name}`, expected a table of flags"
+        )));
+    };
+    flags_to_args(flags)
+}
+
+/// Turns a table of flags into arguments: `true` enables a switch, `false`
+/// leaves it out, and arrays repeat the flag once per value.
+pub fn flags_to_args(flags: &toml::Table) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    for (flag, value) in flags {
+        let values = match value {
+            toml::Value::Boolean(false) => continue,
+            toml::Value::Boolean(true) => {
+                args.push(format!("--{flag}"));
+                continue;
+            }
+            toml::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(value) => value.to_string(),
+                _ => {
+                    return Err(Error::InvalidConfig(format!(
+                        "profile flag `{flag}`, expected a boolean, string, integer or array"
+                    )));
+                }
+            };
+            args.push(format!("--{flag}"));
+            args.push(value);
+        }
+    }
+    Ok(args)
+}
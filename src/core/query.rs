@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+use super::{DependencyNode, DependencyTree, NodeId, stats::GraphStats};
+
+/// Graph query run without the TUI, e.g. from `cargo tree-tui query`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// Shortest path from each workspace member to the matching crates.
+    PathsTo(String),
+    /// Crates resolved in more than one version.
+    Duplicates,
+    /// Crates directly depending on the matching crates.
+    ReverseDeps(String),
+    /// Everything the matching crates transitively depend on.
+    Closure(String),
+}
+
+/// A crate in query output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrateRef {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize)]
+struct Duplicate {
+    name: String,
+    versions: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Related {
+    #[serde(rename = "crate")]
+    krate: CrateRef,
+    crates: Vec<CrateRef>,
+}
+
+impl Query {
+    /// Parses a query from its words, e.g. `["paths-to", "serde"]`.
+    ///
+    /// Crates are given as `name` or `name@version`.
+    pub fn parse(words: &[String]) -> Result<Self> {
+        let invalid = || {
+            Error::InvalidQuery(format!(
+                "`{}`; expected `paths-to <crate>`, `duplicates`, \
+                 `reverse-deps <crate>` or `closure <crate>`",
+                words.join(" ")
+            ))
+        };
+        match words {
+            [query] if query == "duplicates" => Ok(Self::Duplicates),
+            [query, spec] => match query.as_str() {
+                "paths-to" => Ok(Self::PathsTo(spec.clone())),
+                "reverse-deps" => Ok(Self::ReverseDeps(spec.clone())),
+                "closure" => Ok(Self::Closure(spec.clone())),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Runs the query, returning its result as JSON.
+    pub fn run(&self, tree: &DependencyTree) -> Result<Value> {
+        let value = match self {
+            Self::PathsTo(spec) => {
+                let targets = find_crates(tree, spec)?;
+                let paths: Vec<Vec<CrateRef>> = tree
+                    .members()
+                    .into_iter()
+                    .flat_map(|member| {
+                        targets
+                            .iter()
+                            .filter_map(move |&target| shortest_path(tree, member, target))
+                    })
+                    .map(|path| crate_refs(tree, path))
+                    .collect();
+                serde_json::to_value(paths)
+            }
+            Self::Duplicates => {
+                let duplicates: Vec<Duplicate> = GraphStats::compute(tree)
+                    .duplicates
+                    .into_iter()
+                    .map(|(name, versions)| Duplicate { name, versions })
+                    .collect();
+                serde_json::to_value(duplicates)
+            }
+            Self::ReverseDeps(spec) => serde_json::to_value(related(tree, spec, |id| {
+                tree.sorted_by_name(tree.dependents(id).into_iter())
+            })?),
+            Self::Closure(spec) => serde_json::to_value(related(tree, spec, |id| {
+                tree.sorted_by_name(tree.transitive_dependencies(id).into_iter())
+            })?),
+        };
+        // Query output only holds strings and lists, which always serialize.
+        Ok(value.unwrap_or_default())
+    }
+}
+
+/// Returns the crates matching `name` or `name@version`.
+fn find_crates(tree: &DependencyTree, spec: &str) -> Result<Vec<NodeId>> {
+    let matches: Vec<NodeId> = tree
+        .crate_nodes()
+        .filter(|&id| {
+            tree.node(id)
+                .and_then(DependencyNode::as_dependency)
+                .is_some_and(|dep| dep.matches_spec(spec))
+        })
+        .collect();
+    if matches.is_empty() {
+        return Err(Error::NotFound(format!("no crate matches `{spec}`")));
+    }
+    Ok(matches)
+}
+
+/// Pairs each crate matching `spec` with the crates picked by `pick`.
+fn related(
+    tree: &DependencyTree,
+    spec: &str,
+    pick: impl Fn(NodeId) -> Vec<NodeId>,
+) -> Result<Vec<Related>> {
+    Ok(find_crates(tree, spec)?
+        .into_iter()
+        .map(|id| Related {
+            krate: crate_refs(tree, vec![id]).remove(0),
+            crates: crate_refs(tree, pick(id)),
+        })
+        .collect())
+}
+
+/// Finds a shortest chain of nodes from `from` to `to` by breadth-first search.
+fn shortest_path(tree: &DependencyTree, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+    let mut previous: Vec<Option<NodeId>> = vec![None; tree.nodes.len()];
+    let mut seen = vec![false; tree.nodes.len()];
+    let mut queue = VecDeque::from([from]);
+    seen[from.0] = true;
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut path = vec![id];
+            while let Some(prev) = previous[path[path.len() - 1].0] {
+                path.push(prev);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &child in tree
+            .node(id)
+            .map(DependencyNode::children)
+            .unwrap_or_default()
+        {
+            if !std::mem::replace(&mut seen[child.0], true) {
+                previous[child.0] = Some(id);
+                queue.push_back(child);
+            }
+        }
+    }
+    None
+}
+
+/// Converts node ids to crate references, dropping group nodes.
+fn crate_refs(tree: &DependencyTree, ids: Vec<NodeId>) -> Vec<CrateRef> {
+    ids.into_iter()
+        .filter_map(|id| tree.node(id)?.as_dependency())
+        .map(|dep| CrateRef {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+        })
+        .collect()
+}
@@ -0,0 +1,299 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use cargo::GlobalContext;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+use super::http;
+
+/// Name Cargo gives the crates.io registry.
+pub const CRATES_IO: &str = "crates-io";
+
+const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+const CRATES_IO_SPARSE_INDEX: &str = "https://index.crates.io";
+
+/// Registry a crate was published to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Registry {
+    /// Name from the `[registries]` config, or the index host if unnamed.
+    pub name: String,
+    /// Index URL without the `registry+` or `sparse+` prefix.
+    pub index: String,
+    /// Whether the index is served over the sparse HTTP protocol.
+    pub sparse: bool,
+    /// Sparse index replacing this one through `[source]` replacement in
+    /// the Cargo config, e.g. a corporate mirror.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<String>,
+}
+
+impl Registry {
+    /// Parses a source URL as written in `Cargo.lock`, e.g.
+    /// `registry+https://github.com/rust-lang/crates.io-index` or
+    /// `sparse+https://my-registry.example/index/`.
+    ///
+    /// Returns `None` for git, path and local registry sources.
+    pub fn from_source(source: &str, names: &RegistryNames) -> Option<Self> {
+        let (index, sparse) = if let Some(index) = source.strip_prefix("registry+") {
+            (index, false)
+        } else {
+            (source.strip_prefix("sparse+")?, true)
+        };
+        let index = index.split(['?', '#']).next().unwrap_or(index);
+        let index = normalize(index);
+
+        let name = if index == CRATES_IO_INDEX || index == CRATES_IO_SPARSE_INDEX {
+            CRATES_IO.to_string()
+        } else if let Some(name) = names.name_of(index) {
+            name.to_string()
+        } else {
+            host(index).to_string()
+        };
+        Some(Self {
+            mirror: names.mirror_of(&name).map(str::to_string),
+            name,
+            index: index.to_string(),
+            sparse,
+        })
+    }
+
+    pub fn is_crates_io(&self) -> bool {
+        self.name == CRATES_IO
+    }
+
+    /// Returns the sparse index to query: the configured mirror if any,
+    /// else crates.io's sparse index for its git one.
+    pub fn sparse_index(&self) -> Option<&str> {
+        if let Some(mirror) = &self.mirror {
+            Some(mirror)
+        } else if self.is_crates_io() {
+            Some(CRATES_IO_SPARSE_INDEX)
+        } else {
+            self.sparse.then_some(self.index.as_str())
+        }
+    }
+
+    /// Fetches the newest release of `name` from the sparse index.
+    ///
+    /// Registries only reachable through a git index are not supported.
+    pub fn latest_version(&self, name: &str) -> Result<Option<String>> {
+        let Some(index) = self.sparse_index() else {
+            return Err(Error::NotFound(format!(
+                "registry {} has no sparse index",
+                self.name
+            )));
+        };
+        let body = http::get(&format!("{index}/{}", index_path(name)))?
+            .ok_or_else(|| Error::NotFound(format!("{name} is not in the {} index", self.name)))?;
+        Ok(latest_in_index(&body))
+    }
+
+    /// Returns the index entries of `name` that Cargo cached under
+    /// `cargo_home` when it last updated this registry, as index file lines.
+    ///
+    /// Cached copies of the git and sparse index, and of the mirror, are
+    /// all read.
+    pub fn cached_index(&self, cargo_home: &Path, name: &str) -> Option<String> {
+        let mut hosts = vec![host(&self.index)];
+        hosts.extend(self.mirror.as_deref().map(host));
+        if self.is_crates_io() {
+            hosts.push(host(CRATES_IO_SPARSE_INDEX));
+        }
+        let path = index_path(name);
+        let lines: Vec<String> = fs::read_dir(cargo_home.join("registry").join("index"))
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let dir = entry.file_name();
+                let dir = dir.to_string_lossy();
+                hosts.iter().any(|host| {
+                    dir.strip_prefix(host)
+                        .is_some_and(|rest| rest.starts_with('-'))
+                })
+            })
+            .filter_map(|entry| fs::read(entry.path().join(".cache").join(&path)).ok())
+            .flat_map(|cache| {
+                // Entries are stored as NUL-separated version and JSON pairs
+                // after a small header.
+                cache
+                    .split(|&byte| byte == 0)
+                    .filter(|chunk| chunk.starts_with(b"{"))
+                    .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// Registry names by index URL, from the `[registries]` Cargo config, and
+/// the sparse mirrors `[source]` replacement points them to.
+#[derive(Debug, Clone, Default)]
+pub struct RegistryNames {
+    names: Vec<(String, String)>,
+    /// Sparse index replacing each registry, by registry name.
+    mirrors: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct RegistryConfig {
+    index: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SourceConfig {
+    replace_with: Option<String>,
+    registry: Option<String>,
+}
+
+impl RegistryNames {
+    pub fn new(registries: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            names: registries
+                .into_iter()
+                .map(|(name, index)| {
+                    let index = index.strip_prefix("sparse+").unwrap_or(&index);
+                    (name, normalize(index).to_string())
+                })
+                .collect(),
+            mirrors: Vec::new(),
+        }
+    }
+
+    /// Sets the sparse indexes replacing registries, keyed by registry name.
+    ///
+    /// Replacements that are not sparse registries (git indexes, vendored
+    /// directories) are ignored, keeping lookups on the original index.
+    pub fn with_replacements(
+        mut self,
+        replacements: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.mirrors = replacements
+            .into_iter()
+            .filter_map(|(name, index)| {
+                let index = index.strip_prefix("sparse+")?;
+                Some((name, normalize(index).to_string()))
+            })
+            .collect();
+        self
+    }
+
+    /// Reads the configured registries and source replacements, ignoring a
+    /// malformed config.
+    pub(crate) fn from_config(gctx: &GlobalContext) -> Self {
+        let registries = gctx
+            .get::<Option<BTreeMap<String, RegistryConfig>>>("registries")
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let sources = gctx
+            .get::<Option<BTreeMap<String, SourceConfig>>>("source")
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        // Follow `replace-with` chains to the registry finally used.
+        let replacements = sources.iter().filter_map(|(name, source)| {
+            let mut replacement = sources.get(source.replace_with.as_ref()?)?;
+            for _ in 0..sources.len() {
+                match &replacement.replace_with {
+                    Some(next) => replacement = sources.get(next)?,
+                    None => break,
+                }
+            }
+            Some((name.clone(), replacement.registry.clone()?))
+        });
+        Self::new(
+            registries
+                .into_iter()
+                .filter_map(|(name, config)| Some((name, config.index?))),
+        )
+        .with_replacements(replacements)
+    }
+
+    fn name_of(&self, index: &str) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(_, url)| url == index)
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn mirror_of(&self, name: &str) -> Option<&str> {
+        self.mirrors
+            .iter()
+            .find(|(registry, _)| registry == name)
+            .map(|(_, index)| index.as_str())
+    }
+}
+
+/// Returns the path of `name` in a registry index, e.g. `se/rd/serde`.
+pub fn index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+    /// Publish time, e.g. `2025-11-12T19:30:12Z`, missing from older entries.
+    pubtime: Option<String>,
+}
+
+/// Returns when `version` was published according to an index file, for
+/// registries recording publish times.
+pub fn published_in_index(body: &str, version: &str) -> Option<String> {
+    body.lines()
+        .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+        .find(|entry| entry.vers == version)?
+        .pubtime
+}
+
+/// Returns the newest non-yanked version in an index file, preferring
+/// stable releases over pre-releases.
+pub fn latest_in_index(body: &str) -> Option<String> {
+    body.lines()
+        .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Version::parse(&entry.vers).ok())
+        .max_by_key(|version| (version.pre.is_empty(), version.clone()))
+        .map(|version| version.to_string())
+}
+
+/// Returns the newest non-yanked version in an index file matching
+/// `requirement`.
+pub fn newest_matching(body: &str, requirement: &str) -> Option<String> {
+    let requirement = VersionReq::parse(requirement).ok()?;
+    body.lines()
+        .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Version::parse(&entry.vers).ok())
+        .filter(|version| requirement.matches(version))
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// Returns whether `latest` is newer than `version`.
+pub fn is_outdated(version: &str, latest: &str) -> bool {
+    match (Version::parse(version), Version::parse(latest)) {
+        (Ok(version), Ok(latest)) => latest > version,
+        _ => false,
+    }
+}
+
+fn normalize(index: &str) -> &str {
+    index.trim_end_matches('/')
+}
+
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
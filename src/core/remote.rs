@@ -0,0 +1,129 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use semver::VersionReq;
+
+use crate::{Error, Result};
+
+use super::{DependencyTree, cache::cache_root};
+
+/// Registry crate to explore without adding it to a workspace, given as
+/// `name` or `name@version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteCrate {
+    pub name: String,
+    /// Version requirement, e.g. `1.38` for the newest `1.38.x`; any
+    /// version when `None`.
+    pub version: Option<String>,
+}
+
+impl FromStr for RemoteCrate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, version) = match s.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (s, None),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("expected `<name>[@<version>]`, got `{s}`"));
+        }
+        if let Some(version) = version {
+            VersionReq::parse(version)
+                .map_err(|err| format!("invalid version `{version}`: {err}"))?;
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        })
+    }
+}
+
+impl RemoteCrate {
+    /// Writes a throwaway package depending only on this crate and returns
+    /// its manifest, so that Cargo resolves the crate's dependency tree.
+    ///
+    /// The package lives in the per-user cache directory, or in a fresh
+    /// private directory under the temporary directory when there is none,
+    /// so other users cannot plant Cargo config next to it. Its lockfile is
+    /// removed so that every run resolves the newest matching versions.
+    pub fn create_project(&self) -> Result<PathBuf> {
+        let version = self.version.as_deref().unwrap_or("*");
+        let name = format!(
+            "{}-{}",
+            self.name,
+            version.replace(['*', '^', '~', '=', '<', '>', ',', ' '], "")
+        );
+        let io_error = |path: PathBuf| move |source| Error::Io { path, source };
+        let dir = match cache_root() {
+            Some(root) => {
+                let dir = root.join("probes").join(name);
+                fs::create_dir_all(&dir).map_err(io_error(dir.clone()))?;
+                dir
+            }
+            None => {
+                let dir =
+                    env::temp_dir().join(format!("cargo-tree-tui-{}-{name}", std::process::id()));
+                private_dir(&dir).map_err(io_error(dir.clone()))?;
+                dir
+            }
+        };
+        let manifest = dir.join("Cargo.toml");
+        let contents = format!(
+            "[package]\n\
+             name = \"tree-tui-probe\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"2021\"\n\
+             publish = false\n\n\
+             [dependencies]\n\
+             {} = \"{version}\"\n\n\
+             [workspace]\n",
+            self.name
+        );
+        let lockfile = dir.join("Cargo.lock");
+        match fs::remove_file(&lockfile) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                return Err(io_error(lockfile)(err));
+            }
+            _ => {}
+        }
+        fs::create_dir_all(dir.join("src")).map_err(io_error(dir.clone()))?;
+        fs::write(dir.join("src/lib.rs"), "").map_err(io_error(dir.join("src/lib.rs")))?;
+        fs::write(&manifest, contents).map_err(io_error(manifest.clone()))?;
+        Ok(manifest)
+    }
+
+    /// Returns the tree below this crate in the tree of its probe package.
+    pub fn root_of(&self, tree: &DependencyTree) -> Result<DependencyTree> {
+        let id = tree
+            .roots()
+            .iter()
+            .filter_map(|&root| tree.node(root))
+            .flat_map(|root| root.children())
+            .copied()
+            .find(|&child| {
+                tree.node(child)
+                    .and_then(|node| node.as_dependency())
+                    .is_some_and(|dependency| dependency.name == self.name)
+            })
+            .ok_or_else(|| Error::NotFound(format!("`{}` was not resolved", self.name)))?;
+        let mut rooted = tree.rooted_at(id);
+        rooted.workspace_name = self.name.clone();
+        Ok(rooted)
+    }
+}
+
+/// Creates `dir` readable only by the current user, failing if it exists.
+fn private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
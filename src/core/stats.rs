@@ -0,0 +1,152 @@
+use rustc_hash::FxHashMap;
+
+use super::{DependencyNode, DependencyTree, NodeId, dependency::SourceKind};
+
+/// Label used for crates that do not declare a license.
+const UNKNOWN_LICENSE: &str = "unknown";
+
+/// Aggregate statistics about a [`DependencyTree`].
+#[derive(Debug, Clone, Default)]
+pub struct GraphStats {
+    /// Number of crate nodes (one per resolved package version).
+    pub total_crates: usize,
+    /// Number of distinct crate names.
+    pub unique_crates: usize,
+    /// Crates resolved in more than one version, as `(name, versions)`.
+    pub duplicates: Vec<(String, Vec<String>)>,
+    /// Crates with the most distinct dependents, as `(node, dependents)`.
+    pub most_depended: Vec<(NodeId, usize)>,
+    /// Longest dependency chain starting from a workspace member.
+    pub deepest_chain: Vec<NodeId>,
+    /// Number of crates per license expression.
+    pub licenses: Vec<(String, usize)>,
+    /// Number of crates per source kind.
+    pub sources: Vec<(SourceKind, usize)>,
+    /// Edges closing a dependency cycle, see [`DependencyTree::back_edges`].
+    pub cycles: Vec<(NodeId, NodeId)>,
+}
+
+impl GraphStats {
+    /// Number of entries kept in [`GraphStats::most_depended`].
+    pub const TOP_DEPENDED: usize = 10;
+
+    /// Computes statistics for the whole tree.
+    pub fn compute(tree: &DependencyTree) -> Self {
+        let mut stats = Self::default();
+        let mut versions: FxHashMap<&str, Vec<String>> = FxHashMap::default();
+        let mut licenses: FxHashMap<&str, usize> = FxHashMap::default();
+        let mut sources: FxHashMap<SourceKind, usize> = FxHashMap::default();
+
+        for id in tree.crate_nodes() {
+            let Some(DependencyNode::Crate(dependency)) = tree.node(id) else {
+                continue;
+            };
+            stats.total_crates += 1;
+            versions
+                .entry(dependency.name.as_str())
+                .or_default()
+                .push(dependency.version.clone());
+            let license = dependency.license.as_deref().unwrap_or(UNKNOWN_LICENSE);
+            *licenses.entry(license).or_default() += 1;
+            *sources.entry(dependency.source).or_default() += 1;
+            stats.most_depended.push((id, tree.dependents(id).len()));
+        }
+
+        stats.unique_crates = versions.len();
+        stats.duplicates = versions
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(name, mut versions)| {
+                versions.sort();
+                (name.to_owned(), versions)
+            })
+            .collect();
+        stats
+            .duplicates
+            .sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+        stats
+            .most_depended
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.0.cmp(&b.0.0)));
+        stats.most_depended.truncate(Self::TOP_DEPENDED);
+
+        stats.licenses = sorted_counts(licenses.into_iter().map(|(k, v)| (k.to_owned(), v)));
+        stats.sources = sources.into_iter().collect();
+        stats
+            .sources
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.label().cmp(b.0.label())));
+        stats.deepest_chain = deepest_chain(tree);
+        stats.cycles = tree.back_edges();
+        stats
+    }
+}
+
+/// Sorts `(label, count)` pairs by descending count, then by label.
+fn sorted_counts(counts: impl Iterator<Item = (String, usize)>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Finds the longest chain of crates starting at a workspace member.
+///
+/// Group nodes are walked through but not counted. Back-edges (cycles through
+/// dev-dependencies) are treated as leaves, mirroring the view cache.
+pub fn deepest_chain(tree: &DependencyTree) -> Vec<NodeId> {
+    let mut depth: Vec<Option<usize>> = vec![None; tree.nodes.len()];
+    let mut next: Vec<Option<NodeId>> = vec![None; tree.nodes.len()];
+    let mut in_progress = vec![false; tree.nodes.len()];
+
+    let Some(root) = tree
+        .members()
+        .into_iter()
+        .max_by_key(|&root| chain_depth(tree, root, &mut depth, &mut next, &mut in_progress))
+    else {
+        return Vec::new();
+    };
+
+    let mut chain = Vec::new();
+    let mut current = Some(root);
+    while let Some(id) = current {
+        if chain.contains(&id) {
+            break;
+        }
+        if tree.node(id).is_some_and(DependencyNode::is_crate) {
+            chain.push(id);
+        }
+        current = next[id.0];
+    }
+    chain
+}
+
+fn chain_depth(
+    tree: &DependencyTree,
+    id: NodeId,
+    depth: &mut [Option<usize>],
+    next: &mut [Option<NodeId>],
+    in_progress: &mut [bool],
+) -> usize {
+    if in_progress[id.0] {
+        return 0;
+    }
+    if let Some(known) = depth[id.0] {
+        return known;
+    }
+
+    in_progress[id.0] = true;
+    let mut best = 0;
+    if let Some(node) = tree.node(id) {
+        for &child in node.children() {
+            let child_depth = chain_depth(tree, child, depth, next, in_progress);
+            if child_depth > best {
+                best = child_depth;
+                next[id.0] = Some(child);
+            }
+        }
+    }
+    in_progress[id.0] = false;
+
+    let own = usize::from(tree.node(id).is_some_and(DependencyNode::is_crate));
+    depth[id.0] = Some(best + own);
+    best + own
+}
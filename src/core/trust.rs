@@ -0,0 +1,135 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::Result;
+
+use super::{changelog::github_repo, crates_io::CrateInfo, http};
+
+/// Releases older than this many days are flagged as stale.
+pub const STALE_DAYS: i64 = 2 * 365;
+/// Versions younger than this many days are flagged as fresh.
+pub const FRESH_DAYS: i64 = 7;
+
+/// Outcome of checking that a repository URL resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoStatus {
+    /// No repository is declared.
+    Undeclared,
+    /// The repository exists; stars are known for GitHub repositories.
+    Resolves { stars: Option<u64> },
+    /// The repository URL returns 404.
+    NotFound,
+    /// The check failed, e.g. offline or rate limited.
+    Unknown(String),
+}
+
+/// Supply-chain heuristics for a crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustSignals {
+    /// Days since the resolved version was published.
+    pub version_age_days: Option<i64>,
+    /// Days since the newest release.
+    pub last_release_age_days: Option<i64>,
+    pub owners: usize,
+    pub repository: RepoStatus,
+}
+
+impl TrustSignals {
+    /// Derives the signals from crates.io data, `today` being days since the
+    /// Unix epoch.
+    pub fn new(info: &CrateInfo, repository: RepoStatus, today: i64) -> Self {
+        let age = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(days_since_epoch)
+                .map(|day| today - day)
+        };
+        Self {
+            version_age_days: age(&info.published),
+            last_release_age_days: age(&info.last_release),
+            owners: info.owners.len(),
+            repository,
+        }
+    }
+
+    /// Returns short descriptions of the signals worth a closer look.
+    pub fn warnings(&self) -> Vec<&'static str> {
+        let mut warnings = Vec::new();
+        if self.version_age_days.is_some_and(|days| days < FRESH_DAYS) {
+            warnings.push("version published this week");
+        }
+        if self
+            .last_release_age_days
+            .is_some_and(|days| days > STALE_DAYS)
+        {
+            warnings.push("no release in over two years");
+        }
+        if self.owners == 1 {
+            warnings.push("single owner");
+        }
+        match self.repository {
+            RepoStatus::Undeclared => warnings.push("no repository declared"),
+            RepoStatus::NotFound => warnings.push("repository not found"),
+            RepoStatus::Resolves { .. } | RepoStatus::Unknown(_) => {}
+        }
+        warnings
+    }
+}
+
+#[derive(Deserialize)]
+struct GithubRepo {
+    stargazers_count: u64,
+}
+
+/// Checks that `repository` resolves, fetching the star count on GitHub.
+pub fn check_repository(repository: Option<&str>) -> RepoStatus {
+    let Some(repository) = repository else {
+        return RepoStatus::Undeclared;
+    };
+    let result = match github_repo(repository) {
+        Some((owner, repo)) => github_stars(owner, repo),
+        None => http::get(repository).map(|body| body.map(|_| None)),
+    };
+    match result {
+        Ok(Some(stars)) => RepoStatus::Resolves { stars },
+        Ok(None) => RepoStatus::NotFound,
+        Err(err) => RepoStatus::Unknown(err.to_string()),
+    }
+}
+
+fn github_stars(owner: &str, repo: &str) -> Result<Option<Option<u64>>> {
+    let Some(body) = http::get(&format!("https://api.github.com/repos/{owner}/{repo}"))? else {
+        return Ok(None);
+    };
+    let stars = serde_json::from_str::<GithubRepo>(&body)
+        .ok()
+        .map(|repo| repo.stargazers_count);
+    Ok(Some(stars))
+}
+
+/// Returns today as days since the Unix epoch.
+pub fn today() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| (elapsed.as_secs() / 86_400) as i64)
+}
+
+/// Converts a `YYYY-MM-DD` date into days since the Unix epoch.
+pub fn days_since_epoch(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.get(..2)?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Howard Hinnant's days_from_civil.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146_097 + day_of_era - 719_468)
+}
@@ -0,0 +1,128 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Search queries remembered per workspace.
+pub const MAX_SEARCH_HISTORY: usize = 100;
+
+/// State of one workspace kept between sessions, e.g. search history,
+/// pinned crates and notes.
+///
+/// Stored as JSON under `$XDG_STATE_HOME/cargo-tree-tui`, falling back to
+/// `~/.local/state`, keyed by the workspace manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceState {
+    /// Committed search queries, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_history: Vec<String>,
+    /// Names of the crates pinned for quick access, in pinning order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<String>,
+    /// Free-text notes attached to crates, keyed by crate name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<String, String>,
+    /// File the state is read from and saved to.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl WorkspaceState {
+    /// Loads the state of the workspace `manifest` belongs to, starting
+    /// empty when there is no usable state directory.
+    pub fn load(manifest: &Path) -> Self {
+        state_file(manifest).map_or_else(Self::default, |path| Self::read(&path))
+    }
+
+    /// Reads the state saved in `path`, starting empty when the file is
+    /// missing or malformed.
+    pub fn read(path: &Path) -> Self {
+        let state = fs::read(path)
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path.to_path_buf()),
+            ..state
+        }
+    }
+
+    /// Pins `name`, or unpins it if already pinned. Returns whether the
+    /// crate is pinned now.
+    pub fn toggle_pin(&mut self, name: &str) -> bool {
+        if let Some(idx) = self.pins.iter().position(|pin| pin == name) {
+            self.pins.remove(idx);
+            false
+        } else {
+            self.pins.push(name.to_string());
+            true
+        }
+    }
+
+    /// Returns the note attached to `name`, if any.
+    pub fn note(&self, name: &str) -> Option<&str> {
+        self.notes.get(name).map(String::as_str)
+    }
+
+    /// Attaches `text` to `name`, removing the note when `text` is blank.
+    pub fn set_note(&mut self, name: &str, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            self.notes.remove(name);
+        } else {
+            self.notes.insert(name.to_string(), text.to_string());
+        }
+    }
+
+    /// Writes the state back to the file it was loaded from, if any.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let io_error = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let contents = serde_json::to_vec_pretty(self).map_err(io::Error::from);
+        // Write then rename so a concurrent session never reads a partial file.
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, contents.map_err(io_error)?).map_err(io_error)?;
+        fs::rename(&tmp, path).map_err(io_error)
+    }
+}
+
+/// Returns the state file of `manifest` under `$XDG_STATE_HOME`, falling
+/// back to `~/.local/state`.
+fn state_file(manifest: &Path) -> Option<PathBuf> {
+    let dir = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".local/state")))?;
+
+    let mut hasher = FxHasher::default();
+    hasher.write(manifest.as_os_str().as_encoded_bytes());
+    Some(
+        dir.join("cargo-tree-tui")
+            .join(format!("{:016x}.json", hasher.finish())),
+    )
+}
+
+/// Appends a committed search query to `history`, moving a repeated one to
+/// the end and dropping the oldest past [`MAX_SEARCH_HISTORY`].
+pub fn push_search(history: &mut Vec<String>, query: &str) {
+    history.retain(|previous| previous != query);
+    history.push(query.to_string());
+    let excess = history.len().saturating_sub(MAX_SEARCH_HISTORY);
+    history.drain(..excess);
+}
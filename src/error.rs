@@ -0,0 +1,75 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Convenience alias for results of library operations.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Failures the library reports, so callers can tell them apart.
+#[derive(Debug)]
+pub enum Error {
+    /// No `Cargo.toml` at the given path, or above the working directory.
+    ManifestNotFound(PathBuf),
+    /// Cargo failed to load or resolve the workspace.
+    MetadataFailed {
+        /// Step that failed, e.g. "failed to load Cargo workspace".
+        context: &'static str,
+        /// Cargo's error message, as it would print it.
+        stderr: String,
+    },
+    /// The resolved dependency graph is missing nodes or edges.
+    ResolveMissing(String),
+    /// Reading or writing a file failed.
+    Io { path: PathBuf, source: io::Error },
+    /// A child process could not be started.
+    Spawn { program: PathBuf, source: io::Error },
+    /// An HTTP request failed or returned an unexpected status.
+    Http { url: String, message: String },
+    /// A response body could not be parsed.
+    InvalidResponse {
+        what: &'static str,
+        source: serde_json::Error,
+    },
+    /// The requested resource, e.g. a changelog or readme, does not exist.
+    NotFound(String),
+    /// The external editor could not be run or exited with an error.
+    Editor(String),
+    /// A headless query could not be parsed.
+    InvalidQuery(String),
+    /// Configuration, e.g. in `[workspace.metadata.tree-tui]`, is malformed.
+    InvalidConfig(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ManifestNotFound(path) => write!(
+                f,
+                "could not find `Cargo.toml` in `{}` or any parent directory; \
+                 pass --manifest-path to point at one",
+                path.display()
+            ),
+            Self::MetadataFailed { context, stderr } => write!(f, "{context}: {stderr}"),
+            Self::ResolveMissing(message) => {
+                write!(f, "resolved dependency graph is inconsistent: {message}")
+            }
+            Self::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Spawn { program, source } => {
+                write!(f, "failed to run {}: {source}", program.display())
+            }
+            Self::Http { url, message } => write!(f, "fetching {url} failed: {message}"),
+            Self::InvalidResponse { what, source } => write!(f, "unexpected {what}: {source}"),
+            Self::InvalidQuery(query) => write!(f, "invalid query {query}"),
+            Self::InvalidConfig(message) => write!(f, "invalid config: {message}"),
+            Self::NotFound(message) | Self::Editor(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } | Self::Spawn { source, .. } => Some(source),
+            Self::InvalidResponse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod core;
+pub mod error;
+#[cfg(feature = "tui")]
 pub mod ops;
+
+pub use error::{Error, Result};
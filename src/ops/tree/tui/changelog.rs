@@ -0,0 +1,114 @@
+use std::{sync::mpsc::Sender, thread};
+
+use clap_cargo::style::ERROR;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::core::{Dependency, changelog::Changelog, dependency::CrateKey};
+
+use super::{
+    help::HelpPopupStyle,
+    pager::{Pager, PagerState},
+    state::Event,
+};
+
+/// Outcome of loading a changelog, kept in the cache either way.
+pub type ChangelogResult = Result<Changelog, String>;
+
+/// Changelog popup for a single crate.
+#[derive(Debug)]
+pub struct ChangelogView {
+    pub key: CrateKey,
+    /// `None` while the changelog is still loading.
+    pub result: Option<ChangelogResult>,
+    pub pager: PagerState,
+}
+
+impl ChangelogView {
+    pub fn new(key: CrateKey) -> Self {
+        Self {
+            key,
+            result: None,
+            pager: PagerState::default(),
+        }
+    }
+
+    /// Shows a loaded changelog, scrolled to the section of the crate version.
+    pub fn set_result(&mut self, result: ChangelogResult) {
+        self.pager.scroll_to(
+            result
+                .as_ref()
+                .ok()
+                .and_then(|changelog| changelog.version_line(&self.key.1))
+                .unwrap_or(0),
+        );
+        self.result = Some(result);
+    }
+
+    /// Scrolls or searches the loaded changelog, returning whether the key
+    /// was consumed.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        let lines: Vec<&str> = match &self.result {
+            Some(Ok(changelog)) => changelog.text.lines().collect(),
+            _ => Vec::new(),
+        };
+        self.pager.handle_key(code, &lines)
+    }
+}
+
+/// Loads the changelog of `dependency` on a background thread.
+///
+/// The result is sent as [`Event::ChangelogLoaded`].
+pub fn spawn_changelog(dependency: Dependency, event_tx: Sender<Event>) {
+    thread::spawn(move || {
+        let result = Changelog::load(&dependency).map_err(|err| err.to_string());
+        let key = dependency.key();
+        let _ = event_tx.send(Event::ChangelogLoaded(key, result));
+    });
+}
+
+/// Scrollable popup showing a [`ChangelogView`].
+#[derive(Debug)]
+pub struct ChangelogPopup<'a> {
+    view: &'a ChangelogView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> ChangelogPopup<'a> {
+    pub fn new(view: &'a ChangelogView) -> Self {
+        Self {
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for ChangelogPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (name, version) = &self.view.key;
+        let mut block = Block::new()
+            .title(Line::from(format!(" {name} v{version} changelog ")))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let lines = match &self.view.result {
+            None => vec![Line::from("loading…")],
+            Some(Err(err)) => vec![Line::styled(err.as_str(), Style::from(ERROR))],
+            Some(Ok(changelog)) => {
+                block = block.title_bottom(Line::from(format!(
+                    " {} · /: search · esc: close ",
+                    changelog.source.describe()
+                )));
+                changelog.text.lines().map(Line::from).collect()
+            }
+        };
+        Pager::new(lines, block, &self.view.pager).render(area, buf);
+    }
+}
@@ -0,0 +1,577 @@
+use std::{
+    env,
+    ffi::OsString,
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap_cargo::style::{ERROR, GOOD};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Widget},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error,
+    core::{DependencyTree, cache, dependency::LoadTimings},
+};
+
+use super::{
+    help::HelpPopupStyle,
+    pager::{Pager, PagerState},
+    state::Event,
+};
+
+/// Output of a cargo command run from the TUI.
+#[derive(Debug)]
+pub struct CommandLog {
+    /// Command line shown in the pane title.
+    pub title: String,
+    /// Captured output lines, in arrival order.
+    pub lines: Vec<String>,
+    /// Exit status once the command has finished.
+    pub success: Option<bool>,
+    /// Scroll and search state, following the output as it arrives.
+    pub pager: PagerState,
+    /// Stops the command while it is running.
+    pub cancel: CancelToken,
+}
+
+impl CommandLog {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            lines: Vec::new(),
+            success: None,
+            pager: PagerState::following(),
+            cancel: CancelToken::default(),
+        }
+    }
+
+    /// Returns whether the command is still running.
+    pub fn is_running(&self) -> bool {
+        self.success.is_none()
+    }
+}
+
+/// Shared flag asking a background command to stop.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How often a [`Watchdog`] checks on the work it guards.
+const WATCHDOG_POLL: Duration = Duration::from_millis(50);
+
+/// Why a [`Watchdog`] gave up on a background command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stopped {
+    Cancelled,
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::TimedOut(timeout) => write!(f, "timed out after {}s", timeout.as_secs()),
+        }
+    }
+}
+
+/// Stops waiting on a background command once it is cancelled or has run
+/// past its timeout.
+#[derive(Debug)]
+struct Watchdog {
+    cancel: CancelToken,
+    timeout: Option<Duration>,
+    start: Instant,
+}
+
+impl Watchdog {
+    fn new(cancel: CancelToken, timeout: Option<Duration>) -> Self {
+        Self {
+            cancel,
+            timeout,
+            start: Instant::now(),
+        }
+    }
+
+    fn check(&self) -> Result<(), Stopped> {
+        if self.cancel.is_cancelled() {
+            return Err(Stopped::Cancelled);
+        }
+        match self.timeout {
+            Some(timeout) if self.start.elapsed() >= timeout => Err(Stopped::TimedOut(timeout)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Waits for `child` to exit, killing it once the watchdog gives up.
+    fn wait(&self, child: &mut std::process::Child) -> Result<bool, Stopped> {
+        loop {
+            if let Err(stopped) = self.check() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(stopped);
+            }
+            match child.try_wait() {
+                Ok(Some(status)) => return Ok(status.success()),
+                Ok(None) => thread::sleep(WATCHDOG_POLL),
+                Err(_) => return Ok(false),
+            }
+        }
+    }
+}
+
+/// Program resolving the workspace in a child process and printing the
+/// tree with its load timings as JSON on stdout, e.g. the `tree-tui load`
+/// subcommand of the `cargo-tree-tui` binary.
+///
+/// [`load_in_child`] appends `--resolve <JSON>` for versions other than
+/// the locked ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadCommand {
+    pub program: PathBuf,
+    pub args: Vec<OsString>,
+}
+
+/// How commands started from the TUI resolve the workspace again.
+#[derive(Debug, Clone, Default)]
+pub struct Loader {
+    /// Manifest of the workspace.
+    pub manifest_path: Option<PathBuf>,
+    /// Child process to resolve in, so that it can be killed. Without one,
+    /// the workspace is resolved in this process, which cannot be stopped
+    /// once started.
+    pub command: Option<LoadCommand>,
+}
+
+impl Loader {
+    /// Resolves the workspace to `resolve`'s versions, bypassing the tree
+    /// cache, unless `cancel` is triggered or it runs past `timeout` first.
+    pub fn load(
+        &self,
+        resolve: &Resolve,
+        cancel: CancelToken,
+        timeout: Option<Duration>,
+    ) -> Result<crate::Result<(DependencyTree, LoadTimings)>, Stopped> {
+        match &self.command {
+            Some(command) => load_in_child(command, resolve, cancel, timeout),
+            None => {
+                Watchdog::new(cancel, timeout).check()?;
+                Ok(resolve.load(self.manifest_path.clone(), true))
+            }
+        }
+    }
+}
+
+/// Resolves the workspace to `resolve`'s versions by running `command`, so
+/// that the child can be killed once `cancel` is triggered or it runs past
+/// `timeout`.
+///
+/// Resolving through the cargo library offers no way to interrupt it, e.g.
+/// while cargo blocks on an unreachable registry, so it is kept out of this
+/// process whenever it may have to be stopped.
+pub fn load_in_child(
+    command: &LoadCommand,
+    resolve: &Resolve,
+    cancel: CancelToken,
+    timeout: Option<Duration>,
+) -> Result<crate::Result<(DependencyTree, LoadTimings)>, Stopped> {
+    let watchdog = Watchdog::new(cancel, timeout);
+    watchdog.check()?;
+    let mut child = Command::new(&command.program);
+    child.args(&command.args);
+    if *resolve != Resolve::Locked {
+        let resolve = serde_json::to_string(resolve).expect("resolve serializes");
+        child.arg("--resolve").arg(resolve);
+    }
+    // Its error becomes ours, so keep backtraces out of the message.
+    let mut child = match child
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(source) => {
+            return Ok(Err(Error::Spawn {
+                program: command.program.clone(),
+                source,
+            }));
+        }
+    };
+
+    // Drained while waiting so a large tree never fills the pipe.
+    let read_all = |mut reader: Box<dyn Read + Send>| {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = reader.read_to_end(&mut bytes);
+            bytes
+        })
+    };
+    let stdout = child.stdout.take().map(|out| read_all(Box::new(out)));
+    let stderr = child.stderr.take().map(|err| read_all(Box::new(err)));
+    let success = watchdog.wait(&mut child)?;
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    let (stdout, stderr) = (collect(stdout), collect(stderr));
+
+    if !success {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Ok(Err(Error::MetadataFailed {
+            context: "failed to resolve the workspace",
+            stderr: stderr.trim().trim_start_matches("Error: ").to_string(),
+        }));
+    }
+    Ok(
+        serde_json::from_slice(&stdout).map_err(|source| Error::InvalidResponse {
+            what: "resolved tree",
+            source,
+        }),
+    )
+}
+
+/// Command line run for the selected crate with the TUI suspended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellRequest {
+    /// Program followed by its arguments, placeholders filled in.
+    pub args: Vec<String>,
+}
+
+/// Joins `args` into a line a POSIX shell splits back into them, quoting
+/// only the arguments that need it.
+pub fn shell_line(args: &[String]) -> String {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "-_./=@,:+%".contains(c);
+    args.iter()
+        .map(|arg| {
+            if !arg.is_empty() && arg.chars().all(is_plain) {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs a [`ShellRequest`] in the foreground and returns its output for the
+/// log pane.
+///
+/// Standard input stays connected to the terminal so the command can
+/// prompt, and its output is copied to the terminal as it arrives while
+/// also being kept for the log pane. The command writes to pipes, so tools
+/// that check for a terminal may drop colors. The terminal must be restored
+/// by the caller before and after.
+pub fn run_suspended(request: &ShellRequest) -> CommandLog {
+    let mut log = CommandLog::new(request.args.join(" "));
+    let Some((program, args)) = request.args.split_first() else {
+        log.success = Some(false);
+        return log;
+    };
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            log.lines.push(format!("failed to run {program}: {err}"));
+            log.success = Some(false);
+            return log;
+        }
+    };
+
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| tee(out, Box::new(io::stdout()))),
+        child
+            .stderr
+            .take()
+            .map(|err| tee(err, Box::new(io::stderr()))),
+    ];
+    let success = child.wait().is_ok_and(|status| status.success());
+    for reader in readers.into_iter().flatten() {
+        let output = reader.join().unwrap_or_default();
+        log.lines
+            .extend(String::from_utf8_lossy(&output).lines().map(str::to_string));
+    }
+    log.success = Some(success);
+    log
+}
+
+/// Copies everything read from `reader` to `terminal` as it arrives, without
+/// waiting for whole lines so prompts show, and returns it all once
+/// `reader` is closed.
+fn tee(
+    mut reader: impl Read + Send + 'static,
+    mut terminal: Box<dyn Write + Send>,
+) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let mut buf = [0; 4096];
+        while let Ok(read @ 1..) = reader.read(&mut buf) {
+            let _ = terminal.write_all(&buf[..read]);
+            let _ = terminal.flush();
+            output.extend_from_slice(&buf[..read]);
+        }
+        output
+    })
+}
+
+/// Runs `cargo <args>` for the workspace on a background thread.
+///
+/// Output lines are streamed as [`Event::CommandOutput`]. Once the command
+/// exits, [`Event::CommandFinished`] is sent, followed on success by
+/// [`Event::TreeReloaded`] with the tree resolved again by `loader`. The
+/// command, and the resolve after it, are stopped when `cancel` is
+/// triggered or they run past `timeout`, e.g. while cargo blocks on an
+/// unreachable registry.
+pub fn spawn_cargo(
+    args: Vec<String>,
+    loader: Loader,
+    event_tx: Sender<Event>,
+    cancel: CancelToken,
+    timeout: Option<Duration>,
+) {
+    thread::spawn(move || {
+        let watchdog = Watchdog::new(cancel, timeout);
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+        let mut command = Command::new(cargo);
+        command.args(&args);
+        if let Some(path) = &loader.manifest_path {
+            command.arg("--manifest-path").arg(path);
+        }
+
+        let mut child = match command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("failed to run cargo: {err}")));
+                let _ = event_tx.send(Event::CommandFinished { success: false });
+                return;
+            }
+        };
+
+        let readers = [
+            child
+                .stdout
+                .take()
+                .map(|out| forward_lines(out, event_tx.clone())),
+            child
+                .stderr
+                .take()
+                .map(|err| forward_lines(err, event_tx.clone())),
+        ];
+
+        let success = match watchdog.wait(&mut child) {
+            Ok(success) => success,
+            Err(stopped) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("cargo {stopped}, killed it")));
+                let _ = event_tx.send(Event::CommandFinished { success: false });
+                return;
+            }
+        };
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.join();
+        }
+        let _ = event_tx.send(Event::CommandFinished { success });
+        if !success {
+            return;
+        }
+
+        let cancel = watchdog.cancel.clone();
+        let remaining = timeout.map(|timeout| timeout.saturating_sub(watchdog.start.elapsed()));
+        match loader.load(&Resolve::Locked, cancel, remaining) {
+            Ok(Ok((tree, _))) => {
+                let _ = event_tx.send(Event::TreeReloaded(tree));
+            }
+            Ok(Err(err)) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("reload failed: {err}")));
+            }
+            Err(stopped) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("reload {stopped}")));
+            }
+        }
+    });
+}
+
+/// Versions the workspace is resolved to by [`spawn_resolve`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resolve {
+    /// The versions recorded in `Cargo.lock`.
+    #[default]
+    Locked,
+    /// The lowest versions every requirement allows.
+    MinimalVersions,
+    /// The lockfile's versions with `name@current` moved to `version`.
+    Upgraded {
+        name: String,
+        current: String,
+        version: String,
+    },
+}
+
+impl Resolve {
+    /// Returns the title of the log pane showing the resolve.
+    pub fn title(&self) -> String {
+        match self {
+            Self::Locked => "locked versions".to_string(),
+            Self::MinimalVersions => "minimal versions".to_string(),
+            Self::Upgraded {
+                name,
+                current,
+                version,
+            } => format!("upgrade {name} {current} → {version}"),
+        }
+    }
+
+    /// Resolves the workspace to these versions in this process.
+    ///
+    /// Only the locked versions are read from the tree cache, and only
+    /// unless `no_cache` is set.
+    pub fn load(
+        &self,
+        manifest_path: Option<PathBuf>,
+        no_cache: bool,
+    ) -> crate::Result<(DependencyTree, LoadTimings)> {
+        let untimed = |tree| (tree, LoadTimings::default());
+        match self {
+            Self::Locked if no_cache => DependencyTree::load_timed(manifest_path),
+            Self::Locked => cache::load(manifest_path),
+            Self::MinimalVersions => {
+                DependencyTree::load_minimal_versions(manifest_path).map(untimed)
+            }
+            Self::Upgraded {
+                name,
+                current,
+                version,
+            } => {
+                DependencyTree::load_upgraded(manifest_path, &format!("{name}@{current}"), version)
+                    .map(untimed)
+            }
+        }
+    }
+}
+
+/// Resolves the workspace to `resolve`'s versions on a background thread.
+///
+/// Sends [`Event::CommandFinished`], followed on success by
+/// [`Event::TreeReloaded`] so the changes are diffed against the shown tree.
+/// The resolve is stopped when `cancel` is triggered or it runs past
+/// `timeout`, see [`Loader::load`].
+pub fn spawn_resolve(
+    resolve: Resolve,
+    loader: Loader,
+    event_tx: Sender<Event>,
+    cancel: CancelToken,
+    timeout: Option<Duration>,
+) {
+    thread::spawn(move || {
+        let result = loader.load(&resolve, cancel, timeout);
+        match result {
+            Ok(Ok((tree, _))) => {
+                let _ = event_tx.send(Event::CommandFinished { success: true });
+                let _ = event_tx.send(Event::TreeReloaded(tree));
+            }
+            Ok(Err(err)) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("resolve failed: {err}")));
+                let _ = event_tx.send(Event::CommandFinished { success: false });
+            }
+            Err(stopped) => {
+                let _ = event_tx.send(Event::CommandOutput(format!("resolve {stopped}")));
+                let _ = event_tx.send(Event::CommandFinished { success: false });
+            }
+        }
+    });
+}
+
+/// Forwards every line read from `reader` as an [`Event::CommandOutput`].
+fn forward_lines(
+    reader: impl Read + Send + 'static,
+    event_tx: Sender<Event>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if event_tx.send(Event::CommandOutput(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Pane showing the output of a [`CommandLog`] in a [`Pager`].
+#[derive(Debug)]
+pub struct LogPane<'a> {
+    log: &'a CommandLog,
+    style: HelpPopupStyle,
+}
+
+impl<'a> LogPane<'a> {
+    pub fn new(log: &'a CommandLog) -> Self {
+        Self {
+            log,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for LogPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let status = match self.log.success {
+            None => Line::styled(" running… · ctrl-c: cancel ", self.style.title),
+            Some(true) => Line::styled(" done · /: search · esc: close ", Style::from(GOOD)),
+            Some(false) => Line::styled(" failed · /: search · esc: close ", Style::from(ERROR)),
+        };
+        let block = Block::new()
+            .title(Line::from(format!(" {} ", self.log.title)))
+            .title_bottom(status)
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let lines = self
+            .log
+            .lines
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        Pager::new(lines, block, &self.log.pager).render(area, buf);
+    }
+}
@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+use clap_cargo::style::ERROR;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use super::{filter::Filter, help::HelpPopupStyle};
+
+/// Command typed after `:` to shape the view in bulk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViewCommand {
+    /// Expands every node with a crate matching the pattern below it.
+    Expand(String),
+    /// Collapses every node with a crate matching the pattern below it.
+    Collapse(String),
+    /// Adds the filter to the filter bar, or removes it when active.
+    Filter(Filter),
+}
+
+impl FromStr for ViewCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, pattern) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let pattern = pattern.trim();
+        let command = match name {
+            "expand" => Self::Expand,
+            "collapse" => Self::Collapse,
+            "filter" => return pattern.parse().map(Self::Filter),
+            _ => {
+                return Err(format!(
+                    "unknown command `{name}`, expected `expand`, `collapse` or `filter`"
+                ));
+            }
+        };
+        if pattern.is_empty() {
+            return Err(format!("`{name}` needs a pattern"));
+        }
+        Ok(command(pattern.to_string()))
+    }
+}
+
+/// Text prompt for a [`ViewCommand`].
+#[derive(Debug, Clone, Default)]
+pub struct CommandLine {
+    /// Command typed so far, without the leading `:`.
+    pub input: String,
+    /// Why the last command could not run.
+    pub error: Option<String>,
+}
+
+/// Single-line input dialog for a [`CommandLine`].
+#[derive(Debug)]
+pub struct CommandLinePopup<'a> {
+    command_line: &'a CommandLine,
+    style: HelpPopupStyle,
+}
+
+impl<'a> CommandLinePopup<'a> {
+    pub fn new(command_line: &'a CommandLine) -> Self {
+        Self {
+            command_line,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for CommandLinePopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let hint = match &self.command_line.error {
+            Some(error) => Line::styled(format!(" {error} "), ERROR),
+            None => Line::from(" expand|collapse <pattern> · filter <predicate> · esc: cancel "),
+        };
+        let block = Block::new()
+            .title(Line::from(" command "))
+            .title_bottom(hint)
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        Paragraph::new(Line::from(format!(" :{}", self.command_line.input)))
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,159 @@
+use clap_cargo::style::PLACEHOLDER;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::core::{DependencyTree, NodeId};
+
+use super::help::HelpPopupStyle;
+
+/// Set operation applied to the transitive dependencies of two marked crates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Crates both marked crates depend on.
+    Common,
+    /// Crates only the first marked crate depends on.
+    OnlyFirst,
+    /// Crates only the second marked crate depends on.
+    OnlySecond,
+}
+
+impl CompareMode {
+    /// Returns the next mode in display order.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Common => Self::OnlyFirst,
+            Self::OnlyFirst => Self::OnlySecond,
+            Self::OnlySecond => Self::Common,
+        }
+    }
+}
+
+/// State of the dependency comparison popup.
+#[derive(Debug)]
+pub struct CompareState {
+    pub first: NodeId,
+    pub second: NodeId,
+    pub mode: CompareMode,
+    /// Result of the current set operation, sorted by name.
+    pub results: Vec<NodeId>,
+    /// Index of the first result shown in the popup.
+    pub scroll: usize,
+}
+
+impl CompareState {
+    /// Compares the dependencies of `first` and `second`, starting with the
+    /// crates they have in common.
+    pub fn new(tree: &DependencyTree, first: NodeId, second: NodeId) -> Self {
+        let mut state = Self {
+            first,
+            second,
+            mode: CompareMode::Common,
+            results: Vec::new(),
+            scroll: 0,
+        };
+        state.refresh(tree);
+        state
+    }
+
+    /// Switches to the next set operation.
+    pub fn cycle_mode(&mut self, tree: &DependencyTree) {
+        self.mode = self.mode.next();
+        self.refresh(tree);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.results.len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    fn refresh(&mut self, tree: &DependencyTree) {
+        self.results = match self.mode {
+            CompareMode::Common => tree.common_dependencies(self.first, self.second),
+            CompareMode::OnlyFirst => tree.exclusive_dependencies(self.first, self.second),
+            CompareMode::OnlySecond => tree.exclusive_dependencies(self.second, self.first),
+        };
+        self.scroll = 0;
+    }
+
+    /// Returns a short description of the current set operation.
+    fn title(&self, tree: &DependencyTree) -> String {
+        let name = |id: NodeId| tree.node(id).map(|n| n.display_name()).unwrap_or("?");
+        let (first, second) = (name(self.first), name(self.second));
+        match self.mode {
+            CompareMode::Common => format!(" {first} ∩ {second} "),
+            CompareMode::OnlyFirst => format!(" {first} − {second} "),
+            CompareMode::OnlySecond => format!(" {second} − {first} "),
+        }
+    }
+}
+
+/// Popup listing the result of a [`CompareState`] set operation.
+#[derive(Debug)]
+pub struct ComparePopup<'a> {
+    tree: &'a DependencyTree,
+    state: &'a CompareState,
+    style: HelpPopupStyle,
+}
+
+impl<'a> ComparePopup<'a> {
+    pub fn new(tree: &'a DependencyTree, state: &'a CompareState) -> Self {
+        Self {
+            tree,
+            state,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for ComparePopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(self.state.title(self.tree)))
+            .title_bottom(Line::from(format!(
+                " {} crates · tab: switch · esc: close ",
+                self.state.results.len()
+            )))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let scroll = self
+            .state
+            .scroll
+            .min(self.state.results.len().saturating_sub(height));
+        let lines = self
+            .state
+            .results
+            .iter()
+            .skip(scroll)
+            .take(height)
+            .filter_map(|&id| self.tree.node(id)?.as_dependency())
+            .map(|dependency| {
+                Line::from(vec![
+                    Span::raw(" "),
+                    Span::raw(dependency.name.clone()),
+                    Span::styled(
+                        format!(" v{}", dependency.version),
+                        Style::from(PLACEHOLDER),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
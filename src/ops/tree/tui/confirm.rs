@@ -0,0 +1,180 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Rect, Size},
+    text::{Line, Text},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::help::HelpPopupStyle;
+
+/// An action waiting for the user to confirm it.
+#[derive(Debug, Clone)]
+pub struct Confirm {
+    /// Question shown to the user.
+    pub prompt: String,
+    /// Arguments passed to `cargo` once confirmed.
+    pub cargo_args: Vec<String>,
+}
+
+/// Yes/no dialog for a [`Confirm`].
+#[derive(Debug)]
+pub struct ConfirmPopup<'a> {
+    confirm: &'a Confirm,
+    style: HelpPopupStyle,
+}
+
+impl<'a> ConfirmPopup<'a> {
+    pub fn new(confirm: &'a Confirm) -> Self {
+        Self {
+            confirm,
+            style: HelpPopupStyle::default(),
+        }
+    }
+
+    fn content(&self) -> Text<'a> {
+        Text::from(vec![
+            Line::from(format!(" {} ", self.confirm.prompt)),
+            Line::from(format!(" $ cargo {} ", self.confirm.cargo_args.join(" "))),
+            Line::default(),
+            Line::from(" y/enter: confirm · n/esc: cancel "),
+        ])
+    }
+
+    pub fn size(&self) -> Size {
+        let content = self.content();
+        Size {
+            width: (content.width() + 2) as u16,
+            height: (content.height() + 2) as u16,
+        }
+    }
+}
+
+impl Widget for ConfirmPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" CONFIRM "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        Paragraph::new(self.content())
+            .style(self.style.default)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// Text prompt collecting the dependency to pass to `cargo add`.
+#[derive(Debug, Clone)]
+pub struct AddPrompt {
+    /// Workspace member the dependency is added to.
+    pub member: String,
+    /// Section the dependency is added to.
+    pub flag: Option<&'static str>,
+    /// Dependency spec typed so far (e.g. `serde@1 --features derive`).
+    pub input: String,
+}
+
+impl AddPrompt {
+    /// Returns the `cargo add` arguments for the current input.
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = vec!["add".to_string()];
+        args.extend(self.input.split_whitespace().map(str::to_string));
+        args.extend(self.flag.map(str::to_string));
+        args.extend(["-p".to_string(), self.member.clone()]);
+        args
+    }
+}
+
+/// Single-line input dialog for an [`AddPrompt`].
+#[derive(Debug)]
+pub struct PromptPopup<'a> {
+    prompt: &'a AddPrompt,
+    style: HelpPopupStyle,
+}
+
+impl<'a> PromptPopup<'a> {
+    pub fn new(prompt: &'a AddPrompt) -> Self {
+        Self {
+            prompt,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for PromptPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let section = self
+            .prompt
+            .flag
+            .map(|flag| format!(" {flag}"))
+            .unwrap_or_default();
+        let block = Block::new()
+            .title(Line::from(format!(
+                " cargo add{section} -p {} ",
+                self.prompt.member
+            )))
+            .title_bottom(Line::from(" enter: add · esc: cancel "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        Paragraph::new(Line::from(format!(" {}", self.prompt.input)))
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// Text prompt collecting the version to preview an upgrade to.
+#[derive(Debug, Clone)]
+pub struct UpgradePrompt {
+    /// Crate being upgraded.
+    pub name: String,
+    /// Version currently in the lockfile.
+    pub current: String,
+    /// Version typed so far.
+    pub input: String,
+}
+
+/// Single-line input dialog for an [`UpgradePrompt`].
+#[derive(Debug)]
+pub struct UpgradePromptPopup<'a> {
+    prompt: &'a UpgradePrompt,
+    style: HelpPopupStyle,
+}
+
+impl<'a> UpgradePromptPopup<'a> {
+    pub fn new(prompt: &'a UpgradePrompt) -> Self {
+        Self {
+            prompt,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for UpgradePromptPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(format!(
+                " upgrade {} {} to ",
+                self.prompt.name, self.prompt.current
+            )))
+            .title_bottom(Line::from(" enter: preview · esc: cancel "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        Paragraph::new(Line::from(format!(" {}", self.prompt.input)))
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
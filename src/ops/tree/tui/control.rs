@@ -0,0 +1,139 @@
+use std::{path::PathBuf, str::FromStr, sync::mpsc::Sender};
+
+use crate::core::export::GraphFormat;
+
+/// Command read from the control socket, one per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Reveals and selects the first crate matching a `name[@version]` spec.
+    Select(String),
+    ExpandAll,
+    CollapseAll,
+    /// Writes the shown tree as a graph to a file.
+    Export(GraphFormat, PathBuf),
+    Quit,
+}
+
+impl FromStr for ControlCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let command = match (words.next(), words.next(), words.next()) {
+            (Some("select"), Some(spec), None) => Self::Select(spec.to_string()),
+            (Some("expand-all"), None, _) => Self::ExpandAll,
+            (Some("collapse-all"), None, _) => Self::CollapseAll,
+            (Some("export"), Some(format), Some(path)) => {
+                Self::Export(format.parse()?, PathBuf::from(path))
+            }
+            (Some("quit"), None, _) => Self::Quit,
+            _ => {
+                return Err(format!(
+                    "unknown command `{}`, expected `select <crate>`, `expand-all`, \
+                     `collapse-all`, `export <json|graphml> <path>` or `quit`",
+                    s.trim()
+                ));
+            }
+        };
+        if words.next().is_some() {
+            return Err(format!("too many arguments in `{}`", s.trim()));
+        }
+        Ok(command)
+    }
+}
+
+/// Command from the control socket along with where its outcome goes.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    pub reply: Sender<Result<(), String>>,
+}
+
+#[cfg(unix)]
+pub use socket::spawn_control_socket;
+
+#[cfg(unix)]
+mod socket {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        os::unix::{
+            fs::FileTypeExt,
+            net::{UnixListener, UnixStream},
+        },
+        path::Path,
+        sync::mpsc::{self, Sender},
+        thread,
+    };
+
+    use tracing::{debug, warn};
+
+    use crate::{Error, Result, ops::tree::tui::state::Event};
+
+    use super::{ControlCommand, ControlRequest};
+
+    /// Listens on a Unix socket at `path` for [`ControlCommand`] lines,
+    /// forwarding each to the TUI and answering `ok` or `error: <why>`.
+    ///
+    /// A socket left at `path` by an earlier session is replaced; any other
+    /// file there is an error.
+    pub fn spawn_control_socket(path: &Path, event_tx: Sender<Event>) -> Result<()> {
+        let io_error = |source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            fs::remove_file(path).map_err(io_error)?;
+        }
+        let listener = UnixListener::bind(path).map_err(io_error)?;
+        debug!(path = %path.display(), "control socket listening");
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let event_tx = event_tx.clone();
+                        thread::spawn(move || serve(stream, &event_tx));
+                    }
+                    Err(err) => warn!(%err, "control connection failed"),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Answers the commands of one connection until it closes or the TUI
+    /// exits.
+    fn serve(stream: UnixStream, event_tx: &Sender<Event>) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let outcome = match line.parse::<ControlCommand>() {
+                Ok(command) => {
+                    let (reply, outcome) = mpsc::channel();
+                    let request = ControlRequest { command, reply };
+                    if event_tx.send(Event::Control(request)).is_err() {
+                        return;
+                    }
+                    outcome
+                        .recv()
+                        .unwrap_or_else(|_| Err("the TUI exited".to_string()))
+                }
+                Err(err) => Err(err),
+            };
+            let answer = match outcome {
+                Ok(()) => "ok".to_string(),
+                Err(err) => format!("error: {err}"),
+            };
+            if writeln!(writer, "{answer}").is_err() {
+                return;
+            }
+        }
+    }
+}
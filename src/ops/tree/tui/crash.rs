@@ -0,0 +1,135 @@
+use std::{
+    backtrace::Backtrace,
+    env,
+    fmt::Write as _,
+    fs, io,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::core::{DependencyTree, stats::deepest_chain};
+
+use super::debug_log::LogBuffer;
+
+/// Number of recent log lines (key presses, searches, commands) included in
+/// a crash report.
+pub const RECENT_ACTIONS: usize = 50;
+
+/// Shape of the loaded workspace, recorded for crash reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceSummary {
+    pub name: String,
+    pub nodes: usize,
+    pub edges: usize,
+    pub members: usize,
+    /// Number of crates on the longest dependency chain.
+    pub max_depth: usize,
+}
+
+impl WorkspaceSummary {
+    pub fn new(tree: &DependencyTree) -> Self {
+        Self {
+            name: tree.workspace_name.clone(),
+            nodes: tree.nodes.len(),
+            edges: tree.nodes.iter().map(|node| node.children().len()).sum(),
+            members: tree.members().len(),
+            max_depth: deepest_chain(tree).len(),
+        }
+    }
+}
+
+/// Everything written to the crash bundle.
+#[derive(Debug)]
+pub struct CrashReport<'a> {
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub workspace: &'a WorkspaceSummary,
+    /// Recent log lines, oldest first.
+    pub actions: Vec<String>,
+}
+
+impl CrashReport<'_> {
+    /// Formats the report as plain text.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} {} crash report",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        let _ = writeln!(out, "\npanic: {}", self.message);
+        if let Some(location) = &self.location {
+            let _ = writeln!(out, "at: {location}");
+        }
+
+        let workspace = self.workspace;
+        let _ = writeln!(out, "\n[workspace]");
+        let _ = writeln!(out, "name: {}", workspace.name);
+        let _ = writeln!(out, "nodes: {}", workspace.nodes);
+        let _ = writeln!(out, "edges: {}", workspace.edges);
+        let _ = writeln!(out, "members: {}", workspace.members);
+        let _ = writeln!(out, "max depth: {}", workspace.max_depth);
+
+        let _ = writeln!(out, "\n[last {} actions]", self.actions.len());
+        for action in &self.actions {
+            let _ = writeln!(out, "{action}");
+        }
+
+        let _ = writeln!(out, "\n[backtrace]\n{}", self.backtrace);
+        out
+    }
+
+    /// Writes the report to a new file in the temp directory.
+    pub fn write(&self) -> io::Result<PathBuf> {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let path = env::temp_dir().join(format!(
+            "{}-crash-{}-{secs}.txt",
+            env!("CARGO_PKG_NAME"),
+            process::id()
+        ));
+        fs::write(&path, self.render())?;
+        Ok(path)
+    }
+}
+
+/// Installs a panic hook writing a [`CrashReport`] and printing its path
+/// before running the previous hook.
+///
+/// Install it before `ratatui::init`, whose hook restores the terminal and
+/// then calls this one. Stack overflows abort without unwinding and are not
+/// reported.
+pub fn install_panic_hook(workspace: WorkspaceSummary, log: LogBuffer) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = CrashReport {
+            message: panic_message(info),
+            location: info.location().map(ToString::to_string),
+            backtrace: Backtrace::force_capture().to_string(),
+            workspace: &workspace,
+            actions: log.tail(RECENT_ACTIONS),
+        };
+        match report.write() {
+            Ok(path) => eprintln!(
+                "crash report written to {}; please attach it when reporting this issue",
+                path.display()
+            ),
+            Err(err) => eprintln!("failed to write crash report: {err}"),
+        }
+        previous(info);
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
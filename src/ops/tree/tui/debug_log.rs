@@ -0,0 +1,128 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use tracing_subscriber::fmt::MakeWriter;
+
+use super::help::HelpPopupStyle;
+
+/// Shared ring buffer of formatted log lines, filled by a `tracing`
+/// subscriber and shown in the log pane.
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    /// Number of lines kept; older ones are dropped.
+    pub const CAPACITY: usize = 1000;
+
+    pub fn push(&self, line: String) {
+        let Ok(mut lines) = self.lines.lock() else {
+            return;
+        };
+        if lines.len() == Self::CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns the last `count` lines, oldest first.
+    pub fn tail(&self, count: usize) -> Vec<String> {
+        let Ok(lines) = self.lines.lock() else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect()
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogWriter {
+            buffer: self.clone(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// Writer for a single event, split into lines once dropped.
+#[derive(Debug)]
+pub struct LogWriter {
+    buffer: LogBuffer,
+    pending: Vec<u8>,
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LogWriter {
+    fn drop(&mut self) {
+        for line in String::from_utf8_lossy(&self.pending).lines() {
+            self.buffer.push(line.to_string());
+        }
+    }
+}
+
+/// Pane showing the most recent log lines.
+#[derive(Debug)]
+pub struct DebugLogPane<'a> {
+    buffer: &'a LogBuffer,
+    style: HelpPopupStyle,
+}
+
+impl<'a> DebugLogPane<'a> {
+    pub fn new(buffer: &'a LogBuffer) -> Self {
+        Self {
+            buffer,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for DebugLogPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" LOG "))
+            .title_bottom(Line::from(" F12: close "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let lines = self
+            .buffer
+            .tail(height)
+            .into_iter()
+            .map(|line| Line::from(format!(" {line}")))
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,363 @@
+use std::{sync::mpsc::Sender, thread};
+
+use tracing::warn;
+
+use clap_cargo::style::{ERROR, GOOD, HEADER, PLACEHOLDER, WARN};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::core::{
+    Dependency, DependencyNode, DependencyTree, NodeId,
+    annotate::Annotation,
+    crates_io::CrateInfo,
+    dependency::{CrateKey, CrateTarget},
+    manifest::ManifestSpot,
+    registry::{Registry, is_outdated},
+    trust::{RepoStatus, TrustSignals, check_repository, today},
+};
+
+use super::{help::HelpPopupStyle, state::Event};
+
+/// Progress of a registry lookup, kept in the cache either way.
+#[derive(Debug, Clone)]
+pub enum CrateInfoStatus {
+    Loading,
+    Loaded {
+        /// crates.io metadata, `None` for crates from other registries.
+        info: Option<CrateInfo>,
+        /// Trust signals are only gathered when asked for.
+        signals: Option<TrustSignals>,
+        /// Newest release in the registry index, if it could be fetched.
+        latest: Option<String>,
+    },
+    Failed(String),
+}
+
+/// Looks `key` up in its registry on a background thread: the sparse index
+/// for the newest release and, for crates.io crates, the crates.io API,
+/// checking the repository too when `trust` is set.
+///
+/// The result is sent as [`Event::CrateInfoLoaded`].
+pub fn spawn_crate_info(key: CrateKey, registry: Registry, trust: bool, event_tx: Sender<Event>) {
+    thread::spawn(move || {
+        let latest = registry
+            .latest_version(&key.0)
+            .inspect_err(|err| warn!(name = key.0, %err, "index lookup failed"))
+            .ok()
+            .flatten();
+        let info = if registry.is_crates_io() {
+            CrateInfo::fetch(&key.0, &key.1).map(Some)
+        } else {
+            Ok(None)
+        };
+        let status = match info {
+            Ok(info) => {
+                let signals = info.as_ref().filter(|_| trust).map(|info| {
+                    let repository = check_repository(info.repository.as_deref());
+                    TrustSignals::new(info, repository, today())
+                });
+                CrateInfoStatus::Loaded {
+                    info,
+                    signals,
+                    latest,
+                }
+            }
+            Err(err) => CrateInfoStatus::Failed(err.to_string()),
+        };
+        let _ = event_tx.send(Event::CrateInfoLoaded(key, status));
+    });
+}
+
+/// Side panel describing the selected crate.
+#[derive(Debug)]
+pub struct DetailsPanel<'a> {
+    tree: &'a DependencyTree,
+    selected: Option<NodeId>,
+    /// Registry lookup of the selection, `None` when offline.
+    crate_info: Option<Option<&'a CrateInfoStatus>>,
+    /// Note attached to the selected crate.
+    note: Option<&'a str>,
+    /// Custom annotation of the selected crate.
+    annotation: Option<&'a Annotation>,
+    /// Where the workspace pins the selected crate for members inheriting it.
+    workspace_declaration: Option<ManifestSpot>,
+    style: HelpPopupStyle,
+}
+
+impl<'a> DetailsPanel<'a> {
+    pub fn new(tree: &'a DependencyTree, selected: Option<NodeId>) -> Self {
+        Self {
+            tree,
+            selected,
+            crate_info: None,
+            note: None,
+            annotation: None,
+            workspace_declaration: None,
+            style: HelpPopupStyle::default(),
+        }
+    }
+
+    /// Shows the registry section, with the lookup state if one was made.
+    pub fn crate_info(mut self, status: Option<&'a CrateInfoStatus>) -> Self {
+        self.crate_info = Some(status);
+        self
+    }
+
+    /// Shows the note attached to the selected crate.
+    pub fn note(mut self, note: Option<&'a str>) -> Self {
+        self.note = note;
+        self
+    }
+
+    /// Lists the fields custom annotators added to the selected crate.
+    pub fn annotation(mut self, annotation: Option<&'a Annotation>) -> Self {
+        self.annotation = annotation;
+        self
+    }
+
+    /// Points at the `[workspace.dependencies]` line the selected crate is
+    /// inherited from.
+    pub fn workspace_declaration(mut self, spot: Option<ManifestSpot>) -> Self {
+        self.workspace_declaration = spot;
+        self
+    }
+
+    fn content(&self, id: NodeId, dependency: &Dependency) -> Vec<Line<'a>> {
+        let label = Style::from(PLACEHOLDER);
+        let field = |name: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!(" {name}: "), label),
+                Span::raw(value),
+            ])
+        };
+        let direct = dependency
+            .children
+            .iter()
+            .filter(|&&child| self.tree.node(child).is_some_and(DependencyNode::is_crate))
+            .count();
+
+        let mut lines = vec![
+            Line::styled(
+                format!(" {} v{}", dependency.name, dependency.version),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+            field(
+                "source",
+                match &dependency.registry {
+                    Some(registry) => format!("registry ({})", registry.name),
+                    None => dependency.source.label().to_string(),
+                },
+            ),
+            field(
+                "license",
+                dependency
+                    .license
+                    .clone()
+                    .unwrap_or_else(|| "unknown".into()),
+            ),
+            field(
+                "repository",
+                dependency.repository.clone().unwrap_or_else(|| "-".into()),
+            ),
+            field("used by", self.tree.dependents(id).len().to_string()),
+            field("direct deps", direct.to_string()),
+        ];
+        if let Some(spot) = &self.workspace_declaration {
+            lines.push(field(
+                "workspace",
+                format!("{}:{}", spot.path.display(), spot.line),
+            ));
+        }
+        if let Some(note) = self.note {
+            lines.push(field("note", note.to_string()));
+        }
+        if let Some(annotation) = self.annotation {
+            lines.extend(
+                annotation
+                    .fields
+                    .iter()
+                    .map(|(name, value)| field(name, value.clone())),
+            );
+        }
+        if let Some(conflict) = self.tree.feature_conflict(id) {
+            let names = |ids: &[NodeId]| {
+                ids.iter()
+                    .filter_map(|&id| self.tree.node(id)?.as_dependency())
+                    .map(|dependency| dependency.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            lines.push(Line::default());
+            lines.push(Line::styled(" ⚠ feature conflict", Style::from(WARN)));
+            lines.push(field("no defaults", names(&conflict.opted_out)));
+            lines.push(field("defaults/std", names(&conflict.enabling)));
+        }
+        lines.extend(target_lines(&dependency.targets));
+
+        let Some(status) = self.crate_info else {
+            return lines;
+        };
+        let header = match &dependency.registry {
+            Some(registry) if !registry.is_crates_io() => registry.name.as_str(),
+            _ => "crates.io",
+        };
+        lines.push(Line::default());
+        lines.push(Line::styled(format!(" {header}"), Style::from(HEADER)));
+        match status {
+            None => lines.push(Line::styled(" not a registry crate", label)),
+            Some(CrateInfoStatus::Loading) => lines.push(Line::styled(" loading…", label)),
+            Some(CrateInfoStatus::Failed(err)) => {
+                lines.push(Line::styled(format!(" {err}"), Style::from(ERROR)));
+            }
+            Some(CrateInfoStatus::Loaded {
+                info,
+                signals,
+                latest,
+            }) => {
+                if let Some(latest) = latest {
+                    lines.push(latest_line(&dependency.version, latest));
+                }
+                let Some(info) = info else {
+                    return lines;
+                };
+                lines.push(field("owners", info.owners.join(", ")));
+                lines.push(field(
+                    "published",
+                    info.published.clone().unwrap_or_else(|| "-".into()),
+                ));
+                lines.push(field("downloads", format_count(info.downloads)));
+                if let Some(recent) = info.recent_downloads {
+                    lines.push(field("recent", format_count(recent)));
+                }
+                if let Some(version) = info.version_downloads {
+                    lines.push(field("this version", format_count(version)));
+                }
+                if let Some(signals) = signals {
+                    lines.extend(trust_lines(signals));
+                }
+            }
+        }
+        lines
+    }
+}
+
+impl Widget for DetailsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" DETAILS "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let selected = self
+            .selected
+            .and_then(|id| Some((id, self.tree.node(id)?.as_dependency()?)));
+        let lines = match selected {
+            Some((id, dependency)) => self.content(id, dependency),
+            None => vec![Line::styled(" no crate selected", Style::from(PLACEHOLDER))],
+        };
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// Renders the targets of a crate, one line per kind.
+fn target_lines(targets: &[CrateTarget]) -> Vec<Line<'static>> {
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        Line::default(),
+        Line::styled(" targets", Style::from(HEADER)),
+    ];
+    for group in targets.chunk_by(|a, b| a.kind == b.kind) {
+        let names: Vec<&str> = group.iter().map(|target| target.name.as_str()).collect();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {}: ", group[0].kind.label()),
+                Style::from(PLACEHOLDER),
+            ),
+            Span::raw(names.join(", ")),
+        ]));
+    }
+    lines
+}
+
+/// Renders the newest release, flagged when the resolved version is older.
+fn latest_line(version: &str, latest: &str) -> Line<'static> {
+    let label = Span::styled(" latest: ", Style::from(PLACEHOLDER));
+    if is_outdated(version, latest) {
+        Line::from(vec![
+            label,
+            Span::styled(format!("{latest} (outdated)"), Style::from(WARN)),
+        ])
+    } else {
+        Line::from(vec![
+            label,
+            Span::styled(latest.to_string(), Style::from(GOOD)),
+        ])
+    }
+}
+
+/// Renders the trust section of the panel.
+fn trust_lines(signals: &TrustSignals) -> Vec<Line<'static>> {
+    let label = Style::from(PLACEHOLDER);
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!(" {name}: "), label),
+            Span::raw(value),
+        ])
+    };
+    let days = |days: Option<i64>| days.map_or_else(|| "-".to_string(), |d| format!("{d} days"));
+    let repository = match &signals.repository {
+        RepoStatus::Undeclared => "undeclared".to_string(),
+        RepoStatus::Resolves { stars: Some(stars) } => format!("ok · ★ {}", format_count(*stars)),
+        RepoStatus::Resolves { stars: None } => "ok".to_string(),
+        RepoStatus::NotFound => "not found".to_string(),
+        RepoStatus::Unknown(_) => "unchecked".to_string(),
+    };
+
+    let mut lines = vec![
+        Line::default(),
+        Line::styled(" trust", Style::from(HEADER)),
+        field("version age", days(signals.version_age_days)),
+        field("last release", days(signals.last_release_age_days)),
+        field("owners", signals.owners.to_string()),
+        field("repository", repository),
+    ];
+    let warnings = signals.warnings();
+    if warnings.is_empty() {
+        lines.push(Line::styled(" ✓ no concerns", Style::from(GOOD)));
+    }
+    lines.extend(
+        warnings
+            .into_iter()
+            .map(|warning| Line::styled(format!(" ⚠ {warning}"), Style::from(WARN))),
+    );
+    lines
+}
+
+/// Formats a count with thousands separators.
+fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
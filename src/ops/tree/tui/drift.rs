@@ -0,0 +1,170 @@
+use clap_cargo::style::{HEADER, PLACEHOLDER};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::core::{
+    DependencyTree,
+    dependency::DependencyType,
+    drift::{Drift, MemberUse, version_drift},
+};
+
+use super::{help::HelpPopupStyle, widget::dependency_type_style};
+
+/// State of the popup listing the dependencies workspace members declare
+/// differently.
+#[derive(Debug)]
+pub struct DriftView {
+    pub drifts: Vec<Drift>,
+    /// Index of the highlighted declaration, counted across all drifts.
+    pub selected: usize,
+}
+
+impl DriftView {
+    pub fn new(tree: &DependencyTree) -> Self {
+        Self {
+            drifts: version_drift(tree),
+            selected: 0,
+        }
+    }
+
+    fn uses(&self) -> impl Iterator<Item = &MemberUse> {
+        self.drifts.iter().flat_map(|drift| &drift.uses)
+    }
+
+    /// Returns the highlighted declaration.
+    pub fn selected_use(&self) -> Option<&MemberUse> {
+        self.uses().nth(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.uses().count();
+        self.selected = (self.selected + 1).min(len.saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Popup showing a [`DriftView`].
+#[derive(Debug)]
+pub struct DriftPopup<'a> {
+    tree: &'a DependencyTree,
+    view: &'a DriftView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> DriftPopup<'a> {
+    pub fn new(tree: &'a DependencyTree, view: &'a DriftView) -> Self {
+        Self {
+            tree,
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+
+    fn use_line(&self, member_use: &MemberUse, selected: bool) -> Line<'a> {
+        let member = self
+            .tree
+            .node(member_use.member)
+            .map_or("?", |node| node.display_name())
+            .to_string();
+        let name_style = if selected {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new()
+        };
+        let mut spans = vec![
+            Span::raw("   "),
+            Span::styled(member, name_style),
+            Span::raw(format!(" {}", member_use.requirement)),
+        ];
+        if member_use.inherited {
+            spans.push(Span::styled(" (workspace)", PLACEHOLDER));
+        }
+        match member_use.kind {
+            DependencyType::Normal => {}
+            kind @ DependencyType::Dev => {
+                spans.push(Span::styled(" dev", dependency_type_style(kind)));
+            }
+            kind @ DependencyType::Build => {
+                spans.push(Span::styled(" build", dependency_type_style(kind)));
+            }
+        }
+        let mut features: Vec<String> = member_use
+            .features
+            .iter()
+            .map(|feature| format!("+{feature}"))
+            .collect();
+        if !member_use.default_features {
+            features.insert(0, "no defaults".to_string());
+        }
+        if !features.is_empty() {
+            spans.push(Span::styled(
+                format!(" [{}]", features.join(", ")),
+                PLACEHOLDER,
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Widget for DriftPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" VERSION DRIFT "))
+            .title_bottom(Line::from(" enter: go to · esc: close "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        if self.view.drifts.is_empty() {
+            Paragraph::new(Line::from(
+                " Workspace members agree on every shared dependency",
+            ))
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+            return;
+        }
+
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+        let mut idx = 0;
+        for drift in &self.view.drifts {
+            let requirements = match drift.requirements() {
+                1 => "same requirement, different features".to_string(),
+                n => format!("{n} requirements"),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {}", drift.name), Style::from(HEADER)),
+                Span::styled(
+                    format!(" ({requirements}, unify in [workspace.dependencies])"),
+                    PLACEHOLDER,
+                ),
+            ]));
+            for member_use in &drift.uses {
+                if idx == self.view.selected {
+                    selected_line = lines.len();
+                }
+                lines.push(self.use_line(member_use, idx == self.view.selected));
+                idx += 1;
+            }
+        }
+
+        let height = block.inner(area).height as usize;
+        let scroll = (selected_line + 1).saturating_sub(height);
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,43 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{Error, Result};
+
+/// A file position to open in the user's editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorRequest {
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+/// Opens `path` at `line` in `$VISUAL`/`$EDITOR` and waits for it to exit.
+///
+/// The line is passed as `+<line>`, which vi, vim, neovim, nano, emacs,
+/// helix and kakoune all understand. The terminal must be restored by the
+/// caller before and after.
+pub fn open_in_editor(path: &Path, line: usize) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::Editor("$EDITOR is empty".to_string()))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(format!("+{line}"))
+        .arg(path)
+        .status()
+        .map_err(|err| Error::Editor(format!("failed to run editor `{editor}`: {err}")))?;
+    if !status.success() {
+        return Err(Error::Editor(format!(
+            "editor `{editor}` exited with {status}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::{fmt::Write as _, fs::File, io::Write, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{Error, Result, core::DependencyTree};
+
+use super::{
+    help::HelpPopupStyle,
+    widget::{TreeWidgetState, TreeWidgetStyle, render::RenderContext},
+};
+
+/// Dependency tables exported from the selected crate.
+#[derive(Debug)]
+pub struct ExportView {
+    /// Crate the tables were exported from.
+    pub name: String,
+    pub text: String,
+    /// Index of the first visible line.
+    pub scroll: usize,
+    /// File the tables were written to, once saved.
+    pub saved: Option<PathBuf>,
+}
+
+impl ExportView {
+    pub fn new(name: String, text: String) -> Self {
+        Self {
+            name,
+            text,
+            scroll: 0,
+            saved: None,
+        }
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        let len = self.text.lines().count();
+        self.scroll = (self.scroll + lines).min(len.saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Writes the tables to `<name>-dependencies.toml` in the current
+    /// directory, never overwriting an existing file.
+    pub fn save(&mut self) -> Result<()> {
+        let path = PathBuf::from(format!("{}-dependencies.toml", self.name));
+        let io_error = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let mut file = File::create_new(&path).map_err(io_error)?;
+        file.write_all(self.text.as_bytes()).map_err(io_error)?;
+        self.saved = Some(path);
+        Ok(())
+    }
+}
+
+/// Scrollable popup showing an [`ExportView`].
+#[derive(Debug)]
+pub struct ExportPopup<'a> {
+    view: &'a ExportView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> ExportPopup<'a> {
+    pub fn new(view: &'a ExportView) -> Self {
+        Self {
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for ExportPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let hint = match &self.view.saved {
+            Some(path) => format!(" saved to {} · esc: close ", path.display()),
+            None => " w: write to file · esc: close ".to_string(),
+        };
+        let block = Block::new()
+            .title(Line::from(format!(" dependencies of {} ", self.view.name)))
+            .title_bottom(Line::from(hint))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let lines = self
+            .view
+            .text
+            .lines()
+            .skip(self.view.scroll)
+            .take(height)
+            .map(|line| Line::from(format!(" {line}")))
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// Renders `tree` fully expanded as text colored with ANSI escape codes,
+/// styled like the TUI draws it.
+pub fn tree_ansi(tree: &DependencyTree, style: &TreeWidgetStyle) -> String {
+    let mut state = TreeWidgetState::default();
+    state.expand_all(tree);
+    state.set_duplicated(tree.duplicated_crates().into_iter().collect());
+    let lines = RenderContext::new(tree, &mut state, style, None).render_all();
+    let mut out = String::new();
+    for line in lines {
+        for span in &line.spans {
+            let codes = sgr_codes(line.style.patch(span.style));
+            if codes.is_empty() {
+                out.push_str(&span.content);
+            } else {
+                let _ = write!(out, "\x1b[{}m{}\x1b[0m", codes.join(";"), span.content);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the SGR parameters setting `style` on a terminal.
+fn sgr_codes(style: Style) -> Vec<String> {
+    const MODIFIERS: [(Modifier, &str); 8] = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    let mut codes: Vec<String> = MODIFIERS
+        .into_iter()
+        .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    codes.extend(style.fg.and_then(|color| color_code(color, false)));
+    codes.extend(style.bg.and_then(|color| color_code(color, true)));
+    codes
+}
+
+/// Returns the SGR parameter for `color` in the foreground, or the
+/// background when `background` is set.
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let named = |offset: u8| Some((base + offset).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => named(60),
+        Color::LightRed => named(61),
+        Color::LightGreen => named(62),
+        Color::LightYellow => named(63),
+        Color::LightBlue => named(64),
+        Color::LightMagenta => named(65),
+        Color::LightCyan => named(66),
+        Color::White => named(67),
+        Color::Indexed(idx) => Some(format!("{};5;{idx}", base + 8)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+    }
+}
@@ -0,0 +1,258 @@
+use std::{fmt, mem, str::FromStr};
+
+use clap_cargo::style::{HEADER, LITERAL};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::core::{
+    Dependency, DependencyNode, DependencyTree, NodeId,
+    dependency::{DependencyType, SourceKind},
+};
+
+use super::widget::{SearchState, TreeWidgetState};
+
+/// Predicate narrowing the tree to the crates it accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Crates depended upon through at least one edge of this kind.
+    Kind(DependencyType),
+    /// Crates coming from this kind of source.
+    Source(SourceKind),
+    /// Direct dependencies with a newer version allowed by their requirement.
+    Outdated,
+    /// Direct dependencies whose requirement trips the publishing lints.
+    Lint,
+    /// Crates with at least this many dependents.
+    MinDependents(usize),
+    /// Crates whose name contains the committed search query.
+    Matches(String),
+}
+
+impl Filter {
+    /// Returns whether both filters constrain the same property, so only
+    /// one of them can be active at a time.
+    fn same_property(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+
+    fn accepts(
+        &self,
+        tree: &DependencyTree,
+        facts: &FilterFacts,
+        id: NodeId,
+        dependency: &Dependency,
+    ) -> bool {
+        match self {
+            Self::Kind(kind) => tree.parents[id.0].iter().any(|&parent| {
+                let edge = match tree.node(parent) {
+                    Some(DependencyNode::Group(group)) => group.kind,
+                    _ => facts
+                        .edge_kinds
+                        .get(&(parent, id))
+                        .copied()
+                        .unwrap_or(DependencyType::Normal),
+                };
+                edge == *kind
+            }),
+            Self::Source(source) => dependency.source == *source,
+            Self::Outdated => facts.outdated.contains(&id),
+            Self::Lint => facts.lint_offenders.contains(&id),
+            Self::MinDependents(min) => tree.dependents(id).len() >= *min,
+            Self::Matches(query) => dependency.name.contains(query.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kind(kind) => {
+                let kind = match kind {
+                    DependencyType::Normal => "normal",
+                    DependencyType::Dev => "dev",
+                    DependencyType::Build => "build",
+                };
+                write!(f, "kind={kind}")
+            }
+            Self::Source(source) => write!(f, "source={}", source.label()),
+            Self::Outdated => f.write_str("outdated"),
+            Self::Lint => f.write_str("lint"),
+            Self::MinDependents(min) => write!(f, "dependents>={min}"),
+            Self::Matches(query) => write!(f, "matches:{query:?}"),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// Parses the chip syntax of [`Filter`]'s `Display`, except `matches`,
+    /// which comes from the search prompt.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(min) = s.strip_prefix("dependents>=") {
+            return min
+                .trim()
+                .parse()
+                .map(Self::MinDependents)
+                .map_err(|_| format!("`{min}` is not a number of dependents"));
+        }
+        let (name, value) = s.split_once('=').unwrap_or((s, ""));
+        match (name.trim(), value.trim()) {
+            ("kind", "normal") => Ok(Self::Kind(DependencyType::Normal)),
+            ("kind", "dev") => Ok(Self::Kind(DependencyType::Dev)),
+            ("kind", "build") => Ok(Self::Kind(DependencyType::Build)),
+            ("kind", value) => Err(format!(
+                "unknown kind `{value}`, expected normal, dev or build"
+            )),
+            ("source", "path") => Ok(Self::Source(SourceKind::Path)),
+            ("source", "git") => Ok(Self::Source(SourceKind::Git)),
+            ("source", "registry") => Ok(Self::Source(SourceKind::Registry)),
+            ("source", value) => Err(format!(
+                "unknown source `{value}`, expected path, git or registry"
+            )),
+            ("outdated", "") => Ok(Self::Outdated),
+            ("lint", "") => Ok(Self::Lint),
+            _ => Err(format!(
+                "unknown filter `{s}`, expected kind=, source=, outdated, lint or dependents>="
+            )),
+        }
+    }
+}
+
+/// Data about the shown tree some filters look up.
+#[derive(Debug, Default)]
+pub struct FilterFacts {
+    outdated: FxHashSet<NodeId>,
+    lint_offenders: FxHashSet<NodeId>,
+    /// Kinds of the edges whose group nodes were dissolved.
+    edge_kinds: FxHashMap<(NodeId, NodeId), DependencyType>,
+}
+
+impl FilterFacts {
+    pub fn new(tree: &DependencyTree, state: &TreeWidgetState) -> Self {
+        Self {
+            outdated: tree
+                .crate_nodes()
+                .filter(|&id| state.has_update_in_range(id))
+                .collect(),
+            lint_offenders: tree
+                .requirement_lints()
+                .into_iter()
+                .map(|(_, id, _)| id)
+                .collect(),
+            edge_kinds: state.edge_kinds().clone(),
+        }
+    }
+}
+
+/// Filters narrowing the tree together: a crate is shown when every one of
+/// them accepts it.
+#[derive(Debug, Clone, Default)]
+pub struct FilterSet {
+    filters: Vec<Filter>,
+}
+
+impl FilterSet {
+    /// Active filters, in the order they were added.
+    pub fn filters(&self) -> &[Filter] {
+        &self.filters
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.filters.is_empty()
+    }
+
+    /// Removes `filter` when active, otherwise adds it in place of any
+    /// filter on the same property. Returns whether it is now active.
+    pub fn toggle(&mut self, filter: Filter) -> bool {
+        if let Some(pos) = self.filters.iter().position(|active| *active == filter) {
+            self.filters.remove(pos);
+            return false;
+        }
+        self.filters.retain(|active| !active.same_property(&filter));
+        self.filters.push(filter);
+        true
+    }
+
+    /// Replaces the search query the tree is narrowed to.
+    pub fn set_query(&mut self, query: Option<&str>) {
+        self.filters
+            .retain(|active| !matches!(active, Filter::Matches(_)));
+        if let Some(query) = query.filter(|query| !query.is_empty()) {
+            self.filters.push(Filter::Matches(query.to_string()));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.filters.clear();
+    }
+
+    /// Computes the crates every filter accepts, along with their
+    /// ancestors, or `None` when no filter is active.
+    pub fn search_state(&self, tree: &DependencyTree, facts: &FilterFacts) -> Option<SearchState> {
+        self.is_active().then(|| {
+            TreeWidgetState::filter(tree, |id, dependency| {
+                self.filters
+                    .iter()
+                    .all(|filter| filter.accepts(tree, facts, id, dependency))
+            })
+        })
+    }
+
+    /// Narrows the results of a search to the crates every other filter
+    /// accepts. The search replaces any committed query.
+    pub fn narrow(
+        &self,
+        tree: &DependencyTree,
+        facts: &FilterFacts,
+        search_state: SearchState,
+    ) -> SearchState {
+        if self
+            .filters
+            .iter()
+            .all(|filter| matches!(filter, Filter::Matches(_)))
+        {
+            return search_state;
+        }
+        TreeWidgetState::filter_matches(tree, &search_state, |id, dependency| {
+            self.filters.iter().all(|filter| {
+                matches!(filter, Filter::Matches(_)) || filter.accepts(tree, facts, id, dependency)
+            })
+        })
+    }
+}
+
+/// One-line bar listing the active filters as chips.
+#[derive(Debug)]
+pub struct FilterBar<'a> {
+    filters: &'a FilterSet,
+}
+
+impl<'a> FilterBar<'a> {
+    pub fn new(filters: &'a FilterSet) -> Self {
+        Self { filters }
+    }
+}
+
+impl Widget for FilterBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let chip_style = Style::from(LITERAL).add_modifier(Modifier::REVERSED);
+        let mut spans = vec![Span::styled("filters ", Style::from(HEADER))];
+        for filter in self.filters.filters() {
+            spans.push(Span::styled(format!(" {filter} ✕ "), chip_style));
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(
+            " (:filter to toggle, C to clear)",
+            Modifier::DIM,
+        ));
+        Line::from(spans).render(area, buf);
+    }
+}
@@ -6,19 +6,89 @@ use ratatui::{
     text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
+use serde::Serialize;
+
+use crate::core::commands::NodeCommands;
 
 const KEY_BINDINGS: &[(&str, &str)] = &[
     ("?", "Show this popup"),
     ("/", "Search"),
+    (
+        ":",
+        "Expand/collapse by pattern or toggle a filter (:filter)",
+    ),
+    ("↑/↓", "Recall earlier searches at the prompt"),
     ("←", "Collapse selected"),
-    ("→", "Expand selected"),
+    ("→", "Expand selected or load more children"),
     ("space", "Toggle expand/collapse"),
     ("[", "Go to previous sibling"),
     ("]", "Go to next sibling"),
+    ("{", "Go to first sibling"),
+    ("}", "Go to last sibling"),
     ("p", "Go to parent"),
+    ("^", "Show full path to selected crate"),
+    ("enter/.", "Re-root view at selected crate"),
+    ("backspace", "Return to previous root"),
+    ("f<key>", "Jump to sibling by first letter"),
+    ("<", "Go to previous workspace member"),
+    (">", "Go to next workspace member"),
+    ("*", "Pin/unpin crate across sessions"),
+    ("P", "Show pinned crates"),
+    ("F", "Toggle requirement lint filter"),
+    ("I", "Show dependencies members declare differently"),
+    ("C", "Clear all filters"),
+    ("!<key>", "Run configured command on crate"),
+    ("n", "Edit note on crate"),
+    ("m", "Mark crate for comparison"),
+    ("K", "Pin crate above the tree once scrolled past it"),
+    ("c", "Compare two marked crates"),
+    ("t", "Trace paths between two crates"),
+    ("tab", "Toggle statistics (s: sort duplicates)"),
+    ("i", "Toggle details panel"),
+    ("u", "Toggle dependent counts"),
+    ("B", "Toggle build cost estimate"),
+    ("H", "Toggle release age heatmap"),
+    ("O", "Show dependency diet report"),
+    ("Y", "Show command line reproducing this view"),
+    ("U", "Run cargo update for crate"),
+    ("A", "Add a dependency (cargo add)"),
+    ("X", "Remove direct dependency"),
+    ("e", "Edit declaring Cargo.toml"),
+    ("L", "Show changelog"),
+    ("R", "Show README"),
+    ("T", "Export dependencies as TOML"),
+    ("Z", "Toggle compact/detailed rows"),
+    ("M", "Toggle minimap"),
+    ("w", "Toggle line wrapping"),
+    ("W", "Toggle workspace members only"),
+    ("G", "Toggle listing each crate with its dependents"),
+    ("V", "Toggle minimal versions resolve and diff"),
+    ("D", "Preview upgrade of crate and diff"),
+    ("F12", "Toggle debug log"),
     ("q", "Quit"),
 ];
 
+/// Key and what it does, as listed by [`keymap`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyBinding {
+    pub key: String,
+    pub action: String,
+}
+
+/// Returns the effective key bindings: the built-in ones, then the `!<key>`
+/// commands configured for the workspace.
+pub fn keymap(commands: &NodeCommands) -> Vec<KeyBinding> {
+    let builtin = KEY_BINDINGS.iter().map(|(key, action)| KeyBinding {
+        key: (*key).to_string(),
+        action: (*action).to_string(),
+    });
+    let configured = commands.iter().map(|command| KeyBinding {
+        key: format!("!{}", command.key),
+        action: format!("Run `{}`", command.template),
+    });
+    builtin.chain(configured).collect()
+}
+
 fn key_bindings() -> Text<'static> {
     let key_style = Style::from(VALID);
     let max_key_len = KEY_BINDINGS
@@ -46,9 +116,9 @@ fn key_bindings() -> Text<'static> {
 
 #[derive(Debug)]
 pub struct HelpPopupStyle {
-    border: Style,
-    title: Style,
-    default: Style,
+    pub border: Style,
+    pub title: Style,
+    pub default: Style,
 }
 
 impl Default for HelpPopupStyle {
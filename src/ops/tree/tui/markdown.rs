@@ -0,0 +1,90 @@
+use clap_cargo::style::{HEADER, LITERAL, PLACEHOLDER};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Renders markdown into styled lines.
+///
+/// Only the block and inline constructs that matter for skimming are
+/// styled: headings, code blocks, list bullets, quotes, inline code and
+/// bold text. Badge and HTML-only lines are dropped.
+pub fn markdown_lines(text: &str) -> Vec<Line<'static>> {
+    let code_style = Style::from(LITERAL);
+    let mut in_code_block = false;
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::styled(format!("    {line}"), code_style));
+            continue;
+        }
+        if trimmed.starts_with('<') || trimmed.starts_with("[![") {
+            continue;
+        }
+
+        if let Some(heading) = heading(trimmed) {
+            let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+            let mut style = Style::from(HEADER).add_modifier(Modifier::BOLD);
+            if level == 1 {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            lines.push(Line::styled(heading.to_string(), style));
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            let indent = &line[..line.len() - trimmed.len()];
+            let mut spans = vec![Span::raw(format!("{indent}• "))];
+            spans.extend(inline_spans(item));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            lines.push(Line::styled(
+                format!("│ {}", quote.trim_start()),
+                Style::from(PLACEHOLDER),
+            ));
+        } else {
+            lines.push(Line::from(inline_spans(line)));
+        }
+    }
+
+    lines
+}
+
+/// Returns the heading text if `line` is an ATX heading.
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    ((1..=6).contains(&level) && (text.is_empty() || text.starts_with(' ')))
+        .then(|| text.trim().trim_end_matches('#').trim_end())
+}
+
+/// Splits a line into spans, styling `inline code` and **bold** text.
+fn inline_spans(line: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (i, part) in line.split('`').enumerate() {
+        if i % 2 == 1 {
+            spans.push(Span::styled(part.to_string(), Style::from(LITERAL)));
+            continue;
+        }
+        for (j, text) in part.split("**").enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            if j % 2 == 1 {
+                spans.push(Span::styled(
+                    text.to_string(),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+            } else {
+                spans.push(Span::raw(text.to_string()));
+            }
+        }
+    }
+    spans
+}
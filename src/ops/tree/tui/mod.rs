@@ -1,23 +1,169 @@
+pub mod changelog;
+pub mod command;
+pub mod command_line;
+pub mod compare;
+pub mod confirm;
+pub mod control;
+pub mod crash;
+pub mod debug_log;
+pub mod details;
+pub mod drift;
+pub mod editor;
+pub mod export;
+pub mod filter;
 pub mod help;
+pub mod markdown;
+pub mod notes;
+pub mod options;
+pub mod pager;
+pub mod path;
+pub mod pins;
+pub mod readme;
 pub mod state;
+pub mod stats;
+pub mod versions;
 pub mod widget;
 
 use clap_cargo::style::{HEADER, USAGE};
 use ratatui::{
     Frame,
-    layout::{Position, Rect},
+    layout::{Constraint, Flex, Layout, Position, Rect, Size},
     style::{Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{Paragraph, Scrollbar, ScrollbarOrientation},
 };
 
+use crate::core::manifest;
+
+use changelog::ChangelogPopup;
+use command::LogPane;
+use command_line::CommandLinePopup;
+use compare::ComparePopup;
+use confirm::{ConfirmPopup, PromptPopup, UpgradePromptPopup};
+use debug_log::DebugLogPane;
+use details::DetailsPanel;
+use drift::DriftPopup;
+use export::ExportPopup;
+use filter::FilterBar;
 use help::HelpPopup;
+use notes::NotePopup;
+use options::ScrollbarSide;
+use path::PathPopup;
+use pins::PinsPopup;
+use readme::ReadmePopup;
 use state::{InputMode, TuiState};
-use widget::TreeWidget;
+use stats::StatsScreen;
+use versions::VersionsPopup;
+use widget::{NodeIcons, TreeWidget, TreeWidgetStyle};
+
+/// Width of the details panel, including its borders.
+const DETAILS_WIDTH: u16 = 42;
 
 pub fn draw_tui(frame: &mut Frame, state: &mut TuiState) {
-    draw_tree(frame, frame.area(), state);
+    if state.show_details {
+        let [tree_area, details_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(DETAILS_WIDTH)])
+                .areas(frame.area());
+        draw_tree(frame, tree_area, state);
+        draw_details(frame, details_area, state);
+    } else {
+        draw_tree(frame, frame.area(), state);
+    }
+    if let Some(stats) = &state.stats {
+        let screen = StatsScreen::new(&state.dependency_tree, stats);
+        frame.render_widget(screen, frame.area());
+    }
     draw_help_text(frame, frame.area());
+    if let Some(compare) = &state.compare {
+        let popup = ComparePopup::new(&state.dependency_tree, compare);
+        frame.render_widget(popup, centered_rect(frame.area(), 60, 70));
+    }
+    if let Some(view) = &state.changelog {
+        let popup = ChangelogPopup::new(view);
+        frame.render_widget(popup, centered_rect(frame.area(), 80, 80));
+    }
+    if let Some(view) = &state.readme {
+        let popup = ReadmePopup::new(view);
+        frame.render_widget(popup, centered_rect(frame.area(), 80, 80));
+    }
+    if let Some(view) = &state.export {
+        let popup = ExportPopup::new(view);
+        frame.render_widget(popup, centered_rect(frame.area(), 70, 70));
+    }
+    if let Some(view) = &state.path_view {
+        frame.render_widget(PathPopup::new(view), centered_rect(frame.area(), 60, 60));
+    }
+    if let Some(view) = &state.versions_view {
+        frame.render_widget(
+            VersionsPopup::new(view),
+            centered_rect(frame.area(), 50, 40),
+        );
+    }
+    if let Some(view) = &state.drift_view {
+        let popup = DriftPopup::new(&state.dependency_tree, view);
+        frame.render_widget(popup, centered_rect(frame.area(), 70, 60));
+    }
+    if let Some(view) = &state.pins_view {
+        let popup = PinsPopup::new(&state.dependency_tree, state.pins(), view);
+        frame.render_widget(popup, centered_rect(frame.area(), 50, 50));
+    }
+    if let Some(log) = &state.command_log {
+        let area = frame.area();
+        let height = area.height / 2;
+        let area = Rect {
+            y: area.bottom().saturating_sub(height),
+            height,
+            ..area
+        };
+        frame.render_widget(LogPane::new(log), area);
+    }
+    if state.show_log {
+        let area = frame.area();
+        let height = area.height / 3;
+        let area = Rect {
+            y: area.bottom().saturating_sub(height),
+            height,
+            ..area
+        };
+        frame.render_widget(DebugLogPane::new(&state.log_buffer), area);
+    }
+    if let Some(prompt) = &state.add_prompt {
+        let area = centered(frame.area(), Size::new(frame.area().width / 2, 3));
+        frame.render_widget(PromptPopup::new(prompt), area);
+        frame.set_cursor_position(Position::new(
+            area.x + Line::from(prompt.input.as_str()).width() as u16 + 2,
+            area.y + 1,
+        ));
+    }
+    if let Some(prompt) = &state.upgrade_prompt {
+        let area = centered(frame.area(), Size::new(frame.area().width / 2, 3));
+        frame.render_widget(UpgradePromptPopup::new(prompt), area);
+        frame.set_cursor_position(Position::new(
+            area.x + Line::from(prompt.input.as_str()).width() as u16 + 2,
+            area.y + 1,
+        ));
+    }
+    if let Some(prompt) = &state.note_prompt {
+        let area = centered(frame.area(), Size::new(frame.area().width / 2, 3));
+        frame.render_widget(NotePopup::new(prompt), area);
+        frame.set_cursor_position(Position::new(
+            area.x + Line::from(prompt.input.as_str()).width() as u16 + 2,
+            area.y + 1,
+        ));
+    }
+    if let Some(command_line) = &state.command_line {
+        let area = centered(frame.area(), Size::new(frame.area().width / 2, 3));
+        frame.render_widget(CommandLinePopup::new(command_line), area);
+        frame.set_cursor_position(Position::new(
+            area.x + Line::from(command_line.input.as_str()).width() as u16 + 3,
+            area.y + 1,
+        ));
+    }
+    if let Some(confirm) = &state.confirm {
+        let popup = ConfirmPopup::new(confirm);
+        let area = centered(frame.area(), popup.size());
+        frame.render_widget(popup, area);
+    }
     if state.show_help {
         draw_help_popup(frame);
     }
@@ -26,7 +172,35 @@ pub fn draw_tui(frame: &mut Frame, state: &mut TuiState) {
 pub fn draw_tree(frame: &mut Frame, area: Rect, state: &mut TuiState) {
     state.advance_spinner();
 
-    let tree_widget = TreeWidget::new(&state.dependency_tree)
+    let area = if state.focus_trail().next().is_some() {
+        let [trail_area, area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        draw_focus_trail(frame, trail_area, state);
+        area
+    } else {
+        area
+    };
+    let area = if state.filters.is_active() {
+        let [bar_area, area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        frame.render_widget(FilterBar::new(&state.filters), bar_area);
+        area
+    } else {
+        area
+    };
+
+    let style = TreeWidgetStyle {
+        icons: state.options.icons.then(NodeIcons::nerd_font),
+        show_dependents: state.options.show_dependents,
+        show_match_counts: state.input_mode == InputMode::SearchResults,
+        compact: state.options.compact,
+        ambiguous_versions_only: state.options.ambiguous_versions_only,
+        accessible: state.options.accessible,
+        breadcrumb_elision: state.options.breadcrumb_elision,
+        ..TreeWidgetStyle::themed(state.options.theme).indented(state.options.indent_width)
+    };
+    let (thumb_style, track_style) = (style.scrollbar_thumb_style, style.scrollbar_track_style);
+    let mut tree_widget = TreeWidget::new(&state.dependency_tree)
         .search_query(
             matches!(
                 state.input_mode,
@@ -35,14 +209,35 @@ pub fn draw_tree(frame: &mut Frame, area: Rect, state: &mut TuiState) {
             .then_some(state.search_query.as_str()),
         )
         .search_prompt_symbol(state.search_prompt_symbol())
-        .scrollbar(
-            Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .track_symbol(Some("┆"))
-                .thumb_symbol("▐")
-                .begin_symbol(Some("▴"))
-                .end_symbol(Some("▾")),
-        );
+        .style(style)
+        .wrap(state.options.wrap)
+        .minimap(state.options.minimap && !state.options.accessible)
+        .smooth_scroll(state.options.smooth_scroll);
+    let scrollbar_side = state.options.scrollbar;
+    if !state.options.accessible && scrollbar_side != ScrollbarSide::Hidden {
+        let orientation = match scrollbar_side {
+            ScrollbarSide::Left => ScrollbarOrientation::VerticalLeft,
+            _ => ScrollbarOrientation::VerticalRight,
+        };
+        tree_widget = tree_widget
+            .scrollbar(
+                Scrollbar::new(orientation)
+                    .track_symbol(Some("┆"))
+                    .thumb_symbol("▐")
+                    .begin_symbol(Some("▴"))
+                    .end_symbol(Some("▾"))
+                    .thumb_style(thumb_style)
+                    .track_style(track_style),
+            )
+            .scrollbar_on_left(scrollbar_side == ScrollbarSide::Left)
+            .scrollbar_auto_hide(!state.options.scrollbar_always);
+    }
     frame.render_stateful_widget(tree_widget, area, &mut state.tree_widget_state);
+    if state.is_rebuilding() {
+        // The old view stays usable until the new one is swapped in.
+        let indicator = Line::styled(" recomputing… ", Modifier::DIM).right_aligned();
+        frame.render_widget(indicator, Rect { height: 1, ..area });
+    }
 
     if state.input_mode == InputMode::Search {
         let query = state.search_query.as_str();
@@ -53,6 +248,47 @@ pub fn draw_tree(frame: &mut Frame, area: Rect, state: &mut TuiState) {
     }
 }
 
+/// Draws the crates the view was re-rooted at, starting from the workspace.
+fn draw_focus_trail(frame: &mut Frame, area: Rect, state: &TuiState) {
+    let separator = Span::styled(" › ", Modifier::DIM);
+    let mut spans = vec![Span::styled(
+        state.dependency_tree.workspace_name.clone(),
+        Style::from(HEADER),
+    )];
+    for name in state.focus_trail() {
+        spans.push(separator.clone());
+        spans.push(Span::raw(name.to_string()));
+    }
+    spans.push(Span::styled("  (backspace to go back)", Modifier::DIM));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+pub fn draw_details(frame: &mut Frame, area: Rect, state: &TuiState) {
+    let selected = state.tree_widget_state.selected_node_id();
+    let mut panel = DetailsPanel::new(&state.dependency_tree, selected);
+    if state.options.crate_lookups() {
+        let status = state
+            .selected_registry_crate()
+            .and_then(|dependency| state.crate_info.get(&dependency.key()));
+        panel = panel.crate_info(status);
+    }
+    let note = selected
+        .and_then(|id| state.dependency_tree.node(id)?.as_dependency())
+        .and_then(|dependency| state.note(&dependency.name));
+    let annotation = selected
+        .and_then(|id| state.dependency_tree.node(id)?.as_dependency())
+        .and_then(|dependency| state.annotations.get(&dependency.key()));
+    let workspace_declaration =
+        selected.and_then(|id| manifest::workspace_declaration(&state.dependency_tree, id));
+    frame.render_widget(
+        panel
+            .note(note)
+            .annotation(annotation)
+            .workspace_declaration(workspace_declaration),
+        area,
+    );
+}
+
 pub fn draw_help_text(frame: &mut Frame, area: Rect) {
     let key_style = Style::from(HEADER)
         .add_modifier(Modifier::BOLD)
@@ -88,3 +324,26 @@ pub fn draw_help_popup(frame: &mut Frame) {
     let area = frame.area().clamp(area);
     frame.render_widget(help_popup, area);
 }
+
+/// Returns a rectangle of the given size centered within `area`, clamped to it.
+fn centered(area: Rect, size: Size) -> Rect {
+    let width = size.width.min(area.width);
+    let height = size.height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Returns a rectangle covering the given percentage of `area`, centered within it.
+fn centered_rect(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Percentage(width_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Percentage(height_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
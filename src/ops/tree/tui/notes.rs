@@ -0,0 +1,51 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use super::help::HelpPopupStyle;
+
+/// Text prompt editing the note attached to a crate.
+#[derive(Debug, Clone)]
+pub struct NotePrompt {
+    /// Crate the note is attached to.
+    pub name: String,
+    /// Note typed so far, starting from the saved one.
+    pub input: String,
+}
+
+/// Single-line input dialog for a [`NotePrompt`].
+#[derive(Debug)]
+pub struct NotePopup<'a> {
+    prompt: &'a NotePrompt,
+    style: HelpPopupStyle,
+}
+
+impl<'a> NotePopup<'a> {
+    pub fn new(prompt: &'a NotePrompt) -> Self {
+        Self {
+            prompt,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for NotePopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(format!(" note on {} ", self.prompt.name)))
+            .title_bottom(Line::from(" enter: save · empty: remove · esc: cancel "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        Paragraph::new(Line::from(format!(" {}", self.prompt.input)))
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::core::{
+    DependencyTree, annotate::Annotators, bloat::BloatReport, commands::NodeCommands,
+    layers::LayerRules, remote::RemoteCrate,
+};
+
+use super::{
+    command::LoadCommand,
+    widget::{BreadcrumbElision, IndentWidth, Theme},
+};
+
+/// Which nodes are expanded when the tree is first shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExpandPolicy {
+    /// Every node is expanded.
+    #[default]
+    All,
+    /// Only the workspace members are shown.
+    Collapsed,
+    /// Dependencies are shown up to this many levels below the members.
+    Depth(usize),
+    /// Only the direct dependencies of workspace members are shown.
+    Members,
+    /// Dependencies are shown as deep as the tree still fits the terminal
+    /// height.
+    Fit,
+}
+
+impl FromStr for ExpandPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "none" => Ok(Self::Collapsed),
+            "members" => Ok(Self::Members),
+            "fit" => Ok(Self::Fit),
+            depth => depth.parse().map(Self::Depth).map_err(|_| {
+                format!("expected `all`, `none`, `members`, `fit` or a depth, got `{s}`")
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ExpandPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Collapsed => f.write_str("none"),
+            Self::Members => f.write_str("members"),
+            Self::Fit => f.write_str("fit"),
+            Self::Depth(depth) => write!(f, "{depth}"),
+        }
+    }
+}
+
+/// Workspace members the tree is rooted on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MemberSelection {
+    /// Every member, as with `--workspace`.
+    #[default]
+    All,
+    /// Members matching any of these `--package` specs.
+    Packages(Vec<String>),
+    /// Every member but those matching these `--exclude` specs.
+    Excluding(Vec<String>),
+    /// Members in these directories, the workspace's `default-members`.
+    Dirs(Vec<PathBuf>),
+}
+
+impl MemberSelection {
+    /// Roots `tree` on the selected members.
+    pub fn apply(&self, tree: DependencyTree) -> DependencyTree {
+        match self {
+            Self::All => tree,
+            Self::Packages(specs) => tree
+                .members_where(|dependency| specs.iter().any(|spec| dependency.matches_spec(spec))),
+            Self::Excluding(specs) if specs.is_empty() => tree,
+            Self::Excluding(specs) => tree.members_where(|dependency| {
+                !specs.iter().any(|spec| dependency.matches_spec(spec))
+            }),
+            Self::Dirs(dirs) => tree.members_where(|dependency| {
+                dependency
+                    .manifest_dir
+                    .as_deref()
+                    .is_some_and(|dir| dirs.iter().any(|member| member == Path::new(dir)))
+            }),
+        }
+    }
+}
+
+/// Where the scrollbar is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollbarSide {
+    Left,
+    #[default]
+    Right,
+    /// No scrollbar at all.
+    Hidden,
+}
+
+impl FromStr for ScrollbarSide {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "none" => Ok(Self::Hidden),
+            _ => Err(format!("expected `left`, `right` or `none`, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for ScrollbarSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Hidden => "none",
+        })
+    }
+}
+
+/// User-facing configuration for the interactive tree.
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Manifest of the workspace being shown, used when running cargo commands.
+    pub manifest_path: Option<PathBuf>,
+    /// Longest a cargo command or resolve started from the TUI may run
+    /// before it is stopped.
+    pub load_timeout: Option<Duration>,
+    /// Child process resolving the workspace again after cargo commands, so
+    /// that a resolve running past `load_timeout` can be killed. Resolved in
+    /// this process when `None`.
+    pub load_command: Option<LoadCommand>,
+    /// Command-line flags the shown graph was loaded with (package
+    /// selection, features, manifest), repeated by [`TuiOptions::view_args`]
+    /// callers to reproduce the view.
+    pub launch_args: Vec<String>,
+    /// Members the tree is rooted on, re-applied to reloaded trees.
+    pub members: MemberSelection,
+    /// Registry crate explored with `--crate`, which reloaded trees of its
+    /// probe package are rooted on again.
+    pub remote_crate: Option<RemoteCrate>,
+    /// Initial expansion of the tree.
+    pub expand: ExpandPolicy,
+    /// Depth below the roots past which nodes are not expanded.
+    pub max_depth_guard: Option<usize>,
+    /// Color theme of the tree.
+    pub theme: Theme,
+    /// Width of each indentation level of the tree guides.
+    pub indent_width: IndentWidth,
+    /// Height of the inline viewport the TUI is drawn in below the prompt,
+    /// `None` to use the alternate screen.
+    pub inline: Option<u16>,
+    /// Render Nerd Font icons in a gutter column.
+    pub icons: bool,
+    /// Soft-wrap long rows instead of truncating them.
+    pub wrap: bool,
+    /// Side the scrollbar is drawn on.
+    pub scrollbar: ScrollbarSide,
+    /// Ancestors the breadcrumb keeps when it is too wide.
+    pub breadcrumb_elision: BreadcrumbElision,
+    /// Keep the scrollbar shown while the whole tree fits on screen.
+    pub scrollbar_always: bool,
+    /// Show a minimap of the whole tree next to it.
+    pub minimap: bool,
+    /// Animate scrolling on long jumps, e.g. to a search hit.
+    pub smooth_scroll: bool,
+    /// Describe rows in plain text for screen readers.
+    pub accessible: bool,
+    /// Wrap around at the ends when moving between siblings.
+    pub wrap_siblings: bool,
+    /// Render crates by name only, without versions and suffixes.
+    pub compact: bool,
+    /// Only show the versions of crates present in more than one version.
+    pub ambiguous_versions_only: bool,
+    /// Show how many crates depend on each crate.
+    pub show_dependents: bool,
+    /// Only show crates with at least this many dependents.
+    pub min_dependents: Option<usize>,
+    /// Only show crates whose name contains this pattern, and their
+    /// ancestors, as if it were a committed search.
+    pub filter: Option<String>,
+    /// List the build targets of workspace members as child nodes.
+    pub show_targets: bool,
+    /// Order children by how many crates depend on them, most used first.
+    pub sort_by_dependents: bool,
+    /// Drop the dependency group nodes, badging dev and build dependencies
+    /// instead.
+    pub hide_groups: bool,
+    /// Restrict the view to workspace members and the edges between them.
+    pub members_only: bool,
+    /// List each crate once with its dependents below it, instead of the
+    /// tree from the members down.
+    pub group_by_crate: bool,
+    /// Workspace root to nest members under folder nodes by directory.
+    pub group_by_dir: Option<PathBuf>,
+    /// Show the estimated build cost share of each subtree.
+    pub show_cost: bool,
+    /// Color versions by how long ago they were released.
+    pub show_age: bool,
+    /// Flag crates pulling `std` into members declaring `#![no_std]`.
+    pub check_no_std: bool,
+    /// `cargo bloat` report whose sizes are attributed to each subtree.
+    pub bloat: Option<BloatReport>,
+    /// Layering rules whose violations are flagged in the tree.
+    pub layer_rules: LayerRules,
+    /// Annotators adding custom badges and detail fields to crates.
+    pub annotators: Annotators,
+    /// Shell commands run on the selected crate with `!<key>`.
+    pub node_commands: NodeCommands,
+    /// Save committed search queries with the workspace state so later
+    /// sessions can recall them.
+    pub save_search_history: bool,
+    /// Query crates.io for registry metadata shown in the details panel.
+    pub online: bool,
+    /// Gather supply-chain trust signals for the details panel.
+    ///
+    /// Implies crates.io lookups and also checks the repository URL.
+    pub trust: bool,
+}
+
+impl TuiOptions {
+    /// Returns whether the shown tree is derived from the full graph rather
+    /// than being the graph itself.
+    pub fn derives_view(&self) -> bool {
+        self.members_only || self.group_by_crate || self.hide_groups
+    }
+
+    /// Returns whether the details panel queries crates.io.
+    pub fn crate_lookups(&self) -> bool {
+        self.online || self.trust
+    }
+
+    /// Returns the flags turning on the view options set here, leaving out
+    /// defaults. `min_dependents` and the `filter` pattern are passed
+    /// separately, since the filter bar can change them.
+    pub fn view_args(&self, min_dependents: Option<usize>, filter: Option<&str>) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut value = |flag: &str, value: String| {
+            args.push(flag.to_string());
+            args.push(value);
+        };
+        if self.expand != ExpandPolicy::All {
+            value("--expand", self.expand.to_string());
+        }
+        if let Some(guard) = self.max_depth_guard {
+            value("--max-depth-guard", guard.to_string());
+        }
+        if self.theme != Theme::Default {
+            value("--theme", self.theme.to_string());
+        }
+        if self.indent_width != IndentWidth::Three {
+            value("--indent", self.indent_width.to_string());
+        }
+        if let Some(rows) = self.inline {
+            value("--inline", rows.to_string());
+        }
+        if self.scrollbar != ScrollbarSide::Right {
+            value("--scrollbar", self.scrollbar.to_string());
+        }
+        if self.breadcrumb_elision != BreadcrumbElision::Head {
+            value("--breadcrumb-elision", self.breadcrumb_elision.to_string());
+        }
+        if let Some(min) = min_dependents {
+            value("--min-used-by", min.to_string());
+        }
+        if let Some(pattern) = filter {
+            value("--filter", pattern.to_string());
+        }
+        let switches = [
+            (self.icons, "--icons"),
+            (self.wrap, "--wrap"),
+            (self.scrollbar_always, "--scrollbar-always"),
+            (self.accessible, "--accessible"),
+            (self.wrap_siblings, "--wrap-siblings"),
+            (self.compact, "--compact"),
+            (self.ambiguous_versions_only, "--clean-versions"),
+            (self.minimap, "--minimap"),
+            (self.smooth_scroll, "--smooth-scroll"),
+            (self.show_dependents, "--used-by"),
+            (self.show_targets, "--targets"),
+            (self.hide_groups, "--hide-groups"),
+            (self.members_only, "--members-only"),
+            (self.group_by_crate, "--by-crate"),
+            (self.group_by_dir.is_some(), "--group-by-dir"),
+            (self.show_cost, "--build-cost"),
+            (self.show_age, "--age-heatmap"),
+            (self.check_no_std, "--no-std-check"),
+            (self.save_search_history, "--save-search-history"),
+            (self.online, "--online"),
+            (self.trust, "--trust"),
+        ];
+        args.extend(
+            switches
+                .into_iter()
+                .filter(|&(on, _)| on)
+                .map(|(_, flag)| flag.to_string()),
+        );
+        args
+    }
+}
@@ -0,0 +1,257 @@
+use std::cell::Cell;
+
+use clap_cargo::style::WARN;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use super::{help::HelpPopupStyle, widget::render::wrap_line};
+
+/// Scroll, wrap and search state of a [`Pager`].
+///
+/// The scroll position is settled while rendering, once the viewport size
+/// is known.
+#[derive(Debug, Default)]
+pub struct PagerState {
+    /// Index of the first visible line.
+    scroll: Cell<usize>,
+    /// Keep the last line in view as lines are appended.
+    pub follow: bool,
+    /// Soft-wrap long lines instead of truncating them.
+    pub wrap: bool,
+    /// Search query being typed, `None` outside of search input.
+    pub input: Option<String>,
+    /// Committed search query, empty for none.
+    pub query: String,
+    /// Line of the match last jumped to.
+    current: Option<usize>,
+    /// Number of lines and visible rows on the last render.
+    len: Cell<usize>,
+    height: Cell<usize>,
+}
+
+impl PagerState {
+    /// Returns a state following the tail of growing output.
+    pub fn following() -> Self {
+        Self {
+            follow: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the index of the first visible line.
+    pub fn scroll(&self) -> usize {
+        self.scroll.get()
+    }
+
+    /// Shows `line` at the top, no longer following the tail.
+    pub fn scroll_to(&mut self, line: usize) {
+        self.follow = false;
+        self.scroll.set(line);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        let max = self.len.get().saturating_sub(1);
+        self.scroll_to((self.scroll() + lines).min(max));
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_to(self.scroll().saturating_sub(lines));
+    }
+
+    /// Handles a key press on the pager showing `lines`, returning whether
+    /// it was consumed.
+    ///
+    /// Keys typed after `/` go to the search query until enter commits it
+    /// (jumping to the first match below the top line) or esc cancels it.
+    pub fn handle_key<S: AsRef<str>>(&mut self, code: KeyCode, lines: &[S]) -> bool {
+        if let Some(input) = &mut self.input {
+            match code {
+                KeyCode::Enter => {
+                    self.query = std::mem::take(input);
+                    self.input = None;
+                    self.current = None;
+                    self.jump(lines, true);
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        let page = self.height.get().max(1);
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_down(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_up(1),
+            KeyCode::PageDown => self.scroll_down(page),
+            KeyCode::PageUp => self.scroll_up(page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll_to(0),
+            KeyCode::End | KeyCode::Char('G') => self.follow = true,
+            KeyCode::Char('w') => self.wrap = !self.wrap,
+            KeyCode::Char('/') => self.input = Some(String::new()),
+            KeyCode::Char('n') => self.jump(lines, true),
+            KeyCode::Char('N') => self.jump(lines, false),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Scrolls to the next (or previous) line matching the query, wrapping
+    /// around at either end.
+    fn jump<S: AsRef<str>>(&mut self, lines: &[S], forward: bool) {
+        let query = self.query.to_lowercase();
+        if query.is_empty() || lines.is_empty() {
+            return;
+        }
+        let len = lines.len();
+        let from = self.current.unwrap_or(if forward {
+            self.scroll().wrapping_sub(1)
+        } else {
+            self.scroll()
+        });
+        let found = (1..=len)
+            .map(|step| {
+                if forward {
+                    from.wrapping_add(step) % len
+                } else {
+                    (from + len - step % len) % len
+                }
+            })
+            .find(|&idx| lines[idx].as_ref().to_lowercase().contains(&query));
+        if let Some(idx) = found {
+            self.current = Some(idx);
+            self.scroll_to(idx);
+        }
+    }
+
+    fn is_match(&self, line: &Line) -> bool {
+        !self.query.is_empty()
+            && line
+                .to_string()
+                .to_lowercase()
+                .contains(&self.query.to_lowercase())
+    }
+
+    /// Returns the search prompt or status shown in the bottom border.
+    fn search_status(&self, lines: &[Line]) -> Option<String> {
+        if let Some(input) = &self.input {
+            return Some(format!(" /{input}█ "));
+        }
+        if self.query.is_empty() {
+            return None;
+        }
+        let matches: Vec<usize> = (0..lines.len())
+            .filter(|&idx| self.is_match(&lines[idx]))
+            .collect();
+        if matches.is_empty() {
+            return Some(format!(" /{}: no matches ", self.query));
+        }
+        let position = self
+            .current
+            .and_then(|current| matches.iter().position(|&idx| idx == current));
+        Some(match position {
+            Some(pos) => format!(" /{}: {}/{} · n/N ", self.query, pos + 1, matches.len()),
+            None => format!(" /{}: {} matches · n/N ", self.query, matches.len()),
+        })
+    }
+}
+
+/// Scrollable view over long text, with optional wrapping and search.
+///
+/// Used for command output, changelogs and error reports so that none of
+/// them is cut off at the popup's height.
+#[derive(Debug)]
+pub struct Pager<'a> {
+    lines: Vec<Line<'a>>,
+    block: Block<'a>,
+    state: &'a PagerState,
+    style: HelpPopupStyle,
+}
+
+impl<'a> Pager<'a> {
+    pub fn new(lines: Vec<Line<'a>>, block: Block<'a>, state: &'a PagerState) -> Self {
+        Self {
+            lines,
+            block,
+            state,
+            style: HelpPopupStyle::default(),
+        }
+    }
+
+    /// Returns the first line to show so that the last one ends the view.
+    fn tail_scroll(&self, width: usize, height: usize, wrap: bool) -> usize {
+        let mut rows = 0;
+        for (idx, line) in self.lines.iter().enumerate().rev() {
+            rows += if wrap {
+                (line.width() + 1).div_ceil(width + 1)
+            } else {
+                1
+            };
+            if rows > height {
+                return idx + 1;
+            }
+        }
+        0
+    }
+}
+
+impl Widget for Pager<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let state = self.state;
+        let inner = self.block.inner(area);
+        let height = inner.height as usize;
+        // One column goes to the left padding.
+        let width = (inner.width as usize).saturating_sub(1);
+        state.len.set(self.lines.len());
+        state.height.set(height);
+        let scroll = if state.follow {
+            self.tail_scroll(width, height, state.wrap)
+        } else {
+            state.scroll().min(self.lines.len().saturating_sub(1))
+        };
+        state.scroll.set(scroll);
+
+        let mut block = self.block;
+        if let Some(status) = state.search_status(&self.lines) {
+            block = block.title_bottom(Line::from(status).right_aligned());
+        }
+
+        let match_style = Style::from(WARN).add_modifier(Modifier::BOLD);
+        let mut rows = Vec::with_capacity(height);
+        for (idx, line) in self.lines.iter().enumerate().skip(scroll) {
+            if rows.len() >= height {
+                break;
+            }
+            let mut line = line.clone();
+            if state.is_match(&line) {
+                line = line.patch_style(if state.current == Some(idx) {
+                    match_style.add_modifier(Modifier::REVERSED)
+                } else {
+                    match_style
+                });
+            }
+            line.spans.insert(0, Span::raw(" "));
+            if state.wrap {
+                rows.extend(wrap_line(line, &[Span::raw(" ")], width + 1));
+            } else {
+                rows.push(line);
+            }
+        }
+        rows.truncate(height);
+
+        Paragraph::new(rows)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
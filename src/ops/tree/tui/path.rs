@@ -0,0 +1,159 @@
+use clap_cargo::style::PLACEHOLDER;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::core::{DependencyNode, DependencyTree, dependency::DependencyType};
+
+use super::{
+    help::HelpPopupStyle,
+    widget::{TreeWidgetState, dependency_type_style},
+};
+
+/// Ancestor of the selected row, or the row itself, in a [`PathView`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStep {
+    pub name: String,
+    /// Version of a crate, `None` for other nodes.
+    pub version: Option<String>,
+    /// Kind of the edge from the step above, when it goes through a dev or
+    /// build group.
+    pub kind: Option<DependencyType>,
+    /// Moves to the parent needed to select this step from the selected row.
+    pub up: usize,
+}
+
+/// State of the popup listing the full path from the root to the selected
+/// row, one ancestor per line.
+#[derive(Debug)]
+pub struct PathView {
+    /// Steps from the root down to the selected row.
+    pub steps: Vec<PathStep>,
+    /// Index of the highlighted step.
+    pub selected: usize,
+}
+
+impl PathView {
+    /// Collects the path to the selected row from the visible rows, so a
+    /// crate reached along several paths lists the one it is shown under.
+    pub fn new(tree: &DependencyTree, state: &TreeWidgetState) -> Self {
+        let visible = state.active_visible_nodes();
+        let mut steps: Vec<PathStep> = Vec::new();
+        let mut current = state.selected_position_cached();
+        let mut up = 0;
+        while let Some(vnode) = current.and_then(|vis_idx| visible.get(vis_idx.0)) {
+            current = vnode.parent_vis_idx;
+            let hops = up;
+            up += 1;
+            // A marker row stands for no node, and groups become the kind
+            // of the crates below them.
+            if vnode.marker.is_some() {
+                continue;
+            }
+            match tree.node(vnode.id) {
+                Some(DependencyNode::Group(group)) => {
+                    if let Some(step) = steps.last_mut() {
+                        step.kind = Some(group.kind);
+                    }
+                }
+                Some(node) => steps.push(PathStep {
+                    name: node.display_name().to_string(),
+                    version: node
+                        .as_dependency()
+                        .map(|dependency| dependency.version.clone()),
+                    kind: None,
+                    up: hops,
+                }),
+                None => break,
+            }
+        }
+        steps.reverse();
+        Self {
+            selected: steps.len().saturating_sub(1),
+            steps,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.steps.len().saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Popup showing a [`PathView`].
+#[derive(Debug)]
+pub struct PathPopup<'a> {
+    view: &'a PathView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> PathPopup<'a> {
+    pub fn new(view: &'a PathView) -> Self {
+        Self {
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for PathPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" PATH TO SELECTION "))
+            .title_bottom(Line::from(" enter: go to · esc: close "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let scroll = (self.view.selected + 1).saturating_sub(height);
+        let lines = self
+            .view
+            .steps
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .map(|(idx, step)| {
+                let name_style = if idx == self.view.selected {
+                    Style::new().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::new()
+                };
+                let mut spans = vec![
+                    Span::raw(format!(" {}", "  ".repeat(idx))),
+                    Span::styled(step.name.clone(), name_style),
+                ];
+                if let Some(version) = &step.version {
+                    spans.push(Span::styled(format!(" v{version}"), PLACEHOLDER));
+                }
+                if let Some(kind) = step.kind {
+                    let label = match kind {
+                        DependencyType::Normal => "normal",
+                        DependencyType::Dev => "dev",
+                        DependencyType::Build => "build",
+                    };
+                    spans.push(Span::styled(
+                        format!(" ({label})"),
+                        dependency_type_style(kind),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,120 @@
+use clap_cargo::style::PLACEHOLDER;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::core::{DependencyTree, NodeId};
+
+use super::help::HelpPopupStyle;
+
+/// State of the quick-access panel listing the pinned crates.
+#[derive(Debug, Default)]
+pub struct PinsView {
+    /// Index of the highlighted pin.
+    pub selected: usize,
+}
+
+impl PinsView {
+    pub fn select_next(&mut self, len: usize) {
+        self.selected = (self.selected + 1).min(len.saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Returns the first crate node named `name`, in arena order.
+pub fn find_crate(tree: &DependencyTree, name: &str) -> Option<NodeId> {
+    tree.crate_nodes().find(|&id| {
+        tree.node(id)
+            .and_then(|node| node.as_dependency())
+            .is_some_and(|d| d.name == name)
+    })
+}
+
+/// Popup listing the pinned crates with their versions in the tree.
+#[derive(Debug)]
+pub struct PinsPopup<'a> {
+    tree: &'a DependencyTree,
+    pins: &'a [String],
+    view: &'a PinsView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> PinsPopup<'a> {
+    pub fn new(tree: &'a DependencyTree, pins: &'a [String], view: &'a PinsView) -> Self {
+        Self {
+            tree,
+            pins,
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+
+    fn versions(&self, name: &str) -> Vec<&'a str> {
+        let mut versions: Vec<&str> = self
+            .tree
+            .crate_nodes()
+            .filter_map(|id| self.tree.node(id)?.as_dependency())
+            .filter(|dependency| dependency.name == name)
+            .map(|dependency| dependency.version.as_str())
+            .collect();
+        versions.sort_unstable();
+        versions.dedup();
+        versions
+    }
+}
+
+impl Widget for PinsPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" PINNED CRATES "))
+            .title_bottom(Line::from(" enter: go to · d: unpin · esc: close "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let scroll = (self.view.selected + 1).saturating_sub(height);
+        let lines = if self.pins.is_empty() {
+            vec![Line::from(" No pins yet, press * on a crate to pin it")]
+        } else {
+            self.pins
+                .iter()
+                .enumerate()
+                .skip(scroll)
+                .take(height)
+                .map(|(idx, name)| {
+                    let versions = self.versions(name);
+                    let name_style = if idx == self.view.selected {
+                        Style::new().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::new()
+                    };
+                    let detail = if versions.is_empty() {
+                        " (not in tree)".to_string()
+                    } else {
+                        format!(" v{}", versions.join(", v"))
+                    };
+                    Line::from(vec![
+                        Span::raw(" "),
+                        Span::styled(name.clone(), name_style),
+                        Span::styled(detail, Style::from(PLACEHOLDER)),
+                    ])
+                })
+                .collect()
+        };
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,107 @@
+use std::{fs, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{Error, Result, core::Dependency};
+
+use super::{help::HelpPopupStyle, markdown::markdown_lines};
+
+/// File names probed for the readme, in order of preference.
+const README_FILES: &[&str] = &["README.md", "README", "readme.md", "README.markdown"];
+
+/// Readme popup for a single crate.
+#[derive(Debug)]
+pub struct ReadmeView {
+    pub title: String,
+    pub path: PathBuf,
+    pub lines: Vec<Line<'static>>,
+    /// Index of the first visible line.
+    pub scroll: usize,
+}
+
+impl ReadmeView {
+    /// Reads the readme of `dependency` from its sources on disk.
+    pub fn load(dependency: &Dependency) -> Result<Self> {
+        for dir in dependency.source_dirs() {
+            for file in README_FILES {
+                let path = dir.join(file);
+                if let Ok(text) = fs::read_to_string(&path) {
+                    return Ok(Self {
+                        title: format!("{} v{}", dependency.name, dependency.version),
+                        path,
+                        lines: markdown_lines(&text),
+                        scroll: 0,
+                    });
+                }
+            }
+        }
+        Err(Error::NotFound(format!(
+            "no readme found in the sources of {} v{}",
+            dependency.name, dependency.version
+        )))
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+}
+
+/// Scrollable popup showing a [`ReadmeView`].
+#[derive(Debug)]
+pub struct ReadmePopup<'a> {
+    view: &'a ReadmeView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> ReadmePopup<'a> {
+    pub fn new(view: &'a ReadmeView) -> Self {
+        Self {
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for ReadmePopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(format!(" {} README ", self.view.title)))
+            .title_bottom(Line::from(format!(
+                " {} · esc: close ",
+                self.view.path.display()
+            )))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let lines = self
+            .view
+            .lines
+            .iter()
+            .skip(self.view.scroll)
+            .take(height)
+            .map(|line| {
+                let mut line = line.clone();
+                line.spans.insert(0, " ".into());
+                line
+            })
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
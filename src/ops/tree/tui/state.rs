@@ -1,10 +1,50 @@
-use std::sync::mpsc::Sender;
+use std::{fs, sync::mpsc::Sender, thread, time::Instant};
 
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use rustc_hash::FxHashMap;
+use tracing::{debug, info, warn};
 
-use crate::core::DependencyTree;
+use crate::{
+    Error,
+    core::{
+        Dependency, DependencyNode, DependencyTree, NodeId,
+        annotate::Annotation,
+        bloat::BinarySize,
+        cost::BuildCost,
+        dependency::{CrateKey, DependencyType, SourceKind, cargo_home},
+        diet::{diet_report, report_lines},
+        diff::TreeDiff,
+        export::{dependencies_toml, graph_export},
+        manifest::find_dependency_line,
+        no_std::std_creep,
+        occurrences::occurrences,
+        trust::today,
+        workspace_state::{WorkspaceState, push_search},
+    },
+};
 
-use super::widget::{SearchState, TreeWidgetState};
+use super::{
+    changelog::{ChangelogResult, ChangelogView, spawn_changelog},
+    command::{CommandLog, Loader, Resolve, ShellRequest, shell_line, spawn_cargo, spawn_resolve},
+    command_line::{CommandLine, ViewCommand},
+    compare::CompareState,
+    confirm::{AddPrompt, Confirm, UpgradePrompt},
+    control::{ControlCommand, ControlRequest},
+    debug_log::LogBuffer,
+    details::{CrateInfoStatus, spawn_crate_info},
+    drift::DriftView,
+    editor::EditorRequest,
+    export::ExportView,
+    filter::{Filter, FilterFacts, FilterSet},
+    notes::NotePrompt,
+    options::{ExpandPolicy, TuiOptions},
+    path::PathView,
+    pins::{PinsView, find_crate},
+    readme::ReadmeView,
+    stats::StatsView,
+    versions::VersionsView,
+    widget::{SearchState, TreeWidgetState, ViewSnapshot},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
@@ -17,6 +57,52 @@ pub enum InputMode {
 pub enum Event {
     Key(KeyEvent),
     SearchResult(SearchResult),
+    /// A line of output from a running cargo command.
+    CommandOutput(String),
+    /// The running cargo command exited.
+    CommandFinished {
+        success: bool,
+    },
+    /// The workspace was resolved again after a cargo command.
+    TreeReloaded(DependencyTree),
+    /// A changelog finished loading.
+    ChangelogLoaded(CrateKey, ChangelogResult),
+    /// A crates.io lookup finished.
+    CrateInfoLoaded(CrateKey, CrateInfoStatus),
+    /// Custom annotators finished going through a tree.
+    AnnotationsLoaded(FxHashMap<CrateKey, Annotation>),
+    /// A view requested by [`TuiState::show_tree`] finished building.
+    ViewRebuilt(Box<RebuiltView>),
+    /// A command arrived on the control socket.
+    Control(ControlRequest),
+}
+
+/// Tree and widget state derived on a worker thread, ready to be swapped in.
+#[derive(Debug)]
+pub struct RebuiltView {
+    generation: u64,
+    tree: DependencyTree,
+    widget_state: TreeWidgetState,
+}
+
+/// View being derived on a worker thread while the current one stays
+/// interactive.
+#[derive(Debug)]
+struct Rebuild {
+    generation: u64,
+    /// Full graph the view is derived from.
+    tree: DependencyTree,
+    /// View state to carry over once the view is swapped in.
+    snapshot: Option<ViewSnapshot>,
+}
+
+/// Message sent to the background search worker.
+#[derive(Debug)]
+pub enum SearchMessage {
+    /// Search the current tree.
+    Search(SearchRequest),
+    /// Replace the tree searched by the worker.
+    SetTree(DependencyTree),
 }
 
 #[derive(Debug, Clone)]
@@ -32,43 +118,965 @@ pub struct SearchResult {
     pub search_state: SearchState,
 }
 
+/// View the tree was re-rooted from, restored when focus is popped.
+#[derive(Debug)]
+struct FocusFrame {
+    tree: DependencyTree,
+    /// Crate the view was re-rooted at, as a node of `tree`.
+    node: NodeId,
+    name: String,
+}
+
 #[derive(Debug)]
 pub struct TuiState {
     pub running: bool,
+    /// Tree being shown, restricted to workspace members in the members-only view.
     pub dependency_tree: DependencyTree,
+    /// Full graph while the members-only view is shown.
+    full_tree: Option<DependencyTree>,
+    /// Views the shown tree was re-rooted from, outermost first.
+    focus: Vec<FocusFrame>,
+    /// Build cost estimate of the shown tree, computed when first shown.
+    build_cost: Option<BuildCost>,
+    /// Changes made by the last reload, badged in the tree until the next one.
+    reload_diff: TreeDiff,
+    /// View being rebuilt in the background, if any.
+    rebuild: Option<Rebuild>,
+    /// Generation of the last view rebuild requested, so that superseded
+    /// ones are dropped.
+    rebuild_generation: u64,
+    pub options: TuiOptions,
     pub tree_widget_state: TreeWidgetState,
     pub show_help: bool,
+    pub show_details: bool,
+    /// crates.io lookups made so far, including failures, keyed by crate.
+    pub crate_info: FxHashMap<CrateKey, CrateInfoStatus>,
+    /// Custom annotations gathered so far, keyed by crate.
+    pub annotations: FxHashMap<CrateKey, Annotation>,
+    /// Filters narrowing the tree, listed in the filter bar.
+    pub filters: FilterSet,
+    /// Statistics shown on the dashboard screen while it is open.
+    pub stats: Option<StatsView>,
+    /// Start of a path trace waiting for its end crate to be picked.
+    pub trace_from: Option<NodeId>,
+    /// Open dependency comparison popup, if any.
+    pub compare: Option<CompareState>,
+    /// Action waiting for confirmation, if any.
+    pub confirm: Option<Confirm>,
+    /// Open `cargo add` prompt, if any.
+    pub add_prompt: Option<AddPrompt>,
+    /// Open upgrade preview prompt, if any.
+    pub upgrade_prompt: Option<UpgradePrompt>,
+    /// Manifest location to open once the terminal is suspended.
+    pub editor_request: Option<EditorRequest>,
+    /// Command to run with the TUI suspended, taken by the main loop.
+    pub shell_request: Option<ShellRequest>,
+    /// Whether `!` was pressed and the next key picks a configured command.
+    awaiting_command: bool,
+    /// Whether `f` was pressed and the next key picks the sibling to jump to.
+    awaiting_jump: bool,
+    /// Output of the last cargo command, shown while the log pane is open.
+    pub command_log: Option<CommandLog>,
+    /// Versions the shown tree was resolved to.
+    resolve: Resolve,
+    /// Open changelog popup, if any.
+    pub changelog: Option<ChangelogView>,
+    /// Open readme popup, if any.
+    pub readme: Option<ReadmeView>,
+    /// Open dependency table export, if any.
+    pub export: Option<ExportView>,
+    /// Open pinned crates panel, if any.
+    pub pins_view: Option<PinsView>,
+    /// Open popup listing the path to the selected row, if any.
+    pub path_view: Option<PathView>,
+    /// Open popup picking a version of the crate a search matched, if any.
+    pub versions_view: Option<VersionsView>,
+    /// Open popup listing dependencies members declare differently, if any.
+    pub drift_view: Option<DriftView>,
+    /// Open note editor, if any.
+    pub note_prompt: Option<NotePrompt>,
+    /// Open `:` command line, if any.
+    pub command_line: Option<CommandLine>,
+    /// Lines captured from `tracing`, shown in the log pane.
+    pub log_buffer: LogBuffer,
+    /// Whether the log pane is open.
+    pub show_log: bool,
+    /// Changelogs loaded so far, including failures, keyed by crate.
+    changelog_cache: FxHashMap<CrateKey, ChangelogResult>,
     pub input_mode: InputMode,
     pub search_query: String,
+    /// Committed search queries, oldest first.
+    search_history: Vec<String>,
+    /// Entry of `search_history` recalled at the prompt, if any.
+    history_pos: Option<usize>,
+    /// Query typed before recalling history, restored past the newest entry.
+    history_draft: String,
+    /// State kept between sessions for this workspace.
+    workspace_state: WorkspaceState,
     pub search_running: bool,
     spinner_frame: usize,
     search_generation: u64,
-    search_tx: Sender<SearchRequest>,
+    search_tx: Sender<SearchMessage>,
+    event_tx: Sender<Event>,
 }
 
 impl TuiState {
-    pub fn new(dependency_tree: DependencyTree, search_tx: Sender<SearchRequest>) -> Self {
-        let mut tree_widget_state = TreeWidgetState::default();
-        tree_widget_state.expand_all(&dependency_tree);
-        TuiState {
+    pub fn new(
+        dependency_tree: DependencyTree,
+        options: TuiOptions,
+        search_tx: Sender<SearchMessage>,
+        event_tx: Sender<Event>,
+    ) -> Self {
+        let mut full_tree = None;
+        let mut edge_kinds = FxHashMap::default();
+        let dependency_tree = if options.derives_view() {
+            let view;
+            (view, edge_kinds) = Self::derive_view(&dependency_tree, &options);
+            let _ = search_tx.send(SearchMessage::SetTree(view.clone()));
+            full_tree = Some(dependency_tree);
+            view
+        } else {
+            dependency_tree
+        };
+        let mut tree_widget_state = Self::initial_widget_state(&dependency_tree, &options);
+        tree_widget_state.set_edge_kinds(edge_kinds);
+        let mut filters = FilterSet::default();
+        if let Some(min) = options.min_dependents {
+            filters.toggle(Filter::MinDependents(min));
+        }
+        filters.set_query(options.filter.as_deref());
+        let mut state = TuiState {
             running: true,
             dependency_tree,
+            full_tree,
+            focus: Vec::new(),
+            build_cost: None,
+            options,
             tree_widget_state,
             show_help: false,
+            show_details: false,
+            crate_info: FxHashMap::default(),
+            annotations: FxHashMap::default(),
+            filters,
+            stats: None,
+            trace_from: None,
+            compare: None,
+            confirm: None,
+            add_prompt: None,
+            upgrade_prompt: None,
+            editor_request: None,
+            shell_request: None,
+            awaiting_command: false,
+            awaiting_jump: false,
+            command_log: None,
+            resolve: Resolve::Locked,
+            changelog: None,
+            readme: None,
+            export: None,
+            pins_view: None,
+            path_view: None,
+            versions_view: None,
+            drift_view: None,
+            note_prompt: None,
+            command_line: None,
+            reload_diff: TreeDiff::default(),
+            rebuild: None,
+            rebuild_generation: 0,
+            log_buffer: LogBuffer::default(),
+            show_log: false,
+            changelog_cache: FxHashMap::default(),
             input_mode: InputMode::Normal,
             search_query: String::new(),
+            search_history: Vec::new(),
+            history_pos: None,
+            history_draft: String::new(),
+            workspace_state: WorkspaceState::default(),
             search_running: false,
             spinner_frame: 0,
             search_generation: 0,
             search_tx,
+            event_tx,
+        };
+        if let Some(pattern) = state.options.filter.clone() {
+            // Behave as if the pattern had been searched and committed, so
+            // `/` refines it and Esc drops it.
+            state.search_query = pattern;
+            state.input_mode = InputMode::SearchResults;
+        }
+        state.apply_filters();
+        state.apply_build_cost();
+        state.apply_binary_size();
+        state.apply_release_ages();
+        state.request_annotations();
+        state
+    }
+
+    /// Sets the state saved for this workspace by earlier sessions.
+    pub fn set_workspace_state(&mut self, workspace_state: WorkspaceState) {
+        if self.options.save_search_history {
+            self.search_history = workspace_state.search_history.clone();
+        }
+        self.workspace_state = workspace_state;
+        self.apply_pins();
+    }
+
+    /// Crates pinned in this workspace, in pinning order.
+    pub fn pins(&self) -> &[String] {
+        &self.workspace_state.pins
+    }
+
+    /// Returns the note attached to the crate `name`, if any.
+    pub fn note(&self, name: &str) -> Option<&str> {
+        self.workspace_state.note(name)
+    }
+
+    /// Badges the pinned and annotated crates of the shown tree.
+    fn apply_pins(&mut self) {
+        let tree = &self.dependency_tree;
+        let (mut pinned, mut annotated) = (Vec::new(), Vec::new());
+        for id in tree.crate_nodes() {
+            let Some(dependency) = tree.node(id).and_then(|node| node.as_dependency()) else {
+                continue;
+            };
+            if self.workspace_state.pins.contains(&dependency.name) {
+                pinned.push(id);
+            }
+            if self.workspace_state.notes.contains_key(&dependency.name) {
+                annotated.push(id);
+            }
+        }
+        self.tree_widget_state.set_pinned(pinned);
+        self.tree_widget_state.set_annotated(annotated);
+    }
+
+    /// Opens the note editor on the selected crate, starting from its
+    /// current note.
+    fn edit_note(&mut self) {
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+        else {
+            return;
+        };
+        self.note_prompt = Some(NotePrompt {
+            name: dependency.name.clone(),
+            input: self.note(&dependency.name).unwrap_or_default().to_string(),
+        });
+    }
+
+    /// Saves the note being edited, removing it if left empty.
+    fn save_note(&mut self) {
+        let Some(prompt) = self.note_prompt.take() else {
+            return;
+        };
+        self.workspace_state.set_note(&prompt.name, &prompt.input);
+        info!(name = %prompt.name, "note saved");
+        self.save_workspace_state();
+        self.apply_pins();
+    }
+
+    /// Runs the command typed at the `:` prompt, keeping the prompt open
+    /// with the reason when it cannot run.
+    fn run_command_line(&mut self) {
+        let Some(command_line) = &mut self.command_line else {
+            return;
+        };
+        let command = match command_line.input.parse::<ViewCommand>() {
+            Ok(command) => command,
+            Err(err) => {
+                command_line.error = Some(err);
+                return;
+            }
+        };
+        let (pattern, open) = match &command {
+            ViewCommand::Expand(pattern) => (pattern, true),
+            ViewCommand::Collapse(pattern) => (pattern, false),
+            ViewCommand::Filter(filter) => {
+                let active = self.filters.toggle(filter.clone());
+                info!(%filter, active, "filter toggled");
+                self.command_line = None;
+                self.apply_filters();
+                return;
+            }
+        };
+        let matches = self
+            .tree_widget_state
+            .set_open_above(&self.dependency_tree, pattern, open);
+        if matches == 0 {
+            command_line.error = Some(format!("no crate matches `{pattern}`"));
+            return;
+        }
+        info!(?command, matches, "view command");
+        self.command_line = None;
+    }
+
+    /// Reveals the first crate matching a `name[@version]` spec and centers
+    /// it in the view, dropping the search only when the filters hide it.
+    pub fn select_spec(&mut self, spec: &str) -> Result<(), String> {
+        let tree = &self.dependency_tree;
+        let id = tree
+            .crate_nodes()
+            .find(|&id| {
+                tree.node(id)
+                    .and_then(DependencyNode::as_dependency)
+                    .is_some_and(|dependency| dependency.matches_spec(spec))
+            })
+            .ok_or_else(|| format!("no crate matches `{spec}`"))?;
+        if self.tree_widget_state.is_filtered_out(id) {
+            self.clear_search();
+        }
+        self.tree_widget_state.reveal(&self.dependency_tree, id);
+        self.tree_widget_state.center_selection();
+        Ok(())
+    }
+
+    /// Runs a command from the control socket.
+    fn run_control(&mut self, command: ControlCommand) -> Result<(), String> {
+        info!(?command, "control command");
+        let tree = &self.dependency_tree;
+        match command {
+            ControlCommand::Select(spec) => self.select_spec(&spec)?,
+            ControlCommand::ExpandAll => self.tree_widget_state.expand_all(tree),
+            ControlCommand::CollapseAll => self.tree_widget_state.collapse_all(tree),
+            ControlCommand::Export(format, path) => {
+                fs::write(&path, graph_export(tree, format))
+                    .map_err(|err| format!("{}: {err}", path.display()))?;
+            }
+            ControlCommand::Quit => self.running = false,
         }
+        Ok(())
+    }
+
+    /// Pins or unpins the selected crate and saves the pins.
+    fn toggle_pin(&mut self) {
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+        else {
+            return;
+        };
+        let pinned = self.workspace_state.toggle_pin(&dependency.name);
+        info!(name = %dependency.name, pinned, "pin toggled");
+        self.save_workspace_state();
+        self.apply_pins();
+    }
+
+    /// Selects the first occurrence of the pin highlighted in the panel,
+    /// expanding the tree down to it.
+    fn go_to_pin(&mut self) {
+        let Some(view) = self.pins_view.take() else {
+            return;
+        };
+        let Some(name) = self.workspace_state.pins.get(view.selected) else {
+            return;
+        };
+        let Some(id) = find_crate(&self.dependency_tree, name) else {
+            self.pins_view = Some(view);
+            return;
+        };
+        self.clear_search();
+        self.tree_widget_state.reveal(&self.dependency_tree, id);
+    }
+
+    /// Selects the first occurrence of the version highlighted in the
+    /// versions popup.
+    fn go_to_version(&mut self) {
+        let Some(id) = self
+            .versions_view
+            .take()
+            .and_then(|view| view.selected_id())
+        else {
+            return;
+        };
+        self.tree_widget_state
+            .set_selected_node_id(&self.dependency_tree, id);
+    }
+
+    /// Selects the declaration highlighted in the drift popup, below the
+    /// member declaring it.
+    fn go_to_drift(&mut self) {
+        let Some(member_use) = self
+            .drift_view
+            .take()
+            .and_then(|view| view.selected_use().cloned())
+        else {
+            return;
+        };
+        self.clear_search();
+        self.tree_widget_state.reveal_under(
+            &self.dependency_tree,
+            member_use.member,
+            member_use.dependency,
+        );
+    }
+
+    /// Selects the ancestor highlighted in the path popup.
+    fn go_to_path_step(&mut self) {
+        let Some(view) = self.path_view.take() else {
+            return;
+        };
+        let Some(step) = view.steps.get(view.selected) else {
+            return;
+        };
+        for _ in 0..step.up {
+            self.tree_widget_state.select_parent(&self.dependency_tree);
+        }
+    }
+
+    /// Saves the workspace state, logging rather than failing on errors.
+    fn save_workspace_state(&self) {
+        if let Err(err) = self.workspace_state.save() {
+            warn!(%err, "failed to save workspace state");
+        }
+    }
+
+    /// Builds the widget state shown right after the tree is loaded.
+    fn initial_widget_state(tree: &DependencyTree, options: &TuiOptions) -> TreeWidgetState {
+        let mut tree_widget_state = TreeWidgetState::default();
+        tree_widget_state.wrap_siblings = options.wrap_siblings;
+        tree_widget_state.set_depth_guard(tree, options.max_depth_guard);
+        match options.expand {
+            ExpandPolicy::All => tree_widget_state.expand_all(tree),
+            ExpandPolicy::Collapsed => tree_widget_state.collapse_all(tree),
+            ExpandPolicy::Depth(depth) => tree_widget_state.open_to_depth(tree, depth + 1),
+            ExpandPolicy::Members => tree_widget_state.expand_members(tree),
+            ExpandPolicy::Fit => {
+                // Leave room for the header and footer lines.
+                let rows = options.inline.map_or_else(
+                    || crossterm::terminal::size().map_or(24, |(_, rows)| rows as usize),
+                    usize::from,
+                );
+                tree_widget_state.open_to_fit(tree, rows.saturating_sub(2));
+            }
+        }
+        tree_widget_state.set_layer_violations(options.layer_rules.violations(tree));
+        tree_widget_state.set_feature_conflicts(tree.feature_conflicts().into_iter().collect());
+        tree_widget_state.set_duplicated(tree.duplicated_crates().into_iter().collect());
+        if options.check_no_std {
+            tree_widget_state.set_std_creep(std_creep(tree));
+        }
+        if let Some(cargo_home) = cargo_home() {
+            tree_widget_state.set_updates_in_range(tree.updates_in_range(&cargo_home));
+        }
+        tree_widget_state
     }
 
     pub fn handle_event(&mut self, event: Event) {
         match event {
-            Event::Key(key_event) => self.handle_key_event(key_event),
+            Event::Key(key_event) => {
+                self.handle_key_event(key_event);
+                self.request_crate_info();
+            }
             Event::SearchResult(search_result) => self.handle_search_result(search_result),
+            Event::CommandOutput(line) => {
+                if let Some(log) = &mut self.command_log {
+                    log.lines.push(line);
+                }
+            }
+            Event::CommandFinished { success } => {
+                if let Some(log) = &mut self.command_log {
+                    log.success = Some(success);
+                }
+            }
+            Event::TreeReloaded(tree) => self.replace_tree(tree),
+            Event::ChangelogLoaded(key, result) => {
+                if let Some(view) = &mut self.changelog
+                    && view.key == key
+                {
+                    view.set_result(result.clone());
+                }
+                self.changelog_cache.insert(key, result);
+            }
+            Event::CrateInfoLoaded(key, status) => {
+                self.crate_info.insert(key, status);
+            }
+            Event::AnnotationsLoaded(annotations) => {
+                self.annotations.extend(annotations);
+                self.apply_annotations();
+            }
+            Event::ViewRebuilt(view) => self.swap_in(*view),
+            Event::Control(request) => {
+                let outcome = self.run_control(request.command);
+                let _ = request.reply.send(outcome);
+            }
+        }
+    }
+
+    /// Swaps in a freshly loaded tree, keeping expansion, selection and
+    /// marks, and logs what changed.
+    ///
+    /// Reloads resolve the whole workspace, so the member selection, the
+    /// `--crate` root and the other view options are applied to `tree`
+    /// again first.
+    fn replace_tree(&mut self, tree: DependencyTree) {
+        let mut tree = self.options.members.apply(tree);
+        if let Some(remote) = &self.options.remote_crate {
+            match remote.root_of(&tree) {
+                Ok(rooted) => tree = rooted,
+                Err(err) => warn!(%err, "failed to root the reloaded tree on the crate"),
+            }
+        }
+        if self.options.show_targets {
+            tree.add_target_nodes();
         }
+        if self.options.sort_by_dependents {
+            tree.sort_by_dependents();
+        }
+        if let Some(root) = &self.options.group_by_dir {
+            tree.group_members_by_dir(root);
+        }
+        let shown = self.full_graph();
+        let diff = TreeDiff::since(shown, &self.reload_diff, &tree);
+        diff.add_tombstones(shown, &mut tree);
+        info!(
+            nodes = tree.nodes.len(),
+            changed = !diff.is_empty(),
+            "tree reloaded"
+        );
+        if let Some(log) = &mut self.command_log {
+            if diff.is_empty() {
+                log.lines.push("no dependency changes".to_string());
+            } else {
+                log.lines.extend(diff.lines());
+            }
+        }
+        self.reload_diff = diff;
+        // Node ids shift between builds, so the view is carried over by key.
+        let snapshot = self.tree_widget_state.snapshot(&self.dependency_tree);
+        self.show_tree(tree, Some(snapshot));
+        self.request_annotations();
+    }
+
+    /// Switches between the full graph and the workspace members only.
+    fn toggle_members_only(&mut self) {
+        self.options.members_only = !self.options.members_only;
+        info!(members_only = self.options.members_only, "view changed");
+        self.show_tree(self.full_graph().clone(), None);
+    }
+
+    /// Switches between the tree and the list of crates with their
+    /// dependents.
+    fn toggle_group_by_crate(&mut self) {
+        self.options.group_by_crate = !self.options.group_by_crate;
+        info!(group_by_crate = self.options.group_by_crate, "view changed");
+        self.show_tree(self.full_graph().clone(), None);
+    }
+
+    /// Derives the view of the full graph `tree` selected by `options`,
+    /// along with the kinds of the edges its dissolved groups stood for.
+    fn derive_view(
+        tree: &DependencyTree,
+        options: &TuiOptions,
+    ) -> (DependencyTree, FxHashMap<(NodeId, NodeId), DependencyType>) {
+        let members = options.members_only.then(|| tree.workspace_only());
+        let tree = members.as_ref().unwrap_or(tree);
+        let grouped = options.group_by_crate.then(|| occurrences(tree));
+        let tree = grouped.as_ref().unwrap_or(tree);
+        if options.hide_groups {
+            tree.without_groups()
+        } else {
+            (tree.clone(), FxHashMap::default())
+        }
+    }
+
+    /// Re-roots the view at the selected crate, remembering the current
+    /// view so that [`Self::unfocus`] can return to it.
+    fn focus_selected(&mut self) {
+        let tree = &self.dependency_tree;
+        let Some(id) = self.tree_widget_state.selected_node_id() else {
+            return;
+        };
+        let Some(dependency) = tree.node(id).and_then(|node| node.as_dependency()) else {
+            return;
+        };
+        if tree.roots() == [id] {
+            return;
+        }
+        info!(name = %dependency.name, "view re-rooted");
+        let name = dependency.name.clone();
+        let focused = tree.rooted_at(id);
+        let tree = std::mem::replace(&mut self.dependency_tree, focused.clone());
+        self.focus.push(FocusFrame {
+            tree,
+            node: id,
+            name,
+        });
+        self.reset_view(focused);
+    }
+
+    /// Returns to the view the tree was last re-rooted from, selecting the
+    /// crate it was re-rooted at.
+    fn unfocus(&mut self) {
+        let Some(frame) = self.focus.pop() else {
+            return;
+        };
+        self.reset_view(frame.tree);
+        self.tree_widget_state
+            .set_selected_node_id(&self.dependency_tree, frame.node);
+    }
+
+    /// Names of the crates the view was re-rooted at, outermost first.
+    pub fn focus_trail(&self) -> impl Iterator<Item = &str> {
+        self.focus.iter().map(|frame| frame.name.as_str())
+    }
+
+    /// Returns the full graph behind the shown view, or the one being
+    /// rebuilt.
+    fn full_graph(&self) -> &DependencyTree {
+        self.rebuild
+            .as_ref()
+            .map(|rebuild| &rebuild.tree)
+            .or(self.full_tree.as_ref())
+            .or(self.focus.first().map(|frame| &frame.tree))
+            .unwrap_or(&self.dependency_tree)
+    }
+
+    /// Shows the full graph `tree`, restricted to workspace members in the
+    /// members-only view.
+    ///
+    /// The view is derived on a worker thread, which can take a while on
+    /// huge graphs, and swapped in by [`Self::swap_in`]; the current view
+    /// stays interactive meanwhile. Node ids are not stable across trees, so
+    /// per-node view state starts over except for what `snapshot` carries.
+    fn show_tree(&mut self, tree: DependencyTree, snapshot: Option<ViewSnapshot>) {
+        self.rebuild_generation += 1;
+        let generation = self.rebuild_generation;
+        let options = self.options.clone();
+        let event_tx = self.event_tx.clone();
+        let full = tree.clone();
+        thread::spawn(move || {
+            let (tree, edge_kinds) = if options.derives_view() {
+                Self::derive_view(&tree, &options)
+            } else {
+                (tree, FxHashMap::default())
+            };
+            let mut widget_state = Self::initial_widget_state(&tree, &options);
+            widget_state.set_edge_kinds(edge_kinds);
+            let _ = event_tx.send(Event::ViewRebuilt(Box::new(RebuiltView {
+                generation,
+                tree,
+                widget_state,
+            })));
+        });
+        self.rebuild = Some(Rebuild {
+            generation,
+            tree: full,
+            snapshot,
+        });
+    }
+
+    /// Swaps in a view built by [`Self::show_tree`], unless a newer one was
+    /// requested since.
+    fn swap_in(&mut self, view: RebuiltView) {
+        let Some(rebuild) = self
+            .rebuild
+            .take_if(|rebuild| rebuild.generation == view.generation)
+        else {
+            return;
+        };
+        debug!(nodes = view.tree.nodes.len(), "view rebuilt");
+        self.full_tree = self.options.derives_view().then_some(rebuild.tree);
+        self.focus.clear();
+        self.install_view(view.tree, view.widget_state);
+        if let Some(snapshot) = &rebuild.snapshot {
+            self.tree_widget_state
+                .restore(&self.dependency_tree, snapshot);
+        }
+    }
+
+    /// Returns whether a view is being rebuilt in the background.
+    pub fn is_rebuilding(&self) -> bool {
+        self.rebuild.is_some()
+    }
+
+    /// Shows `tree` as is, with fresh view state.
+    fn reset_view(&mut self, tree: DependencyTree) {
+        let widget_state = Self::initial_widget_state(&tree, &self.options);
+        self.install_view(tree, widget_state);
+    }
+
+    /// Shows `tree` with `widget_state` derived from it.
+    fn install_view(&mut self, tree: DependencyTree, widget_state: TreeWidgetState) {
+        self.tree_widget_state = widget_state;
+        self.stats = None;
+        self.trace_from = None;
+        self.compare = None;
+        let _ = self.search_tx.send(SearchMessage::SetTree(tree.clone()));
+        self.dependency_tree = tree;
+        self.clear_search();
+        self.build_cost = None;
+        self.apply_build_cost();
+        self.apply_binary_size();
+        self.apply_release_ages();
+        self.apply_pins();
+        self.apply_reload_diff();
+        self.apply_annotations();
+    }
+
+    /// Runs the custom annotators over the whole graph on a background
+    /// thread, sending [`Event::AnnotationsLoaded`].
+    fn request_annotations(&self) {
+        if self.options.annotators.is_empty() {
+            return;
+        }
+        let annotators = self.options.annotators.clone();
+        let tree = self.full_graph().clone();
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let annotations = annotators.annotate_tree(&tree);
+            let _ = event_tx.send(Event::AnnotationsLoaded(annotations));
+        });
+    }
+
+    /// Badges the crates custom annotators gave a badge.
+    fn apply_annotations(&mut self) {
+        let tree = &self.dependency_tree;
+        let badges = tree
+            .crate_nodes()
+            .filter_map(|id| {
+                let dependency = tree.node(id)?.as_dependency()?;
+                let badge = self.annotations.get(&dependency.key())?.badge.clone()?;
+                Some((id, badge))
+            })
+            .collect();
+        self.tree_widget_state.set_badges(badges);
+    }
+
+    /// Badges the crates added, removed or changed by the last reload.
+    fn apply_reload_diff(&mut self) {
+        let tree = &self.dependency_tree;
+        let changes = tree
+            .crate_nodes()
+            .filter_map(|id| {
+                let dependency = tree.node(id)?.as_dependency()?;
+                Some((id, self.reload_diff.change_of(dependency)?))
+            })
+            .collect();
+        self.tree_widget_state.set_changes(changes);
+    }
+
+    /// Shows or hides the build cost of each subtree, estimating it first
+    /// if needed.
+    fn apply_build_cost(&mut self) {
+        if !self.options.show_cost {
+            self.tree_widget_state.set_cost_shares(Vec::new());
+            return;
+        }
+        let tree = &self.dependency_tree;
+        let cost = self.build_cost.get_or_insert_with(|| {
+            let start = Instant::now();
+            let cost = BuildCost::estimate(tree);
+            debug!(elapsed = ?start.elapsed(), total = cost.total, "estimated build cost");
+            cost
+        });
+        let shares = (0..tree.nodes.len())
+            .map(|idx| cost.subtree_share(NodeId(idx)).unwrap_or_default())
+            .collect();
+        self.tree_widget_state.set_cost_shares(shares);
+    }
+
+    /// Shows or hides the release age heatmap, reading publish times from
+    /// Cargo's index cache.
+    fn apply_release_ages(&mut self) {
+        let ages = match cargo_home() {
+            Some(cargo_home) if self.options.show_age => {
+                let start = Instant::now();
+                let ages = self.dependency_tree.release_ages(&cargo_home, today());
+                debug!(elapsed = ?start.elapsed(), crates = ages.len(), "read release ages");
+                ages
+            }
+            _ => FxHashMap::default(),
+        };
+        self.tree_widget_state.set_release_ages(ages);
+    }
+
+    /// Attributes the sizes of the `cargo bloat` report, if any, to the
+    /// subtrees of the shown tree.
+    fn apply_binary_size(&mut self) {
+        let Some(report) = &self.options.bloat else {
+            return;
+        };
+        let size = BinarySize::attribute(&self.dependency_tree, report);
+        self.tree_widget_state.set_binary_sizes(size.subtree);
+    }
+
+    /// Switches between the lockfile's resolve and the one picking the
+    /// lowest allowed versions, showing what differs in the log pane and as
+    /// badges in the tree.
+    ///
+    /// From an upgrade preview, goes back to the lockfile's versions.
+    fn toggle_minimal_versions(&mut self) {
+        let resolve = if self.resolve == Resolve::Locked {
+            Resolve::MinimalVersions
+        } else {
+            Resolve::Locked
+        };
+        self.start_resolve(resolve);
+    }
+
+    /// Opens the version prompt for previewing an upgrade of the selected
+    /// registry crate.
+    fn open_upgrade_prompt(&mut self) {
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id))
+            .and_then(|node| node.as_dependency())
+            .filter(|dependency| dependency.source == SourceKind::Registry)
+        else {
+            return;
+        };
+        self.upgrade_prompt = Some(UpgradePrompt {
+            name: dependency.name.clone(),
+            current: dependency.version.clone(),
+            input: String::new(),
+        });
+    }
+
+    /// Resolves the workspace to `resolve` in the background, diffing the
+    /// result against the shown tree once it arrives.
+    fn start_resolve(&mut self, resolve: Resolve) {
+        if self
+            .command_log
+            .as_ref()
+            .is_some_and(CommandLog::is_running)
+        {
+            return;
+        }
+        info!(?resolve, "resolving");
+        let log = CommandLog::new(resolve.title());
+        spawn_resolve(
+            resolve.clone(),
+            self.loader(),
+            self.event_tx.clone(),
+            log.cancel.clone(),
+            self.options.load_timeout,
+        );
+        self.command_log = Some(log);
+        self.resolve = resolve;
+    }
+
+    /// Opens the log pane on the command line reproducing the current view,
+    /// listing what it cannot carry over.
+    fn show_invocation(&mut self) {
+        if self
+            .command_log
+            .as_ref()
+            .is_some_and(CommandLog::is_running)
+        {
+            return;
+        }
+        let min_dependents = self
+            .filters
+            .filters()
+            .iter()
+            .find_map(|filter| match filter {
+                Filter::MinDependents(min) => Some(*min),
+                _ => None,
+            });
+        let query = self
+            .filters
+            .filters()
+            .iter()
+            .find_map(|filter| match filter {
+                Filter::Matches(query) => Some(query.as_str()),
+                _ => None,
+            });
+        let args: Vec<String> = ["cargo", "tree-tui"]
+            .into_iter()
+            .map(String::from)
+            .chain(self.options.launch_args.iter().cloned())
+            .chain(self.options.view_args(min_dependents, query))
+            .collect();
+        let mut log = CommandLog::new("reproduce this view".to_string());
+        log.lines.push(shell_line(&args));
+        let mut left_out: Vec<String> = self
+            .filters
+            .filters()
+            .iter()
+            .filter(|filter| !matches!(filter, Filter::MinDependents(_) | Filter::Matches(_)))
+            .map(|filter| format!("filter {filter}"))
+            .collect();
+        left_out.extend(
+            self.focus_trail()
+                .map(|name| format!("re-rooted at {name}")),
+        );
+        if !left_out.is_empty() {
+            log.lines.push(String::new());
+            log.lines.push("not carried over by flags:".to_string());
+            log.lines
+                .extend(left_out.into_iter().map(|item| format!("  {item}")));
+        }
+        log.success = Some(true);
+        log.pager.scroll_to(0);
+        info!(args = args.len(), "view invocation shown");
+        self.command_log = Some(log);
+    }
+
+    /// Opens the log pane on the dependency-reduction opportunities of the
+    /// shown tree.
+    fn show_diet_report(&mut self) {
+        if self
+            .command_log
+            .as_ref()
+            .is_some_and(CommandLog::is_running)
+        {
+            return;
+        }
+        let tree = &self.dependency_tree;
+        let findings = diet_report(tree);
+        info!(findings = findings.len(), "diet report");
+        let mut log = CommandLog::new("diet report".to_string());
+        log.lines = report_lines(tree, &findings);
+        log.success = Some(true);
+        log.pager.scroll_to(0);
+        self.command_log = Some(log);
+    }
+
+    /// Narrows the tree to the filters in [`Self::filters`], or shows it
+    /// whole when none is active.
+    fn apply_filters(&mut self) {
+        let tree = &self.dependency_tree;
+        let facts = FilterFacts::new(tree, &self.tree_widget_state);
+        match self.filters.search_state(tree, &facts) {
+            Some(search_state) => self
+                .tree_widget_state
+                .apply_search_state(tree, search_state),
+            None => self.tree_widget_state.clear_search(),
+        }
+    }
+
+    /// Drops every filter, the search query included.
+    fn clear_filters(&mut self) {
+        info!(filters = self.filters.filters().len(), "filters cleared");
+        self.filters.clear();
+        self.clear_search();
+    }
+
+    /// Returns how commands started from here resolve the workspace again.
+    fn loader(&self) -> Loader {
+        Loader {
+            manifest_path: self.options.manifest_path.clone(),
+            command: self.options.load_command.clone(),
+        }
+    }
+
+    /// Runs a confirmed cargo command and opens the log pane for it.
+    fn run_cargo(&mut self, args: Vec<String>) {
+        info!(?args, "running cargo");
+        // The reload after the command resolves from the lockfile again.
+        self.resolve = Resolve::Locked;
+        let log = CommandLog::new(format!("cargo {}", args.join(" ")));
+        spawn_cargo(
+            args,
+            self.loader(),
+            self.event_tx.clone(),
+            log.cancel.clone(),
+            self.options.load_timeout,
+        );
+        self.command_log = Some(log);
     }
 
     pub fn advance_spinner(&mut self) {
@@ -94,6 +1102,227 @@ impl TuiState {
         if key_event.kind != KeyEventKind::Press && key_event.modifiers.is_empty() {
             return;
         }
+        debug!(code = ?key_event.code, mode = ?self.input_mode, "key");
+
+        if key_event.code == KeyCode::F(12) {
+            self.show_log = !self.show_log;
+            return;
+        }
+
+        if let Some(confirm) = &self.confirm {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    let args = confirm.cargo_args.clone();
+                    self.confirm = None;
+                    self.run_cargo(args);
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.confirm = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(prompt) = &mut self.add_prompt {
+            match key_event.code {
+                KeyCode::Enter if !prompt.input.trim().is_empty() => {
+                    self.confirm = Some(Confirm {
+                        prompt: format!("Add {} to {}?", prompt.input.trim(), prompt.member),
+                        cargo_args: prompt.cargo_args(),
+                    });
+                    self.add_prompt = None;
+                }
+                KeyCode::Esc => self.add_prompt = None,
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(prompt) = &mut self.upgrade_prompt {
+            match key_event.code {
+                KeyCode::Enter if !prompt.input.trim().is_empty() => {
+                    let resolve = Resolve::Upgraded {
+                        name: prompt.name.clone(),
+                        current: prompt.current.clone(),
+                        version: prompt.input.trim().to_string(),
+                    };
+                    self.upgrade_prompt = None;
+                    self.start_resolve(resolve);
+                }
+                KeyCode::Esc => self.upgrade_prompt = None,
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(prompt) = &mut self.note_prompt {
+            match key_event.code {
+                KeyCode::Enter => self.save_note(),
+                KeyCode::Esc => self.note_prompt = None,
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(command_line) = &mut self.command_line {
+            match key_event.code {
+                KeyCode::Enter => self.run_command_line(),
+                KeyCode::Esc => self.command_line = None,
+                KeyCode::Backspace => {
+                    command_line.error = None;
+                    command_line.input.pop();
+                }
+                KeyCode::Char(c) => {
+                    command_line.error = None;
+                    command_line.input.push(c);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.versions_view {
+            match key_event.code {
+                KeyCode::Down => view.select_next(),
+                KeyCode::Up => view.select_previous(),
+                KeyCode::Enter => self.go_to_version(),
+                KeyCode::Esc | KeyCode::Char('q') => self.versions_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.drift_view {
+            match key_event.code {
+                KeyCode::Down => view.select_next(),
+                KeyCode::Up => view.select_previous(),
+                KeyCode::Enter => self.go_to_drift(),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('I') => self.drift_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.path_view {
+            match key_event.code {
+                KeyCode::Down => view.select_next(),
+                KeyCode::Up => view.select_previous(),
+                KeyCode::Enter => self.go_to_path_step(),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('^') => self.path_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.pins_view {
+            let len = self.workspace_state.pins.len();
+            match key_event.code {
+                KeyCode::Down => view.select_next(len),
+                KeyCode::Up => view.select_previous(),
+                KeyCode::Enter => self.go_to_pin(),
+                KeyCode::Char('d') | KeyCode::Delete if view.selected < len => {
+                    self.workspace_state.pins.remove(view.selected);
+                    view.selected = view.selected.min(len.saturating_sub(2));
+                    self.save_workspace_state();
+                    self.apply_pins();
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('P') => self.pins_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(log) = &mut self.command_log {
+            if log.is_running()
+                && key_event.code == KeyCode::Char('c')
+                && key_event.modifiers.contains(KeyModifiers::CONTROL)
+            {
+                info!(title = log.title, "command cancelled");
+                log.cancel.cancel();
+                return;
+            }
+            if log.pager.handle_key(key_event.code, &log.lines) {
+                return;
+            }
+            if matches!(key_event.code, KeyCode::Esc | KeyCode::Char('q')) && !log.is_running() {
+                self.command_log = None;
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.changelog {
+            if view.handle_key(key_event.code) {
+                return;
+            }
+            if matches!(
+                key_event.code,
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L')
+            ) {
+                self.changelog = None;
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.readme {
+            match key_event.code {
+                KeyCode::Down => view.scroll_down(1),
+                KeyCode::Up => view.scroll_up(1),
+                KeyCode::PageDown => view.scroll_down(20),
+                KeyCode::PageUp => view.scroll_up(20),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => self.readme = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(view) = &mut self.export {
+            match key_event.code {
+                KeyCode::Down => view.scroll_down(1),
+                KeyCode::Up => view.scroll_up(1),
+                KeyCode::PageDown => view.scroll_down(20),
+                KeyCode::PageUp => view.scroll_up(20),
+                KeyCode::Char('w') if view.saved.is_none() => {
+                    if let Err(err) = view.save() {
+                        self.report_error("export", &err);
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => self.export = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(stats) = &mut self.stats {
+            match key_event.code {
+                KeyCode::Tab | KeyCode::Esc => self.stats = None,
+                KeyCode::Char('s') => stats.cycle_sort(),
+                KeyCode::Char('q') => self.running = false,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(compare) = &mut self.compare {
+            match key_event.code {
+                KeyCode::Tab => compare.cycle_mode(&self.dependency_tree),
+                KeyCode::Down => compare.scroll_down(),
+                KeyCode::Up => compare.scroll_up(),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('c') => self.compare = None,
+                _ => {}
+            }
+            return;
+        }
 
         if self.input_mode == InputMode::Search {
             match key_event.code {
@@ -105,9 +1334,24 @@ impl TuiState {
                         self.clear_search();
                     } else {
                         self.input_mode = InputMode::SearchResults;
+                        self.tree_widget_state.commit_preview();
+                        self.commit_search();
+                        self.versions_view =
+                            self.tree_widget_state
+                                .selected_node_id()
+                                .and_then(|selected| {
+                                    VersionsView::new(
+                                        &self.dependency_tree,
+                                        self.tree_widget_state.search_match_ids(),
+                                        selected,
+                                    )
+                                });
                     }
                 }
+                KeyCode::Up => self.recall_search(true),
+                KeyCode::Down => self.recall_search(false),
                 KeyCode::Backspace => {
+                    self.history_pos = None;
                     if self.search_query.pop().is_none() {
                         self.clear_search();
                     } else {
@@ -115,6 +1359,7 @@ impl TuiState {
                     }
                 }
                 KeyCode::Char(c) => {
+                    self.history_pos = None;
                     self.search_query.push(c);
                     self.request_search();
                 }
@@ -123,22 +1368,144 @@ impl TuiState {
             return;
         }
 
+        if std::mem::take(&mut self.awaiting_command) {
+            if let KeyCode::Char(key) = key_event.code {
+                self.request_node_command(key);
+            }
+            return;
+        }
+
+        if std::mem::take(&mut self.awaiting_jump) {
+            if let KeyCode::Char(key) = key_event.code {
+                self.tree_widget_state
+                    .select_sibling_starting_with(&self.dependency_tree, key);
+            }
+            return;
+        }
+
         match (key_event.code, key_event.modifiers) {
             (KeyCode::Esc, _) if self.input_mode == InputMode::SearchResults => {
                 self.clear_search();
             }
+            (KeyCode::Esc, _)
+                if self.trace_from.is_some() || self.tree_widget_state.is_tracing_path() =>
+            {
+                self.trace_from = None;
+                self.tree_widget_state.clear_path();
+            }
             (KeyCode::Char('q'), _) => {
                 self.running = false;
             }
             (KeyCode::Char('?'), _) => {
                 self.show_help = !self.show_help;
             }
+            (KeyCode::Tab, _) => {
+                self.stats = Some(StatsView::new(
+                    &self.dependency_tree,
+                    &self.tree_widget_state,
+                ));
+            }
+            (KeyCode::Char('m'), _) => {
+                self.tree_widget_state.toggle_mark(&self.dependency_tree);
+            }
+            (KeyCode::Char('K'), _) => {
+                self.tree_widget_state.toggle_frozen(&self.dependency_tree);
+            }
+            (KeyCode::Char('*'), _) => self.toggle_pin(),
+            (KeyCode::Char('P'), _) => self.pins_view = Some(PinsView::default()),
+            (KeyCode::Char('I'), _) => {
+                self.drift_view = Some(DriftView::new(&self.dependency_tree));
+            }
+            (KeyCode::Char('^'), _) => {
+                self.path_view = Some(PathView::new(
+                    &self.dependency_tree,
+                    &self.tree_widget_state,
+                ));
+            }
+            (KeyCode::Char('F'), _) => {
+                self.filters.toggle(Filter::Lint);
+                self.apply_filters();
+            }
+            (KeyCode::Char('C'), _) => self.clear_filters(),
+            (KeyCode::Char('!'), _) if !self.options.node_commands.is_empty() => {
+                self.awaiting_command = true;
+            }
+            (KeyCode::Char('n'), _) => self.edit_note(),
+            (KeyCode::Char('c'), _) => {
+                if let &[first, second] = self.tree_widget_state.marked() {
+                    self.compare = Some(CompareState::new(&self.dependency_tree, first, second));
+                }
+            }
+            (KeyCode::Char('t'), _) => {
+                let selected = self.tree_widget_state.selected_node_id();
+                match (self.trace_from.take(), selected) {
+                    (None, selected) => self.trace_from = selected,
+                    (Some(from), Some(to)) => {
+                        self.tree_widget_state
+                            .trace_path(&self.dependency_tree, from, to);
+                    }
+                    (Some(_), None) => {}
+                }
+            }
+            (KeyCode::Char('U'), _) => {
+                if let Some(dependency) = self
+                    .tree_widget_state
+                    .selected_node_id()
+                    .and_then(|id| self.dependency_tree.node(id))
+                    .and_then(|node| node.as_dependency())
+                {
+                    let spec = format!("{}@{}", dependency.name, dependency.version);
+                    self.confirm = Some(Confirm {
+                        prompt: format!("Update {spec} in Cargo.lock?"),
+                        cargo_args: vec!["update".to_string(), "-p".to_string(), spec],
+                    });
+                }
+            }
+            (KeyCode::Char('X'), _) => self.confirm_remove(),
+            (KeyCode::Char('e'), _) => self.request_edit_manifest(),
+            (KeyCode::Char('A'), _) => self.open_add_prompt(),
+            (KeyCode::Char('L'), _) => self.open_changelog(),
+            (KeyCode::Char('R'), _) => self.open_readme(),
+            (KeyCode::Char('T'), _) => self.open_export(),
+            (KeyCode::Char('i'), _) => {
+                self.show_details = !self.show_details;
+            }
+            (KeyCode::Char('u'), _) => {
+                self.options.show_dependents = !self.options.show_dependents;
+            }
+            (KeyCode::Char('Z'), _) => {
+                self.options.compact = !self.options.compact;
+            }
+            (KeyCode::Char('M'), _) => {
+                self.options.minimap = !self.options.minimap;
+            }
+            (KeyCode::Char('w'), _) => {
+                self.options.wrap = !self.options.wrap;
+            }
+            (KeyCode::Char('W'), _) => self.toggle_members_only(),
+            (KeyCode::Char('G'), _) => self.toggle_group_by_crate(),
+            (KeyCode::Char('V'), _) => self.toggle_minimal_versions(),
+            (KeyCode::Char('D'), _) => self.open_upgrade_prompt(),
+            (KeyCode::Enter | KeyCode::Char('.'), _) => self.focus_selected(),
+            (KeyCode::Backspace, _) => self.unfocus(),
+            (KeyCode::Char('B'), _) => {
+                self.options.show_cost = !self.options.show_cost;
+                self.apply_build_cost();
+            }
+            (KeyCode::Char('O'), _) => self.show_diet_report(),
+            (KeyCode::Char('Y'), _) => self.show_invocation(),
+            (KeyCode::Char('H'), _) => {
+                self.options.show_age = !self.options.show_age;
+                self.apply_release_ages();
+            }
             (KeyCode::Char('/'), _) => {
                 self.input_mode = InputMode::Search;
             }
+            (KeyCode::Char(':'), _) => self.command_line = Some(CommandLine::default()),
             (KeyCode::Char('p'), _) => {
                 self.tree_widget_state.select_parent(&self.dependency_tree);
             }
+            (KeyCode::Char('f'), _) => self.awaiting_jump = true,
             (KeyCode::Char(']'), _) => {
                 self.tree_widget_state
                     .select_next_sibling(&self.dependency_tree);
@@ -147,6 +1514,22 @@ impl TuiState {
                 self.tree_widget_state
                     .select_previous_sibling(&self.dependency_tree);
             }
+            (KeyCode::Char('>'), _) => {
+                self.tree_widget_state
+                    .select_next_member(&self.dependency_tree);
+            }
+            (KeyCode::Char('<'), _) => {
+                self.tree_widget_state
+                    .select_previous_member(&self.dependency_tree);
+            }
+            (KeyCode::Char('{'), _) => {
+                self.tree_widget_state
+                    .select_first_sibling(&self.dependency_tree);
+            }
+            (KeyCode::Char('}'), _) => {
+                self.tree_widget_state
+                    .select_last_sibling(&self.dependency_tree);
+            }
             (KeyCode::Down, _) => {
                 self.tree_widget_state.select_next(&self.dependency_tree);
             }
@@ -173,6 +1556,223 @@ impl TuiState {
         }
     }
 
+    /// Asks to `cargo remove` the selected crate from the member declaring it.
+    fn confirm_remove(&mut self) {
+        let tree = &self.dependency_tree;
+        let Some((owner, kind)) = self.tree_widget_state.selected_direct_dependency_of(tree) else {
+            return;
+        };
+        let (Some(dependency), Some(member)) = (
+            self.tree_widget_state
+                .selected_node_id()
+                .and_then(|id| tree.node(id)?.as_dependency()),
+            tree.node(owner).and_then(|node| node.as_dependency()),
+        ) else {
+            return;
+        };
+
+        // `cargo remove` takes the manifest key, which differs from the
+        // package name for renamed dependencies.
+        let key = member
+            .declaration_on(&dependency.name, kind)
+            .and_then(|declared| declared.rename.clone())
+            .unwrap_or_else(|| dependency.name.clone());
+        let mut cargo_args = vec!["remove".to_string(), key];
+        cargo_args.extend(kind.cargo_flag().map(str::to_string));
+        cargo_args.extend(["-p".to_string(), member.name.clone()]);
+        self.confirm = Some(Confirm {
+            prompt: format!("Remove {} from {}?", dependency.name, member.name),
+            cargo_args,
+        });
+    }
+
+    /// Requests opening the manifest declaring the selected crate at the
+    /// line that declares it.
+    fn request_edit_manifest(&mut self) {
+        let tree = &self.dependency_tree;
+        let Some((owner, kind)) = self.tree_widget_state.selected_direct_dependency_of(tree) else {
+            return;
+        };
+        let (Some(dependency), Some(manifest_dir)) = (
+            self.tree_widget_state
+                .selected_node_id()
+                .and_then(|id| tree.node(id)?.as_dependency()),
+            tree.node(owner)
+                .and_then(|node| node.as_dependency()?.manifest_dir.as_ref()),
+        ) else {
+            return;
+        };
+
+        let path = std::path::Path::new(manifest_dir).join("Cargo.toml");
+        let line = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| find_dependency_line(&contents, &dependency.name, kind))
+            .unwrap_or(1);
+        self.editor_request = Some(EditorRequest { path, line });
+    }
+
+    /// Asks the main loop to run the command bound to `key` on the selected
+    /// crate.
+    fn request_node_command(&mut self, key: char) {
+        let Some(command) = self.options.node_commands.get(key) else {
+            return;
+        };
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+        else {
+            return;
+        };
+        let args = command.expand(dependency);
+        info!(?args, "running node command");
+        self.shell_request = Some(ShellRequest { args });
+    }
+
+    /// Returns the selected crate if it was resolved from a registry.
+    pub fn selected_registry_crate(&self) -> Option<&Dependency> {
+        self.tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+            .filter(|dependency| dependency.registry.is_some())
+    }
+
+    /// Starts a registry lookup for the selection shown in the details
+    /// panel, unless lookups are disabled or it was already made.
+    fn request_crate_info(&mut self) {
+        if !self.show_details || !self.options.crate_lookups() {
+            return;
+        }
+        let Some(dependency) = self.selected_registry_crate() else {
+            return;
+        };
+        let (key, registry) = (dependency.key(), dependency.registry.clone());
+        if let Some(registry) = registry
+            && !self.crate_info.contains_key(&key)
+        {
+            self.crate_info
+                .insert(key.clone(), CrateInfoStatus::Loading);
+            spawn_crate_info(key, *registry, self.options.trust, self.event_tx.clone());
+        }
+    }
+
+    /// Opens the changelog of the selected crate, loading it unless cached.
+    fn open_changelog(&mut self) {
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+        else {
+            return;
+        };
+
+        let key = dependency.key();
+        let mut view = ChangelogView::new(key.clone());
+        match self.changelog_cache.get(&key) {
+            Some(result) => view.set_result(result.clone()),
+            None => spawn_changelog(dependency.clone(), self.event_tx.clone()),
+        }
+        self.changelog = Some(view);
+    }
+
+    /// Opens the readme of the selected crate.
+    fn open_readme(&mut self) {
+        let Some(dependency) = self
+            .tree_widget_state
+            .selected_node_id()
+            .and_then(|id| self.dependency_tree.node(id)?.as_dependency())
+        else {
+            return;
+        };
+
+        match ReadmeView::load(dependency) {
+            Ok(view) => self.readme = Some(view),
+            Err(err) => self.report_error("readme", &err),
+        }
+    }
+
+    /// Exports the direct dependencies of the selected crate as manifest
+    /// tables.
+    fn open_export(&mut self) {
+        let Some(id) = self.tree_widget_state.selected_node_id() else {
+            return;
+        };
+        let Some(dependency) = self
+            .dependency_tree
+            .node(id)
+            .and_then(|n| n.as_dependency())
+        else {
+            return;
+        };
+        if let Some(text) = dependencies_toml(&self.dependency_tree, id) {
+            self.export = Some(ExportView::new(dependency.name.clone(), text));
+        }
+    }
+
+    /// Shows an error in the log pane.
+    pub fn report_error(&mut self, title: &str, error: &Error) {
+        let mut log = CommandLog::new(title.to_string());
+        log.lines = error.to_string().lines().map(str::to_string).collect();
+        log.success = Some(false);
+        self.command_log = Some(log);
+    }
+
+    /// Shows the warnings Cargo printed while resolving the workspace in
+    /// the log pane, if there are any.
+    pub fn show_cargo_warnings(&mut self, warnings: &[String]) {
+        if warnings.is_empty() {
+            return;
+        }
+        info!(warnings = warnings.len(), "cargo warnings");
+        let mut log = CommandLog::new("cargo warnings".to_string());
+        log.lines = warnings
+            .iter()
+            .flat_map(|warning| {
+                let mut lines = warning.lines();
+                let first = lines.next().map(|line| format!("warning: {line}"));
+                first
+                    .into_iter()
+                    .chain(lines.map(|line| format!("  {line}")))
+            })
+            .collect();
+        log.success = Some(true);
+        log.pager.scroll_to(0);
+        self.command_log = Some(log);
+    }
+
+    /// Opens the `cargo add` prompt for the workspace member in focus.
+    ///
+    /// The member is the selected crate itself when it is a workspace member,
+    /// otherwise the member declaring the selection, falling back to the
+    /// first member. Adding from inside a dev/build group targets that group.
+    fn open_add_prompt(&mut self) {
+        let tree = &self.dependency_tree;
+        let selected = self.tree_widget_state.selected_node_id();
+        let (member, flag) = match selected {
+            Some(id) if tree.is_workspace_member(id) => (Some(id), None),
+            Some(id) if tree.node(id).and_then(|n| n.as_group()).is_some() => (
+                self.tree_widget_state.selected_parent_id(),
+                tree.node(id).and_then(|n| n.as_group()?.kind.cargo_flag()),
+            ),
+            _ => match self.tree_widget_state.selected_direct_dependency_of(tree) {
+                Some((owner, kind)) => (Some(owner), kind.cargo_flag()),
+                None => (tree.members().first().copied(), None),
+            },
+        };
+        let Some(member) = member
+            .and_then(|id| tree.node(id)?.as_dependency())
+            .map(|dependency| dependency.name.clone())
+        else {
+            return;
+        };
+
+        self.add_prompt = Some(AddPrompt {
+            member,
+            flag,
+            input: String::new(),
+        });
+    }
+
     fn handle_search_result(&mut self, search_result: SearchResult) {
         if search_result.generation != self.search_generation
             || search_result.query != self.search_query
@@ -180,9 +1780,24 @@ impl TuiState {
             return;
         }
 
+        debug!(
+            query = %search_result.query,
+            matches = search_result.search_state.match_ids.len(),
+            "search finished"
+        );
         self.search_running = false;
-        self.tree_widget_state
-            .apply_search_state(&self.dependency_tree, search_result.search_state);
+        let tree = &self.dependency_tree;
+        let facts = FilterFacts::new(tree, &self.tree_widget_state);
+        let search_state = self
+            .filters
+            .narrow(tree, &facts, search_result.search_state);
+        // While typing, the first match is only previewed until Enter.
+        if self.input_mode == InputMode::Search {
+            self.tree_widget_state.preview_search(tree, search_state);
+        } else {
+            self.tree_widget_state
+                .apply_search_state(tree, search_state);
+        }
     }
 
     fn request_search(&mut self) {
@@ -194,19 +1809,66 @@ impl TuiState {
 
         if request.query.is_empty() {
             self.search_running = false;
-            self.tree_widget_state.clear_search();
+            self.filters.set_query(None);
+            self.apply_filters();
+            self.tree_widget_state.cancel_preview(&self.dependency_tree);
             return;
         }
 
         self.search_running = true;
-        let _ = self.search_tx.send(request);
+        let _ = self.search_tx.send(SearchMessage::Search(request));
+    }
+
+    /// Remembers the committed query, saving it with the workspace state
+    /// when search history is persisted.
+    fn commit_search(&mut self) {
+        self.history_pos = None;
+        self.filters.set_query(Some(&self.search_query));
+        push_search(&mut self.search_history, &self.search_query);
+        if self.options.save_search_history {
+            push_search(&mut self.workspace_state.search_history, &self.search_query);
+            self.save_workspace_state();
+        }
+    }
+
+    /// Replaces the query at the prompt with an older (`back`) or newer
+    /// entry of the search history, like a shell.
+    fn recall_search(&mut self, back: bool) {
+        let len = self.search_history.len();
+        let pos = match (self.history_pos, back) {
+            (None, true) if len > 0 => {
+                self.history_draft = self.search_query.clone();
+                Some(len - 1)
+            }
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) if pos + 1 < len => Some(pos + 1),
+            (Some(_), false) => None,
+            (None, _) => return,
+        };
+        self.history_pos = pos;
+        self.search_query = match pos {
+            Some(pos) => self.search_history[pos].clone(),
+            None => std::mem::take(&mut self.history_draft),
+        };
+        if self.search_query.is_empty() {
+            self.search_generation += 1;
+            self.search_running = false;
+            self.filters.set_query(None);
+            self.apply_filters();
+            self.tree_widget_state.cancel_preview(&self.dependency_tree);
+        } else {
+            self.request_search();
+        }
     }
 
     fn clear_search(&mut self) {
+        self.history_pos = None;
         self.input_mode = InputMode::Normal;
         self.search_generation += 1;
         self.search_query.clear();
         self.search_running = false;
-        self.tree_widget_state.clear_search();
+        self.filters.set_query(None);
+        self.apply_filters();
+        self.tree_widget_state.cancel_preview(&self.dependency_tree);
     }
 }
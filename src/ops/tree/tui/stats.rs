@@ -0,0 +1,331 @@
+use std::cmp::Ordering;
+
+use clap_cargo::style::{HEADER, LITERAL};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, Block, Borders, Cell, Clear, Paragraph, Row, Table, Widget, Wrap},
+};
+use semver::Version;
+
+use crate::core::{DependencyNode, DependencyTree, NodeId, bloat::format_size, stats::GraphStats};
+
+use super::widget::TreeWidgetState;
+
+/// Maximum number of bars drawn per chart.
+const MAX_BARS: usize = 10;
+
+/// Column the duplicates table is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortColumn {
+    #[default]
+    Name,
+    Version,
+    Dependents,
+    Size,
+    Age,
+}
+
+impl SortColumn {
+    const ALL: [Self; 5] = [
+        Self::Name,
+        Self::Version,
+        Self::Dependents,
+        Self::Size,
+        Self::Age,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Version => "Version",
+            Self::Dependents => "Dependents",
+            Self::Size => "Size",
+            Self::Age => "Age",
+        }
+    }
+
+    fn next(self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|&column| column == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Column and direction the duplicates table is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableSort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+impl TableSort {
+    /// Flips an ascending sort, or moves a descending one on to the next
+    /// column, ascending.
+    pub fn cycle(&mut self) {
+        if self.descending {
+            self.column = self.column.next();
+        }
+        self.descending = !self.descending;
+    }
+}
+
+/// Version of a crate resolved more than once, as listed in the duplicates
+/// table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateRow {
+    pub id: NodeId,
+    pub name: String,
+    pub version: String,
+    pub dependents: usize,
+    /// Binary size of the subtree, when a `cargo bloat` report was loaded.
+    pub size: Option<u64>,
+    /// Days since release, when the age heatmap is on.
+    pub age: Option<i64>,
+}
+
+impl DuplicateRow {
+    fn cmp_by(&self, other: &Self, column: SortColumn) -> Ordering {
+        match column {
+            SortColumn::Name => self.name.cmp(&other.name),
+            SortColumn::Version => {
+                match (
+                    Version::parse(&self.version),
+                    Version::parse(&other.version),
+                ) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    _ => self.version.cmp(&other.version),
+                }
+            }
+            SortColumn::Dependents => self.dependents.cmp(&other.dependents),
+            SortColumn::Size => self.size.cmp(&other.size),
+            SortColumn::Age => self.age.cmp(&other.age),
+        }
+    }
+}
+
+/// Contents of the statistics screen.
+#[derive(Debug, Clone)]
+pub struct StatsView {
+    pub stats: GraphStats,
+    /// Every version of the duplicated crates, in `sort` order.
+    pub duplicates: Vec<DuplicateRow>,
+    pub sort: TableSort,
+}
+
+impl StatsView {
+    /// Computes the statistics of `tree`, taking sizes and ages from the
+    /// overlays enabled in `state`.
+    pub fn new(tree: &DependencyTree, state: &TreeWidgetState) -> Self {
+        let stats = GraphStats::compute(tree);
+        let duplicates = tree
+            .crate_nodes()
+            .filter_map(|id| {
+                let Some(DependencyNode::Crate(dependency)) = tree.node(id) else {
+                    return None;
+                };
+                stats
+                    .duplicates
+                    .iter()
+                    .any(|(name, _)| *name == dependency.name)
+                    .then(|| DuplicateRow {
+                        id,
+                        name: dependency.name.clone(),
+                        version: dependency.version.clone(),
+                        dependents: tree.dependents(id).len(),
+                        size: state.binary_size(id),
+                        age: state.release_age(id),
+                    })
+            })
+            .collect();
+        let mut view = Self {
+            stats,
+            duplicates,
+            sort: TableSort::default(),
+        };
+        view.sort_rows();
+        view
+    }
+
+    /// Moves to the next sort column or direction, see [`TableSort::cycle`].
+    pub fn cycle_sort(&mut self) {
+        self.sort.cycle();
+        self.sort_rows();
+    }
+
+    /// Sorts the duplicates table, keeping rows without a value last in
+    /// either direction and breaking ties by name and version.
+    fn sort_rows(&mut self) {
+        let TableSort { column, descending } = self.sort;
+        self.duplicates.sort_by(|a, b| {
+            let missing = |row: &DuplicateRow| match column {
+                SortColumn::Size => row.size.is_none(),
+                SortColumn::Age => row.age.is_none(),
+                _ => false,
+            };
+            let order = a.cmp_by(b, column);
+            missing(a)
+                .cmp(&missing(b))
+                .then(if descending { order.reverse() } else { order })
+                .then_with(|| a.cmp_by(b, SortColumn::Name))
+                .then_with(|| a.cmp_by(b, SortColumn::Version))
+        });
+    }
+}
+
+/// Full-screen dashboard summarizing a [`StatsView`].
+#[derive(Debug)]
+pub struct StatsScreen<'a> {
+    tree: &'a DependencyTree,
+    stats: &'a GraphStats,
+    view: &'a StatsView,
+}
+
+impl<'a> StatsScreen<'a> {
+    pub fn new(tree: &'a DependencyTree, view: &'a StatsView) -> Self {
+        Self {
+            tree,
+            stats: &view.stats,
+            view,
+        }
+    }
+
+    fn block(title: &str) -> Block<'_> {
+        Block::new()
+            .title(Line::from(format!(" {title} ")))
+            .title_style(Style::from(HEADER).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::from(HEADER))
+    }
+
+    fn summary(&self) -> Paragraph<'a> {
+        let value_style = Style::from(LITERAL);
+        let chain = self
+            .stats
+            .deepest_chain
+            .iter()
+            .filter_map(|&id| self.tree.node(id).map(|node| node.display_name()))
+            .collect::<Vec<_>>()
+            .join(" → ");
+
+        let lines = vec![
+            Line::from(vec![
+                Span::raw(" Crates: "),
+                Span::styled(self.stats.total_crates.to_string(), value_style),
+                Span::raw("  Unique names: "),
+                Span::styled(self.stats.unique_crates.to_string(), value_style),
+                Span::raw("  Duplicated: "),
+                Span::styled(self.stats.duplicates.len().to_string(), value_style),
+                Span::raw("  Cycles: "),
+                Span::styled(self.stats.cycles.len().to_string(), value_style),
+            ]),
+            Line::from(vec![
+                Span::raw(" Deepest chain ("),
+                Span::styled(self.stats.deepest_chain.len().to_string(), value_style),
+                Span::raw("): "),
+                Span::raw(chain),
+            ]),
+        ];
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Self::block("Summary"))
+    }
+
+    fn duplicates(&self) -> Table<'a> {
+        let sort = self.view.sort;
+        let header = Row::new(SortColumn::ALL.map(|column| {
+            let arrow = match (column == sort.column, sort.descending) {
+                (false, _) => "",
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+            };
+            Cell::from(format!("{}{arrow}", column.label()))
+        }))
+        .style(Style::from(HEADER).add_modifier(Modifier::BOLD));
+        let rows = self.view.duplicates.iter().map(|row| {
+            Row::new([
+                row.name.clone(),
+                format!("v{}", row.version),
+                row.dependents.to_string(),
+                row.size.map_or_else(|| "-".to_string(), format_size),
+                row.age
+                    .map_or_else(|| "-".to_string(), |days| format!("{days}d")),
+            ])
+        });
+        Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header)
+        .block(Self::block("Duplicates").title_bottom(Line::from(" s: sort ")))
+    }
+
+    fn chart(title: &'a str, bars: Vec<Bar<'a>>) -> BarChart<'a> {
+        BarChart::horizontal(bars)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::from(HEADER))
+            .value_style(Style::from(HEADER).add_modifier(Modifier::REVERSED))
+            .block(Self::block(title))
+    }
+}
+
+impl Widget for StatsScreen<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let [summary_area, top_area, bottom_area] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [depended_area, duplicates_area] =
+            Layout::horizontal([Constraint::Fill(1); 2]).areas(top_area);
+        let [licenses_area, sources_area] =
+            Layout::horizontal([Constraint::Fill(1); 2]).areas(bottom_area);
+
+        self.summary().render(summary_area, buf);
+
+        let depended = self
+            .stats
+            .most_depended
+            .iter()
+            .filter_map(|&(id, count)| {
+                let name = self.tree.node(id)?.display_name().to_string();
+                Some(Bar::with_label(name, count as u64))
+            })
+            .collect();
+        Self::chart("Most depended upon", depended).render(depended_area, buf);
+
+        self.duplicates().render(duplicates_area, buf);
+
+        let licenses = self
+            .stats
+            .licenses
+            .iter()
+            .take(MAX_BARS)
+            .map(|(license, count)| Bar::with_label(license.clone(), *count as u64))
+            .collect();
+        Self::chart("Licenses", licenses).render(licenses_area, buf);
+
+        let sources = self
+            .stats
+            .sources
+            .iter()
+            .map(|(source, count)| Bar::with_label(source.label(), *count as u64))
+            .collect();
+        Self::chart("Sources", sources).render(sources_area, buf);
+    }
+}
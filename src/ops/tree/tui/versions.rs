@@ -0,0 +1,146 @@
+use clap_cargo::style::PLACEHOLDER;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use semver::Version;
+
+use crate::core::{DependencyTree, NodeId};
+
+use super::help::HelpPopupStyle;
+
+/// Version of a crate matched by a search, in a [`VersionsView`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionChoice {
+    pub id: NodeId,
+    pub version: String,
+    /// Rows the crate is listed in, one per edge leading to it.
+    pub occurrences: usize,
+}
+
+/// State of the popup picking which version of a crate a search lands on,
+/// when it matches several.
+#[derive(Debug)]
+pub struct VersionsView {
+    pub name: String,
+    /// Matched versions, oldest first.
+    pub choices: Vec<VersionChoice>,
+    /// Index of the highlighted version.
+    pub selected: usize,
+}
+
+impl VersionsView {
+    /// Lists the versions among `matches` of the crate `selected`, or
+    /// returns `None` unless there are several.
+    pub fn new(tree: &DependencyTree, matches: &[NodeId], selected: NodeId) -> Option<Self> {
+        let name = &tree.node(selected)?.as_dependency()?.name;
+        let mut choices: Vec<VersionChoice> = matches
+            .iter()
+            .filter_map(|&id| {
+                let dependency = tree.node(id)?.as_dependency()?;
+                (dependency.name == *name).then(|| VersionChoice {
+                    id,
+                    version: dependency.version.clone(),
+                    occurrences: tree.parents[id.0].len().max(1),
+                })
+            })
+            .collect();
+        if choices.len() < 2 {
+            return None;
+        }
+        choices.sort_by(
+            |a, b| match (Version::parse(&a.version), Version::parse(&b.version)) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.version.cmp(&b.version),
+            },
+        );
+        Some(Self {
+            name: name.clone(),
+            selected: choices
+                .iter()
+                .position(|choice| choice.id == selected)
+                .unwrap_or_default(),
+            choices,
+        })
+    }
+
+    /// Returns the crate of the highlighted version.
+    pub fn selected_id(&self) -> Option<NodeId> {
+        self.choices.get(self.selected).map(|choice| choice.id)
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.choices.len().saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Popup showing a [`VersionsView`].
+#[derive(Debug)]
+pub struct VersionsPopup<'a> {
+    view: &'a VersionsView,
+    style: HelpPopupStyle,
+}
+
+impl<'a> VersionsPopup<'a> {
+    pub fn new(view: &'a VersionsView) -> Self {
+        Self {
+            view,
+            style: HelpPopupStyle::default(),
+        }
+    }
+}
+
+impl Widget for VersionsPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(Line::from(" WHICH VERSION? "))
+            .title_bottom(Line::from(" enter: go to · esc: keep "))
+            .title_style(self.style.title)
+            .borders(Borders::ALL)
+            .border_style(self.style.border);
+
+        let height = block.inner(area).height as usize;
+        let scroll = (self.view.selected + 1).saturating_sub(height);
+        let lines = self
+            .view
+            .choices
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .map(|(idx, choice)| {
+                let name_style = if idx == self.view.selected {
+                    Style::new().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::new()
+                };
+                let occurrences = match choice.occurrences {
+                    1 => "1 occurrence".to_string(),
+                    n => format!("{n} occurrences"),
+                };
+                Line::from(vec![
+                    Span::raw(" "),
+                    Span::styled(
+                        format!("{} v{}", self.view.name, choice.version),
+                        name_style,
+                    ),
+                    Span::styled(format!(" ({occurrences})"), Style::from(PLACEHOLDER)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.style.default)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -8,12 +10,51 @@ use ratatui::{
 
 use crate::core::DependencyTree;
 
-use super::{state::TreeWidgetState, style::TreeWidgetStyle};
+use super::{
+    state::TreeWidgetState,
+    style::{TreeWidgetStyle, dependency_type_style},
+};
 
 const CONNECTOR_SYMBOL: char = '→';
 const CONTINUATION_SYMBOL: char = '…';
 const FOOTER_RESERVED_WIDTH: u16 = 32;
 
+/// Which ancestors a breadcrumb too wide for the footer keeps, besides
+/// the root and the selection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BreadcrumbElision {
+    /// As many ancestors below the root as fit.
+    #[default]
+    Head,
+    /// As many ancestors above the selection as fit.
+    Tail,
+    /// Only the parent of the selection.
+    Ends,
+}
+
+impl FromStr for BreadcrumbElision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(Self::Head),
+            "tail" => Ok(Self::Tail),
+            "ends" => Ok(Self::Ends),
+            _ => Err(format!("expected `head`, `tail` or `ends`, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for BreadcrumbElision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Head => "head",
+            Self::Tail => "tail",
+            Self::Ends => "ends",
+        })
+    }
+}
+
 #[derive(Clone)]
 struct Crumb {
     name: String,
@@ -48,17 +89,23 @@ impl<'a> Breadcrumb<'a> {
             let Some(vnode) = visible.get(vis_idx.0) else {
                 break;
             };
+            current_vis = vnode.parent_vis_idx;
+            // A marker row ends with its parent's crumb.
+            if vnode.marker.is_some() {
+                continue;
+            }
             let Some(node) = self.tree.node(vnode.id) else {
                 break;
             };
 
-            let group_style = node.as_group().map(|group| group.kind.style());
+            let group_style = node
+                .as_group()
+                .map(|group| dependency_type_style(group.kind));
             crumbs.push(Crumb {
                 name: node.display_name().to_string(),
                 group_style,
                 is_group: node.is_group(),
             });
-            current_vis = vnode.parent_vis_idx;
         }
 
         crumbs.reverse();
@@ -67,9 +114,13 @@ impl<'a> Breadcrumb<'a> {
 
     /// Elide middle items with a continuation marker when the breadcrumb is too wide.
     ///
-    /// The output always keeps the root and current node, then adds as many
-    /// prefix items as will fit between them.
-    fn elide_crumbs(mut crumbs: Vec<Crumb>, max_width: usize) -> Vec<Crumb> {
+    /// The output always keeps the root and current node; `elision` picks
+    /// which of the items between them are kept.
+    fn elide_crumbs(
+        crumbs: Vec<Crumb>,
+        max_width: usize,
+        elision: BreadcrumbElision,
+    ) -> Vec<Crumb> {
         if crumbs.len() <= 2 {
             return crumbs;
         }
@@ -90,31 +141,47 @@ impl<'a> Breadcrumb<'a> {
             group_style: None,
             is_group: false,
         };
-        let last_idx = crumbs.len() - 1;
-        let mut prefix_len = 1usize;
-
-        let total_len = |prefix_count: usize, crumbs: &[Crumb]| -> usize {
-            let prefix_len_sum: usize = crumbs
+        // Width of the first `head` and last `tail` items around the ellipsis.
+        let total_len = |head: usize, tail: usize| -> usize {
+            let kept_len: usize = crumbs[..head]
                 .iter()
-                .take(prefix_count)
+                .chain(&crumbs[crumbs.len() - tail..])
                 .map(|crumb| crumb.name.chars().count())
                 .sum();
-            let last_len = crumbs[last_idx].name.chars().count();
-            let item_count = prefix_count + 2;
-            prefix_len_sum
+            let item_count = head + tail + 1;
+            kept_len
                 .saturating_add(ellipsis.name.chars().count())
-                .saturating_add(last_len)
                 .saturating_add(sep_len.saturating_mul(item_count.saturating_sub(1)))
         };
+        // At least one item must be left out for the ellipsis to stand for.
+        let fits = |head: usize, tail: usize| {
+            head + tail < crumbs.len() && total_len(head, tail) <= max_width
+        };
 
-        while prefix_len + 1 < last_idx && total_len(prefix_len + 1, &crumbs) <= max_width {
-            prefix_len += 1;
+        let (mut head, mut tail) = (1, 1);
+        match elision {
+            BreadcrumbElision::Head => {
+                while fits(head + 1, tail) {
+                    head += 1;
+                }
+            }
+            BreadcrumbElision::Tail => {
+                while fits(head, tail + 1) {
+                    tail += 1;
+                }
+            }
+            BreadcrumbElision::Ends => {
+                if crumbs.len() == 3 {
+                    return crumbs;
+                }
+                tail = 2;
+            }
         }
 
-        let mut minimized = Vec::with_capacity(prefix_len + 2);
-        minimized.extend_from_slice(&crumbs[..prefix_len]);
+        let mut minimized = Vec::with_capacity(head + tail + 1);
+        minimized.extend_from_slice(&crumbs[..head]);
         minimized.push(ellipsis);
-        minimized.push(crumbs.remove(last_idx));
+        minimized.extend_from_slice(&crumbs[crumbs.len() - tail..]);
         minimized
     }
 }
@@ -124,7 +191,7 @@ impl Widget for Breadcrumb<'_> {
         let crumbs = self.collect_crumbs();
 
         let max_width = area.width.saturating_sub(FOOTER_RESERVED_WIDTH) as usize;
-        let display_crumbs = Self::elide_crumbs(crumbs, max_width);
+        let display_crumbs = Self::elide_crumbs(crumbs, max_width, self.style.breadcrumb_elision);
         let mut spans = Vec::new();
 
         for (i, crumb) in display_crumbs.iter().enumerate() {
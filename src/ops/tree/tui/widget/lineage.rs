@@ -1,7 +1,10 @@
 use crate::core::DependencyTree;
 use ratatui::style::Style;
 
-use super::state::{VisIdx, VisibleNode};
+use super::{
+    state::{VisIdx, VisibleNode},
+    style::dependency_type_style,
+};
 
 /// Lineage information for a dependency node.
 #[derive(Debug)]
@@ -44,9 +47,11 @@ impl Lineage {
             if let Some(grand_vis) = ancestor_vnode.parent_vis_idx {
                 let has_more_siblings = !ancestor_vnode.is_last_non_group_child;
                 let grand_node_id = visible_nodes[grand_vis.0].id;
-                let edge_style = tree
-                    .node(grand_node_id)
-                    .and_then(|parent| parent.as_group().map(|group| group.kind.style()));
+                let edge_style = tree.node(grand_node_id).and_then(|parent| {
+                    parent
+                        .as_group()
+                        .map(|group| dependency_type_style(group.kind))
+                });
                 lineage.push(LineageSegment {
                     has_more_siblings,
                     edge_style,
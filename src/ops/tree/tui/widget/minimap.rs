@@ -0,0 +1,77 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+use crate::core::DependencyTree;
+
+use super::{state::TreeWidgetState, style::TreeWidgetStyle, viewport::Viewport};
+
+/// Width of the minimap strip, not counting the gap before it.
+pub const MINIMAP_WIDTH: u16 = 4;
+
+const BAR_SYMBOL: &str = "▬";
+
+/// Strip showing the whole visible tree compressed vertically.
+///
+/// Each row stands for a run of lines, drawn as a bar indented by the depth
+/// of its shallowest node. Rows holding the selection or search matches are
+/// highlighted and the rows covering the viewport are marked.
+pub struct Minimap<'a> {
+    tree: &'a DependencyTree,
+    state: &'a TreeWidgetState,
+    style: &'a TreeWidgetStyle,
+    viewport: Viewport,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(
+        tree: &'a DependencyTree,
+        state: &'a TreeWidgetState,
+        style: &'a TreeWidgetStyle,
+        viewport: Viewport,
+    ) -> Self {
+        Self {
+            tree,
+            state,
+            style,
+            viewport,
+        }
+    }
+}
+
+impl Widget for Minimap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = self.state.summarize_lines(self.tree, area.height as usize);
+        if rows.is_empty() || area.width == 0 {
+            return;
+        }
+        let total_lines = self.state.active_total_virtual_lines().max(1);
+        let row_of = |line: usize| (line * rows.len() / total_lines).min(rows.len() - 1);
+        // The viewport counts screen rows, which differ from lines when wrapped.
+        let line_at = |row: usize| self.state.rows.line_at(row);
+        let view_start = row_of(line_at(self.viewport.offset));
+        let view_end = row_of(line_at(
+            self.viewport.offset + self.viewport.height.saturating_sub(1),
+        ));
+        let selected = self.state.selected_virtual_pos().map(|vpos| row_of(vpos.0));
+
+        for (idx, row) in rows.iter().enumerate() {
+            let y = area.y + idx as u16;
+            let bar_style = if selected == Some(idx) {
+                self.style.highlight_style
+            } else if row.has_match {
+                self.style.filtered_style
+            } else {
+                self.style.context_style
+            };
+            let start = row.depth.min(area.width as usize - 1) as u16;
+            for x in area.x + start..area.right() {
+                buf[(x, y)].set_symbol(BAR_SYMBOL).set_style(bar_style);
+            }
+            if (view_start..=view_end).contains(&idx) {
+                buf.set_style(
+                    Rect::new(area.x, y, area.width, 1),
+                    self.style.minimap_viewport_style,
+                );
+            }
+        }
+    }
+}
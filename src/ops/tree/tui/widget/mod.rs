@@ -8,17 +8,26 @@ use ratatui::{
 
 use crate::{core::DependencyTree, ops::tree::tui::widget::viewport::Viewport};
 
-use self::{breadcrumb::Breadcrumb, render::RenderContext};
+use self::{
+    breadcrumb::Breadcrumb,
+    minimap::{MINIMAP_WIDTH, Minimap},
+    render::RenderContext,
+};
 
 pub use self::{
+    breadcrumb::BreadcrumbElision,
     render::RenderOutput,
-    state::{SearchState, TreeWidgetState, VisIdx},
-    style::TreeWidgetStyle,
+    search_index::SearchIndex,
+    state::{SearchState, TreeWidgetState, ViewSnapshot, VisIdx},
+    style::{IndentWidth, NodeIcons, Theme, TreeWidgetStyle, dependency_type_style},
+    view_cache::CHILD_PAGE,
 };
 
 mod breadcrumb;
 mod lineage;
+mod minimap;
 pub mod render;
+mod search_index;
 pub mod state;
 mod style;
 mod view_cache;
@@ -30,9 +39,14 @@ pub struct TreeWidget<'a> {
     tree: &'a DependencyTree,
     block: Option<Block<'a>>,
     scrollbar: Option<Scrollbar<'a>>,
+    scrollbar_on_left: bool,
+    scrollbar_auto_hide: bool,
     search_query: Option<&'a str>,
     search_prompt_symbol: char,
     style: TreeWidgetStyle,
+    wrap: bool,
+    minimap: bool,
+    smooth_scroll: bool,
 }
 
 impl<'a> TreeWidget<'a> {
@@ -41,9 +55,14 @@ impl<'a> TreeWidget<'a> {
             tree,
             block: None,
             scrollbar: None,
+            scrollbar_on_left: false,
+            scrollbar_auto_hide: true,
             search_query: None,
             search_prompt_symbol: '/',
             style: TreeWidgetStyle::default(),
+            wrap: false,
+            minimap: false,
+            smooth_scroll: false,
         }
     }
 
@@ -57,11 +76,48 @@ impl<'a> TreeWidget<'a> {
         self
     }
 
+    /// Draws the scrollbar in its own column along the left edge instead of
+    /// over the last column.
+    pub fn scrollbar_on_left(mut self, on_left: bool) -> Self {
+        self.scrollbar_on_left = on_left;
+        self
+    }
+
+    /// Hides the scrollbar while the whole tree fits in the viewport.
+    pub fn scrollbar_auto_hide(mut self, auto_hide: bool) -> Self {
+        self.scrollbar_auto_hide = auto_hide;
+        self
+    }
+
     pub fn search_query(mut self, search_query: Option<&'a str>) -> Self {
         self.search_query = search_query;
         self
     }
 
+    pub fn style(mut self, style: TreeWidgetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Soft-wraps long rows with a hanging indent instead of truncating them.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Shows a minimap of the whole tree along the right edge.
+    pub fn minimap(mut self, minimap: bool) -> Self {
+        self.minimap = minimap;
+        self
+    }
+
+    /// Scrolls through a few intermediate frames on jumps longer than the
+    /// viewport.
+    pub fn smooth_scroll(mut self, smooth_scroll: bool) -> Self {
+        self.smooth_scroll = smooth_scroll;
+        self
+    }
+
     pub fn search_prompt_symbol(mut self, search_prompt_symbol: char) -> Self {
         self.search_prompt_symbol = search_prompt_symbol;
         self
@@ -76,6 +132,39 @@ impl StatefulWidget for TreeWidget<'_> {
             return;
         }
 
+        // Leave the minimap out on areas too narrow to hold the tree as well.
+        let (area, minimap_area) = if self.minimap && area.width > MINIMAP_WIDTH * 5 {
+            let tree_width = area.width - MINIMAP_WIDTH - 1;
+            let minimap_area = Rect {
+                x: area.x + tree_width + 1,
+                width: MINIMAP_WIDTH,
+                ..area
+            };
+            (
+                Rect {
+                    width: tree_width,
+                    ..area
+                },
+                Some(minimap_area),
+            )
+        } else {
+            (area, None)
+        };
+
+        let (area, scrollbar_column) =
+            if self.scrollbar.is_some() && self.scrollbar_on_left && area.width > 1 {
+                (
+                    Rect {
+                        x: area.x + 1,
+                        width: area.width - 1,
+                        ..area
+                    },
+                    Some(Rect { width: 1, ..area }),
+                )
+            } else {
+                (area, None)
+            };
+
         let block_ref = self.block.as_ref();
         let RenderOutput {
             lines,
@@ -83,7 +172,9 @@ impl StatefulWidget for TreeWidget<'_> {
             total_lines,
             viewport,
         } = {
-            let mut ctx = RenderContext::new(self.tree, state, &self.style, block_ref);
+            let mut ctx = RenderContext::new(self.tree, state, &self.style, block_ref)
+                .wrap(self.wrap)
+                .smooth_scroll(self.smooth_scroll);
             ctx.render(area)
         };
 
@@ -156,26 +247,44 @@ impl StatefulWidget for TreeWidget<'_> {
             Breadcrumb::new(self.tree, state, &self.style).render(area, buf);
         }
 
+        if let Some(area) = minimap_area {
+            Minimap::new(self.tree, state, &self.style, viewport).render(area, buf);
+        }
+
         if let Some(scrollbar) = self.scrollbar {
-            render_scrollbar(scrollbar, &viewport, total_lines, buf);
+            let area = scrollbar_column.unwrap_or(viewport.inner);
+            render_scrollbar(
+                scrollbar,
+                area,
+                &viewport,
+                total_lines,
+                self.scrollbar_auto_hide,
+                buf,
+            );
         }
     }
 }
 
-/// Renders the scrollbar if applicable.
+/// Renders the scrollbar in `area` if applicable.
+///
+/// With `auto_hide`, nothing is drawn while the whole tree fits in the
+/// viewport; otherwise the thumb then fills the track.
 pub fn render_scrollbar(
     scrollbar: Scrollbar<'_>,
+    area: Rect,
     viewport: &Viewport,
     total_lines: usize,
+    auto_hide: bool,
     buf: &mut Buffer,
 ) {
-    if viewport.height == 0 || viewport.max_offset == 0 {
+    if viewport.height == 0 || (auto_hide && viewport.max_offset == 0) {
         return;
     }
 
-    let mut scrollbar_state = ScrollbarState::new(total_lines.saturating_sub(viewport.height))
+    let content_length = total_lines.saturating_sub(viewport.height).max(1);
+    let mut scrollbar_state = ScrollbarState::new(content_length)
         .position(viewport.offset)
         .viewport_content_length(viewport.height);
 
-    scrollbar.render(viewport.inner, buf, &mut scrollbar_state);
+    scrollbar.render(area, buf, &mut scrollbar_state);
 }
@@ -1,15 +1,29 @@
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+    mem,
+};
+
 use ratatui::{
     layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::Block,
 };
+use rustc_hash::{FxHashMap, FxHasher};
+use unicode_width::UnicodeWidthChar;
 
-use crate::core::{Dependency, DependencyNode, DependencyTree};
+use crate::core::{
+    Dependency, DependencyNode, DependencyTree, NodeId,
+    bloat::format_size,
+    dependency::{DependencyType, RequirementLint, SourceKind},
+    diff::CrateChange,
+};
 
 use super::{
     lineage::Lineage,
-    state::{TreeWidgetState, VisIdx, VisibleNode},
-    style::TreeWidgetStyle,
+    state::{RowMarker, TreeWidgetState, VirtualPos, VisIdx, VisibleNode},
+    style::{NodeIcons, TreeWidgetStyle, dependency_type_style},
     viewport::Viewport,
 };
 
@@ -21,6 +35,117 @@ pub struct RenderOutput<'a> {
     pub viewport: Viewport,
 }
 
+/// Intermediate frames a smooth scroll is drawn over.
+const SCROLL_FRAMES: usize = 3;
+
+/// Identifies a rendered row: its position, node and whether it was selected.
+type RowKey = (VirtualPos, NodeId, bool);
+
+/// Rows rendered on the last frame, reused while only the selection or the
+/// scroll position changes.
+///
+/// Moving the selection re-renders just the rows it moved between, and
+/// scrolling renders just the rows coming into view. Anything else that
+/// changes how rows look, e.g. expanding a node or marking a crate, clears
+/// the cache through [`TreeWidgetState`].
+#[derive(Debug, Default)]
+pub(super) struct RowCache {
+    /// Hash of the style and the wrap width the rows were rendered with.
+    frame: u64,
+    rows: FxHashMap<RowKey, Vec<Line<'static>>>,
+    /// Screen rows taken by the lines of the materialized window while rows
+    /// are wrapped.
+    window: Option<WindowRows>,
+    /// Whether `window` was measured before the view last changed.
+    stale: bool,
+}
+
+/// Screen rows taken by a run of consecutive lines.
+///
+/// Only the materialized window is measured, so that wrapping costs no more
+/// than rendering it. Lines outside of it count as one row each, which keeps
+/// the rows of the lines in view exact and the rest an estimate.
+#[derive(Debug)]
+struct WindowRows {
+    /// First line of the run.
+    first: usize,
+    /// Row of each line of the run relative to `first`, followed by the
+    /// rows of the whole run.
+    starts: Vec<usize>,
+}
+
+impl WindowRows {
+    fn lines(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    fn rows(&self) -> usize {
+        self.starts[self.lines()]
+    }
+}
+
+impl RowCache {
+    pub(super) fn clear(&mut self) {
+        self.rows.clear();
+        self.stale = true;
+    }
+
+    /// Drops the cached rows if they were rendered with another style or
+    /// wrap width.
+    fn start_frame(&mut self, style: &TreeWidgetStyle, wrap_width: Option<usize>) {
+        let mut hasher = FxHasher::default();
+        (style, wrap_width).hash(&mut hasher);
+        let frame = hasher.finish();
+        if frame != self.frame {
+            self.frame = frame;
+            self.clear();
+        }
+        if wrap_width.is_none() {
+            self.window = None;
+        }
+    }
+
+    /// Returns the first screen row of `line`, or `None` while rows are not
+    /// wrapped and every line takes one row.
+    pub(super) fn row_of(&self, line: usize) -> Option<usize> {
+        let window = self.window.as_ref()?;
+        let offset = line.saturating_sub(window.first);
+        Some(if offset <= window.lines() {
+            window.first + window.starts[offset]
+        } else {
+            window.first + window.rows() + offset - window.lines()
+        })
+    }
+
+    /// Returns the line drawn on screen row `row`, the inverse of
+    /// [`RowCache::row_of`].
+    pub(super) fn line_at(&self, row: usize) -> usize {
+        let Some(window) = &self.window else {
+            return row;
+        };
+        let offset = row.saturating_sub(window.first);
+        if row < window.first {
+            row
+        } else if offset < window.rows() {
+            window.first + window.starts.partition_point(|&start| start <= offset) - 1
+        } else {
+            window.first + window.lines() + offset - window.rows()
+        }
+    }
+
+    /// Keeps the rows of the frame just rendered, `fresh` ones included,
+    /// dropping those that went out of view.
+    fn finish_frame(&mut self, shown: &[RowKey], fresh: Vec<(RowKey, Vec<Line<'static>>)>) {
+        let mut rows: FxHashMap<RowKey, Vec<Line<'static>>> = fresh.into_iter().collect();
+        for key in shown {
+            if let Some(row) = self.rows.remove(key) {
+                rows.insert(*key, row);
+            }
+        }
+        self.rows = rows;
+    }
+}
+
 /// Context for rendering the dependency tree.
 ///
 /// # Note for lifetimes
@@ -36,6 +161,10 @@ pub struct RenderContext<'a, 's> {
     pub state: &'s mut TreeWidgetState,
     pub style: &'a TreeWidgetStyle,
     pub block: Option<&'a Block<'a>>,
+    /// Soft-wrap long rows instead of truncating them.
+    pub wrap: bool,
+    /// Animate jumps longer than the viewport.
+    pub smooth_scroll: bool,
 }
 
 impl<'a, 's> RenderContext<'a, 's> {
@@ -50,7 +179,93 @@ impl<'a, 's> RenderContext<'a, 's> {
             state,
             style,
             block,
+            wrap: false,
+            smooth_scroll: false,
+        }
+    }
+
+    /// Enables soft-wrapping of rows that are wider than the viewport.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Enables animated scrolling on long jumps.
+    pub fn smooth_scroll(mut self, smooth_scroll: bool) -> Self {
+        self.smooth_scroll = smooth_scroll;
+        self
+    }
+
+    /// Moves `viewport` only part of the way from `prev_offset` when it
+    /// jumps further than its height, so the scroll lands over
+    /// [`SCROLL_FRAMES`] frames.
+    fn animate_scroll(&mut self, viewport: &mut Viewport, prev_offset: usize) {
+        let distance = viewport.offset.abs_diff(prev_offset);
+        if !self.smooth_scroll
+            || self.style.accessible
+            || distance <= viewport.height
+            || self.state.scroll_frames >= SCROLL_FRAMES
+        {
+            self.state.scroll_frames = 0;
+            return;
+        }
+        // Even steps over the frames left, landing on the frame after.
+        let step = distance / (SCROLL_FRAMES - self.state.scroll_frames + 1);
+        viewport.offset = if viewport.offset > prev_offset {
+            prev_offset + step
+        } else {
+            prev_offset - step
+        };
+        self.state.scroll_frames += 1;
+    }
+
+    /// Measures how many rows the lines of the materialized window wrap to
+    /// at `width`, unless the row cache still knows.
+    ///
+    /// Returns `prev_offset` moved to the same spot of the same line in the
+    /// new measurement.
+    fn measure_rows(&mut self, width: usize, prev_offset: usize) -> usize {
+        let visible_nodes = self.state.active_visible_nodes();
+        // The window is the run of consecutive lines ending the slice; the
+        // ancestor prefix before it is only drawn as context lines.
+        let mut first = visible_nodes.len().saturating_sub(1);
+        while first > 0
+            && visible_nodes[first - 1].virtual_pos.0 + 1 == visible_nodes[first].virtual_pos.0
+        {
+            first -= 1;
         }
+        let first_line = visible_nodes
+            .get(first)
+            .map_or(0, |node| node.virtual_pos.0);
+        let rows = &self.state.rows;
+        if !rows.stale
+            && rows.window.as_ref().is_some_and(|window| {
+                window.first == first_line && window.lines() == visible_nodes.len() - first
+            })
+        {
+            return prev_offset;
+        }
+
+        let top = rows.line_at(prev_offset);
+        let skip = prev_offset - rows.row_of(top).unwrap_or(top);
+        let mut starts = Vec::with_capacity(visible_nodes.len() - first + 1);
+        let mut row = 0;
+        for idx in first..visible_nodes.len() {
+            starts.push(row);
+            row += self
+                .render_node_parts(visible_nodes, VisIdx(idx), None, false)
+                .map_or(1, |(line, hang)| wrap_line(line, &hang, width).len());
+        }
+        starts.push(row);
+        let rows = &mut self.state.rows;
+        rows.window = Some(WindowRows {
+            first: first_line,
+            starts,
+        });
+        rows.stale = false;
+        let top_row = rows.row_of(top).unwrap_or(top);
+        let top_rows = rows.row_of(top + 1).unwrap_or(top + 1) - top_row;
+        top_row + skip.min(top_rows.saturating_sub(1))
     }
 
     pub fn render(&mut self, area: Rect) -> RenderOutput<'a> {
@@ -60,22 +275,49 @@ impl<'a, 's> RenderContext<'a, 's> {
 
         self.state.ensure_visible_nodes(self.tree);
 
-        let total_lines = self.state.total_lines(self.tree);
+        // With wrapping, the viewport counts screen rows rather than lines.
+        let wrap_width = self
+            .wrap
+            .then(|| Viewport::new(area, self.block).inner.width as usize);
+        self.state.rows.start_frame(self.style, wrap_width);
+        let mut prev_offset = self.state.viewport.offset;
+        if let Some(width) = wrap_width {
+            prev_offset = self.measure_rows(width, prev_offset);
+        }
+        let lines = self.state.total_lines(self.tree);
         let selected_vpos = self.state.selected_virtual_pos();
-        let prev_offset = self.state.viewport.offset;
         let selected_vline = selected_vpos.map(|vp| vp.0).unwrap_or(0);
+        let rows = &self.state.rows;
+        let row_of = |line: usize| rows.row_of(line).unwrap_or(line);
+        let total_lines = row_of(lines);
+        let selected_row = row_of(selected_vline);
+        let selected_last_row = row_of(selected_vline + 1)
+            .saturating_sub(1)
+            .max(selected_row);
         let mut viewport = Viewport::new(area, self.block).scroll_into_view(
-            selected_vline,
+            selected_last_row,
             total_lines,
             1,
             prev_offset,
         );
+        // Keep the top of a node taller than the view in sight.
+        viewport.offset = viewport.offset.min(selected_row);
+        if self.style.accessible {
+            // Keep the selection on the first row for a predictable reading order.
+            viewport.offset = selected_row;
+        } else if mem::take(&mut self.state.center_pending) {
+            viewport.offset = selected_row.saturating_sub(viewport.height / 2);
+        }
+        self.animate_scroll(&mut viewport, prev_offset);
         self.state.update_viewport(viewport);
 
         // Context lines: walk parent_vis_idx from the node at viewport.offset.min(max_offset),
         // matching the original context bar behavior.
-        let context_vpos = viewport.offset.min(viewport.max_offset);
-        let context_lines = if context_vpos > 0 {
+        let context_vpos = self
+            .state
+            .rows
+            .line_at(viewport.offset.min(viewport.max_offset));
+        let context_lines = if context_vpos > 0 && !self.style.accessible {
             let visible_nodes = self.state.active_visible_nodes();
             let selected_vis = self.state.selected_position_cached();
             if let Some(context_idx) = visible_nodes
@@ -91,31 +333,62 @@ impl<'a, 's> RenderContext<'a, 's> {
         };
 
         let content_height = viewport.height.saturating_sub(context_lines.len());
-        viewport.clamp_offset(total_lines, context_lines.len());
+        if !self.style.accessible {
+            viewport.clamp_offset(total_lines, context_lines.len());
+        }
         self.state.update_viewport(viewport);
 
-        // Render viewport rows: find nodes with virtual_pos in [viewport.offset, offset + content_height).
-        let render_start_vpos = viewport.offset;
-        let render_end_vpos = viewport.offset + content_height;
+        // Render viewport rows: nodes from the one drawn on the first row, the
+        // rows of a wrapped node above it skipped, until the content is full.
+        let render_start_vpos = self.state.rows.line_at(viewport.offset);
+        let skip_rows = viewport.offset
+            - self
+                .state
+                .rows
+                .row_of(render_start_vpos)
+                .unwrap_or(render_start_vpos);
         let mut lines = Vec::with_capacity(content_height);
+        let mut shown = Vec::with_capacity(content_height);
+        let mut fresh = Vec::new();
         {
             let visible_nodes = self.state.active_visible_nodes();
             let selected_vis = self.state.selected_position_cached();
+            let mut row_count = 0;
             for (i, vnode) in visible_nodes.iter().enumerate() {
                 if vnode.virtual_pos.0 < render_start_vpos {
                     continue;
                 }
-                if vnode.virtual_pos.0 >= render_end_vpos {
+                if row_count >= skip_rows + content_height {
                     break;
                 }
                 let vis = VisIdx(i);
-                if let Some(line) =
-                    self.render_visible_node(visible_nodes, vis, selected_vis, false)
-                {
-                    lines.push(line);
+                let key = (vnode.virtual_pos, vnode.id, selected_vis == Some(vis));
+                if let Some(row) = self.state.rows.rows.get(&key) {
+                    row_count += row.len();
+                    lines.push(row.clone());
+                } else {
+                    let Some((line, hang)) =
+                        self.render_node_parts(visible_nodes, vis, selected_vis, false)
+                    else {
+                        continue;
+                    };
+                    let row: Vec<Line<'static>> = match wrap_width {
+                        Some(width) => wrap_line(line, &hang, width),
+                        None => vec![line],
+                    }
+                    .into_iter()
+                    .map(into_owned)
+                    .collect();
+                    row_count += row.len();
+                    lines.push(row.clone());
+                    fresh.push((key, row));
                 }
+                shown.push(key);
             }
         }
+        self.state.rows.finish_frame(&shown, fresh);
+        let mut lines: Vec<Line<'a>> = lines.into_iter().flatten().skip(skip_rows).collect();
+        lines.truncate(content_height);
 
         RenderOutput {
             lines,
@@ -125,6 +398,16 @@ impl<'a, 's> RenderContext<'a, 's> {
         }
     }
 
+    /// Renders every row of the expanded nodes, ignoring the viewport, with
+    /// nothing selected.
+    pub fn render_all(&mut self) -> Vec<Line<'a>> {
+        self.state.ensure_visible_nodes(self.tree);
+        let visible_nodes = self.state.active_visible_nodes();
+        (0..visible_nodes.len())
+            .filter_map(|idx| self.render_visible_node(visible_nodes, VisIdx(idx), None, false))
+            .collect()
+    }
+
     pub fn render_visible_node(
         &self,
         visible_nodes: &[VisibleNode],
@@ -132,19 +415,70 @@ impl<'a, 's> RenderContext<'a, 's> {
         selected_vis: Option<VisIdx>,
         context_lines: bool,
     ) -> Option<Line<'a>> {
+        self.render_node_parts(visible_nodes, vis_idx, selected_vis, context_lines)
+            .map(|(line, _)| line)
+    }
+
+    /// Renders a node row along with the hanging indent used for its wrapped
+    /// continuation rows.
+    ///
+    /// The indent keeps the tree guides running and aligns wrapped text under
+    /// the crate name.
+    fn render_node_parts(
+        &self,
+        visible_nodes: &[VisibleNode],
+        vis_idx: VisIdx,
+        selected_vis: Option<VisIdx>,
+        context_lines: bool,
+    ) -> Option<(Line<'a>, Vec<Span<'a>>)> {
         let vnode = visible_nodes.get(vis_idx.0)?;
         let node_id = vnode.id;
         let node_data = self.tree.node(node_id)?;
+        // Marker rows carry their parent's id but stand for no node.
+        let is_marker = vnode.marker.is_some();
+        let owner = self.owner_of(visible_nodes, vnode).filter(|_| !is_marker);
+        let is_violation = owner.is_some_and(|owner| self.state.is_layer_violation(owner, node_id));
+        let declared = owner.and_then(|owner| self.declared(visible_nodes, vnode, owner));
+        let dissolved_kind = owner.and_then(|owner| self.state.edge_kind(owner, node_id));
+        if self.style.accessible {
+            let line = self.describe_node(
+                vnode,
+                node_data,
+                selected_vis == Some(vis_idx),
+                is_violation,
+                declared.as_ref(),
+            );
+            return Some((line, Vec::new()));
+        }
         let lineage = Lineage::build(self.tree, visible_nodes, vis_idx, selected_vis)?;
-        let has_children = !node_data.children().is_empty();
+        // A back-edge is drawn as a leaf, matching how it is sized.
+        let has_children = !node_data.children().is_empty() && !vnode.is_cycle && !is_marker;
         let is_open = self.state.open.get(node_id.0).copied().unwrap_or(false);
-        let is_group = node_data.is_group();
+        let is_group = node_data.is_group() && !is_marker;
 
         let is_root = vnode.parent_vis_idx.is_none();
         let show_connector = !is_root;
 
         let mut spans = Vec::new();
 
+        if let Some(icons) = &self.style.icons {
+            let mut icon = node_icon(self.tree, node_id, node_data, icons).to_string();
+            if is_marker {
+                icon = " ".repeat(Span::raw(icon).width());
+            }
+            let gutter_style = if context_lines {
+                self.style.context_style
+            } else {
+                self.style.style
+            };
+            spans.push(Span::styled(icon, gutter_style));
+            spans.push(Span::styled(" ", gutter_style));
+        }
+        let mut hang = spans
+            .iter()
+            .map(|span| Span::raw(" ".repeat(span.width())))
+            .collect::<Vec<_>>();
+
         let toggle = if has_children {
             if is_open {
                 format!("{} ", self.style.node_open_symbol)
@@ -154,8 +488,27 @@ impl<'a, 's> RenderContext<'a, 's> {
         } else {
             format!("{} ", self.style.node_symbol)
         };
+        // Text on marker rows lines up with the names of its siblings.
+        let toggle = if is_marker {
+            " ".repeat(Span::raw(toggle).width())
+        } else {
+            toggle
+        };
 
-        if show_connector {
+        if self.style.compact {
+            let depth = lineage
+                .segments
+                .iter()
+                .filter(|segment| !segment.is_group)
+                .count()
+                + usize::from(show_connector && !is_group);
+            let indent = "  ".repeat(depth);
+            hang.push(Span::raw(" ".repeat(indent.len() + 2)));
+            spans.push(Span::styled(indent, self.style.style));
+            if show_connector && !is_group {
+                spans.push(Span::styled(toggle, self.style.style));
+            }
+        } else if show_connector {
             for segment in &lineage.segments {
                 if segment.is_group {
                     continue;
@@ -172,6 +525,7 @@ impl<'a, 's> RenderContext<'a, 's> {
                 };
 
                 spans.push(Span::styled(symbol, base_style));
+                hang.push(Span::styled(symbol, base_style));
             }
 
             if !is_group {
@@ -184,33 +538,118 @@ impl<'a, 's> RenderContext<'a, 's> {
                     .parent_vis_idx
                     .and_then(|pvis| visible_nodes.get(pvis.0))
                     .and_then(|pvnode| self.tree.node(pvnode.id))
-                    .and_then(|parent| parent.as_group().map(|group| group.kind.style()));
+                    .and_then(|parent| {
+                        parent
+                            .as_group()
+                            .map(|group| dependency_type_style(group.kind))
+                    })
+                    .or(dissolved_kind.map(dependency_type_style));
                 let connector_style = parent_group_style.unwrap_or(self.style.style);
                 spans.push(Span::styled(connector, connector_style));
                 spans.push(Span::styled(toggle, self.style.style));
+
+                let sibling_guide = if lineage.is_last {
+                    self.style.empty_symbol
+                } else {
+                    self.style.continuation_symbol
+                };
+                let child_guide = if has_children && is_open {
+                    self.style.continuation_symbol
+                } else {
+                    self.style.empty_symbol
+                };
+                hang.push(Span::styled(sibling_guide, connector_style));
+                hang.push(Span::styled(
+                    child_guide.chars().take(2).collect::<String>(),
+                    self.style.style,
+                ));
             }
         }
 
+        if let Some(marker) = vnode.marker {
+            let marker_style = if lineage.is_selected {
+                self.style.highlight_style
+            } else {
+                self.style.context_style
+            };
+            let text = match marker {
+                RowMarker::More(more) => format!("… and {more} more (press → to load)"),
+                RowMarker::DepthLimit => "(depth limit reached)".to_string(),
+            };
+            spans.push(Span::styled(text, marker_style));
+            return Some((Line::from(spans), hang));
+        }
+
         let name_style = if lineage.is_selected {
             self.style.highlight_style
+        } else if self.state.is_marked(node_id) {
+            self.style.marked_style
+        } else if self.state.is_on_path(node_id) {
+            self.style.path_style
         } else if self.state.is_search_match(node_id) {
             self.style.filtered_style
+        } else if self.tree.is_workspace_member(node_id) {
+            self.style.member_style
         } else {
             self.style.name_style
         };
 
         match node_data {
             DependencyNode::Crate(dependency) => {
-                spans.push(Span::styled(dependency.name.clone(), name_style));
-                if !dependency.version.is_empty() {
-                    spans.push(Span::styled(
-                        format!(" v{}", dependency.version),
-                        self.style.version_style,
-                    ));
+                let change = self.state.change(node_id);
+                let name_style = if change == Some(CrateChange::Removed) {
+                    name_style.add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    name_style
+                };
+                spans.push(Span::styled(dependency.name.as_str(), name_style));
+                // Without group headers, dev and build edges are told apart
+                // by a badge.
+                let kind_badge = match dissolved_kind {
+                    Some(kind @ DependencyType::Dev) => Some((" dev", kind)),
+                    Some(kind @ DependencyType::Build) => Some((" build", kind)),
+                    _ => None,
+                };
+                if let Some((badge, kind)) = kind_badge {
+                    spans.push(Span::styled(badge, dependency_type_style(kind)));
                 }
-
-                if let Some(extra) = format_suffixes(dependency, self.style) {
-                    spans.extend(extra);
+                if let Some(change) = change {
+                    let badge_style = match change {
+                        CrateChange::Added => self.style.added_style,
+                        CrateChange::Removed => self.style.removed_style,
+                        CrateChange::VersionChanged => self.style.changed_style,
+                    };
+                    spans.push(Span::styled(format!(" {}", change.badge()), badge_style));
+                }
+                if self.state.is_pinned(node_id) {
+                    spans.push(Span::styled(" ★", self.style.suffix_style));
+                }
+                if self.state.is_annotated(node_id) {
+                    spans.push(Span::styled(" ✎", self.style.suffix_style));
+                }
+                if let Some(badge) = self.state.badge(node_id) {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(badge.to_string(), self.style.badge_style));
+                }
+                if !self.style.compact {
+                    spans.extend(self.node_details(
+                        dependency,
+                        node_id,
+                        vnode,
+                        is_violation,
+                        declared.as_ref(),
+                    ));
+                } else if self.tree.is_workspace_member(node_id) {
+                    // Members keep their version and path to head their subtree.
+                    if self.shows_version(dependency, node_id) {
+                        spans.push(Span::styled(
+                            format!(" v{}", dependency.version),
+                            self.style.version_style,
+                        ));
+                    }
+                    if let Some(path) = &dependency.manifest_dir {
+                        spans.push(Span::styled(format!(" ({path})"), self.style.suffix_style));
+                    }
                 }
             }
             DependencyNode::Group(group) => {
@@ -219,13 +658,327 @@ impl<'a, 's> RenderContext<'a, 's> {
                 } else if self.state.is_search_match(node_id) {
                     self.style.filtered_style
                 } else {
-                    group.kind.style()
+                    dependency_type_style(group.kind)
                 };
-                spans.push(Span::styled(group.label().to_string(), group_style));
+                spans.push(Span::styled(group.label(), group_style));
+            }
+            DependencyNode::Target(target) => {
+                spans.push(Span::styled(target.kind.label(), self.style.version_style));
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(target.name.as_str(), name_style));
+            }
+            DependencyNode::Folder(folder) => {
+                spans.push(Span::styled(folder.path.as_str(), name_style));
             }
         }
 
-        Some(Line::from(spans))
+        if self.style.show_match_counts && has_children && !is_open {
+            let hits = self.state.search_hits_below(node_id);
+            if hits > 0 {
+                spans.push(Span::styled(format!(" •{hits}"), self.style.filtered_style));
+            }
+        }
+
+        let mut line = Line::from(spans);
+        if self.state.is_tracing_path() && !self.state.is_on_path(node_id) && !lineage.is_selected {
+            line = line.patch_style(self.style.dimmed_style);
+        }
+
+        Some((line, hang))
+    }
+
+    /// Returns whether the version of a crate is rendered after its name.
+    fn shows_version(&self, dependency: &Dependency, node_id: NodeId) -> bool {
+        !dependency.version.is_empty()
+            && (!self.style.ambiguous_versions_only || self.state.is_duplicated(node_id))
+    }
+
+    /// Returns the version, badges and suffixes shown after a crate's name
+    /// in the detailed view.
+    fn node_details(
+        &self,
+        dependency: &'a Dependency,
+        node_id: NodeId,
+        vnode: &VisibleNode,
+        is_violation: bool,
+        declared: Option<&Declared<'a>>,
+    ) -> Vec<Span<'a>> {
+        let mut spans = Vec::new();
+        if let Some(declared) = declared {
+            let requirement_style = if declared.lint.is_some() {
+                self.style.lint_style
+            } else {
+                self.style.version_style
+            };
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(declared.requirement, requirement_style));
+            if declared.inherited {
+                spans.push(Span::styled(" (workspace)", self.style.version_style));
+            }
+            if self.shows_version(dependency, node_id) {
+                let version_style = self
+                    .state
+                    .release_age(node_id)
+                    .map_or(self.style.version_style, |days| self.style.age_style(days));
+                spans.push(Span::styled(" → v", self.style.version_style));
+                spans.push(Span::styled(dependency.version.as_str(), version_style));
+            }
+        } else if self.shows_version(dependency, node_id) {
+            let version_style = self
+                .state
+                .release_age(node_id)
+                .map_or(self.style.version_style, |days| self.style.age_style(days));
+            spans.push(Span::styled(" v", version_style));
+            spans.push(Span::styled(dependency.version.as_str(), version_style));
+        }
+
+        let dependents = self
+            .style
+            .show_dependents
+            .then(|| self.tree.dependents(node_id).len());
+        let cost = self.state.cost_share(node_id);
+        let size = self.state.binary_size(node_id);
+        if let Some(extra) = format_suffixes(
+            dependency,
+            dependents,
+            cost,
+            size,
+            vnode.is_cycle,
+            self.style,
+        ) {
+            spans.extend(extra);
+        }
+        if let Some(newer) = declared.and_then(|declared| declared.newer.as_ref()) {
+            spans.push(Span::styled(
+                format!(" ⇡ v{newer} allowed"),
+                self.style.update_style,
+            ));
+        }
+        if let Some(lint) = declared.and_then(|declared| declared.lint) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("⚠ {}", lint.label()),
+                self.style.lint_style,
+            ));
+        }
+        if self.state.has_feature_conflict(node_id) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled("⚠ feature conflict", self.style.lint_style));
+        }
+        if let Some(std_use) = self.state.std_use(node_id) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("⚠ {}", std_use.label()),
+                self.style.lint_style,
+            ));
+        }
+        if is_violation {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                "✗ layer violation",
+                self.style.violation_style,
+            ));
+        }
+        spans
+    }
+
+    /// Returns the crate declaring the row's dependency: its parent, or the
+    /// crate owning its parent group.
+    fn owner_of(&self, visible_nodes: &[VisibleNode], vnode: &VisibleNode) -> Option<NodeId> {
+        let parent = visible_nodes.get(vnode.parent_vis_idx?.0)?;
+        if self.tree.node(parent.id)?.is_group() {
+            Some(visible_nodes.get(parent.parent_vis_idx?.0)?.id)
+        } else {
+            Some(parent.id)
+        }
+    }
+
+    /// Returns the kind of the edge leading to the row, from its group node
+    /// or, when groups are dissolved, from the widget state.
+    fn edge_kind(&self, visible_nodes: &[VisibleNode], vnode: &VisibleNode) -> DependencyType {
+        let Some(parent) = vnode
+            .parent_vis_idx
+            .and_then(|parent| visible_nodes.get(parent.0))
+        else {
+            return DependencyType::Normal;
+        };
+        match self.tree.node(parent.id) {
+            Some(DependencyNode::Group(group)) => group.kind,
+            _ => self
+                .state
+                .edge_kind(parent.id, vnode.id)
+                .unwrap_or(DependencyType::Normal),
+        }
+    }
+
+    /// Returns the requirement `owner` declares on the row's crate when it is
+    /// a workspace member, along with the newer version it allows.
+    fn declared(
+        &self,
+        visible_nodes: &[VisibleNode],
+        vnode: &VisibleNode,
+        owner: NodeId,
+    ) -> Option<Declared<'a>> {
+        if !self.tree.is_workspace_member(owner) {
+            return None;
+        }
+        let dependency = self.tree.node(vnode.id)?.as_dependency()?;
+        let kind = self.edge_kind(visible_nodes, vnode);
+        let declaration = self
+            .tree
+            .node(owner)?
+            .as_dependency()?
+            .declaration_on(&dependency.name, kind)?;
+        let requirement = declaration.requirement.as_str();
+        Some(Declared {
+            requirement,
+            inherited: declaration.inherited,
+            lint: RequirementLint::of(requirement),
+            newer: self
+                .state
+                .update_in_range(owner, vnode.id)
+                .map(str::to_string),
+        })
+    }
+
+    /// Describes a node as a plain sentence, e.g.
+    /// `depth 3: serde v1.0.200, 4 children, collapsed`.
+    ///
+    /// Everything conveyed by color in the regular rows is spelled out, and
+    /// the selected row is prefixed with `>`.
+    fn describe_node(
+        &self,
+        vnode: &VisibleNode,
+        node: &DependencyNode,
+        is_selected: bool,
+        is_violation: bool,
+        declared: Option<&Declared<'a>>,
+    ) -> Line<'a> {
+        let parts = match vnode.marker {
+            Some(RowMarker::More(more)) => {
+                vec![
+                    format!("{more} more children"),
+                    "press right to load".to_string(),
+                ]
+            }
+            Some(RowMarker::DepthLimit) => vec!["depth limit reached".to_string()],
+            None => self.node_facts(vnode, node, is_violation, declared),
+        };
+
+        let prefix = if is_selected { "> " } else { "  " };
+        let text = format!("{prefix}depth {}: {}", vnode.depth, parts.join(", "));
+        if is_selected {
+            Line::styled(text, self.style.highlight_style)
+        } else {
+            Line::styled(text, self.style.name_style)
+        }
+    }
+
+    /// Lists what [`Self::describe_node`] says about a node row.
+    fn node_facts(
+        &self,
+        vnode: &VisibleNode,
+        node: &DependencyNode,
+        is_violation: bool,
+        declared: Option<&Declared<'a>>,
+    ) -> Vec<String> {
+        let node_id = vnode.id;
+        let mut parts = vec![match node {
+            DependencyNode::Crate(dependency) if dependency.version.is_empty() => {
+                dependency.name.clone()
+            }
+            DependencyNode::Crate(dependency) => {
+                format!("{} v{}", dependency.name, dependency.version)
+            }
+            DependencyNode::Group(group) => {
+                format!("{} group", group.label().trim_matches(['[', ']']))
+            }
+            DependencyNode::Target(target) => {
+                format!("{} target {}", target.kind.label(), target.name)
+            }
+            DependencyNode::Folder(folder) => format!("folder {}", folder.path),
+        }];
+
+        let children = node.children().len();
+        match children {
+            _ if vnode.is_cycle => parts.push("dependency cycle".to_string()),
+            0 => parts.push("no children".to_string()),
+            1 => parts.push("1 child".to_string()),
+            n => parts.push(format!("{n} children")),
+        }
+        if children > 0 && !vnode.is_cycle {
+            let open = self.state.open.get(node_id.0).copied().unwrap_or(false);
+            parts.push(if open { "expanded" } else { "collapsed" }.to_string());
+        }
+
+        if let DependencyNode::Crate(dependency) = node {
+            if self.tree.is_workspace_member(node_id) {
+                parts.push("workspace member".to_string());
+            }
+            if dependency.is_proc_macro {
+                parts.push("proc-macro".to_string());
+            }
+            if let Some(declared) = declared {
+                parts.push(format!("requires {}", declared.requirement));
+                if declared.inherited {
+                    parts.push("inherited from the workspace".to_string());
+                }
+                if let Some(newer) = &declared.newer {
+                    parts.push(format!("v{newer} allowed"));
+                }
+                if let Some(lint) = declared.lint {
+                    parts.push(lint.label().to_string());
+                }
+            }
+            if is_violation {
+                parts.push("layer violation".to_string());
+            }
+            if self.state.has_feature_conflict(node_id) {
+                parts.push("feature conflict".to_string());
+            }
+            if let Some(std_use) = self.state.std_use(node_id) {
+                parts.push(std_use.label().to_string());
+            }
+            if self.style.show_dependents {
+                parts.push(format!("used by {}", self.tree.dependents(node_id).len()));
+            }
+            if let Some(share) = self.state.cost_share(node_id) {
+                parts.push(format!("about {share:.1}% of the build"));
+            }
+            if let Some(days) = self.state.release_age(node_id) {
+                parts.push(format!("released {days} days ago"));
+            }
+            if let Some(size) = self.state.binary_size(node_id) {
+                parts.push(format!("{} of the binary", format_size(size)));
+            }
+        }
+        if self.state.is_marked(node_id) {
+            parts.push("marked".to_string());
+        }
+        if self.state.is_pinned(node_id) {
+            parts.push("pinned".to_string());
+        }
+        if self.state.is_annotated(node_id) {
+            parts.push("has note".to_string());
+        }
+        if let Some(badge) = self.state.badge(node_id) {
+            parts.push(badge.to_string());
+        }
+        match self.state.change(node_id) {
+            Some(CrateChange::Added) => parts.push("added by reload".to_string()),
+            Some(CrateChange::Removed) => parts.push("removed by reload".to_string()),
+            Some(CrateChange::VersionChanged) => {
+                parts.push("version changed by reload".to_string())
+            }
+            None => {}
+        }
+        if self.state.is_on_path(node_id) {
+            parts.push("on path".to_string());
+        }
+        if self.state.is_search_match(node_id) {
+            parts.push("search match".to_string());
+        }
+        parts
     }
 
     /// Renders context lines by walking the parent chain from the first window-zone node.
@@ -252,27 +1005,189 @@ impl<'a, 's> RenderContext<'a, 's> {
             }
         }
 
+        let frozen = self.state.frozen();
+        // A frozen node scrolled past is pinned above the ancestors, unless
+        // it is one of them.
+        let pinned = if ancestor_vis_indices
+            .iter()
+            .any(|&vis_idx| frozen == Some(visible_nodes[vis_idx.0].id))
+        {
+            None
+        } else {
+            self.state
+                .frozen_above(self.tree, first_visible.virtual_pos.0)
+                .and_then(|nodes| {
+                    let last = nodes.len().checked_sub(1)?;
+                    self.render_visible_node(&nodes, VisIdx(last), None, true)
+                })
+        };
         // Render top → bottom.
-        ancestor_vis_indices
+        pinned
             .into_iter()
-            .rev()
-            .filter_map(|vis_idx| {
-                self.render_visible_node(visible_nodes, vis_idx, selected_vis, true)
-            })
+            .map(|line| line.patch_style(self.style.frozen_style))
+            .chain(
+                ancestor_vis_indices
+                    .into_iter()
+                    .rev()
+                    .filter_map(|vis_idx| {
+                        let line =
+                            self.render_visible_node(visible_nodes, vis_idx, selected_vis, true)?;
+                        Some(if frozen == Some(visible_nodes[vis_idx.0].id) {
+                            line.patch_style(self.style.frozen_style)
+                        } else {
+                            line
+                        })
+                    }),
+            )
             .collect()
     }
 }
 
+/// Requirement a workspace member declares on a direct dependency.
+struct Declared<'a> {
+    requirement: &'a str,
+    /// Whether the member inherits it from `[workspace.dependencies]`.
+    inherited: bool,
+    /// Lint the requirement triggers before publishing.
+    lint: Option<RequirementLint>,
+    /// Newer version than the resolved one that the requirement allows.
+    newer: Option<String>,
+}
+
+/// Soft-wraps `line` into rows of at most `width` columns.
+///
+/// Rows break at a space, which is dropped, or after a `-` or `_`, and only
+/// mid-word when a word does not fit on a row of its own. The first `hang`
+/// columns of the line are never broken, and continuation rows start with
+/// `hang` so that wrapped text lines up under the crate name. Rows are
+/// returned unchanged when the indent alone would not leave room for any
+/// text.
+pub(crate) fn wrap_line<'a>(line: Line<'a>, hang: &[Span<'a>], width: usize) -> Vec<Line<'a>> {
+    let hang_width: usize = hang.iter().map(Span::width).sum();
+    if line.width() <= width || hang_width >= width {
+        return vec![line];
+    }
+
+    let cells: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(|ch| (ch, span.style)))
+        .collect();
+    let cell_width = |idx: usize| cells[idx].0.width().unwrap_or(0);
+    // The first row may only break past the tree guides.
+    let mut guides = 0;
+    let mut guides_width = 0;
+    while guides < cells.len() && guides_width + cell_width(guides) <= hang_width {
+        guides_width += cell_width(guides);
+        guides += 1;
+    }
+
+    let mut rows = Vec::new();
+    let mut start = 0;
+    while start < cells.len() {
+        let first = rows.is_empty();
+        let mut row_width = if first { 0 } else { hang_width };
+        let mut end = start;
+        while end < cells.len() && row_width + cell_width(end) <= width {
+            row_width += cell_width(end);
+            end += 1;
+        }
+        // Always make progress, even if a single character is too wide.
+        end = end.max(start + 1);
+
+        let (row_end, next) = if end == cells.len() {
+            (end, end)
+        } else {
+            let earliest = if first {
+                guides.max(start + 1)
+            } else {
+                start + 1
+            };
+            (earliest..=end)
+                .rev()
+                .find_map(|idx| {
+                    if cells.get(idx).is_some_and(|&(ch, _)| ch == ' ') {
+                        Some((idx, idx + 1))
+                    } else if matches!(cells[idx - 1].0, '-' | '_') {
+                        Some((idx, idx))
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or_else(|| {
+                    let skip = cells[end].0 == ' ';
+                    (end, end + usize::from(skip))
+                })
+        };
+
+        let mut row: Vec<Span<'a>> = if first { Vec::new() } else { hang.to_vec() };
+        let text_start = row.len();
+        for &(ch, style) in &cells[start..row_end] {
+            match row[text_start..].last_mut() {
+                Some(span) if span.style == style => span.content.to_mut().push(ch),
+                _ => row.push(Span::styled(ch.to_string(), style)),
+            }
+        }
+        rows.push(Line::from(row).style(line.style));
+        start = next;
+    }
+    rows
+}
+
+/// Picks the gutter icon for a node, preferring the most specific kind.
+fn node_icon(
+    tree: &DependencyTree,
+    node_id: NodeId,
+    node: &DependencyNode,
+    icons: &NodeIcons,
+) -> &'static str {
+    match node {
+        DependencyNode::Group(_) | DependencyNode::Folder(_) => icons.group,
+        DependencyNode::Target(_) => icons.target,
+        DependencyNode::Crate(_) if tree.is_workspace_member(node_id) => icons.workspace_member,
+        DependencyNode::Crate(dependency) if dependency.is_proc_macro => icons.proc_macro,
+        DependencyNode::Crate(dependency) if dependency.source == SourceKind::Git => icons.git,
+        DependencyNode::Crate(_) => icons.crate_icon,
+    }
+}
+
 /// Formats suffixes for a dependency node.
-fn format_suffixes<'a>(node: &Dependency, style: &TreeWidgetStyle) -> Option<Vec<Span<'a>>> {
-    let mut suffixes = Vec::new();
+fn format_suffixes<'a>(
+    node: &'a Dependency,
+    dependents: Option<usize>,
+    cost: Option<f64>,
+    size: Option<u64>,
+    is_cycle: bool,
+    style: &TreeWidgetStyle,
+) -> Option<Vec<Span<'a>>> {
+    let mut suffixes: Vec<Cow<'a, str>> = Vec::new();
+
+    if is_cycle {
+        suffixes.push("cycle".into());
+    }
 
     if let Some(path) = &node.manifest_dir {
-        suffixes.push(path.to_string());
+        suffixes.push(path.as_str().into());
     }
 
     if node.is_proc_macro {
-        suffixes.push("proc-macro".to_string());
+        suffixes.push("proc-macro".into());
+    }
+
+    if let Some(registry) = node.registry.as_ref().filter(|r| !r.is_crates_io()) {
+        suffixes.push(registry.name.as_str().into());
+    }
+
+    if let Some(count) = dependents.filter(|&count| count > 0) {
+        suffixes.push(format!("used by {count}").into());
+    }
+
+    if let Some(share) = cost {
+        suffixes.push(format!("build ~{share:.1}%").into());
+    }
+
+    if let Some(size) = size {
+        suffixes.push(format!("bin {}", format_size(size)).into());
     }
 
     if suffixes.is_empty() {
@@ -282,14 +1197,27 @@ fn format_suffixes<'a>(node: &Dependency, style: &TreeWidgetStyle) -> Option<Vec
     let mut spans = Vec::new();
     spans.push(Span::styled(" (", style.style));
 
-    for (idx, suffix) in suffixes.iter().enumerate() {
+    for (idx, suffix) in suffixes.into_iter().enumerate() {
         if idx > 0 {
             spans.push(Span::styled(", ", style.style));
         }
-        spans.push(Span::styled(suffix.clone(), style.suffix_style));
+        spans.push(Span::styled(suffix, style.suffix_style));
     }
 
     spans.push(Span::styled(")", style.style));
 
     Some(spans)
 }
+
+/// Copies the text of `line` so that it no longer borrows the tree.
+fn into_owned(line: Line<'_>) -> Line<'static> {
+    Line {
+        spans: line
+            .spans
+            .into_iter()
+            .map(|span| Span::styled(span.content.into_owned(), span.style))
+            .collect(),
+        style: line.style,
+        alignment: line.alignment,
+    }
+}
@@ -0,0 +1,73 @@
+use rustc_hash::FxHashMap;
+
+use crate::core::{DependencyNode, DependencyTree, NodeId};
+
+/// Trigram index over crate names, so substring searches only verify a few
+/// candidates instead of scanning every node.
+///
+/// Names are indexed lowercased, making the candidates a superset of the
+/// exact matches for any query casing.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    trigrams: FxHashMap<[u8; 3], Vec<NodeId>>,
+    node_count: usize,
+}
+
+impl SearchIndex {
+    /// Indexes every crate node of `tree`.
+    pub fn build(tree: &DependencyTree) -> Self {
+        let mut trigrams: FxHashMap<[u8; 3], Vec<NodeId>> = FxHashMap::default();
+        for id in tree.crate_nodes() {
+            let Some(DependencyNode::Crate(dependency)) = tree.node(id) else {
+                continue;
+            };
+            let name = dependency.name.to_ascii_lowercase();
+            for trigram in name.as_bytes().windows(3) {
+                let ids = trigrams
+                    .entry([trigram[0], trigram[1], trigram[2]])
+                    .or_default();
+                // Ids are visited in ascending order, so a repeated trigram
+                // within one name can only be the last entry.
+                if ids.last() != Some(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+
+        Self {
+            trigrams,
+            node_count: tree.nodes.len(),
+        }
+    }
+
+    /// Returns whether the index was built for a tree of this size.
+    pub fn matches_tree(&self, tree: &DependencyTree) -> bool {
+        self.node_count == tree.nodes.len()
+    }
+
+    /// Returns the nodes whose names may contain `query`, in ascending id
+    /// order, or `None` when the query is too short to narrow down.
+    pub fn candidates(&self, query: &str) -> Option<Vec<NodeId>> {
+        let query = query.to_ascii_lowercase();
+        let mut lists = Vec::new();
+        for trigram in query.as_bytes().windows(3) {
+            match self.trigrams.get(trigram) {
+                Some(ids) => lists.push(ids.as_slice()),
+                None => return Some(Vec::new()),
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+        let (shortest, rest) = lists.split_first()?;
+
+        Some(
+            shortest
+                .iter()
+                .copied()
+                .filter(|id| {
+                    rest.iter()
+                        .all(|ids| ids.binary_search_by_key(&id.0, |other| other.0).is_ok())
+                })
+                .collect(),
+        )
+    }
+}
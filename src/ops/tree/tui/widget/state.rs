@@ -1,8 +1,15 @@
-use rustc_hash::FxHashSet;
-
-use crate::core::{DependencyNode, DependencyTree, NodeId};
-
-use super::view_cache::ViewCache;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::core::{
+    Dependency, DependencyNode, DependencyTree, NodeId,
+    dependency::{DependencyType, StableKey},
+    diff::CrateChange,
+    no_std::StdUse,
+};
+
+use super::render::RowCache;
+use super::search_index::SearchIndex;
+use super::view_cache::{ChildLimits, RowSummary, ViewCache};
 use super::viewport::Viewport;
 
 /// The widget uses three different index spaces:
@@ -32,6 +39,16 @@ pub struct VisIdx(pub usize);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct VirtualPos(pub usize);
 
+/// View state of a tree keyed by [`StableKey`] instead of [`NodeId`], see
+/// [`TreeWidgetState::snapshot`].
+#[derive(Debug, Clone, Default)]
+pub struct ViewSnapshot {
+    open: FxHashMap<StableKey, bool>,
+    selected: Option<StableKey>,
+    marked: Vec<StableKey>,
+    frozen: Option<StableKey>,
+}
+
 /// [`TreeWidget`] state that tracks open nodes and the current selection.
 ///
 /// [`TreeWidget`]: super::TreeWidget
@@ -59,6 +76,62 @@ pub struct TreeWidgetState {
     search_visible_ids: Vec<NodeId>,
     /// Node ids whose `search_matches` bit is currently set, used for cheap resets and refinement.
     search_match_ids: Vec<NodeId>,
+    /// Number of distinct matches below each ancestor of a match.
+    search_hits_below: FxHashMap<NodeId, usize>,
+    /// Selection from before a live search started previewing its first
+    /// match, restored when the search is cancelled.
+    preview_origin: Option<(VirtualPos, NodeId)>,
+    /// Crates marked for comparison, oldest first (at most two).
+    marked: Vec<NodeId>,
+    /// Node kept among the context lines while scrolling below it.
+    frozen: Option<NodeId>,
+    /// Crates pinned for quick access across sessions.
+    pinned: Vec<NodeId>,
+    /// Crates carrying a note.
+    annotated: Vec<NodeId>,
+    /// Crates that changed in the last reload.
+    changes: FxHashMap<NodeId, CrateChange>,
+    /// Badges added to crates by custom annotators.
+    badges: FxHashMap<NodeId, String>,
+    /// Newer versions allowed by the requirement a member declares on a
+    /// direct dependency, keyed by `(member, dependency)`.
+    updates_in_range: FxHashMap<(NodeId, NodeId), String>,
+    /// Nodes on the traced path, indexed by node id. Empty when no path is traced.
+    path_nodes: Vec<bool>,
+    /// `(member, dependency)` edges breaking the layering rules.
+    layer_violations: Vec<(NodeId, NodeId)>,
+    /// Crates whose dependents ask for conflicting features.
+    feature_conflicts: FxHashSet<NodeId>,
+    /// Crates present in more than one version, whose versions are kept
+    /// when the others are hidden.
+    duplicated: FxHashSet<NodeId>,
+    /// Kinds of the `(owner, dependency)` edges whose group nodes were
+    /// dissolved.
+    edge_kinds: FxHashMap<(NodeId, NodeId), DependencyType>,
+    /// Days since each crate's version was released, when the heatmap is
+    /// shown.
+    release_ages: FxHashMap<NodeId, i64>,
+    /// Crates pulling `std` into `no_std` members, when checked.
+    std_creep: FxHashMap<NodeId, StdUse>,
+    /// Estimated share of the build spent on each subtree, in percent and
+    /// indexed by node id. Empty when the estimate is not shown.
+    cost_shares: Vec<f64>,
+    /// Binary size attributed to each subtree in bytes, indexed by node id.
+    /// Empty without a `cargo bloat` report.
+    binary_sizes: Vec<u64>,
+    /// Child last selected before jumping to its parent, keyed by parent.
+    last_visited_child: FxHashMap<NodeId, NodeId>,
+    /// Whether sibling navigation wraps around at either end.
+    pub wrap_siblings: bool,
+    /// Children shown per node before a marker row.
+    child_limits: ChildLimits,
+    /// Rows rendered on the last frame, cleared whenever what they show
+    /// changes.
+    pub(super) rows: RowCache,
+    /// Intermediate frames drawn so far by the ongoing smooth scroll.
+    pub(super) scroll_frames: usize,
+    /// Scroll the selection to the middle of the view on the next frame.
+    pub(super) center_pending: bool,
 }
 
 /// Visible node metadata used for navigation and rendering.
@@ -80,6 +153,21 @@ pub struct VisibleNode {
     /// full virtual stream under the current open/filter (not just within
     /// the materialized window). Drives the `└─` vs `├─` decision.
     pub is_last_non_group_child: bool,
+    /// Whether this row is a back-edge to a node already on its path,
+    /// rendered as a leaf to break the cycle.
+    pub is_cycle: bool,
+    /// Placeholder for the children left out of the view, when this row is
+    /// one. Its `id` is then the parent's.
+    pub marker: Option<RowMarker>,
+}
+
+/// Row standing in for children of its parent that are not shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowMarker {
+    /// "… and N more" row, loading the next page of children.
+    More(usize),
+    /// The parent is at the depth guard, so none of its children are shown.
+    DepthLimit,
 }
 
 /// Search result payload computed off the UI thread.
@@ -120,6 +208,30 @@ impl Default for TreeWidgetState {
             search_matches: Vec::new(),
             search_visible_ids: Vec::new(),
             search_match_ids: Vec::new(),
+            search_hits_below: FxHashMap::default(),
+            marked: Vec::new(),
+            frozen: None,
+            preview_origin: None,
+            pinned: Vec::new(),
+            annotated: Vec::new(),
+            changes: FxHashMap::default(),
+            badges: FxHashMap::default(),
+            updates_in_range: FxHashMap::default(),
+            path_nodes: Vec::new(),
+            layer_violations: Vec::new(),
+            cost_shares: Vec::new(),
+            binary_sizes: Vec::new(),
+            feature_conflicts: FxHashSet::default(),
+            duplicated: FxHashSet::default(),
+            edge_kinds: FxHashMap::default(),
+            std_creep: FxHashMap::default(),
+            release_ages: FxHashMap::default(),
+            last_visited_child: FxHashMap::default(),
+            wrap_siblings: false,
+            child_limits: ChildLimits::default(),
+            rows: RowCache::default(),
+            scroll_frames: 0,
+            center_pending: false,
         }
     }
 }
@@ -136,23 +248,83 @@ impl TreeWidgetState {
 
     /// Returns the `NodeId` of the currently selected visible position.
     ///
-    /// Returns `None` if nothing is selected or the cache doesn't contain the
-    /// selected position (call [`ensure_visible_nodes`] first).
+    /// Returns `None` if nothing is selected, a marker row is, or the cache
+    /// doesn't contain the selected position (call [`ensure_visible_nodes`]
+    /// first).
     pub fn selected_node_id(&self) -> Option<NodeId> {
         let vpos = self.selected_virtual_pos?;
-        self.find_by_vpos(vpos).map(|(_, n)| n.id)
+        self.find_by_vpos(vpos)
+            .filter(|(_, n)| n.marker.is_none())
+            .map(|(_, n)| n.id)
+    }
+
+    /// Returns the selected marker row along with the parent whose children
+    /// it stands for, if one is selected.
+    fn selected_marker(&self) -> Option<(NodeId, RowMarker)> {
+        let vpos = self.selected_virtual_pos?;
+        let (_, vnode) = self.find_by_vpos(vpos)?;
+        Some((vnode.id, vnode.marker?))
+    }
+
+    /// Returns the `NodeId` of the selected node's parent in the current view.
+    ///
+    /// Unlike [`DependencyTree::parents`], this is the single parent the
+    /// selected row is displayed under.
+    pub fn selected_parent_id(&self) -> Option<NodeId> {
+        let vpos = self.selected_virtual_pos?;
+        let (_, vnode) = self
+            .find_by_vpos(vpos)
+            .filter(|(_, n)| n.marker.is_none())?;
+        let parent_vis = vnode.parent_vis_idx?;
+        self.active_visible_nodes().get(parent_vis.0).map(|n| n.id)
+    }
+
+    /// Returns the `NodeId` of the ancestor `levels` rows up from the selected node.
+    fn selected_ancestor_id(&self, levels: usize) -> Option<NodeId> {
+        let vpos = self.selected_virtual_pos?;
+        let (mut vis, _) = self.find_by_vpos(vpos)?;
+        let nodes = self.active_visible_nodes();
+        for _ in 0..levels {
+            vis = nodes.get(vis.0)?.parent_vis_idx?;
+        }
+        nodes.get(vis.0).map(|n| n.id)
+    }
+
+    /// Returns the workspace member that directly declares the selected crate,
+    /// along with the kind of dependency, following the current view.
+    pub fn selected_direct_dependency_of(
+        &self,
+        tree: &DependencyTree,
+    ) -> Option<(NodeId, DependencyType)> {
+        let parent = self.selected_parent_id()?;
+        let (owner, kind) = match tree.node(parent)? {
+            DependencyNode::Group(group) => (self.selected_ancestor_id(2)?, group.kind),
+            DependencyNode::Crate(_) => (parent, DependencyType::Normal),
+            DependencyNode::Target(_) | DependencyNode::Folder(_) => return None,
+        };
+        tree.is_workspace_member(owner).then_some((owner, kind))
     }
 
     /// Sets the selection to the virtual position of the given `NodeId`.
     ///
-    /// Requires a DFS walk using subtree sizes to locate the first occurrence.
+    /// Requires a DFS walk using subtree sizes to locate the first occurrence;
+    /// only the target's ancestors are descended into, everything else is
+    /// skipped by its size.
     pub fn set_selected_node_id(&mut self, tree: &DependencyTree, id: NodeId) {
         self.ensure_subtree_sizes(tree);
         let sizes = self.active_subtree_sizes();
         let filter = self.active_filter();
         let roots = tree.roots();
 
-        self.selected_virtual_pos = find_virtual_pos(tree, &self.open, sizes, filter, roots, id);
+        self.selected_virtual_pos = find_virtual_pos(
+            tree,
+            &self.open,
+            &self.child_limits,
+            sizes,
+            filter,
+            roots,
+            id,
+        );
         self.dirty = true;
     }
 
@@ -176,16 +348,97 @@ impl TreeWidgetState {
         for node_id in self.search_match_ids.drain(..) {
             self.search_matches[node_id.0] = false;
         }
+        self.search_hits_below.clear();
         self.search.clear();
+        self.rows.clear();
         // Rematerialize the main view with the current selection.
         self.dirty = true;
     }
 
+    /// Applies search state like [`Self::apply_search_state`] and
+    /// provisionally selects its first match, in the order of the rows,
+    /// remembering the selection it replaces until the preview is committed
+    /// or cancelled.
+    ///
+    /// Without a match, the remembered selection is shown meanwhile.
+    pub fn preview_search(&mut self, tree: &DependencyTree, search_state: SearchState) {
+        if self.preview_origin.is_none() {
+            self.ensure_visible_nodes(tree);
+            self.preview_origin = self.selected_virtual_pos.zip(self.selected_node_id());
+        }
+        self.apply_search_state(tree, search_state);
+        self.ensure_subtree_sizes(tree);
+        let sizes = self.active_subtree_sizes();
+        let filter = self.active_filter();
+        let first = self
+            .search_match_ids
+            .iter()
+            .filter_map(|&id| {
+                find_virtual_pos(
+                    tree,
+                    &self.open,
+                    &self.child_limits,
+                    sizes,
+                    filter,
+                    tree.roots(),
+                    id,
+                )
+            })
+            .min_by_key(|vpos| vpos.0);
+        match first {
+            Some(vpos) => {
+                self.selected_virtual_pos = Some(vpos);
+                self.dirty = true;
+            }
+            None => self.restore_preview_origin(tree),
+        }
+    }
+
+    /// Keeps the previewed selection.
+    pub fn commit_preview(&mut self) {
+        self.preview_origin = None;
+    }
+
+    /// Returns to the selection from before the preview. Call after the
+    /// search is cleared, so it lands in the unfiltered view.
+    pub fn cancel_preview(&mut self, tree: &DependencyTree) {
+        self.restore_preview_origin(tree);
+        self.preview_origin = None;
+    }
+
+    /// Selects the row remembered by [`Self::preview_search`], looking
+    /// the node up again when the row now holds another one.
+    fn restore_preview_origin(&mut self, tree: &DependencyTree) {
+        let Some((vpos, id)) = self.preview_origin else {
+            return;
+        };
+        self.selected_virtual_pos = Some(vpos);
+        self.dirty = true;
+        self.ensure_visible_nodes(tree);
+        if self.selected_node_id() != Some(id) {
+            self.set_selected_node_id(tree, id);
+        }
+    }
+
+    /// Returns the crates matching the active search query.
+    pub fn search_match_ids(&self) -> &[NodeId] {
+        &self.search_match_ids
+    }
+
     /// Returns whether a node directly matches the active search query.
     pub fn is_search_match(&self, node_id: NodeId) -> bool {
         self.search_matches.get(node_id.0).copied().unwrap_or(false)
     }
 
+    /// Returns how many distinct matches of the active search lie below a
+    /// node, or 0 without a search.
+    pub fn search_hits_below(&self, node_id: NodeId) -> usize {
+        self.search_hits_below
+            .get(&node_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Applies externally computed search state to the visible tree.
     pub fn apply_search_state(&mut self, tree: &DependencyTree, search_state: SearchState) {
         self.ensure_node_capacity(tree);
@@ -193,9 +446,31 @@ impl TreeWidgetState {
         self.search_matches = search_state.matches;
         self.search_visible_ids = search_state.visible_ids;
         self.search_match_ids = search_state.match_ids;
+        self.search_hits_below = Self::count_hits_below(tree, &self.search_match_ids);
+        self.rows.clear();
         self.rebuild_search_view(tree);
     }
 
+    /// Counts the distinct matches below every ancestor of a match.
+    fn count_hits_below(tree: &DependencyTree, match_ids: &[NodeId]) -> FxHashMap<NodeId, usize> {
+        let mut counts = FxHashMap::default();
+        // Index of the match whose ancestors were last walked, per node.
+        let mut seen = vec![usize::MAX; tree.nodes.len()];
+        for (idx, &match_id) in match_ids.iter().enumerate() {
+            seen[match_id.0] = idx;
+            let mut stack = tree.parents[match_id.0].clone();
+            while let Some(node_id) = stack.pop() {
+                if seen[node_id.0] == idx {
+                    continue;
+                }
+                seen[node_id.0] = idx;
+                *counts.entry(node_id).or_default() += 1;
+                stack.extend_from_slice(&tree.parents[node_id.0]);
+            }
+        }
+        counts
+    }
+
     /// Updates search-filtered nodes by matching crate names case-sensitively.
     pub fn set_search_query(&mut self, tree: &DependencyTree, query: &str) {
         if query.is_empty() {
@@ -212,14 +487,59 @@ impl TreeWidgetState {
             return SearchState::new(tree.nodes.len());
         }
 
+        Self::filter(tree, |_, dependency| dependency.name.contains(query))
+    }
+
+    /// Same as [`TreeWidgetState::search`], only verifying the candidates of
+    /// `index` when it covers `tree` and the query is long enough.
+    pub fn search_indexed(
+        tree: &DependencyTree,
+        index: Option<&SearchIndex>,
+        query: &str,
+    ) -> SearchState {
+        let candidates = index
+            .filter(|index| index.matches_tree(tree))
+            .and_then(|index| index.candidates(query));
+        match candidates {
+            Some(ids) => {
+                Self::filter_nodes(tree, ids, |_, dependency| dependency.name.contains(query))
+            }
+            None => Self::search(tree, query),
+        }
+    }
+
+    /// Computes a filtered view keeping the crates accepted by `predicate`
+    /// along with their ancestors.
+    pub fn filter(
+        tree: &DependencyTree,
+        predicate: impl Fn(NodeId, &Dependency) -> bool,
+    ) -> SearchState {
+        Self::filter_nodes(tree, tree.crate_nodes(), predicate)
+    }
+
+    /// Narrows `search_state` to its matches accepted by `predicate`, along
+    /// with their ancestors.
+    pub fn filter_matches(
+        tree: &DependencyTree,
+        search_state: &SearchState,
+        predicate: impl Fn(NodeId, &Dependency) -> bool,
+    ) -> SearchState {
+        Self::filter_nodes(tree, search_state.match_ids.iter().copied(), predicate)
+    }
+
+    fn filter_nodes(
+        tree: &DependencyTree,
+        ids: impl IntoIterator<Item = NodeId>,
+        predicate: impl Fn(NodeId, &Dependency) -> bool,
+    ) -> SearchState {
         let mut search_state = SearchState::new(tree.nodes.len());
 
-        for node_id in tree.crate_nodes() {
+        for node_id in ids {
             let Some(DependencyNode::Crate(dependency)) = tree.node(node_id) else {
                 continue;
             };
 
-            if dependency.name.contains(query) {
+            if predicate(node_id, dependency) {
                 search_state.matches[node_id.0] = true;
                 search_state.match_ids.push(node_id);
                 Self::include_ancestors(
@@ -234,6 +554,390 @@ impl TreeWidgetState {
         search_state
     }
 
+    /// Marks or unmarks the selected crate.
+    ///
+    /// At most two crates are marked at a time; marking a third one drops the
+    /// oldest mark. Group nodes cannot be marked.
+    pub fn toggle_mark(&mut self, tree: &DependencyTree) {
+        if !self.ensure_selection(tree) {
+            return;
+        }
+        self.ensure_visible_nodes(tree);
+        let Some(node_id) = self.selected_node_id() else {
+            return;
+        };
+        if tree.node(node_id).is_none_or(DependencyNode::is_group) {
+            return;
+        }
+
+        self.rows.clear();
+        if let Some(idx) = self.marked.iter().position(|&id| id == node_id) {
+            self.marked.remove(idx);
+            return;
+        }
+        if self.marked.len() == 2 {
+            self.marked.remove(0);
+        }
+        self.marked.push(node_id);
+    }
+
+    /// Returns the marked crates, oldest first.
+    pub fn marked(&self) -> &[NodeId] {
+        &self.marked
+    }
+
+    /// Returns whether a node is marked.
+    pub fn is_marked(&self, node_id: NodeId) -> bool {
+        self.marked.contains(&node_id)
+    }
+
+    /// Freezes the selected node, or unfreezes it when already frozen.
+    ///
+    /// While the view is scrolled below a frozen node, it stays pinned among
+    /// the context lines above the tree, whether or not it is an ancestor of
+    /// the rows in view.
+    pub fn toggle_frozen(&mut self, tree: &DependencyTree) {
+        if !self.ensure_selection(tree) {
+            return;
+        }
+        self.ensure_visible_nodes(tree);
+        let Some(node_id) = self.selected_node_id() else {
+            return;
+        };
+        self.frozen = (self.frozen != Some(node_id)).then_some(node_id);
+    }
+
+    /// Returns the frozen node, if any.
+    pub fn frozen(&self) -> Option<NodeId> {
+        self.frozen
+    }
+
+    /// Materializes the first occurrence of the frozen node, after its
+    /// ancestors, if it is shown above `line`.
+    pub(super) fn frozen_above(
+        &self,
+        tree: &DependencyTree,
+        line: usize,
+    ) -> Option<Vec<VisibleNode>> {
+        let vpos = find_virtual_pos(
+            tree,
+            &self.open,
+            &self.child_limits,
+            self.active_subtree_sizes(),
+            self.active_filter(),
+            tree.roots(),
+            self.frozen?,
+        )?;
+        (vpos.0 < line).then(|| {
+            self.active_cache().materialize_detached(
+                tree,
+                &self.open,
+                &self.child_limits,
+                self.active_filter(),
+                tree.roots(),
+                vpos.0..vpos.0 + 1,
+            )
+        })
+    }
+
+    /// Sets the crates to badge as pinned.
+    pub fn set_pinned(&mut self, pinned: Vec<NodeId>) {
+        self.pinned = pinned;
+        self.rows.clear();
+    }
+
+    /// Returns whether a crate is pinned.
+    pub fn is_pinned(&self, node_id: NodeId) -> bool {
+        self.pinned.contains(&node_id)
+    }
+
+    /// Sets the crates to badge as carrying a note.
+    pub fn set_annotated(&mut self, annotated: Vec<NodeId>) {
+        self.annotated = annotated;
+        self.rows.clear();
+    }
+
+    /// Returns whether a crate carries a note.
+    pub fn is_annotated(&self, node_id: NodeId) -> bool {
+        self.annotated.contains(&node_id)
+    }
+
+    /// Sets the crates to badge as changed by the last reload.
+    pub fn set_changes(&mut self, changes: FxHashMap<NodeId, CrateChange>) {
+        self.changes = changes;
+        self.rows.clear();
+    }
+
+    /// Returns how a crate changed in the last reload, if it did.
+    pub fn change(&self, node_id: NodeId) -> Option<CrateChange> {
+        self.changes.get(&node_id).copied()
+    }
+
+    /// Sets the badges added to crates by custom annotators.
+    pub fn set_badges(&mut self, badges: FxHashMap<NodeId, String>) {
+        self.badges = badges;
+        self.rows.clear();
+    }
+
+    /// Returns the badge custom annotators added to a crate, if any.
+    pub fn badge(&self, node_id: NodeId) -> Option<&str> {
+        self.badges.get(&node_id).map(String::as_str)
+    }
+
+    /// Sets the `(member, dependency, version)` edges whose requirement
+    /// allows a newer version than the resolved one.
+    pub fn set_updates_in_range(&mut self, updates: Vec<(NodeId, NodeId, String)>) {
+        self.updates_in_range = updates
+            .into_iter()
+            .map(|(member, dependency, version)| ((member, dependency), version))
+            .collect();
+        self.rows.clear();
+    }
+
+    /// Returns the newer version the requirement of `member` on
+    /// `dependency` allows, if any.
+    pub fn update_in_range(&self, member: NodeId, dependency: NodeId) -> Option<&str> {
+        self.updates_in_range
+            .get(&(member, dependency))
+            .map(String::as_str)
+    }
+
+    /// Returns whether any member could update `dependency` within its
+    /// requirement.
+    pub fn has_update_in_range(&self, dependency: NodeId) -> bool {
+        self.updates_in_range
+            .keys()
+            .any(|&(_, updatable)| updatable == dependency)
+    }
+
+    /// Expands the nodes along one path from a root down to `id` and selects it,
+    /// loading enough children on the way to show it.
+    pub fn reveal(&mut self, tree: &DependencyTree, id: NodeId) {
+        self.ensure_node_capacity(tree);
+        let mut seen = FxHashSet::default();
+        let mut current = id;
+        while let Some(&parent) = tree.parents[current.0].first() {
+            if !seen.insert(parent) {
+                break;
+            }
+            self.open[parent.0] = true;
+            if let Some(index) = tree
+                .node(parent)
+                .and_then(|node| node.children().iter().position(|&child| child == current))
+            {
+                self.child_limits.show_at_least(parent, index + 1);
+            }
+            current = parent;
+        }
+        self.subtree_dirty = true;
+        self.dirty = true;
+        self.set_selected_node_id(tree, id);
+    }
+
+    /// Reveals `owner` like [`reveal`](Self::reveal), then expands it and
+    /// selects its direct dependency `id`, looking through group nodes.
+    pub fn reveal_under(&mut self, tree: &DependencyTree, owner: NodeId, id: NodeId) {
+        self.reveal(tree, owner);
+        let Some(owner_pos) = self.selected_virtual_pos else {
+            return;
+        };
+        let Some(node) = tree.node(owner) else {
+            return;
+        };
+        self.open[owner.0] = true;
+        for (index, &child) in node.children().iter().enumerate() {
+            let group_index = match tree.node(child) {
+                Some(DependencyNode::Group(group)) => {
+                    group.children.iter().position(|&dep| dep == id)
+                }
+                _ => None,
+            };
+            if child == id || group_index.is_some() {
+                self.child_limits.show_at_least(owner, index + 1);
+            }
+            if let Some(group_index) = group_index {
+                self.open[child.0] = true;
+                self.child_limits.show_at_least(child, group_index + 1);
+            }
+        }
+        self.subtree_dirty = true;
+        self.ensure_subtree_sizes(tree);
+        if let Some(offset) = find_virtual_pos(
+            tree,
+            &self.open,
+            &self.child_limits,
+            self.active_subtree_sizes(),
+            self.active_filter(),
+            &[owner],
+            id,
+        ) {
+            self.selected_virtual_pos = Some(VirtualPos(owner_pos.0 + offset.0));
+        }
+        self.dirty = true;
+    }
+
+    /// Returns whether the active search or filters hide `id`.
+    pub fn is_filtered_out(&self, id: NodeId) -> bool {
+        self.active_filter()
+            .is_some_and(|filter| !filter.get(id.0).copied().unwrap_or(false))
+    }
+
+    /// Scrolls the selection to the middle of the view on the next frame,
+    /// rather than just into view.
+    pub fn center_selection(&mut self) {
+        self.center_pending = true;
+    }
+
+    /// Highlights every path from `from` to `to` and expands the nodes along it.
+    ///
+    /// Returns `false` (leaving the view unchanged) when `to` is not reachable
+    /// from `from`.
+    pub fn trace_path(&mut self, tree: &DependencyTree, from: NodeId, to: NodeId) -> bool {
+        let path_nodes = tree.nodes_on_paths(from, to);
+        if !path_nodes.get(to.0).copied().unwrap_or(false) {
+            return false;
+        }
+
+        self.ensure_node_capacity(tree);
+        for (idx, &on_path) in path_nodes.iter().enumerate() {
+            if on_path && idx != to.0 {
+                self.open[idx] = true;
+            }
+        }
+        self.path_nodes = path_nodes;
+        self.subtree_dirty = true;
+        self.dirty = true;
+        self.set_selected_node_id(tree, to);
+        true
+    }
+
+    /// Clears the traced path highlight.
+    pub fn clear_path(&mut self) {
+        self.path_nodes.clear();
+        self.rows.clear();
+    }
+
+    /// Returns whether a path is currently traced.
+    pub fn is_tracing_path(&self) -> bool {
+        !self.path_nodes.is_empty()
+    }
+
+    /// Returns whether a node lies on the traced path.
+    pub fn is_on_path(&self, node_id: NodeId) -> bool {
+        self.path_nodes.get(node_id.0).copied().unwrap_or(false)
+    }
+
+    /// Stops expanding nodes at least `guard` levels below a root, showing a
+    /// "(depth limit reached)" row under them instead. `None` lifts the guard.
+    pub fn set_depth_guard(&mut self, tree: &DependencyTree, guard: Option<usize>) {
+        self.child_limits.set_guard(tree, guard);
+        self.subtree_dirty = true;
+        self.dirty = true;
+    }
+
+    /// Sets the edges to flag as layering violations.
+    pub fn set_layer_violations(&mut self, violations: Vec<(NodeId, NodeId)>) {
+        self.layer_violations = violations;
+        self.rows.clear();
+    }
+
+    /// Sets the crates to flag for conflicting feature wishes.
+    pub fn set_feature_conflicts(&mut self, conflicts: FxHashSet<NodeId>) {
+        self.feature_conflicts = conflicts;
+        self.rows.clear();
+    }
+
+    /// Returns whether the dependents of `node_id` ask for conflicting
+    /// features.
+    pub fn has_feature_conflict(&self, node_id: NodeId) -> bool {
+        self.feature_conflicts.contains(&node_id)
+    }
+
+    /// Sets the crates present in more than one version.
+    pub fn set_duplicated(&mut self, duplicated: FxHashSet<NodeId>) {
+        self.duplicated = duplicated;
+        self.rows.clear();
+    }
+
+    /// Returns whether another version of `node_id` is in the graph.
+    pub fn is_duplicated(&self, node_id: NodeId) -> bool {
+        self.duplicated.contains(&node_id)
+    }
+
+    /// Sets the kinds of the edges whose group nodes were dissolved.
+    pub fn set_edge_kinds(&mut self, kinds: FxHashMap<(NodeId, NodeId), DependencyType>) {
+        self.edge_kinds = kinds;
+        self.rows.clear();
+    }
+
+    /// Returns the kinds of the edges whose group nodes were dissolved.
+    pub fn edge_kinds(&self) -> &FxHashMap<(NodeId, NodeId), DependencyType> {
+        &self.edge_kinds
+    }
+
+    /// Returns the kind of the edge from `owner` to `dependency` when its
+    /// group node was dissolved.
+    pub fn edge_kind(&self, owner: NodeId, dependency: NodeId) -> Option<DependencyType> {
+        self.edge_kinds.get(&(owner, dependency)).copied()
+    }
+
+    /// Sets the release age of each crate in days, or clears the heatmap
+    /// when empty.
+    pub fn set_release_ages(&mut self, ages: FxHashMap<NodeId, i64>) {
+        self.release_ages = ages;
+        self.rows.clear();
+    }
+
+    /// Returns how many days ago the version of `node_id` was released.
+    pub fn release_age(&self, node_id: NodeId) -> Option<i64> {
+        self.release_ages.get(&node_id).copied()
+    }
+
+    /// Sets the crates to flag for pulling `std` into `no_std` members.
+    pub fn set_std_creep(&mut self, creep: FxHashMap<NodeId, StdUse>) {
+        self.std_creep = creep;
+        self.rows.clear();
+    }
+
+    /// Returns how `node_id` pulls `std` into a `no_std` member, if flagged.
+    pub fn std_use(&self, node_id: NodeId) -> Option<StdUse> {
+        self.std_creep.get(&node_id).copied()
+    }
+
+    /// Sets the build cost share of each subtree, or clears it when empty.
+    pub fn set_cost_shares(&mut self, shares: Vec<f64>) {
+        self.cost_shares = shares;
+        self.rows.clear();
+    }
+
+    /// Returns the build cost share of the subtree below `node_id`.
+    pub fn cost_share(&self, node_id: NodeId) -> Option<f64> {
+        self.cost_shares
+            .get(node_id.0)
+            .copied()
+            .filter(|&share| share > 0.0)
+    }
+
+    /// Sets the binary size of each subtree, or clears it when empty.
+    pub fn set_binary_sizes(&mut self, sizes: Vec<u64>) {
+        self.binary_sizes = sizes;
+        self.rows.clear();
+    }
+
+    /// Returns the binary size of the subtree below `node_id` in bytes.
+    pub fn binary_size(&self, node_id: NodeId) -> Option<u64> {
+        self.binary_sizes
+            .get(node_id.0)
+            .copied()
+            .filter(|&size| size > 0)
+    }
+
+    /// Returns whether the edge from `member` to `dependency` breaks the
+    /// layering rules.
+    pub fn is_layer_violation(&self, member: NodeId, dependency: NodeId) -> bool {
+        self.layer_violations.contains(&(member, dependency))
+    }
+
     /// Moves the selection to the next visible dependency.
     pub fn select_next(&mut self, tree: &DependencyTree) {
         if !self.ensure_selection(tree) {
@@ -290,6 +994,9 @@ impl TreeWidgetState {
     }
 
     /// Expands the selected node or moves into its first child when already expanded.
+    ///
+    /// On a "… and N more" row, loads the next page of children instead, the
+    /// first of which takes the row's place.
     pub fn expand(&mut self, tree: &DependencyTree) {
         if !self.ensure_selection(tree) {
             return;
@@ -298,6 +1005,16 @@ impl TreeWidgetState {
             return;
         };
         self.ensure_visible_nodes(tree);
+        match self.selected_marker() {
+            Some((parent, RowMarker::More(_))) => {
+                self.child_limits.load_more(parent);
+                self.subtree_dirty = true;
+                self.dirty = true;
+                return;
+            }
+            Some((_, RowMarker::DepthLimit)) => return,
+            None => {}
+        }
         let Some(node_id) = self.selected_node_id() else {
             return;
         };
@@ -316,20 +1033,58 @@ impl TreeWidgetState {
             return;
         }
 
-        // Already open — move into first child (next virtual position).
+        // Already open — move into the last visited child, or the first one.
+        let offset = self
+            .last_visited_child
+            .get(&node_id)
+            .copied()
+            .and_then(|child| self.child_offset(tree, node_id, child))
+            .unwrap_or(1);
         let total = self.active_total_virtual_lines();
-        if vpos.0 + 1 < total {
-            self.selected_virtual_pos = Some(VirtualPos(vpos.0 + 1));
+        if vpos.0 + offset < total {
+            self.selected_virtual_pos = Some(VirtualPos(vpos.0 + offset));
             self.dirty = true;
         }
     }
 
+    /// Returns how many rows below an open `parent` its direct `child` is
+    /// displayed, or `None` if the child is hidden.
+    fn child_offset(
+        &mut self,
+        tree: &DependencyTree,
+        parent: NodeId,
+        child: NodeId,
+    ) -> Option<usize> {
+        self.ensure_subtree_sizes(tree);
+        let sizes = self.active_subtree_sizes();
+        let filter = self.active_filter();
+
+        let (children, _) = self
+            .child_limits
+            .children(parent, tree.node(parent)?, filter);
+        let mut offset = 1;
+        for id in children {
+            if sizes[id.0] == 0 {
+                continue;
+            }
+            if id == child {
+                return Some(offset);
+            }
+            offset += sizes[id.0];
+        }
+        None
+    }
+
     /// Collapses the selected node or moves focus to its parent when already closed.
     pub fn collapse(&mut self, tree: &DependencyTree) {
         if !self.ensure_selection(tree) {
             return;
         }
         self.ensure_visible_nodes(tree);
+        if self.selected_marker().is_some() {
+            self.select_parent(tree);
+            return;
+        }
         let Some(node_id) = self.selected_node_id() else {
             return;
         };
@@ -362,38 +1117,173 @@ impl TreeWidgetState {
             && let Some(parent_vis) = vnode.parent_vis_idx
             && let Some(parent_node) = self.active_visible_nodes().get(parent_vis.0)
         {
-            self.selected_virtual_pos = Some(parent_node.virtual_pos);
+            let (parent_id, parent_vpos) = (parent_node.id, parent_node.virtual_pos);
+            if vnode.marker.is_none() {
+                self.last_visited_child.insert(parent_id, vnode.id);
+            }
+            self.selected_virtual_pos = Some(parent_vpos);
             self.dirty = true;
         }
     }
 
-    /// Moves the selection to the next sibling, if any.
+    /// Moves the selection to the next sibling, wrapping around to the first
+    /// one when [`wrap_siblings`](Self::wrap_siblings) is set.
     pub fn select_next_sibling(&mut self, tree: &DependencyTree) {
-        self.select_sibling(tree, |n| n.next_sibling);
+        self.select_sibling(tree, |index, len, wrap| match index + 1 {
+            next if next < len => Some(next),
+            _ => wrap.then_some(0),
+        });
     }
 
-    /// Moves the selection to the previous sibling, if any.
+    /// Moves the selection to the previous sibling, wrapping around to the
+    /// last one when [`wrap_siblings`](Self::wrap_siblings) is set.
     pub fn select_previous_sibling(&mut self, tree: &DependencyTree) {
-        self.select_sibling(tree, |n| n.prev_sibling);
+        self.select_sibling(tree, |index, len, wrap| match index.checked_sub(1) {
+            Some(previous) => Some(previous),
+            None => wrap.then_some(len - 1),
+        });
+    }
+
+    /// Moves the selection to the first sibling.
+    pub fn select_first_sibling(&mut self, tree: &DependencyTree) {
+        self.select_sibling(tree, |_, _, _| Some(0));
+    }
+
+    /// Moves the selection to the last sibling.
+    pub fn select_last_sibling(&mut self, tree: &DependencyTree) {
+        self.select_sibling(tree, |_, len, _| Some(len - 1));
+    }
+
+    /// Moves the selection to the next sibling whose name starts with
+    /// `letter`, ignoring case and wrapping around.
+    pub fn select_sibling_starting_with(&mut self, tree: &DependencyTree, letter: char) {
+        if !self.ensure_selection(tree) {
+            return;
+        }
+        self.ensure_visible_nodes(tree);
+        let Some((positions, index)) = self.sibling_positions(tree) else {
+            return;
+        };
+
+        let starts_with = |id: NodeId| {
+            tree.node(id)
+                .and_then(|node| node.display_name().chars().next())
+                .is_some_and(|first| first.eq_ignore_ascii_case(&letter))
+        };
+        let len = positions.len();
+        if let Some(&(_, target)) = (1..len)
+            .map(|step| &positions[(index + step) % len])
+            .find(|&&(id, _)| starts_with(id))
+        {
+            self.selected_virtual_pos = Some(target);
+            self.dirty = true;
+        }
     }
 
+    /// Moves the selection to the sibling picked from the index of the
+    /// selection, the sibling count and whether wrapping is enabled.
     fn select_sibling(
         &mut self,
         tree: &DependencyTree,
-        pick: impl Fn(&VisibleNode) -> Option<VisIdx>,
+        pick: impl Fn(usize, usize, bool) -> Option<usize>,
     ) {
         if !self.ensure_selection(tree) {
             return;
         }
-        let Some(vpos) = self.selected_virtual_pos else {
+        self.ensure_visible_nodes(tree);
+        let Some((positions, index)) = self.sibling_positions(tree) else {
             return;
         };
 
-        if let Some((_, vnode)) = self.find_by_vpos(vpos)
-            && let Some(sibling) = pick(vnode)
-            && let Some(sibling_node) = self.active_visible_nodes().get(sibling.0)
+        if let Some(&(_, target)) = pick(index, positions.len(), self.wrap_siblings)
+            .and_then(|target| positions.get(target))
+            && Some(target) != self.selected_virtual_pos
         {
-            self.selected_virtual_pos = Some(sibling_node.virtual_pos);
+            self.selected_virtual_pos = Some(target);
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the virtual positions of the selection and its siblings, in
+    /// display order, along with the index of the selection among them.
+    ///
+    /// Positions come from subtree sizes, so siblings outside the
+    /// materialized window are included.
+    fn sibling_positions(
+        &mut self,
+        tree: &DependencyTree,
+    ) -> Option<(Vec<(NodeId, VirtualPos)>, usize)> {
+        let vpos = self.selected_virtual_pos?;
+        let (_, vnode) = self.find_by_vpos(vpos)?;
+        let (siblings, base) = match vnode.parent_vis_idx {
+            Some(parent_vis) => {
+                let parent = self.active_visible_nodes().get(parent_vis.0)?;
+                let (parent_id, parent_vpos) = (parent.id, parent.virtual_pos);
+                let (children, _) = self.child_limits.children(
+                    parent_id,
+                    tree.node(parent_id)?,
+                    self.active_filter(),
+                );
+                (children.collect(), parent_vpos.0 + 1)
+            }
+            None => (tree.roots().to_vec(), 0),
+        };
+
+        let positions = self.row_positions(tree, &siblings, base);
+        let index = positions.iter().position(|&(_, pos)| pos == vpos)?;
+        Some((positions, index))
+    }
+
+    /// Returns the virtual positions of the visible nodes among `ids`, laid
+    /// out one after another starting at `base`.
+    fn row_positions(
+        &mut self,
+        tree: &DependencyTree,
+        ids: &[NodeId],
+        base: usize,
+    ) -> Vec<(NodeId, VirtualPos)> {
+        self.ensure_subtree_sizes(tree);
+        let sizes = self.active_subtree_sizes();
+        let filter = self.active_filter();
+        let mut positions = Vec::new();
+        let mut offset = base;
+        for &id in ids {
+            if filter.is_some_and(|f| !f[id.0]) || sizes[id.0] == 0 {
+                continue;
+            }
+            positions.push((id, VirtualPos(offset)));
+            offset += sizes[id.0];
+        }
+        positions
+    }
+
+    /// Moves the selection to the next workspace member, from any depth.
+    pub fn select_next_member(&mut self, tree: &DependencyTree) {
+        if !self.ensure_selection(tree) {
+            return;
+        }
+        let Some(vpos) = self.selected_virtual_pos else {
+            return;
+        };
+        let members = self.row_positions(tree, tree.roots(), 0);
+        if let Some(&(_, next)) = members.iter().find(|&&(_, pos)| pos > vpos) {
+            self.selected_virtual_pos = Some(next);
+            self.dirty = true;
+        }
+    }
+
+    /// Moves the selection to the workspace member containing it, or to the
+    /// previous member when the selection is already on one.
+    pub fn select_previous_member(&mut self, tree: &DependencyTree) {
+        if !self.ensure_selection(tree) {
+            return;
+        }
+        let Some(vpos) = self.selected_virtual_pos else {
+            return;
+        };
+        let members = self.row_positions(tree, tree.roots(), 0);
+        if let Some(&(_, previous)) = members.iter().rev().find(|&&(_, pos)| pos < vpos) {
+            self.selected_virtual_pos = Some(previous);
             self.dirty = true;
         }
     }
@@ -401,13 +1291,27 @@ impl TreeWidgetState {
     /// Moves the selection up by approximately one page.
     pub fn page_up(&mut self, tree: &DependencyTree) {
         let step = self.viewport.height.saturating_sub(1).max(1) as isize;
-        self.move_by(tree, -step);
+        self.move_by_rows(tree, -step);
     }
 
     /// Moves the selection down by approximately one page.
     pub fn page_down(&mut self, tree: &DependencyTree) {
         let step = self.viewport.height.saturating_sub(1).max(1) as isize;
-        self.move_by(tree, step);
+        self.move_by_rows(tree, step);
+    }
+
+    /// Moves the selection by `delta` screen rows, which span fewer lines
+    /// when rows are wrapped.
+    fn move_by_rows(&mut self, tree: &DependencyTree, delta: isize) {
+        self.ensure_subtree_sizes(tree);
+        let Some(vpos) = self.selected_virtual_pos else {
+            return self.move_by(tree, delta);
+        };
+        let Some(row) = self.rows.row_of(vpos.0) else {
+            return self.move_by(tree, delta);
+        };
+        let target = self.rows.line_at(row.saturating_add_signed(delta));
+        self.move_by(tree, target as isize - vpos.0 as isize);
     }
 
     /// Moves the selection by a specified delta.
@@ -446,6 +1350,27 @@ impl TreeWidgetState {
         self.ensure_selection(tree);
     }
 
+    /// Opens the tree one level at a time, stopping before the level that
+    /// would make it longer than `rows` lines.
+    ///
+    /// Returns the depth that was opened, as passed to
+    /// [`TreeWidgetState::open_to_depth`].
+    pub fn open_to_fit(&mut self, tree: &DependencyTree, rows: usize) -> usize {
+        let mut lines = 0;
+        let mut depth = 1;
+        loop {
+            self.open_to_depth(tree, depth + 1);
+            let next_lines = self.total_lines(tree);
+            if next_lines > rows || next_lines == lines {
+                break;
+            }
+            lines = next_lines;
+            depth += 1;
+        }
+        self.open_to_depth(tree, depth);
+        depth
+    }
+
     fn open_node(
         &mut self,
         tree: &DependencyTree,
@@ -487,12 +1412,18 @@ impl TreeWidgetState {
         }
 
         self.ensure_node_capacity(tree);
+        self.rows.clear();
 
-        self.normal.refresh_sizes(tree, &self.open, None);
+        self.normal
+            .refresh_sizes(tree, &self.open, &self.child_limits, None);
 
         if self.is_searching() {
-            self.search
-                .refresh_sizes(tree, &self.open, Some(&self.search_visible_nodes));
+            self.search.refresh_sizes(
+                tree,
+                &self.open,
+                &self.child_limits,
+                Some(&self.search_visible_nodes),
+            );
         }
 
         self.subtree_dirty = false;
@@ -515,7 +1446,7 @@ impl TreeWidgetState {
     }
 
     /// Returns the total virtual line count for the active view.
-    fn active_total_virtual_lines(&self) -> usize {
+    pub(super) fn active_total_virtual_lines(&self) -> usize {
         self.active_cache().total_virtual_lines
     }
 
@@ -542,6 +1473,22 @@ impl TreeWidgetState {
         }
     }
 
+    /// Compresses the active view into at most `rows` runs of lines for the
+    /// minimap, flagging runs holding search matches.
+    pub(super) fn summarize_lines(&self, tree: &DependencyTree, rows: usize) -> Vec<RowSummary> {
+        let matches = self
+            .is_searching()
+            .then_some(self.search_matches.as_slice());
+        self.active_cache().summarize(
+            tree,
+            &self.open,
+            &self.child_limits,
+            self.active_filter(),
+            matches,
+            rows,
+        )
+    }
+
     /// Returns the active filter, if searching.
     fn active_filter(&self) -> Option<&[bool]> {
         if self.is_searching() {
@@ -579,6 +1526,7 @@ impl TreeWidgetState {
         cache.rematerialize(
             tree,
             &self.open,
+            &self.child_limits,
             filter,
             tree.roots(),
             window_start..window_start + window_count,
@@ -592,8 +1540,12 @@ impl TreeWidgetState {
             return;
         }
 
-        self.search
-            .refresh_sizes(tree, &self.open, Some(&self.search_visible_nodes));
+        self.search.refresh_sizes(
+            tree,
+            &self.open,
+            &self.child_limits,
+            Some(&self.search_visible_nodes),
+        );
 
         // Clamp selection to search view bounds.
         if let Some(vpos) = self.selected_virtual_pos
@@ -718,61 +1670,217 @@ impl TreeWidgetState {
         self.dirty = true;
         self.ensure_selection(tree);
     }
+
+    /// Collapses every node, leaving only the roots visible.
+    pub fn collapse_all(&mut self, tree: &DependencyTree) {
+        self.ensure_node_capacity(tree);
+        self.open.fill(false);
+        self.subtree_dirty = true;
+        self.dirty = true;
+        self.ensure_selection(tree);
+    }
+
+    /// Expands the workspace members and their dependency groups, showing
+    /// direct dependencies only.
+    pub fn expand_members(&mut self, tree: &DependencyTree) {
+        self.ensure_node_capacity(tree);
+        self.open.fill(false);
+        for root in tree.members() {
+            let Some(node) = tree.node(root) else {
+                continue;
+            };
+            let mut folders = tree.parents[root.0].clone();
+            while let Some(folder) = folders.pop() {
+                if tree.node(folder).is_some_and(|n| n.as_folder().is_some()) {
+                    self.open[folder.0] = true;
+                    folders.extend_from_slice(&tree.parents[folder.0]);
+                }
+            }
+            self.open[root.0] = !node.children().is_empty();
+            for &child in node.children() {
+                if tree.node(child).is_some_and(|child| child.is_group()) {
+                    self.open[child.0] = true;
+                }
+            }
+        }
+        self.subtree_dirty = true;
+        self.dirty = true;
+        self.ensure_selection(tree);
+    }
+
+    /// Expands, or collapses when `open` is false, every node with a crate
+    /// whose name contains `query` somewhere below it.
+    ///
+    /// Returns how many crates matched.
+    pub fn set_open_above(&mut self, tree: &DependencyTree, query: &str, open: bool) -> usize {
+        self.ensure_node_capacity(tree);
+        let matches: Vec<NodeId> = tree
+            .crate_nodes()
+            .filter(|&id| {
+                tree.node(id)
+                    .and_then(DependencyNode::as_dependency)
+                    .is_some_and(|dependency| dependency.name.contains(query))
+            })
+            .collect();
+        let mut seen = FxHashSet::default();
+        let mut stack: Vec<NodeId> = matches
+            .iter()
+            .flat_map(|&id| tree.parents[id.0].iter().copied())
+            .collect();
+        while let Some(id) = stack.pop() {
+            if seen.insert(id) {
+                self.open[id.0] = open;
+                stack.extend_from_slice(&tree.parents[id.0]);
+            }
+        }
+        self.subtree_dirty = true;
+        self.dirty = true;
+        self.ensure_selection(tree);
+        matches.len()
+    }
+
+    /// Captures expansion, selection and marks by [`StableKey`], to carry
+    /// them over to a rebuilt tree with [`Self::restore`].
+    pub fn snapshot(&mut self, tree: &DependencyTree) -> ViewSnapshot {
+        self.ensure_visible_nodes(tree);
+        let keys = tree.stable_keys();
+        ViewSnapshot {
+            open: keys
+                .iter()
+                .copied()
+                .zip(self.open.iter().copied())
+                .collect(),
+            selected: self.selected_node_id().map(|id| keys[id.0]),
+            marked: self.marked.iter().map(|id| keys[id.0]).collect(),
+            frozen: self.frozen.map(|id| keys[id.0]),
+        }
+    }
+
+    /// Applies a [`ViewSnapshot`] taken on an earlier build of `tree`.
+    ///
+    /// Nodes new to `tree` keep their current expansion, and the selection
+    /// stays put when the selected node is gone.
+    pub fn restore(&mut self, tree: &DependencyTree, snapshot: &ViewSnapshot) {
+        self.ensure_node_capacity(tree);
+        let keys = tree.stable_keys();
+        for (idx, key) in keys.iter().enumerate() {
+            if let Some(&open) = snapshot.open.get(key) {
+                self.open[idx] = open;
+            }
+        }
+        let ids: FxHashMap<StableKey, NodeId> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(idx, key)| (key, NodeId(idx)))
+            .collect();
+        self.marked = snapshot
+            .marked
+            .iter()
+            .filter_map(|key| ids.get(key).copied())
+            .collect();
+        self.frozen = snapshot.frozen.and_then(|key| ids.get(&key).copied());
+        self.subtree_dirty = true;
+        self.dirty = true;
+        if let Some(&id) = snapshot.selected.and_then(|key| ids.get(&key)) {
+            self.set_selected_node_id(tree, id);
+        }
+        self.ensure_selection(tree);
+    }
 }
 
 /// Finds the virtual position of the first occurrence of a `NodeId` in the virtual tree.
+///
+/// Subtrees that cannot contain `target` are skipped in O(1) using `sizes`,
+/// so the walk is bounded by the target's ancestry rather than by the number
+/// of rows before it.
 fn find_virtual_pos(
     tree: &DependencyTree,
     open: &[bool],
+    limits: &ChildLimits,
     sizes: &[usize],
     filter: Option<&[bool]>,
     roots: &[NodeId],
     target: NodeId,
 ) -> Option<VirtualPos> {
-    let mut vpos = 0usize;
+    let mut walk = FindVpos {
+        tree,
+        open,
+        limits,
+        sizes,
+        filter,
+        target,
+        ancestors: ancestors_of(tree, target),
+        in_progress: FxHashSet::default(),
+        vpos: 0,
+    };
     for &root in roots {
         if filter.is_some_and(|f| !f[root.0]) {
             continue;
         }
-        if let Some(found) = find_vpos_recursive(tree, open, sizes, filter, root, target, &mut vpos)
-        {
+        if let Some(found) = walk.visit(root) {
             return Some(VirtualPos(found));
         }
     }
     None
 }
 
-fn find_vpos_recursive(
-    tree: &DependencyTree,
-    open: &[bool],
-    sizes: &[usize],
-    filter: Option<&[bool]>,
-    id: NodeId,
-    target: NodeId,
-    vpos: &mut usize,
-) -> Option<usize> {
-    if id == target {
-        return Some(*vpos);
+/// Returns every node from which `target` is reachable, `target` excluded.
+fn ancestors_of(tree: &DependencyTree, target: NodeId) -> FxHashSet<NodeId> {
+    let mut ancestors = FxHashSet::default();
+    let mut stack = tree.parents[target.0].clone();
+    while let Some(id) = stack.pop() {
+        if ancestors.insert(id) {
+            stack.extend_from_slice(&tree.parents[id.0]);
+        }
     }
+    ancestors
+}
 
-    *vpos += 1;
+/// Working state of [`find_virtual_pos`].
+struct FindVpos<'a> {
+    tree: &'a DependencyTree,
+    open: &'a [bool],
+    limits: &'a ChildLimits,
+    sizes: &'a [usize],
+    filter: Option<&'a [bool]>,
+    target: NodeId,
+    ancestors: FxHashSet<NodeId>,
+    /// Cycle guard matching the size pass: nodes on the current path are
+    /// leaves.
+    in_progress: FxHashSet<NodeId>,
+    vpos: usize,
+}
 
-    if open[id.0]
-        && let Some(node) = tree.node(id)
-    {
-        for &child in node.children() {
-            if filter.is_some_and(|f| !f[child.0]) {
-                continue;
-            }
-            if child != target && sizes[child.0] == 0 {
-                continue;
-            }
-            if let Some(found) = find_vpos_recursive(tree, open, sizes, filter, child, target, vpos)
-            {
-                return Some(found);
+impl FindVpos<'_> {
+    fn visit(&mut self, id: NodeId) -> Option<usize> {
+        if id == self.target {
+            return Some(self.vpos);
+        }
+        let on_path = self.in_progress.contains(&id);
+        if on_path || !self.ancestors.contains(&id) {
+            self.vpos += if on_path { 1 } else { self.sizes[id.0] };
+            return None;
+        }
+
+        self.vpos += 1;
+        if self.open[id.0]
+            && let Some(node) = self.tree.node(id)
+        {
+            self.in_progress.insert(id);
+            let (children, marker) = self.limits.children(id, node, self.filter);
+            for child in children {
+                if child != self.target && self.sizes[child.0] == 0 {
+                    continue;
+                }
+                if let Some(found) = self.visit(child) {
+                    return Some(found);
+                }
             }
+            // Skip the marker row.
+            self.vpos += usize::from(marker.is_some());
+            self.in_progress.remove(&id);
         }
-    }
 
-    None
+        None
+    }
 }
@@ -1,16 +1,52 @@
-use clap_cargo::style::{DEP_BUILD, DEP_FEATURE, NOP, PLACEHOLDER, WARN};
-use ratatui::style::{Modifier, Style};
+use std::{fmt, str::FromStr};
+
+use clap_cargo::style::{
+    DEP_BUILD, DEP_DEV, DEP_FEATURE, DEP_NORMAL, ERROR, GOOD, LITERAL, NOP, PLACEHOLDER, WARN,
+};
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::core::dependency::DependencyType;
+
+use super::breadcrumb::BreadcrumbElision;
 
 /// Visual configuration for [`TreeWidget`](super::TreeWidget).
-#[derive(Debug)]
+#[derive(Debug, Hash)]
 pub struct TreeWidgetStyle {
     pub highlight_style: Style,
     pub filtered_style: Style,
+    pub marked_style: Style,
+    /// Style of the frozen node among the context lines.
+    pub frozen_style: Style,
+    pub path_style: Style,
+    pub dimmed_style: Style,
     pub style: Style,
     pub context_style: Style,
     pub name_style: Style,
+    /// Style of workspace member names, which head their subtrees.
+    pub member_style: Style,
     pub version_style: Style,
     pub suffix_style: Style,
+    /// Style of the marker on edges that break the layering rules.
+    pub violation_style: Style,
+    /// Styles of the badges on crates added, removed or changed by a reload.
+    pub added_style: Style,
+    pub removed_style: Style,
+    pub changed_style: Style,
+    /// Style of the newer version a declared requirement allows.
+    pub update_style: Style,
+    /// Style of requirements flagged by the lint layer, and their marker.
+    pub lint_style: Style,
+    /// Style of the badges added by custom annotators.
+    pub badge_style: Style,
+    /// Styles of versions released within a year, within three years, and
+    /// earlier, in the release age heatmap.
+    pub age_styles: [Style; 3],
+    /// Style of the scrollbar thumb.
+    pub scrollbar_thumb_style: Style,
+    /// Style of the scrollbar track.
+    pub scrollbar_track_style: Style,
+    /// Style of the minimap rows covering the viewport.
+    pub minimap_viewport_style: Style,
     pub node_symbol: char,
     pub node_closed_symbol: char,
     pub node_open_symbol: char,
@@ -18,6 +54,23 @@ pub struct TreeWidgetStyle {
     pub last_branch_symbol: &'static str,
     pub continuation_symbol: &'static str,
     pub empty_symbol: &'static str,
+    /// Icons rendered in the gutter column, or `None` to disable the gutter.
+    pub icons: Option<NodeIcons>,
+    /// Whether to append the number of dependent crates (`used by N`).
+    pub show_dependents: bool,
+    /// Append the number of search matches hidden below collapsed nodes
+    /// (`•N`).
+    pub show_match_counts: bool,
+    /// Render crates by name only, indented two spaces per level, to fit
+    /// more of the structure on screen.
+    pub compact: bool,
+    /// Hide versions, except of crates present in more than one version.
+    pub ambiguous_versions_only: bool,
+    /// Render rows as plain sentences for screen readers, without tree
+    /// guides, keeping the selected row first.
+    pub accessible: bool,
+    /// Ancestors kept when the breadcrumb is too wide for the footer.
+    pub breadcrumb_elision: BreadcrumbElision,
 }
 
 impl Default for TreeWidgetStyle {
@@ -28,11 +81,29 @@ impl Default for TreeWidgetStyle {
                 let style: Style = DEP_FEATURE.into();
                 style.remove_modifier(Modifier::DIM)
             },
+            marked_style: Style::from(LITERAL).add_modifier(Modifier::UNDERLINED),
+            frozen_style: Style::from(LITERAL)
+                .add_modifier(Modifier::BOLD)
+                .remove_modifier(Modifier::DIM),
+            path_style: Style::from(GOOD),
+            dimmed_style: Modifier::DIM.into(),
             style: NOP.into(),
             context_style: Modifier::DIM.into(),
             name_style: NOP.into(),
+            member_style: Modifier::BOLD.into(),
             version_style: PLACEHOLDER.into(),
             suffix_style: DEP_BUILD.into(),
+            violation_style: Style::from(ERROR).add_modifier(Modifier::BOLD),
+            added_style: Style::from(GOOD).add_modifier(Modifier::BOLD),
+            removed_style: Style::from(ERROR).add_modifier(Modifier::BOLD),
+            changed_style: Style::from(WARN).add_modifier(Modifier::BOLD),
+            update_style: WARN.into(),
+            lint_style: Style::from(WARN).add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            badge_style: Style::from(LITERAL).add_modifier(Modifier::REVERSED),
+            age_styles: [GOOD.into(), WARN.into(), ERROR.into()],
+            scrollbar_thumb_style: Style::default(),
+            scrollbar_track_style: Modifier::DIM.into(),
+            minimap_viewport_style: Modifier::REVERSED.into(),
             node_symbol: '•',
             node_closed_symbol: '▸',
             node_open_symbol: '▾',
@@ -40,6 +111,231 @@ impl Default for TreeWidgetStyle {
             last_branch_symbol: "└──",
             continuation_symbol: "│  ",
             empty_symbol: "   ",
+            icons: None,
+            show_dependents: false,
+            show_match_counts: false,
+            compact: false,
+            ambiguous_versions_only: false,
+            accessible: false,
+            breadcrumb_elision: BreadcrumbElision::default(),
+        }
+    }
+}
+
+impl TreeWidgetStyle {
+    /// Returns the heatmap style of a version released `days` ago.
+    pub fn age_style(&self, days: i64) -> Style {
+        match days {
+            ..365 => self.age_styles[0],
+            365..1095 => self.age_styles[1],
+            _ => self.age_styles[2],
+        }
+    }
+}
+
+/// Built-in color themes for [`TreeWidgetStyle`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Cargo's own color palette.
+    #[default]
+    Default,
+    /// Bold, saturated colors on reversed backgrounds for low-vision users.
+    HighContrast,
+    /// Blue/orange palette safe for deuteranopia and protanopia, with
+    /// states told apart by modifiers rather than hue alone.
+    Colorblind,
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "high-contrast" => Ok(Self::HighContrast),
+            "colorblind" | "deuteranopia" | "protanopia" => Ok(Self::Colorblind),
+            _ => Err(format!(
+                "expected `default`, `high-contrast` or `colorblind`, got `{s}`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::HighContrast => "high-contrast",
+            Self::Colorblind => "colorblind",
+        })
+    }
+}
+
+/// Width of each indentation level of the tree guides, in cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndentWidth {
+    Two,
+    #[default]
+    Three,
+    Four,
+}
+
+impl FromStr for IndentWidth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2" => Ok(Self::Two),
+            "3" => Ok(Self::Three),
+            "4" => Ok(Self::Four),
+            _ => Err(format!("expected `2`, `3` or `4`, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for IndentWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Two => "2",
+            Self::Three => "3",
+            Self::Four => "4",
+        })
+    }
+}
+
+impl TreeWidgetStyle {
+    /// Returns the style with the tree guides drawn `width` cells wide per
+    /// level.
+    pub fn indented(self, width: IndentWidth) -> Self {
+        let (branch_symbol, last_branch_symbol, continuation_symbol, empty_symbol) = match width {
+            IndentWidth::Two => ("├─", "└─", "│ ", "  "),
+            IndentWidth::Three => ("├──", "└──", "│  ", "   "),
+            IndentWidth::Four => ("├───", "└───", "│   ", "    "),
+        };
+        Self {
+            branch_symbol,
+            last_branch_symbol,
+            continuation_symbol,
+            empty_symbol,
+            ..self
+        }
+    }
+
+    /// Returns the style for a built-in theme.
+    pub fn themed(theme: Theme) -> Self {
+        let default = Self::default();
+        match theme {
+            Theme::Default => default,
+            Theme::HighContrast => Self {
+                highlight_style: Style::new()
+                    .fg(Color::Black)
+                    .bg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD),
+                filtered_style: Style::new()
+                    .fg(Color::LightMagenta)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                marked_style: Style::new()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                path_style: Style::new()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                style: Style::new().fg(Color::White),
+                context_style: Style::new().fg(Color::Gray),
+                name_style: Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
+                member_style: Style::new()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                version_style: Style::new().fg(Color::LightCyan),
+                suffix_style: Style::new().fg(Color::LightYellow),
+                violation_style: Style::new()
+                    .fg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                age_styles: [
+                    Style::new()
+                        .fg(Color::LightGreen)
+                        .add_modifier(Modifier::BOLD),
+                    Style::new()
+                        .fg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                    Style::new()
+                        .fg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD),
+                ],
+                scrollbar_thumb_style: Style::new().fg(Color::LightYellow),
+                scrollbar_track_style: Style::new().fg(Color::Gray),
+                node_closed_symbol: '▶',
+                node_open_symbol: '▼',
+                node_symbol: '●',
+                ..default
+            },
+            Theme::Colorblind => Self {
+                highlight_style: Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                filtered_style: Style::new()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::UNDERLINED),
+                marked_style: Style::new()
+                    .fg(Color::Indexed(208))
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                path_style: Style::new()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::BOLD),
+                version_style: Style::new().fg(Color::Indexed(208)),
+                suffix_style: Style::new().fg(Color::Cyan),
+                added_style: Style::new()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::BOLD),
+                removed_style: Style::new()
+                    .fg(Color::Indexed(208))
+                    .add_modifier(Modifier::BOLD),
+                violation_style: Style::new()
+                    .fg(Color::Indexed(208))
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                age_styles: [
+                    Style::new().fg(Color::LightBlue),
+                    Style::new().fg(Color::Indexed(208)),
+                    Style::new()
+                        .fg(Color::Indexed(208))
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                ],
+                scrollbar_thumb_style: Style::new().fg(Color::LightBlue),
+                ..default
+            },
+        }
+    }
+}
+
+/// Returns the style of a `kind` dependency group, colored like Cargo's own
+/// output.
+pub fn dependency_type_style(kind: DependencyType) -> Style {
+    match kind {
+        DependencyType::Normal => DEP_NORMAL.into(),
+        DependencyType::Dev => DEP_DEV.into(),
+        DependencyType::Build => DEP_BUILD.into(),
+    }
+}
+
+/// Per-node glyphs rendered in the gutter column.
+#[derive(Debug, Clone, Copy, Hash)]
+pub struct NodeIcons {
+    pub crate_icon: &'static str,
+    pub workspace_member: &'static str,
+    pub proc_macro: &'static str,
+    pub git: &'static str,
+    pub group: &'static str,
+    pub target: &'static str,
+}
+
+impl NodeIcons {
+    /// Icon set using [Nerd Font](https://www.nerdfonts.com) glyphs.
+    pub fn nerd_font() -> Self {
+        Self {
+            crate_icon: "\u{f487}",
+            workspace_member: "\u{f015}",
+            proc_macro: "\u{f0ad}",
+            git: "\u{e702}",
+            group: "\u{f07b}",
+            target: "\u{f013}",
         }
     }
 }
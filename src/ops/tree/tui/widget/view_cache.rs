@@ -1,8 +1,87 @@
 use std::ops::Range;
 
-use crate::core::{DependencyTree, NodeId};
+use rustc_hash::FxHashMap;
 
-use super::state::{VirtualPos, VisIdx, VisibleNode};
+use crate::core::{DependencyNode, DependencyTree, NodeId};
+
+use super::state::{RowMarker, VirtualPos, VisIdx, VisibleNode};
+
+/// Children an open node shows at first, and how many more each "… and N
+/// more" row loads.
+pub const CHILD_PAGE: usize = 100;
+
+/// How many children each node shows before a marker row.
+///
+/// Expanding a crate with hundreds of dependencies then only lays out the
+/// first [`CHILD_PAGE`] of them, and the rest are loaded a page at a time.
+/// Nodes at the depth guard show none of their children.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ChildLimits {
+    /// Pages loaded beyond the first, per node.
+    extra_pages: FxHashMap<NodeId, usize>,
+    /// Nodes at or past the depth guard, indexed by node id. Empty without a
+    /// guard.
+    guarded: Vec<bool>,
+}
+
+impl ChildLimits {
+    /// Returns how many children `id` shows.
+    pub(super) fn limit(&self, id: NodeId) -> usize {
+        if self.is_guarded(id) {
+            return 0;
+        }
+        let extra = self.extra_pages.get(&id).copied().unwrap_or_default();
+        CHILD_PAGE * (1 + extra)
+    }
+
+    /// Stops expanding nodes whose shortest path from a root is at least
+    /// `guard` long, or lifts the guard for `None`.
+    ///
+    /// Measuring the shortest path keeps each node's rows the same wherever
+    /// it appears in the graph.
+    pub(super) fn set_guard(&mut self, tree: &DependencyTree, guard: Option<usize>) {
+        self.guarded = match guard {
+            Some(guard) => tree.depths().into_iter().map(|d| d >= guard).collect(),
+            None => Vec::new(),
+        };
+    }
+
+    fn is_guarded(&self, id: NodeId) -> bool {
+        self.guarded.get(id.0).copied().unwrap_or(false)
+    }
+
+    /// Shows another page of the children of `id`.
+    pub(super) fn load_more(&mut self, id: NodeId) {
+        *self.extra_pages.entry(id).or_default() += 1;
+    }
+
+    /// Grows the limit of `id` to show at least `count` children.
+    pub(super) fn show_at_least(&mut self, id: NodeId, count: usize) {
+        let extra = count.div_ceil(CHILD_PAGE).saturating_sub(1);
+        let pages = self.extra_pages.entry(id).or_default();
+        *pages = (*pages).max(extra);
+    }
+
+    /// Returns the children of `node` passing `filter` that fit in its
+    /// limit, along with the marker row standing for those left out.
+    pub(super) fn children<'t>(
+        &self,
+        id: NodeId,
+        node: &'t DependencyNode,
+        filter: Option<&'t [bool]>,
+    ) -> (impl Iterator<Item = NodeId> + 't, Option<RowMarker>) {
+        let passes = move |child: &NodeId| filter.is_none_or(|f| f[child.0]);
+        let limit = self.limit(id);
+        let count = node.children().iter().filter(|child| passes(child)).count();
+        let shown = node.children().iter().copied().filter(passes).take(limit);
+        let marker = match count.checked_sub(limit) {
+            Some(0) | None => None,
+            Some(_) if self.is_guarded(id) => Some(RowMarker::DepthLimit),
+            Some(more) => Some(RowMarker::More(more)),
+        };
+        (shown, marker)
+    }
+}
 
 /// Cached render state for a single view of the dependency tree.
 ///
@@ -99,10 +178,11 @@ impl ViewCache {
         &mut self,
         tree: &DependencyTree,
         open: &[bool],
+        limits: &ChildLimits,
         filter: Option<&[bool]>,
     ) {
         self.total_virtual_lines =
-            compute_subtree_sizes(tree, open, filter, &mut self.subtree_sizes);
+            compute_subtree_sizes(tree, open, limits, filter, &mut self.subtree_sizes);
     }
 
     /// Refills the materialized window using the cache's existing `subtree_sizes`.
@@ -114,11 +194,150 @@ impl ViewCache {
         &mut self,
         tree: &DependencyTree,
         open: &[bool],
+        limits: &ChildLimits,
         filter: Option<&[bool]>,
         roots: &[NodeId],
         window: Range<usize>,
     ) {
-        self.nodes = materialize_window(tree, open, &self.subtree_sizes, filter, roots, window);
+        self.nodes = materialize_window(
+            tree,
+            open,
+            limits,
+            &self.subtree_sizes,
+            filter,
+            roots,
+            window,
+        );
+    }
+
+    /// Materializes `window` without replacing the cached window, e.g. to
+    /// draw a node that is out of view.
+    pub(super) fn materialize_detached(
+        &self,
+        tree: &DependencyTree,
+        open: &[bool],
+        limits: &ChildLimits,
+        filter: Option<&[bool]>,
+        roots: &[NodeId],
+        window: Range<usize>,
+    ) -> Vec<VisibleNode> {
+        materialize_window(
+            tree,
+            open,
+            limits,
+            &self.subtree_sizes,
+            filter,
+            roots,
+            window,
+        )
+    }
+
+    /// Compresses the full virtual stream into at most `rows` runs of
+    /// consecutive lines, for the minimap.
+    ///
+    /// Like materialization, this relies on `subtree_sizes` to skip every
+    /// subtree that falls within a single run, unless `matches` is given and
+    /// the walk has to look for matches inside it.
+    pub(super) fn summarize(
+        &self,
+        tree: &DependencyTree,
+        open: &[bool],
+        limits: &ChildLimits,
+        filter: Option<&[bool]>,
+        matches: Option<&[bool]>,
+        rows: usize,
+    ) -> Vec<RowSummary> {
+        let rows = rows.min(self.total_virtual_lines);
+        if rows == 0 || self.subtree_sizes.len() != tree.nodes.len() {
+            return Vec::new();
+        }
+        let mut ctx = SummarizeCtx {
+            tree,
+            open,
+            limits,
+            sizes: &self.subtree_sizes,
+            filter,
+            matches,
+            total: self.total_virtual_lines,
+            virtual_pos: 0,
+            in_progress: vec![false; tree.nodes.len()],
+            rows: vec![
+                RowSummary {
+                    depth: usize::MAX,
+                    has_match: false,
+                };
+                rows
+            ],
+        };
+        for &root in tree.roots() {
+            ctx.summarize_node(root, 0);
+        }
+        ctx.rows
+    }
+}
+
+/// Run of consecutive lines of the virtual stream, as drawn by the minimap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowSummary {
+    /// Depth of the shallowest node starting in the run.
+    pub depth: usize,
+    /// Whether a search match starts in the run.
+    pub has_match: bool,
+}
+
+/// Mutable working state for one [`ViewCache::summarize`] call.
+struct SummarizeCtx<'a> {
+    tree: &'a DependencyTree,
+    open: &'a [bool],
+    limits: &'a ChildLimits,
+    sizes: &'a [usize],
+    filter: Option<&'a [bool]>,
+    matches: Option<&'a [bool]>,
+    total: usize,
+    virtual_pos: usize,
+    /// Cycle guard, mirroring [`MaterializeCtx::in_progress`].
+    in_progress: Vec<bool>,
+    rows: Vec<RowSummary>,
+}
+
+impl SummarizeCtx<'_> {
+    /// Returns the run a line of the virtual stream falls in.
+    fn row_of(&self, line: usize) -> usize {
+        (line * self.rows.len() / self.total).min(self.rows.len() - 1)
+    }
+
+    fn summarize_node(&mut self, id: NodeId, depth: usize) {
+        if self.filter.is_some_and(|f| !f[id.0]) {
+            return;
+        }
+
+        let first = self.row_of(self.virtual_pos);
+        let row = &mut self.rows[first];
+        row.depth = row.depth.min(depth);
+        row.has_match |= self.matches.is_some_and(|m| m[id.0]);
+
+        let is_leaf = !self.open[id.0] || self.in_progress[id.0];
+        let size = if is_leaf { 1 } else { self.sizes[id.0] };
+        let last = self.row_of(self.virtual_pos + size - 1);
+        if is_leaf || (first == last && self.matches.is_none()) {
+            self.virtual_pos += size;
+            return;
+        }
+
+        self.virtual_pos += 1;
+        self.in_progress[id.0] = true;
+        if let Some(node) = self.tree.node(id) {
+            let (children, marker) = self.limits.children(id, node, self.filter);
+            for child in children {
+                self.summarize_node(child, depth + 1);
+            }
+            if marker.is_some() {
+                let row = self.row_of(self.virtual_pos);
+                self.rows[row].depth = self.rows[row].depth.min(depth + 1);
+                self.virtual_pos += 1;
+            }
+        }
+        self.in_progress[id.0] = false;
     }
 }
 
@@ -169,6 +388,8 @@ struct MaterializeCtx<'a> {
     tree: &'a DependencyTree,
     /// Per-`NodeId` expansion state. Closed nodes don't recurse into children and fill one row.
     open: &'a [bool],
+    /// Children shown per node before a "… and N more" row.
+    limits: &'a ChildLimits,
     /// Memoized subtree sizes from [`compute_subtree_sizes`]. Lets the DFS
     /// skip entire subtrees that fall before the window in O(1).
     sizes: &'a [usize],
@@ -246,7 +467,13 @@ impl MaterializeCtx<'_> {
             } else {
                 None
             };
-            let last_non_group_child_id = self.last_non_group_child_of(node);
+            let (children, marker) = self.limits.children(id, node, self.filter);
+            // With a marker row, that row ends the children instead.
+            let last_non_group_child_id = if marker.is_some() {
+                None
+            } else {
+                self.last_non_group_child_of(node)
+            };
             self.ancestor_stack.push(Ancestor {
                 id,
                 depth,
@@ -257,18 +484,46 @@ impl MaterializeCtx<'_> {
             });
             self.in_progress[id.0] = true;
 
-            for &child in node.children() {
+            for child in children {
                 if self.virtual_pos >= self.window.end {
                     break;
                 }
                 self.materialize_node(child, depth + 1, Some(my_ancestor_idx));
             }
+            if let Some(marker) = marker {
+                self.materialize_marker(marker, depth + 1, my_ancestor_idx);
+            }
 
             self.in_progress[id.0] = false;
             self.ancestor_stack.pop();
         }
     }
 
+    /// Emits the marker row closing the children of the ancestor at
+    /// `parent_ancestor_idx`, if it falls in the window.
+    ///
+    /// The row carries the parent's id.
+    fn materialize_marker(&mut self, marker: RowMarker, depth: usize, parent_ancestor_idx: usize) {
+        let current_vpos = self.virtual_pos;
+        if current_vpos >= self.window.end {
+            return;
+        }
+        self.virtual_pos += 1;
+        if current_vpos < self.window.start {
+            return;
+        }
+        if self.output.is_empty() {
+            self.emit_ancestor_prefix();
+        }
+        let parent = self.ancestor_stack[parent_ancestor_idx].id;
+        self.emit_node(parent, depth, current_vpos, Some(parent_ancestor_idx));
+        if let Some(row) = self.output.last_mut() {
+            row.marker = Some(marker);
+            row.is_last_non_group_child = true;
+            row.is_cycle = false;
+        }
+    }
+
     /// Pushes a node into `output` and wires up parent / sibling metadata.
     fn emit_node(
         &mut self,
@@ -307,6 +562,8 @@ impl MaterializeCtx<'_> {
             next_sibling: None,
             prev_sibling,
             is_last_non_group_child,
+            is_cycle: self.in_progress[id.0],
+            marker: None,
         });
     }
 
@@ -349,6 +606,8 @@ impl MaterializeCtx<'_> {
                 next_sibling: None,
                 prev_sibling: None,
                 is_last_non_group_child,
+                is_cycle: false,
+                marker: None,
             });
             self.ancestor_stack[i].output_idx = Some(my_output_idx);
             self.ancestor_stack[i].last_child_output_idx = None;
@@ -386,6 +645,7 @@ impl MaterializeCtx<'_> {
 fn materialize_window(
     tree: &DependencyTree,
     open: &[bool],
+    limits: &ChildLimits,
     sizes: &[usize],
     filter: Option<&[bool]>,
     roots: &[NodeId],
@@ -395,6 +655,7 @@ fn materialize_window(
     let mut ctx = MaterializeCtx {
         tree,
         open,
+        limits,
         sizes,
         filter,
         virtual_pos: 0,
@@ -418,78 +679,83 @@ fn materialize_window(
 fn compute_subtree_sizes(
     tree: &DependencyTree,
     open: &[bool],
+    limits: &ChildLimits,
     filter: Option<&[bool]>,
     sizes: &mut Vec<usize>,
 ) -> usize {
     sizes.clear();
     sizes.resize(tree.nodes.len(), 0);
-    // prevents recomputing already-visited nodes
-    let mut computed = vec![false; tree.nodes.len()];
-    // avoid infinite graphs by breaking hypothetical cycles;
-    // in-progress nodes are treated as leaves to avoid infinite recursion
-    let mut in_progress = vec![false; tree.nodes.len()];
+    let mut ctx = SizeCtx {
+        tree,
+        open,
+        limits,
+        filter,
+        sizes,
+        computed: vec![false; tree.nodes.len()],
+        in_progress: vec![false; tree.nodes.len()],
+    };
 
     let mut total = 0usize;
     for &root in tree.roots() {
         if filter.is_some_and(|f| !f[root.0]) {
             continue;
         }
-        total += compute_size_recursive(
-            tree,
-            open,
-            filter,
-            root,
-            sizes,
-            &mut computed,
-            &mut in_progress,
-        );
+        total += ctx.size_of(root);
     }
     total
 }
 
-fn compute_size_recursive(
-    tree: &DependencyTree,
-    open: &[bool],
-    filter: Option<&[bool]>,
-    id: NodeId,
-    sizes: &mut [usize],
-    computed: &mut [bool],
-    in_progress: &mut [bool],
-) -> usize {
-    if in_progress[id.0] {
-        return 1; // cycle break
-    }
-    if computed[id.0] {
-        // Shared subtree: reuse the size already computed from another parent.
-        return sizes[id.0];
-    }
+/// Working state of [`compute_subtree_sizes`].
+struct SizeCtx<'a> {
+    tree: &'a DependencyTree,
+    open: &'a [bool],
+    limits: &'a ChildLimits,
+    filter: Option<&'a [bool]>,
+    sizes: &'a mut [usize],
+    /// Prevents recomputing already-visited nodes.
+    computed: Vec<bool>,
+    /// Avoids infinite graphs by breaking hypothetical cycles; in-progress
+    /// nodes are treated as leaves to avoid infinite recursion.
+    in_progress: Vec<bool>,
+}
 
-    in_progress[id.0] = true;
+impl SizeCtx<'_> {
+    fn size_of(&mut self, id: NodeId) -> usize {
+        if self.in_progress[id.0] {
+            return 1; // cycle break
+        }
+        if self.computed[id.0] {
+            // Shared subtree: reuse the size already computed from another parent.
+            return self.sizes[id.0];
+        }
+
+        self.in_progress[id.0] = true;
 
-    // Every visible node contributes at least one row for itself.
-    let mut size: usize = 1;
-    if open[id.0]
-        && let Some(node) = tree.node(id)
-    {
-        // Open nodes contribute the sizes of all visible children.
-        for &child in node.children() {
-            if filter.is_some_and(|f| !f[child.0]) {
-                continue;
+        // Every visible node contributes at least one row for itself.
+        let mut size: usize = 1;
+        if self.open[id.0]
+            && let Some(node) = self.tree.node(id)
+        {
+            // Open nodes contribute the sizes of their visible children, plus
+            // the marker row when some are left out.
+            let (children, marker) = self.limits.children(id, node, self.filter);
+            for child in children {
+                size += self.size_of(child);
             }
-            size += compute_size_recursive(tree, open, filter, child, sizes, computed, in_progress);
+            size += usize::from(marker.is_some());
         }
-    }
 
-    sizes[id.0] = size;
-    computed[id.0] = true;
-    in_progress[id.0] = false;
-    size
+        self.sizes[id.0] = size;
+        self.computed[id.0] = true;
+        self.in_progress[id.0] = false;
+        size
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::{Dependency, DependencyNode, DependencyTree};
+    use crate::core::{Dependency, DependencyNode, DependencyTree, dependency::SourceKind};
 
     /// Builds an arena tree from a slice of `(name, children)` tuples.
     /// Node ids are positional; the first entry is the sole root.
@@ -502,6 +768,16 @@ mod tests {
                     version: String::from("0.0.0"),
                     manifest_dir: None,
                     is_proc_macro: false,
+                    source: SourceKind::Registry,
+                    registry: None,
+                    license: None,
+                    repository: None,
+                    targets: Vec::new(),
+                    features: Vec::new(),
+                    default_features: true,
+                    has_default_features: false,
+                    feature_requests: Box::default(),
+                    declared: Vec::new(),
                     children: children.iter().copied().map(NodeId).collect(),
                 })
             })
@@ -554,8 +830,15 @@ mod tests {
         count: usize,
     ) -> (Vec<usize>, Vec<VisibleNode>) {
         let mut cache = ViewCache::default();
-        cache.refresh_sizes(tree, open, None);
-        cache.rematerialize(tree, open, None, tree.roots(), start..start + count);
+        cache.refresh_sizes(tree, open, &ChildLimits::default(), None);
+        cache.rematerialize(
+            tree,
+            open,
+            &ChildLimits::default(),
+            None,
+            tree.roots(),
+            start..start + count,
+        );
         let root_sum: usize = tree.roots().iter().map(|r| cache.subtree_sizes[r.0]).sum();
         assert_eq!(cache.total_virtual_lines, root_sum);
         (cache.subtree_sizes, cache.nodes)
@@ -565,7 +848,13 @@ mod tests {
     fn subtree_sizes_all_open() {
         let tree = fixture();
         let mut sizes = Vec::new();
-        let total = compute_subtree_sizes(&tree, &all_open(&tree), None, &mut sizes);
+        let total = compute_subtree_sizes(
+            &tree,
+            &all_open(&tree),
+            &ChildLimits::default(),
+            None,
+            &mut sizes,
+        );
         assert_eq!(sizes, vec![6, 3, 1, 1, 2, 1]);
         assert_eq!(total, 6);
     }
@@ -582,7 +871,7 @@ mod tests {
         // `- b
         //    `- bb
         let mut sizes = Vec::new();
-        let total = compute_subtree_sizes(&tree, &open, None, &mut sizes);
+        let total = compute_subtree_sizes(&tree, &open, &ChildLimits::default(), None, &mut sizes);
         assert_eq!(sizes[1], 1);
         assert_eq!(sizes[0], 4); // root, a, b, bb
         assert_eq!(total, 4);
@@ -598,7 +887,13 @@ mod tests {
         //       `- a   (back-edge, counted as a leaf)
         let tree = build(&[("root", &[1]), ("a", &[2]), ("b", &[1])]);
         let mut sizes = Vec::new();
-        let total = compute_subtree_sizes(&tree, &all_open(&tree), None, &mut sizes);
+        let total = compute_subtree_sizes(
+            &tree,
+            &all_open(&tree),
+            &ChildLimits::default(),
+            None,
+            &mut sizes,
+        );
         // sizes:
         //
         // a(back-edge leaf) = 1
@@ -619,7 +914,13 @@ mod tests {
         //    `- bb
         let filter = vec![true, false, false, false, true, true];
         let mut sizes = Vec::new();
-        let total = compute_subtree_sizes(&tree, &all_open(&tree), Some(&filter), &mut sizes);
+        let total = compute_subtree_sizes(
+            &tree,
+            &all_open(&tree),
+            &ChildLimits::default(),
+            Some(&filter),
+            &mut sizes,
+        );
         // root keeps only the `b` subtree: 1 + 2 = 3
         assert_eq!(sizes[0], 3);
         assert_eq!(total, 3);
@@ -760,18 +1061,84 @@ mod tests {
         let tree = fixture();
         let filter = vec![true, false, false, false, true, true];
         let mut cache = ViewCache::default();
-        cache.refresh_sizes(&tree, &all_open(&tree), Some(&filter));
-        cache.rematerialize(&tree, &all_open(&tree), Some(&filter), tree.roots(), 0..10);
+        cache.refresh_sizes(
+            &tree,
+            &all_open(&tree),
+            &ChildLimits::default(),
+            Some(&filter),
+        );
+        cache.rematerialize(
+            &tree,
+            &all_open(&tree),
+            &ChildLimits::default(),
+            Some(&filter),
+            tree.roots(),
+            0..10,
+        );
         let ids: Vec<usize> = cache.nodes.iter().map(|n| n.id.0).collect();
         assert_eq!(ids, vec![0, 4, 5]);
     }
 
+    #[test]
+    fn summarize_compresses_stream_into_runs() {
+        let tree = fixture();
+        let open = all_open(&tree);
+        let mut cache = ViewCache::default();
+        cache.refresh_sizes(&tree, &open, &ChildLimits::default(), None);
+
+        // Runs of two lines: [root, a], [aa, ab], [b, bb].
+        let depths: Vec<usize> = cache
+            .summarize(&tree, &open, &ChildLimits::default(), None, None, 3)
+            .iter()
+            .map(|row| row.depth)
+            .collect();
+        assert_eq!(depths, [0, 2, 1]);
+
+        // Never more runs than lines.
+        assert_eq!(
+            cache
+                .summarize(&tree, &open, &ChildLimits::default(), None, None, 100)
+                .len(),
+            6
+        );
+
+        let mut matches = vec![false; tree.nodes.len()];
+        matches[3] = true; // ab
+        let rows = cache.summarize(
+            &tree,
+            &open,
+            &ChildLimits::default(),
+            None,
+            Some(&matches),
+            3,
+        );
+        let flagged: Vec<bool> = rows.iter().map(|row| row.has_match).collect();
+        assert_eq!(flagged, [false, true, false]);
+    }
+
+    #[test]
+    fn summarize_skips_closed_subtrees() {
+        let tree = fixture();
+        let mut open = all_open(&tree);
+        open[1] = false; // a
+        let mut cache = ViewCache::default();
+        cache.refresh_sizes(&tree, &open, &ChildLimits::default(), None);
+
+        let depths: Vec<usize> = cache
+            .summarize(&tree, &open, &ChildLimits::default(), None, None, 4)
+            .iter()
+            .map(|row| row.depth)
+            .collect();
+        assert_eq!(depths, [0, 1, 1, 2]);
+    }
+
     fn build_cache(tree: &DependencyTree) -> ViewCache {
         let mut cache = ViewCache::default();
-        cache.refresh_sizes(tree, &all_open(tree), None);
+        cache.refresh_sizes(tree, &all_open(tree), &ChildLimits::default(), None);
         cache.rematerialize(
             tree,
             &all_open(tree),
+            &ChildLimits::default(),
             None,
             tree.roots(),
             0..tree.nodes.len(),
@@ -855,4 +1222,32 @@ mod tests {
         let a = nodes.iter().find(|n| n.id.0 == 1).unwrap();
         assert!(!a.is_last_non_group_child);
     }
+
+    #[test]
+    fn long_child_lists_end_with_a_more_row() {
+        let children: Vec<usize> = (1..=CHILD_PAGE + 5).collect();
+        let mut spec: Vec<(&str, &[usize])> = vec![("root", &children)];
+        spec.extend(std::iter::repeat_n(("leaf", &[][..]), CHILD_PAGE + 5));
+        let tree = build(&spec);
+        let open = all_open(&tree);
+        let mut limits = ChildLimits::default();
+
+        let mut cache = ViewCache::default();
+        cache.refresh_sizes(&tree, &open, &limits, None);
+        assert_eq!(cache.total_virtual_lines, CHILD_PAGE + 2);
+        cache.rematerialize(&tree, &open, &limits, None, tree.roots(), 0..CHILD_PAGE + 2);
+        let more = cache.nodes.last().unwrap();
+        assert_eq!(
+            (more.id, more.depth, more.marker),
+            (NodeId(0), 1, Some(RowMarker::More(5)))
+        );
+        assert!(more.is_last_non_group_child);
+        assert!(!cache.nodes[CHILD_PAGE].is_last_non_group_child);
+
+        limits.load_more(NodeId(0));
+        cache.refresh_sizes(&tree, &open, &limits, None);
+        assert_eq!(cache.total_virtual_lines, CHILD_PAGE + 6);
+        cache.rematerialize(&tree, &open, &limits, None, tree.roots(), 0..CHILD_PAGE + 6);
+        assert!(cache.nodes.iter().all(|node| node.marker.is_none()));
+    }
 }
@@ -1,4 +1,4 @@
-use cargo_tree_tui::core::dependency::DependencyType;
+use cargo_tree_tui::core::dependency::{DependencyType, SourceKind};
 use cargo_tree_tui::core::{Dependency, DependencyGroup, DependencyNode, DependencyTree, NodeId};
 use cargo_tree_tui::ops::tree::tui::widget::render::RenderContext;
 use cargo_tree_tui::ops::tree::tui::widget::{TreeWidget, TreeWidgetState, TreeWidgetStyle};
@@ -19,6 +19,19 @@ pub struct TestNode {
     pub kind: TestNodeKind,
 }
 
+pub fn crate_node(
+    name: &'static str,
+    parent: Option<usize>,
+    children: &'static [usize],
+) -> TestNode {
+    TestNode {
+        name,
+        parent,
+        children,
+        kind: TestNodeKind::Crate,
+    }
+}
+
 pub fn build_tree(nodes: &[TestNode]) -> DependencyTree {
     let mut arena = Vec::with_capacity(nodes.len());
     for node in nodes {
@@ -29,6 +42,16 @@ pub fn build_tree(nodes: &[TestNode]) -> DependencyTree {
                 version: "".into(),
                 manifest_dir: None,
                 is_proc_macro: false,
+                source: SourceKind::Registry,
+                registry: None,
+                license: None,
+                repository: None,
+                targets: Vec::new(),
+                features: Vec::new(),
+                default_features: true,
+                has_default_features: false,
+                feature_requests: Box::default(),
+                declared: Vec::new(),
                 children,
             }),
             TestNodeKind::Group(kind) => DependencyNode::Group(DependencyGroup { kind, children }),
@@ -60,6 +83,10 @@ pub fn build_tree(nodes: &[TestNode]) -> DependencyTree {
 }
 
 pub fn render_tree_context(tree: &DependencyTree) -> String {
+    render_tree_context_with_style(tree, TreeWidgetStyle::default())
+}
+
+pub fn render_tree_context_with_style(tree: &DependencyTree, style: TreeWidgetStyle) -> String {
     let mut state = TreeWidgetState::default();
     state.expand_all(tree);
 
@@ -70,7 +97,6 @@ pub fn render_tree_context(tree: &DependencyTree) -> String {
         height: 24,
     };
 
-    let style = TreeWidgetStyle::default();
     let mut context = RenderContext::new(tree, &mut state, &style, None);
     let output = context.render(area);
 
@@ -1,8 +1,37 @@
 use std::path::PathBuf;
 
 use cargo::core::dependency::DepKind;
-use cargo_tree_tui::core::dependency::DependencyType;
+use cargo_tree_tui::Error;
+use cargo_tree_tui::core::annotate::{Annotation, Annotators, parse_annotation};
+use cargo_tree_tui::core::bloat::{BinarySize, BloatReport, format_size};
+use cargo_tree_tui::core::cache::{cache_root, workspace_key};
+use cargo_tree_tui::core::changelog::{Changelog, ChangelogSource, raw_urls};
+use cargo_tree_tui::core::commands::{NodeCommand, NodeCommands};
+use cargo_tree_tui::core::cost::BuildCost;
+use cargo_tree_tui::core::crates_io::CrateInfo;
+use cargo_tree_tui::core::dependency::{
+    CrateTarget, DeclaredDependency, DependencyType, FeatureRequest, RequirementLint, SourceKind,
+    TargetKind, default_members,
+};
+use cargo_tree_tui::core::diet::{Finding, diet_report, report_lines};
+use cargo_tree_tui::core::diff::{CrateChange, TreeDiff};
+use cargo_tree_tui::core::drift::version_drift;
+use cargo_tree_tui::core::export::{GraphFormat, dependencies_toml, graph_export, tree_html};
+use cargo_tree_tui::core::layers::LayerRules;
+use cargo_tree_tui::core::manifest::{find_dependency_line, locate, workspace_declaration};
+use cargo_tree_tui::core::no_std::{StdUse, declares_no_std, std_creep};
+use cargo_tree_tui::core::occurrences::occurrences;
+use cargo_tree_tui::core::profile::profile_args;
+use cargo_tree_tui::core::query::Query;
+use cargo_tree_tui::core::registry::{
+    Registry, RegistryNames, index_path, is_outdated, latest_in_index, newest_matching,
+};
+use cargo_tree_tui::core::remote::RemoteCrate;
+use cargo_tree_tui::core::stats::GraphStats;
+use cargo_tree_tui::core::trust::{RepoStatus, TrustSignals, days_since_epoch};
+use cargo_tree_tui::core::workspace_state::{MAX_SEARCH_HISTORY, WorkspaceState, push_search};
 use cargo_tree_tui::core::{Dependency, DependencyGroup, DependencyNode, DependencyTree, NodeId};
+use cargo_tree_tui::ops::tree::tui::options::MemberSelection;
 
 fn project_manifest() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml")
@@ -14,6 +43,292 @@ fn load_returns_tree_for_own_manifest() {
     assert_eq!(tree.workspace_name, "cargo-tree-tui");
 }
 
+#[test]
+fn load_honors_configured_build_target() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-target-{}", std::process::id()));
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [target.'cfg(windows)'.dependencies]\nwin = { path = \"win\" }\n\
+             [target.'cfg(unix)'.dependencies]\nnix = { path = \"nix\" }\n",
+        ),
+        ("src/lib.rs", ""),
+        (
+            "win/Cargo.toml",
+            "[package]\nname = \"win\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        ),
+        ("win/src/lib.rs", ""),
+        (
+            "nix/Cargo.toml",
+            "[package]\nname = \"nix\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        ),
+        ("nix/src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    let names = |tree: &DependencyTree| {
+        let mut names: Vec<String> = tree
+            .crate_nodes()
+            .filter_map(|id| Some(tree.node(id)?.as_dependency()?.name.clone()))
+            .collect();
+        names.sort();
+        names
+    };
+
+    let tree = DependencyTree::load(Some(dir.join("Cargo.toml"))).unwrap();
+    assert_eq!(names(&tree), ["app", "nix", "win"]);
+
+    std::fs::create_dir_all(dir.join(".cargo")).unwrap();
+    std::fs::write(
+        dir.join(".cargo/config.toml"),
+        "[build]\ntarget = \"x86_64-pc-windows-msvc\"\n",
+    )
+    .unwrap();
+    let tree = DependencyTree::load(Some(dir.join("Cargo.toml"))).unwrap();
+    assert_eq!(names(&tree), ["app", "win"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn default_members_select_roots() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-default-{}", std::process::id()));
+    for (path, contents) in [
+        ("Cargo.toml", "[workspace]\nmembers = [\"a\", \"b\"]\n"),
+        (
+            "a/Cargo.toml",
+            "[package]\nname = \"a\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        ),
+        ("a/src/lib.rs", ""),
+        (
+            "b/Cargo.toml",
+            "[package]\nname = \"b\"\nversion = \"0.2.0\"\nedition = \"2021\"\n",
+        ),
+        ("b/src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    let manifest = dir.join("Cargo.toml");
+    assert_eq!(default_members(&manifest).unwrap(), None);
+
+    std::fs::write(
+        &manifest,
+        "[workspace]\nmembers = [\"a\", \"b\"]\ndefault-members = [\"b\"]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        default_members(&manifest).unwrap(),
+        Some(vec![dir.join("b")])
+    );
+
+    let tree = DependencyTree::load(Some(manifest)).unwrap();
+    assert_eq!(tree.roots.len(), 2);
+    let root_names = |selection: MemberSelection| {
+        let selected = selection.apply(tree.clone());
+        selected
+            .roots
+            .iter()
+            .map(|&root| selected.node(root).unwrap().display_name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        root_names(MemberSelection::Packages(vec!["b@0.2.0".into()])),
+        ["b"]
+    );
+    assert!(root_names(MemberSelection::Packages(vec!["b@9".into()])).is_empty());
+    assert_eq!(
+        root_names(MemberSelection::Dirs(vec![dir.join("b")])),
+        ["b"]
+    );
+    assert_eq!(
+        root_names(MemberSelection::Excluding(vec!["b".into()])),
+        ["a"]
+    );
+    assert_eq!(root_names(MemberSelection::All).len(), 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_keeps_cargo_warnings() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-warnings-{}", std::process::id()));
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"a\"\nversion = \"0.1.0\"\nedition = \"2021\"\nflavour = \"mild\"\n",
+        ),
+        ("src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    let (_, timings) = DependencyTree::load_timed(Some(dir.join("Cargo.toml"))).unwrap();
+    assert_eq!(timings.warnings, ["unused manifest key: package.flavour"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn minimal_versions_leave_lockfile_alone() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-minimal-{}", std::process::id()));
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [dependencies]\nutil = { path = \"util\", version = \"0.2\" }\n",
+        ),
+        ("src/lib.rs", ""),
+        (
+            "util/Cargo.toml",
+            "[package]\nname = \"util\"\nversion = \"0.2.1\"\nedition = \"2021\"\n",
+        ),
+        ("util/src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    let tree = DependencyTree::load_minimal_versions(Some(dir.join("Cargo.toml"))).unwrap();
+    let mut crates: Vec<(String, String)> = tree
+        .crate_nodes()
+        .filter_map(|id| Some(tree.node(id)?.as_dependency()?.key()))
+        .collect();
+    crates.sort();
+    assert_eq!(
+        crates,
+        [
+            ("app".to_string(), "0.1.0".to_string()),
+            ("util".to_string(), "0.2.1".to_string())
+        ]
+    );
+    assert!(!dir.join("Cargo.lock").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn feature_conflicts_name_both_sides() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-features-{}", std::process::id()));
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [dependencies]\ncore-io = { path = \"core-io\", default-features = false }\n\
+             shim = { path = \"shim\" }\n",
+        ),
+        ("src/lib.rs", ""),
+        (
+            "shim/Cargo.toml",
+            "[package]\nname = \"shim\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [dependencies]\ncore-io = { path = \"../core-io\", default-features = false, \
+             optional = true }\n[features]\ndefault = [\"io\"]\nio = [\"core-io/std\"]\n",
+        ),
+        ("shim/src/lib.rs", ""),
+        (
+            "core-io/Cargo.toml",
+            "[package]\nname = \"core-io\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [features]\ndefault = [\"std\"]\nstd = []\n",
+        ),
+        ("core-io/src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    let tree = DependencyTree::load(Some(dir.join("Cargo.toml"))).unwrap();
+    let find = |name: &str| {
+        tree.crate_nodes()
+            .find(|&id| tree.node(id).unwrap().as_dependency().unwrap().name == name)
+            .unwrap()
+    };
+    // `shim` turns on `std` through its own default feature.
+    let conflict = tree.feature_conflict(find("core-io")).unwrap();
+    assert_eq!(conflict.opted_out, [find("app")]);
+    assert_eq!(conflict.enabling, [find("shim")]);
+    assert_eq!(tree.feature_conflicts(), [find("core-io")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn std_creep_into_no_std_members() {
+    assert!(declares_no_std("//! Docs\n#![no_std]\n"));
+    assert!(declares_no_std(
+        "#![cfg_attr(not(feature = \"std\"), no_std)]"
+    ));
+    assert!(!declares_no_std("// #![no_std]\n#![forbid(unsafe_code)]"));
+
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-no-std-{}", std::process::id()));
+    let package = |name: &str| {
+        format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n")
+    };
+    for (path, contents) in [
+        (
+            "Cargo.toml".to_string(),
+            package("firmware")
+                + "[dependencies]\nbare = { path = \"bare\" }\nhosted = { path = \"hosted\" }\n\
+                   opt = { path = \"opt\" }\n[build-dependencies]\nbuilder = { path = \"builder\" }\n",
+        ),
+        ("src/lib.rs".to_string(), "#![no_std]\n".to_string()),
+        ("bare/Cargo.toml".to_string(), package("bare")),
+        ("bare/src/lib.rs".to_string(), "#![no_std]\n".to_string()),
+        ("hosted/Cargo.toml".to_string(), package("hosted")),
+        ("hosted/src/lib.rs".to_string(), String::new()),
+        (
+            "opt/Cargo.toml".to_string(),
+            package("opt") + "[features]\ndefault = [\"std\"]\nstd = []\n",
+        ),
+        (
+            "opt/src/lib.rs".to_string(),
+            "#![cfg_attr(not(feature = \"std\"), no_std)]\n".to_string(),
+        ),
+        ("builder/Cargo.toml".to_string(), package("builder")),
+        ("builder/src/lib.rs".to_string(), String::new()),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    let tree = DependencyTree::load(Some(dir.join("Cargo.toml"))).unwrap();
+    let mut creep: Vec<(String, StdUse)> = std_creep(&tree)
+        .into_iter()
+        .map(|(id, std_use)| {
+            (
+                tree.node(id).unwrap().as_dependency().unwrap().name.clone(),
+                std_use,
+            )
+        })
+        .collect();
+    creep.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        creep,
+        [
+            ("hosted".to_string(), StdUse::Always),
+            ("opt".to_string(), StdUse::Feature)
+        ]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_reports_missing_manifest() {
+    let missing = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("missing/Cargo.toml");
+    match DependencyTree::load(Some(missing.clone())) {
+        Err(Error::ManifestNotFound(path)) => assert_eq!(path, missing),
+        other => panic!("expected ManifestNotFound, got {other:?}"),
+    }
+}
+
 #[test]
 fn load_has_single_root() {
     let tree = DependencyTree::load(Some(project_manifest())).unwrap();
@@ -264,6 +579,16 @@ fn as_dependency_returns_some_for_crate() {
         version: "1.0.0".into(),
         manifest_dir: None,
         is_proc_macro: false,
+        source: SourceKind::Registry,
+        registry: None,
+        license: None,
+        repository: None,
+        targets: Vec::new(),
+        features: Vec::new(),
+        default_features: true,
+        has_default_features: false,
+        feature_requests: Box::default(),
+        declared: Vec::new(),
         children: vec![],
     });
     assert!(dep.as_dependency().is_some());
@@ -289,6 +614,16 @@ fn display_name_for_crate_and_group() {
         version: "1.0.0".into(),
         manifest_dir: None,
         is_proc_macro: false,
+        source: SourceKind::Registry,
+        registry: None,
+        license: None,
+        repository: None,
+        targets: Vec::new(),
+        features: Vec::new(),
+        default_features: true,
+        has_default_features: false,
+        feature_requests: Box::default(),
+        declared: Vec::new(),
         children: vec![NodeId(1)],
     });
     assert_eq!(crate_node.display_name(), "serde");
@@ -320,13 +655,1714 @@ fn dependency_type_labels() {
     assert_eq!(DependencyType::Build.label(), "[build-dependencies]");
 }
 
+fn crate_node(name: &str, children: &[usize]) -> DependencyNode {
+    DependencyNode::Crate(Dependency {
+        name: name.into(),
+        version: "1.0.0".into(),
+        manifest_dir: None,
+        is_proc_macro: false,
+        source: SourceKind::Registry,
+        registry: None,
+        license: None,
+        repository: None,
+        targets: Vec::new(),
+        features: Vec::new(),
+        default_features: true,
+        has_default_features: false,
+        feature_requests: Box::default(),
+        declared: Vec::new(),
+        children: children.iter().copied().map(NodeId).collect(),
+    })
+}
+
+/// app
+/// |- foo
+/// |  |- shared
+/// |  `- only-foo
+/// `- bar
+///    |- shared
+///    `- [dev-dependencies]
+///       `- only-bar
+fn set_algebra_tree() -> DependencyTree {
+    let nodes = vec![
+        crate_node("app", &[1, 2]),
+        crate_node("foo", &[3, 4]),
+        crate_node("bar", &[3, 6]),
+        crate_node("shared", &[]),
+        crate_node("only-foo", &[]),
+        crate_node("only-bar", &[]),
+        DependencyNode::Group(DependencyGroup {
+            kind: DependencyType::Dev,
+            children: vec![NodeId(5)],
+        }),
+    ];
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        for &child in node.children() {
+            parents[child.0].push(NodeId(idx));
+        }
+    }
+    DependencyTree {
+        workspace_name: "app".into(),
+        nodes,
+        parents,
+        roots: vec![NodeId(0)],
+    }
+}
+
+#[test]
+fn stable_keys_ignore_node_order() {
+    let tree = set_algebra_tree();
+    let mut reversed = tree.clone();
+    let len = reversed.nodes.len();
+    let flip = |id: NodeId| NodeId(len - 1 - id.0);
+    reversed.nodes.reverse();
+    for node in &mut reversed.nodes {
+        let children = match node {
+            DependencyNode::Crate(dependency) => &mut dependency.children,
+            DependencyNode::Group(group) => &mut group.children,
+            _ => continue,
+        };
+        *children = children.iter().copied().map(flip).collect();
+    }
+    reversed.roots = vec![flip(NodeId(0))];
+    reversed.parents.reverse();
+    for parents in &mut reversed.parents {
+        *parents = parents.iter().copied().map(flip).collect();
+    }
+
+    let keys = tree.stable_keys();
+    let reversed_keys = reversed.stable_keys();
+    for idx in 0..len {
+        assert_eq!(keys[idx], reversed_keys[len - 1 - idx]);
+    }
+    let distinct: std::collections::HashSet<_> = keys.iter().collect();
+    assert_eq!(distinct.len(), len);
+}
+
+#[test]
+fn depths_follow_shortest_paths() {
+    let tree = set_algebra_tree();
+    // `only-bar` sits below the dev group of `bar`.
+    assert_eq!(tree.depths(), [0, 1, 1, 2, 2, 3, 2]);
+}
+
+#[test]
+fn occurrences_list_each_crate_once() {
+    let tree = occurrences(&set_algebra_tree());
+    let name = |id: NodeId| tree.node(id).unwrap().as_dependency().unwrap().name.clone();
+    let names: Vec<String> = tree.roots.iter().map(|&id| name(id)).collect();
+    assert_eq!(
+        names,
+        ["app", "bar", "foo", "only-bar", "only-foo", "shared"]
+    );
+
+    let shared = tree.node(NodeId(5)).unwrap();
+    let dependents: Vec<String> = shared.children().iter().map(|&id| name(id)).collect();
+    assert_eq!(dependents, ["foo", "bar"]);
+    assert!(tree.node(NodeId(0)).unwrap().children().is_empty());
+
+    let only_bar = tree.node(NodeId(3)).unwrap();
+    let [group] = only_bar.children()[..] else {
+        panic!("expected a single group below only-bar");
+    };
+    let Some(DependencyNode::Group(group)) = tree.node(group) else {
+        panic!("expected a group below only-bar");
+    };
+    assert_eq!(group.kind, DependencyType::Dev);
+    assert_eq!(group.children, [NodeId(1)]);
+    assert_eq!(tree.parents[1], [NodeId(5), NodeId(6)]);
+}
+
+#[test]
+fn diet_report_ranks_findings() {
+    let mut nodes = vec![
+        crate_node("app", &[1, 2, 8]),
+        crate_node("heavy", &[3, 4, 5, 7]),
+        crate_node("light", &[6]),
+        crate_node("h1", &[]),
+        crate_node("h2", &[]),
+        crate_node("tester", &[]),
+        crate_node("log", &[]),
+        crate_node("log", &[]),
+        DependencyNode::Group(DependencyGroup {
+            kind: DependencyType::Dev,
+            children: vec![NodeId(5)],
+        }),
+    ];
+    if let DependencyNode::Crate(dependency) = &mut nodes[7] {
+        dependency.version = "0.4.0".into();
+    }
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        for &child in node.children() {
+            parents[child.0].push(NodeId(idx));
+        }
+    }
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        nodes,
+        parents,
+        roots: vec![NodeId(0)],
+    };
+
+    let findings = diet_report(&tree);
+    assert_eq!(
+        findings,
+        [
+            Finding::HeavySubtree {
+                member: NodeId(0),
+                dependency: NodeId(1),
+                unique: 4,
+            },
+            Finding::HeavySubtree {
+                member: NodeId(0),
+                dependency: NodeId(2),
+                unique: 1,
+            },
+            Finding::Duplicate {
+                name: "log".into(),
+                versions: vec!["0.4.0".into(), "1.0.0".into()],
+            },
+            Finding::DevLeak {
+                member: NodeId(0),
+                dependency: NodeId(5),
+                via: NodeId(1),
+            },
+        ]
+    );
+    let lines = report_lines(&tree, &findings);
+    assert_eq!(
+        lines[0],
+        "  1. [-4] heavy v1.0.0 pulls in 4 crates only it uses (direct dependency of app v1.0.0)"
+    );
+    assert_eq!(
+        lines[3],
+        "  4. [-1] tester v1.0.0 is a dev-dependency of app v1.0.0 but ships through heavy v1.0.0"
+    );
+}
+
+#[test]
+fn group_members_by_dir_nests_folders() {
+    let mut nodes = vec![
+        crate_node("root", &[]),
+        crate_node("cli", &[2]),
+        crate_node("core", &[3]),
+        crate_node("util", &[]),
+        crate_node("xtask", &[]),
+    ];
+    for (node, dir) in nodes.iter_mut().zip([
+        "/ws",
+        "/ws/cli",
+        "/ws/crates/core",
+        "/ws/crates/util",
+        "/ws/tools/dev/xtask",
+    ]) {
+        if let DependencyNode::Crate(dependency) = node {
+            dependency.manifest_dir = Some(dir.into());
+        }
+    }
+    let mut parents = vec![Vec::new(); nodes.len()];
+    parents[2].push(NodeId(1));
+    parents[3].push(NodeId(2));
+    let mut tree = DependencyTree {
+        workspace_name: "ws".into(),
+        nodes,
+        parents,
+        roots: (0..5).map(NodeId).collect(),
+    };
+    tree.group_members_by_dir(std::path::Path::new("/ws"));
+
+    let labels = |ids: &[NodeId]| -> Vec<String> {
+        ids.iter()
+            .map(|&id| tree.node(id).unwrap().display_name().to_string())
+            .collect()
+    };
+    assert_eq!(
+        labels(&tree.roots),
+        ["root", "cli", "crates/", "tools/dev/"]
+    );
+    let crates = tree.node(tree.roots[2]).unwrap().as_folder().unwrap();
+    assert_eq!(labels(&crates.children), ["core", "util"]);
+    assert_eq!(
+        tree.members(),
+        [0, 1, 2, 3, 4].map(NodeId),
+        "members are listed through folders"
+    );
+    assert!(tree.is_workspace_member(NodeId(3)));
+    assert!(!tree.is_workspace_member(tree.roots[2]));
+    assert_eq!(tree.dependents(NodeId(3)), [NodeId(2)]);
+    assert_eq!(tree.parents[2], [NodeId(1), tree.roots[2]]);
+}
+
+#[test]
+fn transitive_dependencies_skip_self_and_groups() {
+    let tree = set_algebra_tree();
+    let mut deps: Vec<usize> = tree
+        .transitive_dependencies(NodeId(2))
+        .into_iter()
+        .map(|id| id.0)
+        .collect();
+    deps.sort();
+    assert_eq!(deps, vec![3, 5]);
+}
+
+#[test]
+fn common_and_exclusive_dependencies() {
+    let tree = set_algebra_tree();
+    assert_eq!(
+        tree.common_dependencies(NodeId(1), NodeId(2)),
+        vec![NodeId(3)]
+    );
+    assert_eq!(
+        tree.exclusive_dependencies(NodeId(1), NodeId(2)),
+        vec![NodeId(4)]
+    );
+    assert_eq!(
+        tree.exclusive_dependencies(NodeId(2), NodeId(1)),
+        vec![NodeId(5)]
+    );
+}
+
+#[test]
+fn dependents_resolve_through_groups() {
+    let tree = set_algebra_tree();
+    assert_eq!(tree.dependents(NodeId(3)), vec![NodeId(1), NodeId(2)]);
+    assert_eq!(tree.dependents(NodeId(5)), vec![NodeId(2)]);
+    assert!(tree.dependents(NodeId(0)).is_empty());
+}
+
+#[test]
+fn export_direct_dependencies_as_toml() {
+    let mut tree = set_algebra_tree();
+    if let DependencyNode::Crate(shared) = &mut tree.nodes[3] {
+        shared.features = vec!["derive".into(), "std".into()];
+        shared.default_features = false;
+    }
+    assert_eq!(
+        dependencies_toml(&tree, NodeId(2)).unwrap(),
+        "[dependencies]\n\
+         shared = { version = \"1.0.0\", default-features = false, features = [\"derive\", \"std\"] }\n\
+         \n\
+         [dev-dependencies]\n\
+         only-bar = \"1.0.0\"\n"
+    );
+    assert_eq!(dependencies_toml(&tree, NodeId(6)), None);
+
+    let tree = DependencyTree::load(Some(project_manifest())).unwrap();
+    let toml = dependencies_toml(&tree, tree.roots[0]).unwrap();
+    assert!(toml.contains("\nserde = { version = "));
+    assert!(toml.contains("\"derive\""));
+    assert!(toml.contains("[dev-dependencies]\npretty_assertions = "));
+}
+
+#[test]
+fn export_tree_as_html() {
+    let mut tree = set_algebra_tree();
+    // `foo` is reached twice: its dependencies are listed once.
+    if let DependencyNode::Crate(app) = &mut tree.nodes[0] {
+        app.children.push(NodeId(1));
+    }
+    let notes = [("bar".to_string(), "replace with <baz>".to_string())].into();
+    let html = tree_html(&tree, &notes);
+
+    assert!(html.contains("<title>app dependency tree</title>"));
+    assert_eq!(html.matches("only-foo").count(), 1);
+    assert_eq!(html.matches("<a href=\"#n1\">").count(), 1);
+    assert!(html.contains("<li id=\"n0\"><details open><summary>"));
+    assert!(html.contains("<span class=\"group\">[dev-dependencies]</span>"));
+    assert!(html.contains("replace with &lt;baz&gt;"));
+}
+
+#[test]
+fn export_graph_with_typed_edges() {
+    let tree = set_algebra_tree();
+    let json: serde_json::Value =
+        serde_json::from_str(&graph_export(&tree, GraphFormat::Json)).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 6);
+    assert_eq!(json["nodes"][0]["workspace_member"], true);
+    assert_eq!(json["nodes"][0]["dependents"], 0);
+    assert_eq!(json["nodes"][3]["dependents"], 2);
+    let edges = json["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 6);
+    assert_eq!(
+        edges.last().unwrap(),
+        &serde_json::json!({ "source": 2, "target": 5, "kind": "dev" })
+    );
+
+    let graphml = graph_export(&tree, GraphFormat::GraphMl);
+    assert_eq!(graphml.matches("<node ").count(), 6);
+    assert!(graphml.contains("<node id=\"n3\">\n  <data key=\"name\">shared</data>"));
+    assert_eq!(
+        graphml.matches("<data key=\"dependents\">2</data>").count(),
+        1
+    );
+    assert!(
+        graphml.contains("<edge source=\"n2\" target=\"n5\"><data key=\"kind\">dev</data></edge>")
+    );
+
+    assert_eq!(
+        GraphFormat::from_path("deps.json".as_ref()),
+        GraphFormat::Json
+    );
+    assert_eq!(
+        GraphFormat::from_path("deps.xml".as_ref()),
+        GraphFormat::GraphMl
+    );
+}
+
+#[test]
+fn graph_stats_summary() {
+    let tree = set_algebra_tree();
+    let stats = GraphStats::compute(&tree);
+
+    assert_eq!(stats.total_crates, 6);
+    assert_eq!(stats.unique_crates, 6);
+    assert!(stats.duplicates.is_empty());
+    assert_eq!(stats.most_depended[0], (NodeId(3), 2));
+    assert_eq!(stats.deepest_chain.len(), 3);
+    assert_eq!(stats.deepest_chain[0], NodeId(0));
+    assert_eq!(stats.licenses, vec![("unknown".to_string(), 6)]);
+    assert_eq!(stats.sources, vec![(SourceKind::Registry, 6)]);
+}
+
+#[test]
+fn sort_by_dependents_keeps_groups_last() {
+    let mut tree = set_algebra_tree();
+    // Make `only-bar` a normal dependency of `foo` too, so it outranks `only-foo`.
+    if let DependencyNode::Crate(foo) = &mut tree.nodes[1] {
+        foo.children.push(NodeId(5));
+    }
+    tree.parents[5].push(NodeId(1));
+    tree.sort_by_dependents();
+
+    assert_eq!(
+        tree.node(NodeId(1)).unwrap().children(),
+        &[NodeId(3), NodeId(5), NodeId(4)]
+    );
+    assert_eq!(
+        tree.node(NodeId(2)).unwrap().children(),
+        &[NodeId(3), NodeId(6)]
+    );
+}
+
+#[test]
+fn nodes_on_paths_between_crates() {
+    let tree = set_algebra_tree();
+    let on_path = |from, to| -> Vec<usize> {
+        tree.nodes_on_paths(NodeId(from), NodeId(to))
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, &on)| on.then_some(idx))
+            .collect()
+    };
+
+    assert_eq!(on_path(0, 3), vec![0, 1, 2, 3]);
+    assert_eq!(on_path(0, 5), vec![0, 2, 5, 6]);
+    assert!(on_path(1, 5).is_empty());
+}
+
+#[test]
+fn tree_diff_reports_changes() {
+    let old = set_algebra_tree();
+    let mut new = set_algebra_tree();
+    if let DependencyNode::Crate(shared) = &mut new.nodes[3] {
+        shared.version = "1.1.0".into();
+    }
+    if let DependencyNode::Crate(only_foo) = &mut new.nodes[4] {
+        only_foo.name = "new-crate".into();
+    }
+
+    let diff = TreeDiff::between(&old, &new);
+    assert_eq!(
+        diff.changed,
+        vec![("shared".into(), "1.0.0".into(), "1.1.0".into())]
+    );
+    assert_eq!(diff.added, vec![("new-crate".into(), "1.0.0".into())]);
+    assert_eq!(diff.removed, vec![("only-foo".into(), "1.0.0".into())]);
+    assert!(TreeDiff::between(&old, &old).is_empty());
+}
+
+#[test]
+fn tree_diff_adds_tombstones() {
+    let old = set_algebra_tree();
+    let mut new = set_algebra_tree();
+    for (id, name) in [(4, "fresh"), (5, "dev-fresh")] {
+        if let DependencyNode::Crate(dependency) = &mut new.nodes[id] {
+            dependency.name = name.into();
+        }
+    }
+    let reloaded = new.clone();
+
+    let diff = TreeDiff::between(&old, &new);
+    diff.add_tombstones(&old, &mut new);
+    assert_eq!(new.nodes.len(), 9);
+    assert_eq!(
+        new.node(NodeId(1)).unwrap().children(),
+        &[NodeId(3), NodeId(4), NodeId(7)]
+    );
+    assert_eq!(
+        new.node(NodeId(6)).unwrap().children(),
+        &[NodeId(5), NodeId(8)]
+    );
+    assert_eq!(new.parents[8], [NodeId(6)]);
+
+    let change = |id: usize| diff.change_of(new.node(NodeId(id)).unwrap().as_dependency().unwrap());
+    assert_eq!(change(4), Some(CrateChange::Added));
+    assert_eq!(change(7), Some(CrateChange::Removed));
+    assert_eq!(change(8), Some(CrateChange::Removed));
+    assert_eq!(change(3), None);
+
+    // Tombstones left in the shown tree are not reported again.
+    assert!(TreeDiff::since(&new, &diff, &reloaded).is_empty());
+}
+
+#[test]
+fn locate_lists_manifest_and_declarations() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-locate-{}", std::process::id()));
+    let mut tree = set_algebra_tree();
+    for (idx, name, manifest) in [
+        (
+            0,
+            "app",
+            "[package]\nname = \"app\"\n\n[dependencies]\nfoo = \"1\"\nbar = \"1\"\n",
+        ),
+        (
+            2,
+            "bar",
+            "[package]\nname = \"bar\"\n\n[dev-dependencies]\nonly-bar = \"1\"\n",
+        ),
+    ] {
+        let crate_dir = dir.join(name);
+        std::fs::create_dir_all(&crate_dir).unwrap();
+        std::fs::write(crate_dir.join("Cargo.toml"), manifest).unwrap();
+        if let DependencyNode::Crate(dependency) = &mut tree.nodes[idx] {
+            dependency.manifest_dir = Some(crate_dir.display().to_string());
+        }
+    }
+
+    let spots = locate(&tree, "bar@1.0.0");
+    let spots: Vec<_> = spots
+        .iter()
+        .map(|spot| (spot.path.clone(), spot.line, spot.description.as_str()))
+        .collect();
+    assert_eq!(
+        spots,
+        [
+            (dir.join("bar/Cargo.toml"), 1, "bar 1.0.0 manifest"),
+            (
+                dir.join("app/Cargo.toml"),
+                6,
+                "bar 1.0.0 in [dependencies] of app"
+            ),
+        ]
+    );
+    let only_bar = locate(&tree, "only-bar");
+    assert_eq!(only_bar.last().unwrap().line, 5);
+    assert_eq!(
+        only_bar.last().unwrap().description,
+        "only-bar 1.0.0 in [dev-dependencies] of bar"
+    );
+    assert!(locate(&tree, "bar@2.0.0").is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn workspace_declaration_points_at_workspace_dependencies() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-inherit-{}", std::process::id()));
+    let mut tree = set_algebra_tree();
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"app\"]\n\n[workspace.dependencies]\nbar = \"1\"\nfoo = \"1\"\n",
+        ),
+        (
+            "app/Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\nfoo.workspace = true\nbar = \"1\"\n",
+        ),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    if let DependencyNode::Crate(app) = &mut tree.nodes[0] {
+        app.manifest_dir = Some(dir.join("app").display().to_string());
+        app.declared = [("foo", true), ("bar", false)]
+            .into_iter()
+            .map(|(name, inherited)| DeclaredDependency {
+                name: name.into(),
+                kind: DependencyType::Normal,
+                requirement: "^1".into(),
+                inherited,
+                rename: None,
+            })
+            .collect();
+    }
+
+    let spot = workspace_declaration(&tree, NodeId(1)).unwrap();
+    assert_eq!((spot.path, spot.line), (dir.join("Cargo.toml"), 6));
+    assert_eq!(spot.description, "foo in [workspace.dependencies]");
+    // Declared by the member itself, even though the workspace lists it too.
+    assert!(workspace_declaration(&tree, NodeId(2)).is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn find_dependency_line_in_manifest() {
+    let manifest = r#"[package]
+name = "app"
+
+[dependencies]
+anyhow = "1" # errors
+serde_json = { version = "1", features = ["std"] }
+yaml = { package = "serde_yaml", version = "0.9" }
+
+[dev-dependencies]
+anyhow = "1"
+
+[target.'cfg(unix)'.build-dependencies]
+cc = "1"
+
+[dependencies.tokio]
+version = "1"
+"#;
+
+    assert_eq!(
+        find_dependency_line(manifest, "anyhow", DependencyType::Normal),
+        Some(5)
+    );
+    assert_eq!(
+        find_dependency_line(manifest, "serde_yaml", DependencyType::Normal),
+        Some(7)
+    );
+    assert_eq!(
+        find_dependency_line(manifest, "anyhow", DependencyType::Dev),
+        Some(10)
+    );
+    assert_eq!(
+        find_dependency_line(manifest, "cc", DependencyType::Build),
+        Some(13)
+    );
+    assert_eq!(
+        find_dependency_line(manifest, "tokio", DependencyType::Normal),
+        Some(15)
+    );
+    assert_eq!(
+        find_dependency_line(manifest, "cc", DependencyType::Normal),
+        None
+    );
+}
+
+#[test]
+fn changelog_urls_and_version_heading() {
+    assert_eq!(
+        raw_urls("https://github.com/orhun/cargo-tree-tui.git/").first(),
+        Some(
+            &"https://raw.githubusercontent.com/orhun/cargo-tree-tui/HEAD/CHANGELOG.md".to_string()
+        )
+    );
+    assert_eq!(
+        raw_urls("https://gitlab.com/owner/repo").first(),
+        Some(&"https://gitlab.com/owner/repo/-/raw/HEAD/CHANGELOG.md".to_string())
+    );
+    assert!(raw_urls("https://example.com/owner/repo").is_empty());
+
+    let changelog = Changelog {
+        source: ChangelogSource::Remote(String::new()),
+        text: "# Changelog\n\n## [1.0.10] - 2025\n\n- fix\n\n## [v1.0.1] - 2024\n".to_string(),
+    };
+    assert_eq!(changelog.version_line("1.0.1"), Some(6));
+    assert_eq!(changelog.version_line("1.0.10"), Some(2));
+    assert_eq!(changelog.version_line("2.0.0"), None);
+}
+
+#[test]
+fn crate_info_from_crates_io_responses() {
+    let crate_json = r#"{
+        "crate": { "name": "foo", "downloads": 12345, "recent_downloads": 678 },
+        "versions": [
+            { "num": "1.1.0", "created_at": "2025-02-01T10:00:00.000000+00:00", "downloads": 10 },
+            { "num": "1.0.0", "created_at": "2024-06-15T08:30:00.000000+00:00", "downloads": 99 }
+        ]
+    }"#;
+    let owners_json = r#"{ "users": [
+        { "id": 1, "login": "alice", "kind": "user" },
+        { "id": 2, "login": "github:org:team", "kind": "team" }
+    ] }"#;
+
+    let info = CrateInfo::from_responses(crate_json, owners_json, "1.0.0").unwrap();
+    assert_eq!(
+        info,
+        CrateInfo {
+            owners: vec!["alice".to_string(), "github:org:team".to_string()],
+            published: Some("2024-06-15".to_string()),
+            last_release: Some("2025-02-01".to_string()),
+            repository: None,
+            downloads: 12345,
+            recent_downloads: Some(678),
+            version_downloads: Some(99),
+        }
+    );
+
+    let info = CrateInfo::from_responses(crate_json, "", "9.9.9").unwrap();
+    assert!(info.owners.is_empty());
+    assert_eq!(info.published, None);
+    assert!(CrateInfo::from_responses("{}", "", "1.0.0").is_err());
+}
+
+#[test]
+fn trust_signals_from_crate_info() {
+    assert_eq!(days_since_epoch("1970-01-01"), Some(0));
+    assert_eq!(days_since_epoch("2000-03-01"), Some(11_017));
+    assert_eq!(days_since_epoch("2024-06-15T08:30:00Z"), Some(19_889));
+    assert_eq!(days_since_epoch("2024-13-01"), None);
+
+    let info = CrateInfo {
+        owners: vec!["alice".to_string()],
+        published: Some("2024-06-15".to_string()),
+        last_release: Some("2024-06-15".to_string()),
+        repository: None,
+        downloads: 0,
+        recent_downloads: None,
+        version_downloads: None,
+    };
+    let today = days_since_epoch("2026-10-16").unwrap();
+    let signals = TrustSignals::new(&info, RepoStatus::Undeclared, today);
+    assert_eq!(signals.version_age_days, Some(853));
+    assert_eq!(
+        signals.warnings(),
+        vec![
+            "no release in over two years",
+            "single owner",
+            "no repository declared"
+        ]
+    );
+
+    let signals = TrustSignals::new(
+        &CrateInfo {
+            owners: vec!["alice".to_string(), "bob".to_string()],
+            published: Some("2026-10-14".to_string()),
+            last_release: Some("2026-10-14".to_string()),
+            ..info
+        },
+        RepoStatus::Resolves { stars: Some(10) },
+        today,
+    );
+    assert_eq!(signals.warnings(), vec!["version published this week"]);
+}
+
+#[test]
+fn workspace_key_tracks_lockfile_and_manifests() {
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-key-{}", std::process::id()));
+    let member = dir.join("member");
+    std::fs::create_dir_all(&member).unwrap();
+    let manifest = dir.join("Cargo.toml");
+    std::fs::write(&manifest, "[workspace]\nmembers = [\"member\"]\n").unwrap();
+    std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"member\"\n").unwrap();
+
+    let tree = DependencyTree {
+        workspace_name: "member".into(),
+        nodes: vec![DependencyNode::Crate(Dependency {
+            name: "member".into(),
+            version: "0.1.0".into(),
+            manifest_dir: Some(member.display().to_string()),
+            is_proc_macro: false,
+            source: SourceKind::Path,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
+            children: Vec::new(),
+        })],
+        parents: vec![Vec::new()],
+        roots: vec![NodeId(0)],
+    };
+
+    assert_eq!(workspace_key(&manifest, &tree), None, "no lockfile yet");
+    std::fs::write(dir.join("Cargo.lock"), "version = 4\n").unwrap();
+    let key = workspace_key(&manifest, &tree).unwrap();
+    assert_eq!(workspace_key(&manifest, &tree), Some(key));
+
+    std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"renamed\"\n").unwrap();
+    let member_changed = workspace_key(&manifest, &tree).unwrap();
+    assert_ne!(member_changed, key);
+
+    std::fs::write(dir.join("Cargo.lock"), "version = 3\n").unwrap();
+    let lock_changed = workspace_key(&manifest, &tree).unwrap();
+    assert_ne!(lock_changed, member_changed);
+
+    std::fs::create_dir_all(dir.join(".cargo")).unwrap();
+    std::fs::write(dir.join(".cargo/config.toml"), "[build]\n").unwrap();
+    assert_ne!(workspace_key(&manifest, &tree), Some(lock_changed));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn cyclic_tree() -> DependencyTree {
+    // app -> a -> b -> a (dev-dependency cycle), b -> c, app -> c
+    let crate_node = |name: &str, children: Vec<NodeId>| {
+        DependencyNode::Crate(Dependency {
+            name: name.into(),
+            version: "0.1.0".into(),
+            manifest_dir: None,
+            is_proc_macro: false,
+            source: SourceKind::Registry,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
+            children,
+        })
+    };
+    DependencyTree {
+        workspace_name: "app".into(),
+        nodes: vec![
+            crate_node("app", vec![NodeId(1), NodeId(3)]),
+            crate_node("a", vec![NodeId(2)]),
+            crate_node("b", vec![NodeId(1), NodeId(3)]),
+            crate_node("c", vec![]),
+        ],
+        parents: vec![
+            vec![],
+            vec![NodeId(0), NodeId(2)],
+            vec![NodeId(1)],
+            vec![NodeId(2), NodeId(0)],
+        ],
+        roots: vec![NodeId(0)],
+    }
+}
+
+#[test]
+fn back_edges_of_cyclic_graph() {
+    let tree = cyclic_tree();
+    tree.validate().unwrap();
+    assert_eq!(tree.back_edges(), vec![(NodeId(2), NodeId(1))]);
+    assert_eq!(GraphStats::compute(&tree).cycles.len(), 1);
+
+    let mut acyclic = tree.clone();
+    if let DependencyNode::Crate(b) = &mut acyclic.nodes[2] {
+        b.children.retain(|&child| child != NodeId(1));
+    }
+    acyclic.parents[1].retain(|&parent| parent != NodeId(2));
+    acyclic.validate().unwrap();
+    assert!(acyclic.back_edges().is_empty());
+}
+
+#[test]
+fn validate_rejects_inconsistent_graphs() {
+    let mut missing_parent = cyclic_tree();
+    missing_parent.parents[3].clear();
+    assert!(missing_parent.validate().is_err());
+
+    let mut dangling_child = cyclic_tree();
+    if let DependencyNode::Crate(c) = &mut dangling_child.nodes[3] {
+        c.children.push(NodeId(7));
+    }
+    assert!(dangling_child.validate().is_err());
+
+    let mut stray_parent = cyclic_tree();
+    stray_parent.parents[0].push(NodeId(3));
+    assert!(stray_parent.validate().is_err());
+}
+
+#[test]
+fn headless_queries() {
+    let words = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+    assert_eq!(
+        Query::parse(&words("paths-to c")).unwrap(),
+        Query::PathsTo("c".into())
+    );
+    assert_eq!(
+        Query::parse(&words("duplicates")).unwrap(),
+        Query::Duplicates
+    );
+    assert!(matches!(
+        Query::parse(&words("closure")),
+        Err(Error::InvalidQuery(_))
+    ));
+
+    let tree = cyclic_tree();
+    let names = |value: &serde_json::Value| {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|krate| krate["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let paths = Query::PathsTo("c".into()).run(&tree).unwrap();
+    assert_eq!(paths.as_array().unwrap().len(), 1);
+    assert_eq!(names(&paths[0]), ["app", "c"]);
+
+    let reverse = Query::ReverseDeps("c@0.1.0".into()).run(&tree).unwrap();
+    assert_eq!(reverse[0]["crate"]["name"], "c");
+    assert_eq!(names(&reverse[0]["crates"]), ["app", "b"]);
+
+    let closure = Query::Closure("b".into()).run(&tree).unwrap();
+    assert_eq!(names(&closure[0]["crates"]), ["a", "c"]);
+
+    assert_eq!(Query::Duplicates.run(&tree).unwrap(), serde_json::json!([]));
+    assert!(matches!(
+        Query::Closure("c@9.9.9".into()).run(&tree),
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[test]
+fn registry_sources_and_index() {
+    let names = RegistryNames::new([(
+        "internal".to_string(),
+        "sparse+https://cargo.example.com/index/".to_string(),
+    )]);
+
+    let crates_io = Registry::from_source(
+        "registry+https://github.com/rust-lang/crates.io-index",
+        &names,
+    )
+    .unwrap();
+    assert!(crates_io.is_crates_io());
+    assert_eq!(crates_io.sparse_index(), Some("https://index.crates.io"));
+
+    let internal =
+        Registry::from_source("sparse+https://cargo.example.com/index/", &names).unwrap();
+    assert_eq!(internal.name, "internal");
+    assert_eq!(
+        internal.sparse_index(),
+        Some("https://cargo.example.com/index")
+    );
+
+    let unnamed = Registry::from_source("registry+https://git.example.org/index", &names).unwrap();
+    assert_eq!(unnamed.name, "git.example.org");
+    assert_eq!(unnamed.sparse_index(), None);
+
+    assert!(Registry::from_source("git+https://github.com/a/b?rev=1#abc", &names).is_none());
+
+    let names = names.with_replacements([
+        (
+            "crates-io".to_string(),
+            "sparse+https://mirror.example.com/crates/".to_string(),
+        ),
+        (
+            "internal".to_string(),
+            "registry+https://git.example.org/index".to_string(),
+        ),
+    ]);
+    let mirrored = Registry::from_source(
+        "registry+https://github.com/rust-lang/crates.io-index",
+        &names,
+    )
+    .unwrap();
+    assert!(mirrored.is_crates_io());
+    assert_eq!(
+        mirrored.sparse_index(),
+        Some("https://mirror.example.com/crates")
+    );
+    let internal =
+        Registry::from_source("sparse+https://cargo.example.com/index/", &names).unwrap();
+    assert_eq!(internal.mirror, None);
+
+    assert_eq!(index_path("a"), "1/a");
+    assert_eq!(index_path("cc"), "2/cc");
+    assert_eq!(index_path("syn"), "3/s/syn");
+    assert_eq!(index_path("Serde"), "se/rd/serde");
+
+    let index = [
+        r#"{"name":"a","vers":"1.0.0","yanked":false}"#,
+        r#"{"name":"a","vers":"1.2.0","yanked":true}"#,
+        r#"{"name":"a","vers":"1.1.0","yanked":false}"#,
+        r#"{"name":"a","vers":"2.0.0-rc.1","yanked":false}"#,
+    ]
+    .join("\n");
+    assert_eq!(latest_in_index(&index).as_deref(), Some("1.1.0"));
+    assert!(is_outdated("1.0.0", "1.1.0"));
+    assert!(!is_outdated("1.1.0", "1.1.0"));
+}
+
+#[test]
+fn declared_requirements_of_members() {
+    let tree = DependencyTree::load(Some(project_manifest())).unwrap();
+    let member = tree.node(tree.roots()[0]).unwrap().as_dependency().unwrap();
+    assert_eq!(
+        member.requirement_on("serde", DependencyType::Normal),
+        Some("^1.0.228")
+    );
+    assert_eq!(member.requirement_on("serde", DependencyType::Dev), None);
+    let serde = member.declaration_on("serde", DependencyType::Normal);
+    assert_eq!(serde.unwrap().rename, None);
+}
+
+#[test]
+fn updates_in_range_from_cached_index() {
+    let home = std::env::temp_dir().join(format!("cargo-tree-tui-home-{}", std::process::id()));
+    let cache = home.join("registry/index/index.crates.io-0123456789abcdef/.cache/se/rd");
+    std::fs::create_dir_all(&cache).unwrap();
+    let mut contents = b"\x03\x00\x00\x00\x00etag\x00".to_vec();
+    for (version, yanked) in [
+        ("1.0.0", false),
+        ("1.0.5", false),
+        ("1.0.9", true),
+        ("2.0.0", false),
+    ] {
+        contents.extend_from_slice(version.as_bytes());
+        contents.push(0);
+        contents.extend_from_slice(
+            format!(r#"{{"name":"serde","vers":"{version}","yanked":{yanked}}}"#).as_bytes(),
+        );
+        contents.push(0);
+    }
+    std::fs::write(cache.join("serde"), contents).unwrap();
+
+    let mut app = crate_node("app", &[1]);
+    if let DependencyNode::Crate(dependency) = &mut app {
+        dependency.declared = vec![DeclaredDependency {
+            name: "serde".into(),
+            kind: DependencyType::Normal,
+            requirement: "^1.0".into(),
+            inherited: false,
+            rename: None,
+        }];
+    }
+    let mut serde = crate_node("serde", &[]);
+    if let DependencyNode::Crate(dependency) = &mut serde {
+        dependency.version = "1.0.0".into();
+        dependency.registry = Some(Box::new(Registry {
+            name: "crates-io".into(),
+            index: "https://github.com/rust-lang/crates.io-index".into(),
+            sparse: false,
+            mirror: None,
+        }));
+    }
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        nodes: vec![app, serde],
+        parents: vec![Vec::new(), vec![NodeId(0)]],
+        roots: vec![NodeId(0)],
+    };
+    assert_eq!(
+        tree.updates_in_range(&home),
+        [(NodeId(0), NodeId(1), "1.0.5".to_string())]
+    );
+    assert!(newest_matching("", "^1.0").is_none());
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn release_ages_from_cached_index() {
+    let home = std::env::temp_dir().join(format!("cargo-tree-tui-ages-{}", std::process::id()));
+    let cache = home.join("registry/index/index.crates.io-0123456789abcdef/.cache/3/l");
+    std::fs::create_dir_all(&cache).unwrap();
+    let mut contents = b"\x03\x00\x00\x00\x00etag\x00".to_vec();
+    for entry in [
+        r#"{"name":"log","vers":"0.4.0","pubtime":"2018-01-10T08:00:00Z"}"#,
+        r#"{"name":"log","vers":"0.4.1"}"#,
+    ] {
+        contents.extend_from_slice(b"0.4.x\x00");
+        contents.extend_from_slice(entry.as_bytes());
+        contents.push(0);
+    }
+    std::fs::write(cache.join("log"), contents).unwrap();
+
+    let mut nodes = vec![crate_node("app", &[1, 2])];
+    for version in ["0.4.0", "0.4.1"] {
+        let mut log = crate_node("log", &[]);
+        if let DependencyNode::Crate(dependency) = &mut log {
+            dependency.version = version.into();
+            dependency.registry = Some(Box::new(Registry {
+                name: "crates-io".into(),
+                index: "https://github.com/rust-lang/crates.io-index".into(),
+                sparse: false,
+                mirror: None,
+            }));
+        }
+        nodes.push(log);
+    }
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        nodes,
+        parents: vec![Vec::new(), vec![NodeId(0)], vec![NodeId(0)]],
+        roots: vec![NodeId(0)],
+    };
+    let released = days_since_epoch("2018-01-10").unwrap();
+    let ages = tree.release_ages(&home, released + 400);
+    // 0.4.1 predates publish times in the index.
+    assert_eq!(ages.into_iter().collect::<Vec<_>>(), [(NodeId(1), 400)]);
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn requirement_lints_flag_risky_requirements() {
+    assert_eq!(RequirementLint::of("*"), Some(RequirementLint::Wildcard));
+    assert_eq!(
+        RequirementLint::of(">=1.0"),
+        Some(RequirementLint::Unbounded)
+    );
+    assert_eq!(
+        RequirementLint::of("^1.0.0-alpha.1"),
+        Some(RequirementLint::PreRelease)
+    );
+    for requirement in ["^1.0", ">=1.0, <2", "1.*", "=0.4.2"] {
+        assert_eq!(RequirementLint::of(requirement), None, "{requirement}");
+    }
+
+    let mut app = crate_node("app", &[1, 2, 3]);
+    if let DependencyNode::Crate(dependency) = &mut app {
+        dependency.declared = [("serde", "*"), ("log", "^0.4"), ("rand", ">=0.8")]
+            .into_iter()
+            .map(|(name, requirement)| DeclaredDependency {
+                name: name.into(),
+                kind: DependencyType::Normal,
+                requirement: requirement.into(),
+                inherited: false,
+                rename: None,
+            })
+            .collect();
+    }
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        nodes: vec![
+            app,
+            crate_node("serde", &[]),
+            crate_node("log", &[]),
+            crate_node("rand", &[]),
+        ],
+        parents: vec![
+            Vec::new(),
+            vec![NodeId(0)],
+            vec![NodeId(0)],
+            vec![NodeId(0)],
+        ],
+        roots: vec![NodeId(0)],
+    };
+    assert_eq!(
+        tree.requirement_lints(),
+        [
+            (NodeId(0), NodeId(1), RequirementLint::Wildcard),
+            (NodeId(0), NodeId(3), RequirementLint::Unbounded),
+        ]
+    );
+}
+
+#[test]
+fn version_drift_lists_diverging_declarations() {
+    let member = |name, children, declared: &[(&str, &str)], requests: Vec<FeatureRequest>| {
+        let mut node = crate_node(name, children);
+        if let DependencyNode::Crate(dependency) = &mut node {
+            dependency.declared = declared
+                .iter()
+                .map(|&(name, requirement)| DeclaredDependency {
+                    name: name.into(),
+                    kind: DependencyType::Normal,
+                    requirement: requirement.into(),
+                    inherited: false,
+                    rename: None,
+                })
+                .collect();
+            dependency.feature_requests = requests.into();
+        }
+        node
+    };
+    let nodes = vec![
+        member(
+            "cli",
+            &[2, 3, 4, 5],
+            &[
+                ("serde", "^1.0.200"),
+                ("log", "^0.4"),
+                ("tokio", "^1"),
+                ("rand", "^0.8"),
+            ],
+            vec![FeatureRequest {
+                name: "tokio".into(),
+                default_features: false,
+                features: vec!["rt".into()],
+            }],
+        ),
+        member(
+            "app",
+            &[2, 3, 4],
+            &[("serde", "^1.0"), ("log", "^0.4"), ("tokio", "^1")],
+            Vec::new(),
+        ),
+        crate_node("serde", &[]),
+        crate_node("log", &[]),
+        crate_node("tokio", &[]),
+        crate_node("rand", &[]),
+    ];
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        for &child in node.children() {
+            parents[child.0].push(NodeId(idx));
+        }
+    }
+    let tree = DependencyTree {
+        workspace_name: "workspace".into(),
+        nodes,
+        parents,
+        roots: vec![NodeId(0), NodeId(1)],
+    };
+
+    let drifts = version_drift(&tree);
+    let summary: Vec<_> = drifts
+        .iter()
+        .map(|drift| {
+            let uses = drift
+                .uses
+                .iter()
+                .map(|member_use| {
+                    (
+                        member_use.member,
+                        member_use.requirement.as_str(),
+                        member_use.default_features,
+                    )
+                })
+                .collect::<Vec<_>>();
+            (drift.name.as_str(), drift.requirements(), uses)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                "serde",
+                2,
+                vec![(NodeId(1), "^1.0", true), (NodeId(0), "^1.0.200", true)]
+            ),
+            (
+                "tokio",
+                1,
+                vec![(NodeId(1), "^1", true), (NodeId(0), "^1", false)]
+            ),
+        ]
+    );
+    assert_eq!(drifts[1].uses[1].features, ["rt"]);
+    assert_eq!(drifts[1].uses[1].dependency, NodeId(4));
+}
+
+#[test]
+fn target_nodes_of_workspace_members() {
+    let mut tree = DependencyTree::load(Some(project_manifest())).unwrap();
+    let root = tree.roots()[0];
+    let member = tree.node(root).unwrap().as_dependency().unwrap();
+    let targets: Vec<(TargetKind, &str)> = member
+        .targets
+        .iter()
+        .map(|target| (target.kind, target.name.as_str()))
+        .take(2)
+        .collect();
+    assert_eq!(
+        targets,
+        [
+            (TargetKind::Lib, "cargo_tree_tui"),
+            (TargetKind::Bin, "cargo-tree-tui")
+        ]
+    );
+
+    let crates = tree.crate_nodes().count();
+    let target_count = member.targets.len();
+    tree.add_target_nodes();
+    tree.validate().unwrap();
+    assert_eq!(tree.crate_nodes().count(), crates);
+
+    let children = tree.node(root).unwrap().children();
+    let first = tree.node(children[0]).unwrap().as_target().unwrap();
+    assert_eq!(first.kind, TargetKind::Lib);
+    assert!(
+        children[..target_count]
+            .iter()
+            .all(|&id| tree.node(id).unwrap().as_target().is_some())
+    );
+    assert!(!tree.transitive_dependencies(root).contains(&children[0]));
+}
+
+#[test]
+fn workspace_only_keeps_member_edges() {
+    let dependency = |name: &str, children: Vec<NodeId>| {
+        DependencyNode::Crate(Dependency {
+            name: name.into(),
+            version: "0.1.0".into(),
+            manifest_dir: None,
+            is_proc_macro: false,
+            source: SourceKind::Path,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
+            children,
+        })
+    };
+    // 0 app -> [1 core, 2 serde, 3 [dev-dependencies]]
+    // 1 core -> [2 serde]
+    // 3 [dev-dependencies] -> [4 proptest]
+    let nodes = vec![
+        dependency("app", vec![NodeId(1), NodeId(2), NodeId(3)]),
+        dependency("core", vec![NodeId(2)]),
+        dependency("serde", Vec::new()),
+        DependencyNode::Group(DependencyGroup {
+            kind: DependencyType::Dev,
+            children: vec![NodeId(4)],
+        }),
+        dependency("proptest", Vec::new()),
+    ];
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        parents: vec![
+            vec![],
+            vec![NodeId(0)],
+            vec![NodeId(0), NodeId(1)],
+            vec![NodeId(0)],
+            vec![NodeId(3)],
+        ],
+        nodes,
+        roots: vec![NodeId(0), NodeId(1)],
+    };
+    tree.validate().unwrap();
+
+    let members = tree.workspace_only();
+    members.validate().unwrap();
+    let names: Vec<&str> = members
+        .nodes
+        .iter()
+        .map(DependencyNode::display_name)
+        .collect();
+    assert_eq!(names, ["app", "core"]);
+    assert_eq!(members.roots(), [NodeId(0), NodeId(1)]);
+    assert_eq!(members.node(NodeId(0)).unwrap().children(), [NodeId(1)]);
+    assert_eq!(members.dependents(NodeId(1)), [NodeId(0)]);
+}
+
+#[test]
+fn rooted_at_keeps_reachable_nodes() {
+    let dependency = |name: &str, children: Vec<NodeId>| {
+        DependencyNode::Crate(Dependency {
+            name: name.into(),
+            version: "0.1.0".into(),
+            manifest_dir: None,
+            is_proc_macro: false,
+            source: SourceKind::Path,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
+            children,
+        })
+    };
+    // 0 app -> [1 core, 2 [dev-dependencies]]
+    // 1 core -> [3 serde]
+    // 2 [dev-dependencies] -> [3 serde]
+    // 3 serde -> [4 serde_derive]
+    let nodes = vec![
+        dependency("app", vec![NodeId(1), NodeId(2)]),
+        dependency("core", vec![NodeId(3)]),
+        DependencyNode::Group(DependencyGroup {
+            kind: DependencyType::Dev,
+            children: vec![NodeId(3)],
+        }),
+        dependency("serde", vec![NodeId(4)]),
+        dependency("serde_derive", Vec::new()),
+    ];
+    let tree = DependencyTree {
+        workspace_name: "app".into(),
+        parents: vec![
+            vec![],
+            vec![NodeId(0)],
+            vec![NodeId(0)],
+            vec![NodeId(1), NodeId(2)],
+            vec![NodeId(3)],
+        ],
+        nodes,
+        roots: vec![NodeId(0), NodeId(1)],
+    };
+    tree.validate().unwrap();
+
+    let focused = tree.rooted_at(NodeId(1));
+    focused.validate().unwrap();
+    let names: Vec<&str> = focused
+        .nodes
+        .iter()
+        .map(DependencyNode::display_name)
+        .collect();
+    assert_eq!(names, ["core", "serde", "serde_derive"]);
+    assert_eq!(focused.roots(), [NodeId(0)]);
+    // Only edges inside the subtree are kept.
+    assert_eq!(focused.dependents(NodeId(1)), [NodeId(0)]);
+    assert_eq!(focused.workspace_name, "app");
+
+    let group = tree.rooted_at(NodeId(2));
+    assert_eq!(group.nodes.len(), 3);
+    assert_eq!(group.node(NodeId(0)).unwrap().children(), [NodeId(1)]);
+}
+
+#[test]
+fn layer_rules() {
+    let rules = LayerRules::parse(&[
+        "* -> core allowed",
+        "core -> * forbidden",
+        "core -> util allowed",
+        "app -> cli forbidden",
+    ])
+    .unwrap();
+    assert!(rules.allows("app", "core"));
+    assert!(!rules.allows("core", "app"));
+    assert!(rules.allows("core", "util"));
+    assert!(!rules.allows("app", "cli"));
+    assert!(rules.allows("cli", "app"));
+    // Same specificity: the later rule wins.
+    assert!(!rules.allows("core", "core"));
+
+    for invalid in [
+        "app core allowed",
+        "app -> core",
+        "app -> core maybe",
+        "-> core allowed",
+    ] {
+        assert!(matches!(
+            LayerRules::parse(&[invalid]),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    let dir = std::env::temp_dir().join(format!("layer-rules-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("app")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\"]\n\n[workspace.metadata.tree-tui]\nlayers = [\"app -> core forbidden\"]\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("app/Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+    let loaded = LayerRules::load(&dir.join("app/Cargo.toml")).unwrap();
+    assert_eq!(
+        loaded,
+        LayerRules::parse(&["app -> core forbidden"]).unwrap()
+    );
+    assert!(LayerRules::load(&project_manifest()).unwrap().is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn startup_profiles_expand_to_flags() {
+    let dir = std::env::temp_dir().join(format!("profiles-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("app")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        r#"[workspace]
+members = ["app"]
+
+[workspace.metadata.tree-tui.profile.security]
+trust = true
+online = false
+edges = ["no-dev", "no-build"]
+depth = 2
+
+[workspace.metadata.tree-tui.profile.broken]
+depth = 1.5
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("app/Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+    let manifest = dir.join("app/Cargo.toml");
+    assert_eq!(
+        profile_args(&manifest, "security").unwrap(),
+        [
+            "--trust", "--edges", "no-dev", "--edges", "no-build", "--depth", "2"
+        ]
+    );
+    for name in ["broken", "missing"] {
+        assert!(matches!(
+            profile_args(&manifest, name),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn annotators_merge_per_crate() {
+    assert_eq!(
+        parse_annotation("approved\nreviewer: alice\nnot a field\n"),
+        Annotation {
+            badge: Some("approved".into()),
+            fields: vec![("reviewer".into(), "alice".into())],
+        }
+    );
+    assert_eq!(parse_annotation("\n").badge, None);
+
+    let mut annotators = Annotators::default();
+    annotators.register(|dependency: &Dependency| {
+        (dependency.name == "b").then(|| Annotation {
+            badge: Some("internal".into()),
+            fields: Vec::new(),
+        })
+    });
+    annotators.register(|dependency: &Dependency| {
+        Some(Annotation {
+            badge: (dependency.name == "b").then(|| "approved".into()),
+            fields: vec![("owner".into(), format!("team-{}", dependency.name))],
+        })
+    });
+    let annotations = annotators.annotate_tree(&cyclic_tree());
+    let b = &annotations[&("b".to_string(), "0.1.0".to_string())];
+    assert_eq!(b.badge.as_deref(), Some("internal approved"));
+    assert_eq!(b.fields, [("owner".to_string(), "team-b".to_string())]);
+    assert_eq!(annotations.len(), cyclic_tree().crate_nodes().count());
+}
+
+#[test]
+fn node_commands_fill_placeholders() {
+    let dir = std::env::temp_dir().join(format!("node-commands-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[package.metadata.tree-tui.commands]\na = \"cargo audit -p {name}@{version}\"\n",
+    )
+    .unwrap();
+    let commands = NodeCommands::load(&dir.join("Cargo.toml")).unwrap();
+    assert_eq!(
+        commands.get('a'),
+        Some(&NodeCommand {
+            key: 'a',
+            template: "cargo audit -p {name}@{version}".into(),
+        })
+    );
+    assert!(commands.get('b').is_none());
+
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[package.metadata.tree-tui.commands]\nab = \"true\"\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        NodeCommands::load(&dir.join("Cargo.toml")),
+        Err(Error::InvalidConfig(_))
+    ));
+    assert!(NodeCommands::load(&project_manifest()).unwrap().is_empty());
+
+    let mut member = crate_node("my app", &[]);
+    if let DependencyNode::Crate(dependency) = &mut member {
+        dependency.version = "0.1.0".into();
+        dependency.manifest_dir = Some(dir.display().to_string());
+    }
+    let command = NodeCommand {
+        key: 'o',
+        template: "open {manifest_dir} --crate {name}".into(),
+    };
+    assert_eq!(
+        command.expand(member.as_dependency().unwrap()),
+        ["open", &dir.display().to_string(), "--crate", "my app"]
+    );
+    let command = NodeCommand {
+        key: 'g',
+        template: r#"git log --grep "bump {name}" -- 'a b'/c\ d"#.into(),
+    };
+    assert_eq!(
+        command.expand(member.as_dependency().unwrap()),
+        ["git", "log", "--grep", "bump my app", "--", "a b/c d"]
+    );
+
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[package.metadata.tree-tui.commands]\na = \"echo 'oops\"\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        NodeCommands::load(&dir.join("Cargo.toml")),
+        Err(Error::InvalidConfig(_))
+    ));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn remote_crate_probe_project() {
+    let remote: RemoteCrate = "b@0.1".parse().unwrap();
+    assert_eq!(remote.version.as_deref(), Some("0.1"));
+    assert_eq!("tokio".parse::<RemoteCrate>().unwrap().version, None);
+    for invalid in ["", "@1", "a b", "serde@one"] {
+        assert!(invalid.parse::<RemoteCrate>().is_err(), "{invalid}");
+    }
+
+    let manifest = remote.create_project().unwrap();
+    let contents = std::fs::read_to_string(&manifest).unwrap();
+    assert!(contents.contains("[dependencies]\nb = \"0.1\"\n"));
+    assert!(manifest.with_file_name("src").join("lib.rs").is_file());
+    assert!(manifest.starts_with(cache_root().unwrap()));
+    // A stale lockfile is dropped so that newer versions are picked up.
+    std::fs::write(manifest.with_file_name("Cargo.lock"), "").unwrap();
+    assert_eq!(remote.create_project().unwrap(), manifest);
+    assert!(!manifest.with_file_name("Cargo.lock").exists());
+    std::fs::remove_dir_all(manifest.parent().unwrap()).unwrap();
+
+    // app -> a -> b; only the probe's direct dependency is picked.
+    let tree = cyclic_tree();
+    assert!(remote.root_of(&tree).is_err());
+    let remote: RemoteCrate = "a".parse().unwrap();
+    let rooted = remote.root_of(&tree).unwrap();
+    assert_eq!(rooted.workspace_name, "a");
+    let root = rooted.node(rooted.roots()[0]).unwrap();
+    assert_eq!(root.as_dependency().unwrap().name, "a");
+}
+
+#[test]
+fn build_cost_estimate() {
+    let mut tree = cyclic_tree();
+    if let DependencyNode::Crate(c) = &mut tree.nodes[3] {
+        c.is_proc_macro = true;
+        c.targets = vec![
+            CrateTarget {
+                kind: TargetKind::Lib,
+                name: "c".into(),
+            },
+            CrateTarget {
+                kind: TargetKind::BuildScript,
+                name: "build-script-build".into(),
+            },
+            CrateTarget {
+                kind: TargetKind::Example,
+                name: "demo".into(),
+            },
+        ];
+    }
+
+    // 20 points per unit, plus one per KiB of sources.
+    let cost = BuildCost::from_sizes(&tree, &[0, 10 * 1024, 0, 4 * 1024]);
+    // c: (lib + build script run) * 20 + 4, doubled as a proc-macro.
+    assert_eq!(cost.own, [20, 30, 20, 128]);
+    assert_eq!(cost.total, 198);
+    // The a <-> b cycle is counted once.
+    assert_eq!(cost.subtree, [198, 178, 178, 128]);
+    assert_eq!(cost.subtree_share(NodeId(0)), Some(100.0));
+    assert!((cost.subtree_share(NodeId(3)).unwrap() - 64.6).abs() < 0.1);
+
+    let own = BuildCost::estimate(&DependencyTree::load(Some(project_manifest())).unwrap());
+    assert!(own.total > 0);
+}
+
+#[test]
+fn bloat_report_sizes_subtrees() {
+    let by_crate = r#"{"file-size": 4096, "text-section-size": 1000,
+        "crates": [{"name": "std", "size": 600}, {"name": "a", "size": 100},
+                   {"name": "c", "size": 300}]}"#;
+    let report = BloatReport::parse(by_crate).unwrap();
+    assert_eq!((report.file_size, report.text_size), (4096, 1000));
+    let by_function = r#"{"file-size": 4096, "text-section-size": 1000,
+        "functions": [{"crate": "c", "name": "c::f", "size": 200},
+                      {"crate": "c", "name": "c::g", "size": 100},
+                      {"name": "[Unknown]", "size": 50}]}"#;
+    assert_eq!(BloatReport::parse(by_function).unwrap().crates["c"], 300);
+    let err = BloatReport::parse("{}").unwrap_err();
+    assert!(matches!(err, Error::InvalidResponse { .. }));
+    assert!(std::error::Error::source(&err).is_some());
+
+    let size = BinarySize::attribute(&cyclic_tree(), &report);
+    assert_eq!(size.own, [0, 100, 0, 300]);
+    // The a <-> b cycle is counted once.
+    assert_eq!(size.subtree, [400, 400, 400, 300]);
+    assert_eq!(size.subtree_share(NodeId(3)), Some(30.0));
+    assert_eq!(format_size(300), "300 B");
+    assert_eq!(format_size(1536 * 1024), "1.5 MiB");
+}
+
+#[test]
+fn workspace_state_round_trip() {
+    let mut history = Vec::new();
+    for query in ["serde", "tokio", "serde"] {
+        push_search(&mut history, query);
+    }
+    assert_eq!(history, ["tokio", "serde"]);
+    for idx in 0..MAX_SEARCH_HISTORY {
+        push_search(&mut history, &idx.to_string());
+    }
+    assert_eq!(history.len(), MAX_SEARCH_HISTORY);
+    assert_eq!(history[0], "0");
+
+    let dir = std::env::temp_dir().join(format!("workspace-state-{}", std::process::id()));
+    let path = dir.join("state.json");
+    let mut state = WorkspaceState::read(&path);
+    assert!(state.search_history.is_empty());
+    state.search_history = vec!["serde".to_string()];
+    assert!(state.toggle_pin("tokio"));
+    assert!(state.toggle_pin("rand"));
+    assert!(!state.toggle_pin("tokio"));
+    state.set_note("rand", "  replace with fastrand ");
+    state.set_note("tokio", "approved 2024-05");
+    state.set_note("tokio", "");
+    state.save().unwrap();
+    let loaded = WorkspaceState::read(&path);
+    assert_eq!(loaded.search_history, ["serde"]);
+    assert_eq!(loaded.pins, ["rand"]);
+    assert_eq!(loaded.note("rand"), Some("replace with fastrand"));
+    assert_eq!(loaded.note("tokio"), None);
+
+    // A corrupt file starts over instead of failing.
+    std::fs::write(&path, "{").unwrap();
+    assert!(WorkspaceState::read(&path).search_history.is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
 #[test]
-fn dependency_type_styles_are_distinct() {
-    let normal = DependencyType::Normal.style();
-    let dev = DependencyType::Dev.style();
-    let build = DependencyType::Build.style();
+fn upgrade_preview_leaves_lockfile_alone() {
+    let dir = std::env::temp_dir().join(format!(
+        "cargo-tree-tui-upgrade-test-{}",
+        std::process::id()
+    ));
+    for (path, contents) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             [dependencies]\nutil = { path = \"util\" }\n",
+        ),
+        ("src/lib.rs", ""),
+        (
+            "util/Cargo.toml",
+            "[package]\nname = \"util\"\nversion = \"0.2.1\"\nedition = \"2021\"\n",
+        ),
+        ("util/src/lib.rs", ""),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    let manifest = dir.join("Cargo.toml");
+    DependencyTree::load(Some(manifest.clone())).unwrap();
+    let lockfile = std::fs::read_to_string(dir.join("Cargo.lock")).unwrap();
+
+    let tree =
+        DependencyTree::load_upgraded(Some(manifest.clone()), "util@0.2.1", "0.2.1").unwrap();
+    assert_eq!(tree.crate_nodes().count(), 2);
+    assert!(matches!(
+        DependencyTree::load_upgraded(Some(manifest), "missing@1.0.0", "1.0.1"),
+        Err(Error::MetadataFailed { .. })
+    ));
+    let scratch =
+        std::env::temp_dir().join(format!("cargo-tree-tui-upgrade-{}", std::process::id()));
+    assert!(!scratch.exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("Cargo.lock")).unwrap(),
+        lockfile
+    );
 
-    assert_ne!(normal, dev);
-    assert_ne!(normal, build);
-    assert_ne!(dev, build);
+    std::fs::remove_dir_all(&dir).unwrap();
 }
@@ -1,11 +1,47 @@
 mod common;
 
-use cargo_tree_tui::core::NodeId;
-use cargo_tree_tui::core::dependency::DependencyType;
-use cargo_tree_tui::ops::tree::tui::widget::TreeWidgetState;
-use common::{TestNode, TestNodeKind, build_tree, render_tree_context, render_tree_widget};
+use cargo_tree_tui::core::commands::NodeCommands;
+use cargo_tree_tui::core::dependency::{DeclaredDependency, DependencyType, SourceKind};
+use cargo_tree_tui::core::diff::CrateChange;
+use cargo_tree_tui::core::export::GraphFormat;
+use cargo_tree_tui::core::layers::LayerRules;
+use cargo_tree_tui::core::{DependencyNode, DependencyTree, NodeId};
+use cargo_tree_tui::ops::tree::tui::command::{
+    CancelToken, Loader, Resolve, shell_line, spawn_cargo, spawn_resolve,
+};
+use cargo_tree_tui::ops::tree::tui::command_line::ViewCommand;
+use cargo_tree_tui::ops::tree::tui::control::ControlCommand;
+use cargo_tree_tui::ops::tree::tui::crash::{CrashReport, WorkspaceSummary};
+use cargo_tree_tui::ops::tree::tui::debug_log::LogBuffer;
+use cargo_tree_tui::ops::tree::tui::export::tree_ansi;
+use cargo_tree_tui::ops::tree::tui::filter::{Filter, FilterBar, FilterFacts, FilterSet};
+use cargo_tree_tui::ops::tree::tui::help::{KeyBinding, keymap};
+use cargo_tree_tui::ops::tree::tui::markdown::markdown_lines;
+use cargo_tree_tui::ops::tree::tui::options::{ExpandPolicy, ScrollbarSide, TuiOptions};
+use cargo_tree_tui::ops::tree::tui::pager::{Pager, PagerState};
+use cargo_tree_tui::ops::tree::tui::path::PathView;
+use cargo_tree_tui::ops::tree::tui::state::Event;
+use cargo_tree_tui::ops::tree::tui::stats::{SortColumn, StatsScreen, StatsView};
+use cargo_tree_tui::ops::tree::tui::versions::VersionsView;
+use cargo_tree_tui::ops::tree::tui::widget::render::{RenderContext, RenderOutput};
+use cargo_tree_tui::ops::tree::tui::widget::{
+    BreadcrumbElision, CHILD_PAGE, IndentWidth, NodeIcons, SearchIndex, Theme, TreeWidget,
+    TreeWidgetState, TreeWidgetStyle, dependency_type_style,
+};
+use clap_cargo::style::LITERAL;
+use common::{
+    TestNode, TestNodeKind, build_tree, crate_node, render_tree_context,
+    render_tree_context_with_style, render_tree_widget,
+};
+use crossterm::event::KeyCode;
 use pretty_assertions::assert_eq;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Scrollbar, ScrollbarOrientation, StatefulWidget, Widget};
 
 #[test]
 fn basic() {
@@ -48,6 +84,163 @@ root
     assert_eq!(expected.trim(), tree_str.trim());
 }
 
+#[test]
+fn indent_width_sets_guide_width() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let render = |width: &str| {
+        let style = TreeWidgetStyle::default().indented(width.parse().unwrap());
+        render_tree_context_with_style(&tree, style)
+    };
+
+    assert_eq!(render("2").trim(), "root\n├─▾ a\n│ └─• c\n└─• b");
+    assert_eq!(render("3"), render_tree_context(&tree));
+    assert_eq!(render("4").trim(), "root\n├───▾ a\n│   └───• c\n└───• b");
+    assert!("5".parse::<IndentWidth>().is_err());
+}
+
+#[test]
+fn clean_versions_keep_ambiguous_ones() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for (node, version) in tree
+        .nodes
+        .iter_mut()
+        .zip(["0.1.0", "1.0.0", "1.0.0", "2.0.0"])
+    {
+        if let DependencyNode::Crate(dependency) = node {
+            dependency.version = version.into();
+        }
+    }
+    assert_eq!(tree.duplicated_crates(), [NodeId(1), NodeId(3)]);
+
+    let style = TreeWidgetStyle {
+        ambiguous_versions_only: true,
+        ..TreeWidgetStyle::default()
+    };
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_duplicated(tree.duplicated_crates().into_iter().collect());
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 6));
+    let lines: Vec<String> = output.lines.iter().map(|line| line.to_string()).collect();
+    assert_eq!(
+        lines.join("\n").trim(),
+        "root\n├──• a v1.0.0\n├──• b\n└──• a v2.0.0"
+    );
+}
+
+#[test]
+fn hidden_groups_badge_their_crates() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 4],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "dev",
+            parent: Some(0),
+            children: &[3, 1],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "b",
+            parent: Some(2),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "build",
+            parent: Some(0),
+            children: &[5],
+            kind: TestNodeKind::Group(DependencyType::Build),
+        },
+        TestNode {
+            name: "c",
+            parent: Some(4),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let (tree, kinds) = build_tree(&nodes).without_groups();
+    tree.validate().unwrap();
+    assert_eq!(
+        tree.node(NodeId(0)).unwrap().children(),
+        [1, 3, 5].map(NodeId)
+    );
+    // `a` is also a normal dependency, which wins.
+    assert_eq!(kinds.get(&(NodeId(0), NodeId(1))), None);
+    assert_eq!(tree.dependents(NodeId(3)), [NodeId(0)]);
+
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_edge_kinds(kinds);
+    let style = TreeWidgetStyle::default();
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 6));
+    let lines: Vec<String> = output.lines.iter().map(|line| line.to_string()).collect();
+    assert_eq!(
+        lines.join("\n").trim(),
+        "root\n├──• a\n├──• b dev\n└──• c build"
+    );
+}
+
 #[test]
 fn root_dev_dependencies_header() {
     let nodes = [
@@ -352,6 +545,83 @@ root → a → b → … → g
     assert_eq!(expected.trim(), output.trim());
 }
 
+#[test]
+fn smooth_scroll_lands_over_a_few_frames() {
+    let children: &'static [usize] = Vec::leak((1..=100).collect());
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children,
+        kind: TestNodeKind::Crate,
+    }];
+    nodes.extend((1..=100).map(|_| TestNode {
+        name: "leaf",
+        parent: Some(0),
+        children: &[],
+        kind: TestNodeKind::Crate,
+    }));
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+    let mut offsets = Vec::new();
+    for frame in 0..6 {
+        if frame == 1 {
+            state.set_selected_node_id(&tree, NodeId(90));
+        }
+        terminal
+            .draw(|frame| {
+                TreeWidget::new(&tree).smooth_scroll(true).render(
+                    frame.area(),
+                    frame.buffer_mut(),
+                    &mut state,
+                );
+            })
+            .unwrap();
+        offsets.push(state.viewport.offset);
+    }
+    let landed = *offsets.last().unwrap();
+    assert_eq!(offsets[0], 0);
+    assert!(offsets[1] > 0 && offsets[1] < offsets[2] && offsets[2] < offsets[3]);
+    assert!(offsets[3] < landed);
+    assert_eq!(offsets[4], landed);
+}
+
+#[test]
+fn center_selection_scrolls_it_to_the_middle() {
+    let children: &'static [usize] = Vec::leak((1..=100).collect());
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children,
+        kind: TestNodeKind::Crate,
+    }];
+    nodes.extend((1..=100).map(|_| TestNode {
+        name: "leaf",
+        parent: Some(0),
+        children: &[],
+        kind: TestNodeKind::Crate,
+    }));
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let area = Rect::new(0, 0, 40, 10);
+
+    state.set_selected_node_id(&tree, NodeId(50));
+    render_tree_widget(&tree, &mut state, area);
+    assert_eq!(state.viewport.offset, 43);
+
+    state.set_selected_node_id(&tree, NodeId(80));
+    state.center_selection();
+    render_tree_widget(&tree, &mut state, area);
+    assert_eq!(state.viewport.offset, 76);
+    // Only the next frame is centered.
+    state.select_next(&tree);
+    render_tree_widget(&tree, &mut state, area);
+    assert_eq!(state.viewport.offset, 76);
+}
+
 #[test]
 fn context_bar_when_scrolled() {
     let nodes = [
@@ -628,6 +898,16 @@ fn large_dag_no_oom() {
         version: "0.1.0".into(),
         manifest_dir: None,
         is_proc_macro: false,
+        source: SourceKind::Registry,
+        registry: None,
+        license: None,
+        repository: None,
+        targets: Vec::new(),
+        features: Vec::new(),
+        default_features: true,
+        has_default_features: false,
+        feature_requests: Box::default(),
+        declared: Vec::new(),
         children: root_children,
     }));
 
@@ -638,6 +918,16 @@ fn large_dag_no_oom() {
             version: "0.1.0".into(),
             manifest_dir: None,
             is_proc_macro: false,
+            source: SourceKind::Registry,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
             children: b_children.clone(),
         }));
     }
@@ -649,6 +939,16 @@ fn large_dag_no_oom() {
             version: "0.1.0".into(),
             manifest_dir: None,
             is_proc_macro: false,
+            source: SourceKind::Registry,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
             children: c_children.clone(),
         }));
     }
@@ -659,6 +959,16 @@ fn large_dag_no_oom() {
             version: "0.1.0".into(),
             manifest_dir: None,
             is_proc_macro: false,
+            source: SourceKind::Registry,
+            registry: None,
+            license: None,
+            repository: None,
+            targets: Vec::new(),
+            features: Vec::new(),
+            default_features: true,
+            has_default_features: false,
+            feature_requests: Box::default(),
+            declared: Vec::new(),
             children: Vec::new(),
         }));
     }
@@ -688,6 +998,14 @@ fn large_dag_no_oom() {
     }
     state.ensure_visible_nodes(&tree);
     assert!(state.selected_node_id().is_some());
+
+    // First occurrences sit under a0; earlier b subtrees are skipped whole.
+    state.set_selected_node_id(&tree, NodeId(30));
+    assert_eq!(state.selected_virtual_pos().map(|pos| pos.0), Some(12));
+    state.set_selected_node_id(&tree, NodeId(20));
+    assert_eq!(state.selected_virtual_pos().map(|pos| pos.0), Some(101));
+    state.set_selected_node_id(&tree, NodeId(10));
+    assert_eq!(state.selected_virtual_pos().map(|pos| pos.0), Some(1000));
 }
 
 /// set_selected_node_id locates a node by its first virtual position.
@@ -764,7 +1082,7 @@ fn cyclic_tree_view_is_finite() {
     // as a leaf, so the visible tree unrolls to exactly:
     //   a            (size 1 + size(b) = 3)
     //   └─ b         (size 1 + size(a as leaf) = 2)
-    //      └─ a      (cycle break, counted as 1)
+    //      └─ a      (cycle break, counted as 1, drawn as a leaf)
     assert_eq!(state.total_lines(&tree), 3);
 
     // Render the materialized window: the cyclic tree must unroll once,
@@ -779,7 +1097,2629 @@ fn cyclic_tree_view_is_finite() {
     let tree_rows: Vec<&str> = rendered.lines().take(3).collect();
     assert_eq!(
         tree_rows,
-        vec!["a", "└──▾ b", "   └──▾ a"],
+        vec!["a", "└──▾ b", "   └──• a (cycle)"],
         "full render:\n{rendered}"
     );
 }
+
+#[test]
+fn gutter_icons() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "[dev-dependencies]",
+            parent: Some(0),
+            children: &[3],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "b",
+            parent: Some(2),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let icons = NodeIcons::nerd_font();
+    let style = TreeWidgetStyle {
+        icons: Some(icons),
+        ..TreeWidgetStyle::default()
+    };
+
+    let expected = format!(
+        "{} root\n{} └──• a\n{} [dev-dependencies]\n{} └──• b",
+        icons.workspace_member, icons.crate_icon, icons.group, icons.crate_icon
+    );
+
+    let tree = build_tree(&nodes);
+    assert_eq!(
+        expected,
+        render_tree_context_with_style(&tree, style).trim()
+    );
+}
+
+#[test]
+fn wrap_long_rows_with_hanging_indent() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "abcdefghijklmnopqrstuvwxyz",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let area = Rect {
+        x: 0,
+        y: 0,
+        width: 16,
+        height: 24,
+    };
+
+    let style = TreeWidgetStyle::default();
+    let output = RenderContext::new(&tree, &mut state, &style, None)
+        .wrap(true)
+        .render(area);
+    let rendered = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let expected = r#"
+root
+├──▾ abcdefghijk
+│  │ lmnopqrstuv
+│  │ wxyz
+│  └──• c
+└──• b
+"#;
+    assert_eq!(expected.trim(), rendered.trim());
+}
+
+#[test]
+fn wrap_rows_at_word_boundaries() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "proc-macro-error_attr",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let area = Rect {
+        x: 0,
+        y: 0,
+        width: 16,
+        height: 24,
+    };
+
+    let style = TreeWidgetStyle::default();
+    let output = RenderContext::new(&tree, &mut state, &style, None)
+        .wrap(true)
+        .render(area);
+    let rendered = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let expected = r#"
+root
+└──• proc-macro-
+     error_attr
+"#;
+    assert_eq!(expected.trim(), rendered.trim());
+}
+
+#[test]
+fn wrapped_rows_count_towards_viewport() {
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children: &[1, 2, 3, 4, 5, 6],
+        kind: TestNodeKind::Crate,
+    }];
+    for name in [
+        "first-long-name",
+        "second-long-name",
+        "third-long-name",
+        "fourth-long-name",
+        "fifth-long-name",
+        "sixth-long-name",
+    ] {
+        nodes.push(TestNode {
+            name,
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        });
+    }
+
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.select_next(&tree);
+    state.select_last_sibling(&tree);
+
+    let area = Rect {
+        x: 0,
+        y: 0,
+        width: 14,
+        height: 6,
+    };
+
+    let style = TreeWidgetStyle::default();
+    let output = RenderContext::new(&tree, &mut state, &style, None)
+        .wrap(true)
+        .render(area);
+    let rendered = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // Every child wraps to two rows, so the view is taller than its lines.
+    assert_eq!(output.total_lines, 13);
+    assert_eq!(output.viewport.offset, 9);
+    let expected = r#"
+├──• fifth-
+│    long-name
+└──• sixth-
+     long-name
+"#;
+    assert!(rendered.ends_with(expected.trim()), "{rendered}");
+
+    state.page_up(&tree);
+    let output = RenderContext::new(&tree, &mut state, &style, None)
+        .wrap(true)
+        .render(area);
+    let id = state.selected_node_id().unwrap();
+    assert_eq!(tree.node(id).unwrap().display_name(), "fourth-long-name");
+    assert!(output.viewport.offset <= 7);
+}
+
+#[test]
+fn dependents_suffix() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let style = TreeWidgetStyle {
+        show_dependents: true,
+        ..TreeWidgetStyle::default()
+    };
+
+    let expected = r#"
+root
+├──▾ a (used by 1)
+│  └──• b (used by 2)
+└──• b (used by 2)
+"#;
+
+    let tree = build_tree(&nodes);
+    assert_eq!(
+        expected.trim(),
+        render_tree_context_with_style(&tree, style).trim()
+    );
+}
+
+#[test]
+fn selected_direct_dependency_of_member() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "[dev-dependencies]",
+            parent: Some(0),
+            children: &[4],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "c",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "d",
+            parent: Some(2),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let direct_of = |state: &mut TreeWidgetState, id| {
+        state.set_selected_node_id(&tree, NodeId(id));
+        state.ensure_visible_nodes(&tree);
+        state.selected_direct_dependency_of(&tree)
+    };
+
+    assert_eq!(
+        direct_of(&mut state, 1),
+        Some((NodeId(0), DependencyType::Normal))
+    );
+    assert_eq!(
+        direct_of(&mut state, 4),
+        Some((NodeId(0), DependencyType::Dev))
+    );
+    assert_eq!(direct_of(&mut state, 3), None);
+    assert_eq!(direct_of(&mut state, 0), None);
+}
+
+#[test]
+fn markdown_lines_styling() {
+    let text = "[![ci](badge)](link)\n# Title\n\nUse `foo` **now**.\n  - item\n```rust\nlet x = 1;\n```\n> note";
+    let lines = markdown_lines(text);
+    let plain = lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        plain,
+        vec![
+            "Title",
+            "",
+            "Use foo now.",
+            "  • item",
+            "    let x = 1;",
+            "│ note"
+        ]
+    );
+    assert!(lines[0].style.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(lines[2].spans[1].style, Style::from(LITERAL));
+    assert!(
+        lines[2].spans[3]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD)
+    );
+}
+
+#[test]
+fn initial_expansion_policies() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "dev",
+            parent: Some(0),
+            children: &[4],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "b",
+            parent: Some(3),
+            children: &[5],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "d",
+            parent: Some(4),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let visible = |state: &mut TreeWidgetState| {
+        state
+            .visible_nodes(&tree)
+            .iter()
+            .map(|node| tree.node(node.id).unwrap().display_name().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let mut state = TreeWidgetState::default();
+    state.expand_members(&tree);
+    assert_eq!(
+        visible(&mut state),
+        vec!["root", "a", "[dev-dependencies]", "b"]
+    );
+
+    let mut state = TreeWidgetState::default();
+    state.collapse_all(&tree);
+    assert_eq!(visible(&mut state), vec!["root"]);
+
+    let mut state = TreeWidgetState::default();
+    state.open_to_depth(&tree, 3);
+    assert_eq!(
+        visible(&mut state),
+        vec!["root", "a", "c", "[dev-dependencies]", "b"]
+    );
+
+    // Fitting stops before the level that would overflow the rows.
+    let mut state = TreeWidgetState::default();
+    assert_eq!(state.open_to_fit(&tree, 4), 2);
+    assert_eq!(visible(&mut state), vec!["root", "a", "[dev-dependencies]"]);
+    assert_eq!(state.open_to_fit(&tree, 5), 3);
+    assert_eq!(visible(&mut state).len(), 5);
+    assert_eq!(state.open_to_fit(&tree, 2), 1);
+    assert_eq!(visible(&mut state), vec!["root"]);
+    // A tree shorter than the rows is opened all the way.
+    state.open_to_fit(&tree, 100);
+    assert_eq!(visible(&mut state).len(), 6);
+}
+
+#[test]
+fn parent_jump_returns_to_last_visited_child() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[4],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "d",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let selected = |state: &mut TreeWidgetState| {
+        state.ensure_visible_nodes(&tree);
+        let id = state.selected_node_id().unwrap();
+        tree.node(id).unwrap().display_name().to_string()
+    };
+
+    // root, a, d, b, c: go to `c`, jump up and back down.
+    for _ in 0..4 {
+        state.select_next(&tree);
+    }
+    assert_eq!(selected(&mut state), "c");
+    state.select_parent(&tree);
+    assert_eq!(selected(&mut state), "root");
+    state.expand(&tree);
+    assert_eq!(selected(&mut state), "c");
+
+    // Parents without history still descend into their first child.
+    state.select_previous_sibling(&tree);
+    state.select_previous_sibling(&tree);
+    assert_eq!(selected(&mut state), "a");
+    state.expand(&tree);
+    assert_eq!(selected(&mut state), "d");
+}
+
+#[test]
+fn sibling_jumps_and_wrap_around() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3, 4],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a1",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let selected = |state: &mut TreeWidgetState| {
+        state.ensure_visible_nodes(&tree);
+        let id = state.selected_node_id().unwrap();
+        tree.node(id).unwrap().display_name().to_string()
+    };
+
+    state.select_next(&tree);
+    state.select_last_sibling(&tree);
+    assert_eq!(selected(&mut state), "c");
+    state.select_next_sibling(&tree);
+    assert_eq!(selected(&mut state), "c");
+    state.select_first_sibling(&tree);
+    assert_eq!(selected(&mut state), "a");
+    state.select_previous_sibling(&tree);
+    assert_eq!(selected(&mut state), "a");
+
+    state.wrap_siblings = true;
+    state.select_previous_sibling(&tree);
+    assert_eq!(selected(&mut state), "c");
+    state.select_next_sibling(&tree);
+    assert_eq!(selected(&mut state), "a");
+    state.select_next_sibling(&tree);
+    assert_eq!(selected(&mut state), "b");
+}
+
+#[test]
+fn workspace_member_jumps() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "lib",
+            parent: None,
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let selected = |state: &mut TreeWidgetState| {
+        state.ensure_visible_nodes(&tree);
+        let id = state.selected_node_id().unwrap();
+        tree.node(id).unwrap().display_name().to_string()
+    };
+
+    state.select_next(&tree);
+    state.select_next(&tree);
+    assert_eq!(selected(&mut state), "b");
+    state.select_next_member(&tree);
+    assert_eq!(selected(&mut state), "lib");
+    state.select_next_member(&tree);
+    assert_eq!(selected(&mut state), "lib");
+    state.select_previous_member(&tree);
+    assert_eq!(selected(&mut state), "app");
+
+    // From inside a member, the first jump back lands on that member.
+    state.select_next(&tree);
+    state.select_previous_member(&tree);
+    assert_eq!(selected(&mut state), "app");
+    assert!(state.open[0], "member expansion is kept");
+}
+
+#[test]
+fn type_ahead_jumps_to_sibling() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3, 4],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "anyhow",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "Smallvec",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "syn",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let selected = |state: &mut TreeWidgetState| {
+        state.ensure_visible_nodes(&tree);
+        let id = state.selected_node_id().unwrap();
+        tree.node(id).unwrap().display_name().to_string()
+    };
+
+    state.select_next(&tree);
+    assert_eq!(selected(&mut state), "serde");
+    state.select_sibling_starting_with(&tree, 's');
+    assert_eq!(selected(&mut state), "Smallvec");
+    state.select_sibling_starting_with(&tree, 's');
+    assert_eq!(selected(&mut state), "syn");
+    state.select_sibling_starting_with(&tree, 's');
+    assert_eq!(selected(&mut state), "serde");
+    state.select_sibling_starting_with(&tree, 'a');
+    assert_eq!(selected(&mut state), "anyhow");
+    state.select_sibling_starting_with(&tree, 'z');
+    assert_eq!(selected(&mut state), "anyhow");
+}
+
+#[test]
+fn accessible_rows_keep_selection_first() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "dev",
+            parent: Some(0),
+            children: &[4],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "b",
+            parent: Some(3),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let style = TreeWidgetStyle {
+        accessible: true,
+        ..TreeWidgetStyle::default()
+    };
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.select_next(&tree);
+
+    let mut context = RenderContext::new(&tree, &mut state, &style, None);
+    let output = context.render(Rect::new(0, 0, 60, 10));
+    let lines = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        lines,
+        vec![
+            "> depth 1: a, 1 child, expanded",
+            "  depth 2: c, no children",
+            "  depth 1: dev-dependencies group, 1 child, expanded",
+            "  depth 2: b, no children",
+        ]
+    );
+}
+
+#[test]
+fn theme_presets() {
+    assert_eq!("protanopia".parse(), Ok(Theme::Colorblind));
+    assert_eq!("high-contrast".parse(), Ok(Theme::HighContrast));
+    assert!("neon".parse::<Theme>().is_err());
+
+    // Selection stays distinguishable without relying on hue.
+    for theme in [Theme::HighContrast, Theme::Colorblind] {
+        let style = TreeWidgetStyle::themed(theme);
+        assert!(
+            style.highlight_style.bg.is_some()
+                || style
+                    .highlight_style
+                    .add_modifier
+                    .contains(Modifier::REVERSED)
+        );
+        assert_ne!(
+            style.marked_style.add_modifier,
+            style.path_style.add_modifier
+        );
+    }
+}
+
+#[test]
+fn indexed_search_matches_linear_scan() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde_json",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "Serde_Derive",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let index = SearchIndex::build(&tree);
+
+    assert_eq!(index.candidates("se"), None);
+    assert_eq!(index.candidates("SERDE").map(|ids| ids.len()), Some(3));
+    assert_eq!(index.candidates("toml"), Some(Vec::new()));
+    for query in ["serde", "Serde", "erde_", "json", "zzz", "s", ""] {
+        let linear = TreeWidgetState::search(&tree, query);
+        let indexed = TreeWidgetState::search_indexed(&tree, Some(&index), query);
+        assert_eq!(indexed.match_ids, linear.match_ids, "query {query:?}");
+        assert_eq!(
+            indexed.visible_nodes, linear.visible_nodes,
+            "query {query:?}"
+        );
+    }
+}
+
+#[test]
+fn log_buffer_keeps_latest_lines() {
+    use std::io::Write;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    let buffer = LogBuffer::default();
+    {
+        let mut writer = buffer.make_writer();
+        writer.write_all(b"first event\nwith two lines\n").unwrap();
+    }
+    assert_eq!(buffer.tail(1), vec!["with two lines"]);
+    assert_eq!(buffer.tail(5), vec!["first event", "with two lines"]);
+
+    for i in 0..LogBuffer::CAPACITY {
+        buffer.push(format!("line {i}"));
+    }
+    assert_eq!(buffer.tail(usize::MAX).len(), LogBuffer::CAPACITY);
+    assert_eq!(buffer.tail(usize::MAX)[0], "line 0");
+}
+
+#[test]
+fn crash_report_contents() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let workspace = WorkspaceSummary::new(&tree);
+    assert_eq!(
+        (
+            workspace.nodes,
+            workspace.edges,
+            workspace.members,
+            workspace.max_depth
+        ),
+        (3, 2, 1, 3)
+    );
+
+    let report = CrashReport {
+        message: "index out of bounds".to_string(),
+        location: Some("src/widget/state.rs:1:1".to_string()),
+        backtrace: "0: main".to_string(),
+        workspace: &workspace,
+        actions: vec!["key code=Down".to_string(), "key code=Enter".to_string()],
+    };
+    let text = report.render();
+    for expected in [
+        "panic: index out of bounds",
+        "at: src/widget/state.rs:1:1",
+        "nodes: 3",
+        "max depth: 3",
+        "[last 2 actions]\nkey code=Down\nkey code=Enter",
+        "[backtrace]\n0: main",
+    ] {
+        assert!(text.contains(expected), "missing {expected:?} in:\n{text}");
+    }
+}
+
+#[test]
+fn layer_violations_are_flagged() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "core",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    tree.roots.push(NodeId(1));
+
+    let rules = LayerRules::parse(&["app -> core forbidden"]).unwrap();
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_layer_violations(rules.violations(&tree));
+    let area = Rect::new(0, 0, 40, 4);
+    let output = render_tree_widget(&tree, &mut state, area);
+    let lines: Vec<&str> = output.lines().take(3).collect();
+    assert_eq!(lines, ["app", "└──• core ✗ layer violation", "core"]);
+
+    let allowed = LayerRules::parse(&["* -> * forbidden", "app -> core allowed"]).unwrap();
+    assert!(allowed.violations(&tree).is_empty());
+}
+
+#[test]
+fn compact_rows_show_names_only() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "[dev-dependencies]",
+            parent: Some(0),
+            children: &[4],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "c",
+            parent: Some(3),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+
+    let style = TreeWidgetStyle {
+        compact: true,
+        show_dependents: true,
+        ..TreeWidgetStyle::default()
+    };
+
+    let expected = r#"
+root
+  ▾ a
+    • b
+[dev-dependencies]
+  • c
+"#;
+
+    let tree = build_tree(&nodes);
+    assert_eq!(
+        expected.trim(),
+        render_tree_context_with_style(&tree, style).trim()
+    );
+}
+
+#[test]
+fn member_header_stays_in_context_lines_when_scrolled() {
+    let tree = build_tree(&[
+        crate_node("app", None, &[1]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("b", Some(1), &[3]),
+        crate_node("c", Some(2), &[4]),
+        crate_node("d", Some(3), &[5]),
+        crate_node("e", Some(4), &[6]),
+        crate_node("f", Some(5), &[]),
+        crate_node("cli", None, &[]),
+    ]);
+    let style = TreeWidgetStyle::default();
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(6));
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 4));
+
+    let header = output.context_lines.first().unwrap();
+    let name = header
+        .spans
+        .iter()
+        .find(|span| span.content == "app")
+        .unwrap();
+    assert_eq!(name.style, style.member_style);
+}
+
+#[test]
+fn member_rows_head_their_subtree() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "cli",
+            parent: None,
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for (id, version, dir) in [(0, "0.1.0", "/ws/app"), (2, "0.2.0", "/ws/cli")] {
+        if let Some(DependencyNode::Crate(dependency)) = tree.nodes.get_mut(id) {
+            dependency.version = version.into();
+            dependency.manifest_dir = Some(dir.into());
+        }
+    }
+
+    let style = TreeWidgetStyle {
+        compact: true,
+        ..TreeWidgetStyle::default()
+    };
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(1));
+    let mut context = RenderContext::new(&tree, &mut state, &style, None);
+    let output = context.render(Rect::new(0, 0, 40, 10));
+
+    let rows: Vec<String> = output.lines.iter().map(|line| line.to_string()).collect();
+    assert_eq!(
+        rows,
+        ["app v0.1.0 (/ws/app)", "  • serde", "cli v0.2.0 (/ws/cli)"]
+    );
+    let name = |row: usize, name: &str| {
+        output.lines[row]
+            .spans
+            .iter()
+            .find(|span| span.content == name)
+            .unwrap()
+            .style
+    };
+    assert_eq!(name(0, "app"), style.member_style);
+    assert_eq!(name(2, "cli"), style.member_style);
+    assert_eq!(name(1, "serde"), style.highlight_style);
+}
+
+#[test]
+fn minimap_compresses_whole_tree() {
+    const NAMES: [&str; 20] = [
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r",
+        "s", "t",
+    ];
+    const CHILDREN: [usize; 20] = [
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+    ];
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children: &CHILDREN,
+        kind: TestNodeKind::Crate,
+    }];
+    nodes.extend(NAMES.iter().map(|&name| TestNode {
+        name,
+        parent: Some(0),
+        children: &[],
+        kind: TestNodeKind::Crate,
+    }));
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let mut terminal = Terminal::new(TestBackend::new(30, 7)).unwrap();
+    terminal
+        .draw(|frame| {
+            let area = frame.area();
+            TreeWidget::new(&tree)
+                .minimap(true)
+                .render(area, frame.buffer_mut(), &mut state);
+        })
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    let strip = |y: u16| -> String { (26..30).map(|x| buffer[(x, y)].symbol()).collect() };
+
+    assert_eq!(buffer[(0, 0)].symbol(), "r");
+    // The root's run starts at depth 0, every other run at depth 1.
+    assert_eq!(strip(0), "▬▬▬▬");
+    assert!((1..7).all(|y| strip(y) == " ▬▬▬"));
+    // The viewport shows the top of the tree.
+    let reversed = |y: u16| buffer[(27, y)].modifier.contains(Modifier::REVERSED);
+    assert!(reversed(0));
+    assert!(!reversed(6));
+}
+
+#[test]
+fn scrollbar_placement_and_auto_hide() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let render = |on_left: bool, auto_hide: bool, height: u16| {
+        let mut state = TreeWidgetState::default();
+        state.expand_all(&tree);
+        let mut terminal = Terminal::new(TestBackend::new(12, height)).unwrap();
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                TreeWidget::new(&tree)
+                    .scrollbar(Scrollbar::new(ScrollbarOrientation::VerticalLeft).thumb_symbol("#"))
+                    .scrollbar_on_left(on_left)
+                    .scrollbar_auto_hide(auto_hide)
+                    .render(area, frame.buffer_mut(), &mut state);
+            })
+            .unwrap();
+        terminal.backend().buffer().clone()
+    };
+
+    // The tree fits, so the scrollbar is hidden but its column is kept.
+    let buffer = render(true, true, 6);
+    assert_eq!(buffer[(0, 0)].symbol(), " ");
+    assert_eq!(buffer[(1, 0)].symbol(), "r");
+
+    // Shown anyway, with the thumb filling the track.
+    let buffer = render(true, false, 6);
+    let column: String = (0..6).map(|y| buffer[(0, y)].symbol()).collect();
+    assert_eq!(column, "▲####▼");
+    assert_eq!(buffer[(1, 1)].symbol(), "├");
+
+    // Scrolling content shows it even with auto-hide.
+    let buffer = render(true, true, 3);
+    assert!((0..3).any(|y| buffer[(0, y)].symbol() == "#"));
+
+    // Without a column of its own it is drawn over the tree.
+    let buffer = render(false, false, 6);
+    assert_eq!(buffer[(0, 1)].symbol(), "#");
+}
+
+#[test]
+fn collapsed_branches_count_hidden_matches() {
+    // root -> [a -> [serde, serde_json -> [serde]], b]
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 4],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde_json",
+            parent: Some(1),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    tree.parents[2].push(NodeId(3));
+    let style = TreeWidgetStyle {
+        show_match_counts: true,
+        ..TreeWidgetStyle::default()
+    };
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_search_query(&tree, "serde");
+    // Collapse `a`: both matches are hidden, `serde` counted once.
+    state.select_next(&tree);
+    state.collapse(&tree);
+
+    let render = |state: &mut TreeWidgetState| {
+        RenderContext::new(&tree, state, &style, None)
+            .render(Rect::new(0, 0, 40, 10))
+            .lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(render(&mut state), ["root", "└──▸ a •2"]);
+
+    // The search survives expanding again, and open rows get no badge.
+    state.expand(&tree);
+    assert_eq!(
+        render(&mut state),
+        [
+            "root",
+            "└──▾ a",
+            "   ├──• serde",
+            "   └──▾ serde_json",
+            "      └──• serde",
+        ]
+    );
+}
+
+#[test]
+fn reveal_pinned_crate() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "pinned",
+            parent: Some(1),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.collapse_all(&tree);
+    state.set_pinned(vec![NodeId(2)]);
+    state.set_annotated(vec![NodeId(2), NodeId(3)]);
+    state.reveal(&tree, NodeId(2));
+
+    let style = TreeWidgetStyle::default();
+    let lines = RenderContext::new(&tree, &mut state, &style, None)
+        .render(Rect::new(0, 0, 40, 10))
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(lines, ["root", "├──▾ a", "│  └──• pinned ★ ✎", "└──• b ✎"]);
+    assert_eq!(state.selected_node_id(), Some(NodeId(2)));
+}
+
+#[test]
+fn reload_changes_are_badged() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "added",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "bumped",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "gone",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_changes(
+        [
+            (NodeId(1), CrateChange::Added),
+            (NodeId(2), CrateChange::VersionChanged),
+            (NodeId(3), CrateChange::Removed),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let style = TreeWidgetStyle::default();
+    let output =
+        RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 10));
+    let lines = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        ["root", "├──• added +", "├──• bumped ~", "└──• gone -"]
+    );
+    let gone = output.lines[3]
+        .spans
+        .iter()
+        .find(|span| span.content == "gone")
+        .unwrap();
+    assert!(gone.style.add_modifier.contains(Modifier::CROSSED_OUT));
+}
+
+#[test]
+fn annotator_badges_follow_names() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "approved",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "pending",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_badges([(NodeId(1), "ok".to_string())].into_iter().collect());
+
+    let style = TreeWidgetStyle::default();
+    let output =
+        RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 10));
+    let lines = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(lines, ["root", "├──• approved ok", "└──• pending"]);
+    let badge = &output.lines[1].spans.last().unwrap();
+    assert_eq!(badge.style, style.badge_style);
+}
+
+#[test]
+fn direct_dependencies_show_declared_requirement() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "log",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for (id, version) in [(1, "1.0.0"), (2, "0.4.22")] {
+        if let Some(DependencyNode::Crate(dependency)) = tree.nodes.get_mut(id) {
+            dependency.version = version.into();
+        }
+    }
+    if let Some(DependencyNode::Crate(app)) = tree.nodes.get_mut(0) {
+        app.declared = [("serde", "^1.0"), ("log", "^0.4")]
+            .into_iter()
+            .map(|(name, requirement)| DeclaredDependency {
+                name: name.into(),
+                kind: DependencyType::Normal,
+                requirement: requirement.into(),
+                inherited: false,
+                rename: None,
+            })
+            .collect();
+    }
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_updates_in_range(vec![(NodeId(0), NodeId(1), "1.0.5".into())]);
+
+    let style = TreeWidgetStyle::default();
+    let lines = RenderContext::new(&tree, &mut state, &style, None)
+        .render(Rect::new(0, 0, 60, 10))
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "app",
+            "├──• serde ^1.0 → v1.0.0 ⇡ v1.0.5 allowed",
+            "└──• log ^0.4 → v0.4.22",
+        ]
+    );
+}
+
+#[test]
+fn risky_requirements_are_linted() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "log",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for (id, version) in [(1, "1.0.0"), (2, "0.5.0-rc.1")] {
+        if let Some(DependencyNode::Crate(dependency)) = tree.nodes.get_mut(id) {
+            dependency.version = version.into();
+        }
+    }
+    if let Some(DependencyNode::Crate(app)) = tree.nodes.get_mut(0) {
+        app.declared = [("serde", "*"), ("log", "^0.5.0-rc.1")]
+            .into_iter()
+            .map(|(name, requirement)| DeclaredDependency {
+                name: name.into(),
+                kind: DependencyType::Normal,
+                requirement: requirement.into(),
+                inherited: false,
+                rename: None,
+            })
+            .collect();
+    }
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let style = TreeWidgetStyle::default();
+    let lines = RenderContext::new(&tree, &mut state, &style, None)
+        .render(Rect::new(0, 0, 70, 10))
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "app",
+            "├──• serde * → v1.0.0 ⚠ wildcard requirement",
+            "└──• log ^0.5.0-rc.1 → v0.5.0-rc.1 ⚠ pre-release requirement",
+        ]
+    );
+}
+
+#[test]
+fn pager_follows_tail_wraps_and_searches() {
+    let text: Vec<String> = (0..10).map(|idx| format!("line {idx}")).collect();
+    let render = |lines: &[String], state: &PagerState| {
+        let area = Rect::new(0, 0, 24, 5);
+        let mut buf = Buffer::empty(area);
+        let lines = lines.iter().map(|line| Line::from(line.as_str())).collect();
+        Pager::new(lines, Block::bordered(), state).render(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut state = PagerState::following();
+    let rows = render(&text, &state);
+    assert_eq!(rows[1], "│ line 7               │");
+    assert_eq!(rows[3], "│ line 9               │");
+
+    for code in [KeyCode::Char('/'), KeyCode::Char('4'), KeyCode::Enter] {
+        assert!(state.handle_key(code, &text));
+    }
+    assert_eq!(state.scroll(), 4);
+    let rows = render(&text, &state);
+    assert_eq!(rows[1], "│ line 4               │");
+    assert!(rows[4].contains("/4: 1/1 · n/N"));
+    assert!(!state.handle_key(KeyCode::Esc, &text));
+
+    let long = vec!["a".repeat(30)];
+    let mut state = PagerState::default();
+    assert!(state.handle_key(KeyCode::Char('w'), &long));
+    let rows = render(&long, &state);
+    assert_eq!(rows[1], format!("│ {}│", "a".repeat(21)));
+    assert_eq!(rows[2], format!("│ {}{}│", "a".repeat(9), " ".repeat(12)));
+}
+
+#[test]
+fn binary_sizes_show_as_suffix() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "regex",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "log",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_binary_sizes(vec![3 * 1024 * 1024, 2 * 1024 * 1024, 0]);
+
+    let style = TreeWidgetStyle::default();
+    let output =
+        RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 10));
+    let lines = output
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        ["app (bin 3.0 MiB)", "├──• regex (bin 2.0 MiB)", "└──• log"]
+    );
+}
+
+#[test]
+fn feature_conflicts_are_flagged() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "libm",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_feature_conflicts([NodeId(1)].into_iter().collect());
+
+    let style = TreeWidgetStyle::default();
+    let output =
+        RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 10));
+    assert_eq!(output.lines[1].to_string(), "└──• libm ⚠ feature conflict");
+    let marker = output.lines[1].spans.last().unwrap();
+    assert_eq!(marker.style, style.lint_style);
+}
+
+#[test]
+fn release_ages_color_versions() {
+    let nodes = [
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "fresh",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "stale",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for node in &mut tree.nodes {
+        if let DependencyNode::Crate(dependency) = node {
+            dependency.version = "1.0.0".into();
+        }
+    }
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_release_ages(
+        [(NodeId(1), 30), (NodeId(2), 4 * 365)]
+            .into_iter()
+            .collect(),
+    );
+
+    let style = TreeWidgetStyle::default();
+    let output =
+        RenderContext::new(&tree, &mut state, &style, None).render(Rect::new(0, 0, 40, 10));
+    let version_style = |row: usize| {
+        output.lines[row]
+            .spans
+            .iter()
+            .find(|span| span.content == "1.0.0")
+            .unwrap()
+            .style
+    };
+    assert_eq!(version_style(0), style.version_style);
+    assert_eq!(version_style(1), style.age_styles[0]);
+    assert_eq!(version_style(2), style.age_styles[2]);
+    assert_eq!(style.age_style(400), style.age_styles[1]);
+}
+
+#[test]
+fn view_state_survives_rebuilds() {
+    let before = build_tree(&[
+        crate_node("root", None, &[1, 2]),
+        crate_node("a", Some(0), &[3]),
+        crate_node("b", Some(0), &[]),
+        crate_node("c", Some(1), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&before);
+    state.set_selected_node_id(&before, NodeId(3));
+    state.toggle_mark(&before);
+    let snapshot = state.snapshot(&before);
+
+    // A new crate shifts every id after the root.
+    let after = build_tree(&[
+        crate_node("root", None, &[1, 2, 3]),
+        crate_node("new", Some(0), &[]),
+        crate_node("a", Some(0), &[4]),
+        crate_node("b", Some(0), &[]),
+        crate_node("c", Some(2), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.collapse_all(&after);
+    state.restore(&after, &snapshot);
+    state.ensure_visible_nodes(&after);
+
+    assert_eq!(state.selected_node_id(), Some(NodeId(4)));
+    assert_eq!(state.marked(), [NodeId(4)]);
+    assert!(state.open[0] && state.open[2]);
+}
+
+#[test]
+fn dependency_type_styles_are_distinct() {
+    let normal = dependency_type_style(DependencyType::Normal);
+    let dev = dependency_type_style(DependencyType::Dev);
+    let build = dependency_type_style(DependencyType::Build);
+
+    assert_ne!(normal, dev);
+    assert_ne!(normal, build);
+    assert_ne!(dev, build);
+}
+
+#[test]
+fn long_child_lists_load_in_pages() {
+    let leaves = CHILD_PAGE + 5;
+    let children: &'static [usize] = Vec::leak((1..=leaves).collect());
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children,
+        kind: TestNodeKind::Crate,
+    }];
+    nodes.extend((0..leaves).map(|_| TestNode {
+        name: "leaf",
+        parent: Some(0),
+        children: &[],
+        kind: TestNodeKind::Crate,
+    }));
+    let tree = build_tree(&nodes);
+    let area = Rect::new(0, 0, 50, 4);
+
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(CHILD_PAGE));
+    state.select_next(&tree);
+    let rendered = render_tree_widget(&tree, &mut state, area);
+    assert!(
+        rendered.contains("└──  … and 5 more (press → to load)"),
+        "{rendered}"
+    );
+    assert_eq!(state.selected_node_id(), None);
+
+    // The first loaded child takes the place of the row.
+    state.expand(&tree);
+    state.ensure_visible_nodes(&tree);
+    assert_eq!(state.selected_node_id(), Some(NodeId(CHILD_PAGE + 1)));
+    assert!(!render_tree_widget(&tree, &mut state, area).contains("more"));
+
+    let mut state = TreeWidgetState::default();
+    state.reveal(&tree, NodeId(leaves));
+    state.ensure_visible_nodes(&tree);
+    assert_eq!(state.selected_node_id(), Some(NodeId(leaves)));
+}
+
+#[test]
+fn depth_guard_stops_expansion() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("b", Some(1), &[3]),
+        crate_node("c", Some(2), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_depth_guard(&tree, Some(2));
+
+    let rendered = render_tree_widget(&tree, &mut state, Rect::new(0, 0, 40, 5));
+    assert_eq!(
+        rendered,
+        "root\n└──▾ a\n   └──▾ b\n      └──  (depth limit reached)\nroot"
+    );
+
+    state.set_selected_node_id(&tree, NodeId(2));
+    state.select_next(&tree);
+    state.expand(&tree);
+    state.ensure_visible_nodes(&tree);
+    assert_eq!(state.selected_node_id(), None);
+    state.collapse(&tree);
+    assert_eq!(state.selected_node_id(), Some(NodeId(2)));
+}
+
+#[test]
+fn view_commands_shape_the_tree_by_pattern() {
+    assert_eq!(
+        "expand  serde ".parse(),
+        Ok(ViewCommand::Expand("serde".to_string()))
+    );
+    assert_eq!(
+        "collapse win".parse(),
+        Ok(ViewCommand::Collapse("win".to_string()))
+    );
+    assert!("expand".parse::<ViewCommand>().is_err());
+    assert!("fold serde".parse::<ViewCommand>().is_err());
+
+    let tree = build_tree(&[
+        crate_node("root", None, &[1, 3]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("serde", Some(1), &[]),
+        crate_node("b", Some(0), &[4]),
+        crate_node("c", Some(3), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.collapse_all(&tree);
+
+    assert_eq!(state.set_open_above(&tree, "serde", true), 1);
+    assert_eq!(state.open, [true, true, false, false, false]);
+    assert_eq!(state.set_open_above(&tree, "missing", true), 0);
+
+    state.expand_all(&tree);
+    state.set_open_above(&tree, "c", false);
+    assert_eq!(state.open, [false, true, false, false, false]);
+}
+
+#[test]
+fn duplicates_table_cycles_sort_columns() {
+    let mut tree = build_tree(&[
+        crate_node("app", None, &[1, 2, 3, 4]),
+        crate_node("log", Some(0), &[]),
+        crate_node("log", Some(0), &[]),
+        crate_node("syn", Some(0), &[2]),
+        crate_node("syn", Some(0), &[2]),
+    ]);
+    for (node, version) in tree.nodes[1..]
+        .iter_mut()
+        .zip(["0.4.20", "0.3.9", "2.0.1", "1.0.109"])
+    {
+        if let DependencyNode::Crate(dependency) = node {
+            dependency.version = version.into();
+        }
+    }
+    let mut view = StatsView::new(&tree, &TreeWidgetState::default());
+    let order = |view: &StatsView| {
+        view.duplicates
+            .iter()
+            .map(|row| format!("{} {}", row.name, row.version))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        order(&view),
+        ["log 0.3.9", "log 0.4.20", "syn 1.0.109", "syn 2.0.1"]
+    );
+
+    view.cycle_sort();
+    assert_eq!(
+        order(&view),
+        ["syn 1.0.109", "syn 2.0.1", "log 0.3.9", "log 0.4.20"]
+    );
+    view.cycle_sort();
+    view.cycle_sort();
+    assert_eq!(
+        order(&view),
+        ["syn 2.0.1", "syn 1.0.109", "log 0.4.20", "log 0.3.9"]
+    );
+    view.cycle_sort();
+    assert_eq!(view.sort.column, SortColumn::Dependents);
+    assert_eq!(
+        order(&view),
+        ["log 0.4.20", "syn 1.0.109", "syn 2.0.1", "log 0.3.9"]
+    );
+
+    let area = Rect::new(0, 0, 120, 20);
+    let mut buffer = Buffer::empty(area);
+    StatsScreen::new(&tree, &view).render(area, &mut buffer);
+    let text = (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(text.contains("Name  "));
+    assert!(text.contains("Dependents ▲"));
+}
+
+#[test]
+fn filters_combine_into_one_view() {
+    assert_eq!(
+        "filter kind=dev".parse(),
+        Ok(ViewCommand::Filter(Filter::Kind(DependencyType::Dev)))
+    );
+    assert_eq!("dependents>=3".parse(), Ok(Filter::MinDependents(3)));
+    assert!("kind=optional".parse::<Filter>().is_err());
+    assert!("vulnerable".parse::<Filter>().is_err());
+
+    let tree = {
+        let mut tree = build_tree(&[
+            TestNode {
+                name: "app",
+                parent: None,
+                children: &[1, 2, 3],
+                kind: TestNodeKind::Crate,
+            },
+            TestNode {
+                name: "serde",
+                parent: Some(0),
+                children: &[],
+                kind: TestNodeKind::Crate,
+            },
+            TestNode {
+                name: "git-dep",
+                parent: Some(0),
+                children: &[],
+                kind: TestNodeKind::Crate,
+            },
+            TestNode {
+                name: "dev",
+                parent: Some(0),
+                children: &[4],
+                kind: TestNodeKind::Group(DependencyType::Dev),
+            },
+            TestNode {
+                name: "tester",
+                parent: Some(3),
+                children: &[],
+                kind: TestNodeKind::Crate,
+            },
+        ]);
+        for idx in [2, 4] {
+            if let DependencyNode::Crate(dependency) = &mut tree.nodes[idx] {
+                dependency.source = SourceKind::Git;
+            }
+        }
+        tree
+    };
+    let facts = FilterFacts::new(&tree, &TreeWidgetState::default());
+    let mut filters = FilterSet::default();
+    assert!(filters.search_state(&tree, &facts).is_none());
+
+    filters.toggle(Filter::Source(SourceKind::Git));
+    let matches = |filters: &FilterSet| filters.search_state(&tree, &facts).unwrap().match_ids;
+    assert_eq!(matches(&filters), [NodeId(2), NodeId(4)]);
+    filters.toggle(Filter::Kind(DependencyType::Dev));
+    assert_eq!(matches(&filters), [NodeId(4)]);
+    filters.set_query(Some("ser"));
+    assert!(matches(&filters).is_empty());
+
+    // The live search replaces the committed query.
+    let search = TreeWidgetState::search(&tree, "e");
+    let narrowed = filters.narrow(&tree, &facts, search);
+    assert_eq!(narrowed.match_ids, [NodeId(4)]);
+
+    filters.set_query(None);
+    filters.toggle(Filter::Source(SourceKind::Registry));
+    assert_eq!(
+        filters.filters(),
+        [
+            Filter::Kind(DependencyType::Dev),
+            Filter::Source(SourceKind::Registry)
+        ]
+    );
+    assert!(!filters.toggle(Filter::Kind(DependencyType::Dev)));
+    assert_eq!(matches(&filters), [NodeId(0), NodeId(1)]);
+
+    filters.set_query(Some("serde"));
+    let area = Rect::new(0, 0, 80, 1);
+    let mut buffer = Buffer::empty(area);
+    FilterBar::new(&filters).render(area, &mut buffer);
+    let bar: String = (0..area.width).map(|x| buffer[(x, 0)].symbol()).collect();
+    assert!(bar.starts_with("filters  source=registry ✕   matches:\"serde\" ✕ "));
+}
+
+#[test]
+fn is_filtered_out_keeps_matches_and_their_ancestors() {
+    let tree = build_tree(&[
+        TestNode {
+            name: "app",
+            parent: None,
+            children: &[1, 2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "toml",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ]);
+    let mut state = TreeWidgetState::default();
+    assert!(!state.is_filtered_out(NodeId(2)));
+
+    let mut filters = FilterSet::default();
+    filters.set_query(Some("ser"));
+    let facts = FilterFacts::new(&tree, &state);
+    let search_state = filters.search_state(&tree, &facts).unwrap();
+    state.apply_search_state(&tree, search_state);
+    assert!(!state.is_filtered_out(NodeId(0)));
+    assert!(!state.is_filtered_out(NodeId(1)));
+    assert!(state.is_filtered_out(NodeId(2)));
+}
+
+#[test]
+fn view_args_reproduce_options() {
+    let options = TuiOptions {
+        expand: ExpandPolicy::Depth(2),
+        theme: Theme::HighContrast,
+        scrollbar: ScrollbarSide::Hidden,
+        compact: true,
+        members_only: true,
+        ..TuiOptions::default()
+    };
+    let args = options.view_args(Some(3), None);
+    assert_eq!(
+        args,
+        [
+            "--expand",
+            "2",
+            "--theme",
+            "high-contrast",
+            "--scrollbar",
+            "none",
+            "--min-used-by",
+            "3",
+            "--compact",
+            "--members-only"
+        ]
+    );
+    assert!(TuiOptions::default().view_args(None, None).is_empty());
+    let inline = TuiOptions {
+        inline: Some(12),
+        ..TuiOptions::default()
+    };
+    assert_eq!(inline.view_args(None, None), ["--inline", "12"]);
+    assert_eq!(
+        TuiOptions::default().view_args(None, Some("tok")),
+        ["--filter", "tok"]
+    );
+    for (flag, value) in args.chunks(2).take(3).map(|pair| (&pair[0], &pair[1])) {
+        match flag.as_str() {
+            "--expand" => assert_eq!(value.parse(), Ok(options.expand)),
+            "--theme" => assert_eq!(value.parse(), Ok(options.theme)),
+            _ => assert_eq!(value.parse(), Ok(options.scrollbar)),
+        }
+    }
+
+    let line = shell_line(&[
+        "cargo".to_string(),
+        "--features".to_string(),
+        "rt,macros".to_string(),
+        "--annotate-command".to_string(),
+        "echo it's {name}".to_string(),
+        String::new(),
+    ]);
+    assert_eq!(
+        line,
+        r"cargo --features rt,macros --annotate-command 'echo it'\''s {name}' ''"
+    );
+}
+
+#[test]
+fn control_commands_parse() {
+    assert_eq!(
+        "select serde@1.0.200".parse(),
+        Ok(ControlCommand::Select("serde@1.0.200".to_string()))
+    );
+    assert_eq!(
+        " export json /tmp/x ".parse(),
+        Ok(ControlCommand::Export(GraphFormat::Json, "/tmp/x".into()))
+    );
+    assert_eq!("expand-all".parse(), Ok(ControlCommand::ExpandAll));
+    assert!("select".parse::<ControlCommand>().is_err());
+    assert!("expand-all now".parse::<ControlCommand>().is_err());
+    assert!("export svg /tmp/x".parse::<ControlCommand>().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn control_socket_forwards_commands() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    use cargo_tree_tui::ops::tree::tui::control::spawn_control_socket;
+
+    let path = std::env::temp_dir().join(format!("cargo-tree-tui-control-{}", std::process::id()));
+    let (event_tx, event_rx) = std::sync::mpsc::channel();
+    spawn_control_socket(&path, event_tx).unwrap();
+    std::thread::spawn(move || {
+        for event in event_rx {
+            if let Event::Control(request) = event {
+                let outcome = match request.command {
+                    ControlCommand::Select(spec) if spec != "serde" => {
+                        Err(format!("no crate matches `{spec}`"))
+                    }
+                    _ => Ok(()),
+                };
+                request.reply.send(outcome).unwrap();
+            }
+        }
+    });
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    let mut replies = BufReader::new(stream.try_clone().unwrap()).lines();
+    for (command, reply) in [
+        ("select serde", "ok"),
+        ("select missing", "error: no crate matches `missing`"),
+        ("fold", "error: unknown command `fold`"),
+    ] {
+        writeln!(stream, "{command}").unwrap();
+        assert!(replies.next().unwrap().unwrap().starts_with(reply));
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn watchdog_stops_background_commands() {
+    // Collects the output of a background command until it finishes.
+    let finish = |event_rx: std::sync::mpsc::Receiver<Event>| {
+        let mut lines = Vec::new();
+        for event in event_rx {
+            match event {
+                Event::CommandOutput(line) => lines.push(line),
+                Event::CommandFinished { success } => return (success, lines),
+                _ => {}
+            }
+        }
+        panic!("command never finished");
+    };
+
+    let cancel = CancelToken::default();
+    cancel.cancel();
+    let (event_tx, event_rx) = std::sync::mpsc::channel();
+    spawn_cargo(
+        vec!["version".to_string()],
+        Loader::default(),
+        event_tx,
+        cancel,
+        None,
+    );
+    let (success, lines) = finish(event_rx);
+    assert!(!success);
+    assert!(lines.contains(&"cargo cancelled, killed it".to_string()));
+
+    let (event_tx, event_rx) = std::sync::mpsc::channel();
+    spawn_resolve(
+        Resolve::Locked,
+        Loader::default(),
+        event_tx,
+        CancelToken::default(),
+        Some(std::time::Duration::ZERO),
+    );
+    let (success, lines) = finish(event_rx);
+    assert!(!success);
+    assert_eq!(lines, ["resolve timed out after 0s"]);
+}
+
+#[test]
+fn ansi_export_colors_rendered_tree() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1, 2]),
+        crate_node("a", Some(0), &[]),
+        crate_node("b", Some(0), &[]),
+    ]);
+    let style = || TreeWidgetStyle {
+        name_style: Style::new().fg(ratatui::style::Color::Indexed(208)),
+        member_style: Modifier::BOLD.into(),
+        ..TreeWidgetStyle::default()
+    };
+    let ansi = tree_ansi(&tree, &style());
+    assert!(ansi.contains("\x1b[38;5;208ma\x1b[0m"));
+    assert!(ansi.contains("\x1b[1mroot\x1b[0m"));
+
+    let mut plain = String::new();
+    let mut chars = ansi.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            plain.push(c);
+        }
+    }
+    assert_eq!(
+        plain.trim_end(),
+        render_tree_context_with_style(&tree, style())
+    );
+}
+
+/// Rows reused between frames follow selection moves and state changes.
+#[test]
+fn cached_rows_match_fresh_renders() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1, 2]),
+        crate_node("a", Some(0), &[3]),
+        crate_node("b", Some(0), &[]),
+        crate_node("c", Some(1), &[]),
+    ]);
+    let area = Rect::new(0, 0, 40, 6);
+    let render = |state: &mut TreeWidgetState| {
+        let mut buf = Buffer::empty(area);
+        TreeWidget::new(&tree).render(area, &mut buf, state);
+        buf
+    };
+    type Step = fn(&mut TreeWidgetState, &DependencyTree);
+    let steps: [Step; 7] = [
+        |state, tree| state.select_next(tree),
+        |state, _| state.set_badges([(NodeId(2), "new".to_string())].into_iter().collect()),
+        |state, tree| state.toggle_mark(tree),
+        |state, tree| state.select_next(tree),
+        |state, tree| state.select_previous(tree),
+        |state, tree| state.collapse(tree),
+        |state, tree| state.select_next(tree),
+    ];
+
+    let mut cached = TreeWidgetState::default();
+    cached.expand_all(&tree);
+    render(&mut cached);
+    for done in 1..=steps.len() {
+        steps[done - 1](&mut cached, &tree);
+        let mut fresh = TreeWidgetState::default();
+        fresh.expand_all(&tree);
+        for step in &steps[..done] {
+            step(&mut fresh, &tree);
+        }
+        assert_eq!(render(&mut cached), render(&mut fresh), "after step {done}");
+    }
+}
+
+#[test]
+fn search_preview_restores_selection_on_cancel() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "b",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "c",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(3));
+    let selected = |state: &mut TreeWidgetState| {
+        state.visible_nodes(&tree);
+        state.selected_node_id()
+    };
+
+    // Typing previews the first match, and cancelling returns to `c`.
+    state.preview_search(&tree, TreeWidgetState::search(&tree, "b"));
+    assert_eq!(selected(&mut state), Some(NodeId(2)));
+    state.preview_search(&tree, TreeWidgetState::search(&tree, "x"));
+    assert_eq!(selected(&mut state), Some(NodeId(3)));
+    state.preview_search(&tree, TreeWidgetState::search(&tree, "b"));
+    state.clear_search();
+    state.cancel_preview(&tree);
+    assert_eq!(selected(&mut state), Some(NodeId(3)));
+
+    // A committed preview is no longer undone.
+    state.preview_search(&tree, TreeWidgetState::search(&tree, "b"));
+    state.commit_preview();
+    state.cancel_preview(&tree);
+    assert_eq!(selected(&mut state), Some(NodeId(2)));
+}
+
+#[test]
+fn versions_view_disambiguates_matched_versions() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[1, 2, 3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "a",
+            parent: Some(0),
+            children: &[3],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "syn",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "syn",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let mut tree = build_tree(&nodes);
+    for (node, version) in tree
+        .nodes
+        .iter_mut()
+        .zip(["0.1.0", "1.0.0", "2.0.10", "2.0.9"])
+    {
+        if let DependencyNode::Crate(dependency) = node {
+            dependency.version = version.into();
+        }
+    }
+    let matches = TreeWidgetState::search(&tree, "syn").match_ids;
+
+    let view = VersionsView::new(&tree, &matches, NodeId(3)).unwrap();
+    let choices: Vec<(&str, usize)> = view
+        .choices
+        .iter()
+        .map(|choice| (choice.version.as_str(), choice.occurrences))
+        .collect();
+    assert_eq!(choices, [("2.0.9", 2), ("2.0.10", 1)]);
+    assert_eq!(view.selected_id(), Some(NodeId(3)));
+
+    // A single matched version needs no popup.
+    assert!(VersionsView::new(&tree, &matches[..1], matches[0]).is_none());
+    assert!(VersionsView::new(&tree, &[NodeId(1)], NodeId(1)).is_none());
+}
+
+#[test]
+fn reveal_under_selects_the_declaring_occurrence() {
+    let nodes = [
+        TestNode {
+            name: "root",
+            parent: None,
+            children: &[3, 1],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "app",
+            parent: Some(0),
+            children: &[2],
+            kind: TestNodeKind::Crate,
+        },
+        TestNode {
+            name: "dev-dependencies",
+            parent: Some(1),
+            children: &[3],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        TestNode {
+            name: "serde",
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        },
+    ];
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.collapse_all(&tree);
+
+    state.reveal(&tree, NodeId(3));
+    assert_eq!(state.selected_virtual_pos().map(|pos| pos.0), Some(1));
+
+    // root, serde, app, dev-dependencies, serde
+    state.reveal_under(&tree, NodeId(1), NodeId(3));
+    assert_eq!(state.selected_virtual_pos().map(|pos| pos.0), Some(4));
+    state.visible_nodes(&tree);
+    assert_eq!(state.selected_node_id(), Some(NodeId(3)));
+    assert_eq!(state.selected_parent_id(), Some(NodeId(2)));
+}
+
+#[test]
+fn keymap_lists_configured_commands_last() {
+    let builtin = keymap(&NodeCommands::default());
+    assert_eq!(
+        builtin.first(),
+        Some(&KeyBinding {
+            key: "?".into(),
+            action: "Show this popup".into(),
+        })
+    );
+
+    let dir = std::env::temp_dir().join(format!("cargo-tree-tui-keys-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[package.metadata.tree-tui.commands]\na = \"cargo audit -p {name}\"\n",
+    )
+    .unwrap();
+    let commands = NodeCommands::load(&dir.join("Cargo.toml")).unwrap();
+    let bindings = keymap(&commands);
+    assert_eq!(bindings.len(), builtin.len() + 1);
+    assert_eq!(
+        bindings.last(),
+        Some(&KeyBinding {
+            key: "!a".into(),
+            action: "Run `cargo audit -p {name}`".into(),
+        })
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn wrapped_rows_scroll_past_the_materialized_window() {
+    let children: &'static [usize] = (1..=40).collect::<Vec<_>>().leak();
+    let mut nodes = vec![TestNode {
+        name: "root",
+        parent: None,
+        children,
+        kind: TestNodeKind::Crate,
+    }];
+    for idx in 1..=40 {
+        nodes.push(TestNode {
+            name: format!("crate-number-{idx}").leak(),
+            parent: Some(0),
+            children: &[],
+            kind: TestNodeKind::Crate,
+        });
+    }
+
+    let tree = build_tree(&nodes);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+
+    let area = Rect {
+        x: 0,
+        y: 0,
+        width: 16,
+        height: 6,
+    };
+
+    let style = TreeWidgetStyle::default();
+    let mut prev_offset = 0;
+    for idx in 1..=40 {
+        state.select_next(&tree);
+        let output = RenderContext::new(&tree, &mut state, &style, None)
+            .wrap(true)
+            .render(area);
+        let rows: Vec<String> = output.lines.iter().map(|line| line.to_string()).collect();
+        // Every child wraps to two rows, the number on the second one.
+        let number = format!(" number-{idx}");
+        assert!(rows.iter().any(|row| row.ends_with(&number)), "{rows:#?}");
+        assert!(output.viewport.offset >= prev_offset, "{rows:#?}");
+        prev_offset = output.viewport.offset;
+    }
+}
+
+#[test]
+fn breadcrumb_elision_policies() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("b", Some(1), &[3]),
+        crate_node("c", Some(2), &[4]),
+        crate_node("d", Some(3), &[5]),
+        crate_node("e", Some(4), &[6]),
+        crate_node("f", Some(5), &[7]),
+        crate_node("g", Some(6), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(7));
+
+    for (elision, expected) in [
+        (BreadcrumbElision::Head, "root → a → b → … → g"),
+        (BreadcrumbElision::Tail, "root → … → e → f → g"),
+        (BreadcrumbElision::Ends, "root → … → f → g"),
+    ] {
+        let mut terminal = Terminal::new(TestBackend::new(55, 5)).unwrap();
+        terminal
+            .draw(|frame| {
+                let style = TreeWidgetStyle {
+                    breadcrumb_elision: elision,
+                    ..TreeWidgetStyle::default()
+                };
+                TreeWidget::new(&tree).style(style).render(
+                    frame.area(),
+                    frame.buffer_mut(),
+                    &mut state,
+                );
+            })
+            .unwrap();
+        let breadcrumb = terminal.backend().buffer().content()[4 * 55..]
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert_eq!(breadcrumb.trim_end(), expected, "{elision}");
+    }
+}
+
+#[test]
+fn path_view_lists_ancestors_with_kinds() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1]),
+        crate_node("x", Some(0), &[2]),
+        TestNode {
+            name: "dev",
+            parent: Some(1),
+            children: &[3],
+            kind: TestNodeKind::Group(DependencyType::Dev),
+        },
+        crate_node("b", Some(2), &[4]),
+        crate_node("c", Some(3), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    state.set_selected_node_id(&tree, NodeId(4));
+    state.ensure_visible_nodes(&tree);
+
+    let view = PathView::new(&tree, &state);
+    let steps: Vec<_> = view
+        .steps
+        .iter()
+        .map(|step| (step.name.as_str(), step.kind, step.up))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            ("root", None, 4),
+            ("x", None, 3),
+            ("b", Some(DependencyType::Dev), 1),
+            ("c", None, 0),
+        ]
+    );
+    assert_eq!(view.selected, 3);
+
+    for _ in 0..view.steps[1].up {
+        state.select_parent(&tree);
+    }
+    assert_eq!(state.selected_node_id(), Some(NodeId(1)));
+}
+
+#[test]
+fn frozen_ancestor_stays_in_context_lines() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("b", Some(1), &[3]),
+        crate_node("c", Some(2), &[4]),
+        crate_node("d", Some(3), &[5]),
+        crate_node("e", Some(4), &[6]),
+        crate_node("f", Some(5), &[7]),
+        crate_node("g", Some(6), &[8]),
+        crate_node("h", Some(7), &[9]),
+        crate_node("i", Some(8), &[10]),
+        crate_node("j", Some(9), &[11]),
+        crate_node("k", Some(10), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let area = Rect::new(0, 0, 40, 8);
+    let style = TreeWidgetStyle::default();
+
+    state.set_selected_node_id(&tree, NodeId(2));
+    state.toggle_frozen(&tree);
+    assert_eq!(state.frozen(), Some(NodeId(2)));
+    state.set_selected_node_id(&tree, NodeId(11));
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(area);
+    let context: Vec<(String, Style)> = output
+        .context_lines
+        .iter()
+        .map(|line| {
+            (
+                line.to_string()
+                    .trim_start_matches(['└', '─', '▾', ' '])
+                    .to_string(),
+                line.style,
+            )
+        })
+        .collect();
+    assert_eq!(
+        context[..3],
+        [
+            ("root".to_string(), Style::default()),
+            ("a".to_string(), Style::default()),
+            ("b".to_string(), style.frozen_style),
+        ]
+    );
+}
+
+#[test]
+fn frozen_node_is_pinned_when_scrolled_past() {
+    let tree = build_tree(&[
+        crate_node("root", None, &[1, 3]),
+        crate_node("a", Some(0), &[2]),
+        crate_node("a1", Some(1), &[]),
+        crate_node("b", Some(0), &[4, 5, 6, 7, 8, 9, 10, 11]),
+        crate_node("b1", Some(3), &[]),
+        crate_node("b2", Some(3), &[]),
+        crate_node("b3", Some(3), &[]),
+        crate_node("b4", Some(3), &[]),
+        crate_node("b5", Some(3), &[]),
+        crate_node("b6", Some(3), &[]),
+        crate_node("b7", Some(3), &[]),
+        crate_node("b8", Some(3), &[]),
+    ]);
+    let mut state = TreeWidgetState::default();
+    state.expand_all(&tree);
+    let area = Rect::new(0, 0, 40, 8);
+    let style = TreeWidgetStyle::default();
+    let context = |output: &RenderOutput| {
+        output
+            .context_lines
+            .iter()
+            .map(|line| {
+                (
+                    line.to_string()
+                        .trim_start_matches(['├', '└', '│', '─', '▾', '•', ' '])
+                        .to_string(),
+                    line.style,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // `a` is not an ancestor of the rows below `b`, yet stays pinned.
+    state.set_selected_node_id(&tree, NodeId(1));
+    state.toggle_frozen(&tree);
+    state.set_selected_node_id(&tree, NodeId(11));
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(area);
+    assert_eq!(
+        context(&output),
+        [
+            ("a".to_string(), style.frozen_style),
+            ("root".to_string(), Style::default()),
+            ("b".to_string(), Style::default()),
+        ]
+    );
+
+    // Nothing is pinned while the frozen node is in view.
+    state.set_selected_node_id(&tree, NodeId(0));
+    let output = RenderContext::new(&tree, &mut state, &style, None).render(area);
+    assert!(output.context_lines.is_empty());
+}